@@ -4,22 +4,28 @@ use super::config::{ClusterTestOpt, Env};
 use async_trait::async_trait;
 use clap::*;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use sui::client_commands::WalletContext;
 use sui::config::{SuiClientConfig, SuiEnv};
 use sui_config::genesis_config::GenesisConfig;
 use sui_config::Config;
-use sui_config::SUI_KEYSTORE_FILENAME;
-use sui_indexer::test_utils::start_test_indexer;
-use sui_indexer::IndexerConfig;
+use sui_config::{SUI_KEYSTORE_FILENAME, SUI_NETWORK_CONFIG};
+use sui_core::authority::authority_store_tables::LiveObject;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_snapshot::reader::StateSnapshotReader;
+use sui_indexer::test_utils::{start_test_indexer, start_test_sqlite_indexer};
+use sui_indexer::{DbBackend, IndexerConfig};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_storage::object_store::ObjectStoreConfig;
 use sui_swarm::memory::Swarm;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{EpochId, SuiAddress};
 use sui_types::crypto::KeypairTraits;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::object::{Object, Owner};
+use sui_types::{is_system_package, SUI_CLOCK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID};
 use test_utils::network::{TestCluster, TestClusterBuilder};
-use tracing::info;
+use tracing::{info, warn};
 
 const DEVNET_FAUCET_ADDR: &str = "https://faucet.devnet.sui.io:443";
 const STAGING_FAUCET_ADDR: &str = "https://faucet.staging.sui.io:443";
@@ -144,13 +150,20 @@ impl Cluster for RemoteRunningCluster {
     }
 }
 
-/// Represents a local Cluster which starts per cluster test run.
+/// Subdirectories of `ClusterTestOpt::config_dir` for a persisted local network.
+const NETWORK_DIR: &str = "network";
+const FAUCET_DIR: &str = "faucet";
+
+/// Represents a local Cluster which starts per cluster test run, or which is restarted from
+/// `ClusterTestOpt::config_dir` when it holds a network created by a previous run.
 pub struct LocalNewCluster {
     test_cluster: TestCluster,
     fullnode_url: String,
     indexer_url: Option<String>,
     faucet_key: AccountKeyPair,
-    config_directory: tempfile::TempDir,
+    config_directory: PathBuf,
+    // Removed on drop when the cluster isn't persisted.
+    _temp_dir: Option<tempfile::TempDir>,
 }
 
 impl LocalNewCluster {
@@ -158,6 +171,11 @@ impl LocalNewCluster {
     pub fn swarm(&self) -> &Swarm {
         &self.test_cluster.swarm
     }
+
+    #[allow(unused)]
+    pub fn wallet(&self) -> &WalletContext {
+        &self.test_cluster.wallet
+    }
 }
 
 #[async_trait]
@@ -182,6 +200,35 @@ impl Cluster for LocalNewCluster {
             .set_genesis_config(genesis_config)
             .enable_fullnode_events();
 
+        let network_dir = options.config_dir.as_ref().map(|dir| dir.join(NETWORK_DIR));
+        let restarted = network_dir
+            .as_ref()
+            .map_or(false, |dir| dir.join(SUI_NETWORK_CONFIG).exists());
+        if let Some(network_dir) = network_dir {
+            cluster_builder = cluster_builder.with_config_dir(network_dir);
+        }
+
+        if let Some(snapshot_dir) = &options.fork_from_snapshot {
+            if restarted {
+                warn!("Ignoring the state snapshot to fork from, the network already exists");
+            } else {
+                let epoch = options
+                    .fork_epoch
+                    .ok_or_else(|| anyhow::anyhow!("Expect 'fork_epoch' for a snapshot fork"))?;
+                let checkpoint_store_dir =
+                    options.fork_checkpoint_store.as_ref().ok_or_else(|| {
+                        anyhow::anyhow!("Expect 'fork_checkpoint_store' for a snapshot fork")
+                    })?;
+                let objects =
+                    objects_from_snapshot(snapshot_dir, epoch, checkpoint_store_dir).await?;
+                info!(
+                    num_objects = objects.len(),
+                    epoch, "Forking from state snapshot"
+                );
+                cluster_builder = cluster_builder.with_objects(objects);
+            }
+        }
+
         if let Some(epoch_duration_ms) = options.epoch_duration_ms {
             cluster_builder = cluster_builder.with_epoch_duration_ms(epoch_duration_ms);
         }
@@ -204,28 +251,56 @@ impl Cluster for LocalNewCluster {
         } else {
             vec![]
         };
-        if options.pg_address.is_some() && indexer_address.is_some() {
+        if let Some(indexer_address) = indexer_address {
             let config = IndexerConfig {
-                db_url: options.pg_address.clone().unwrap(),
                 rpc_client_url: fullnode_url.clone(),
-                rpc_server_url: indexer_address.as_ref().unwrap().ip().to_string(),
-                rpc_server_port: indexer_address.as_ref().unwrap().port(),
+                rpc_server_url: indexer_address.ip().to_string(),
+                rpc_server_port: indexer_address.port(),
                 migrated_methods,
-                reset_db: true,
+                // Keep the indexed history of a restarted network.
+                reset_db: !restarted,
                 ..Default::default()
             };
-            start_test_indexer(config).await.unwrap();
+            if let Some(sqlite_path) = &options.indexer_sqlite_path {
+                start_test_sqlite_indexer(IndexerConfig {
+                    db_url: sqlite_path.clone(),
+                    db_backend: DbBackend::Sqlite,
+                    ..config
+                })
+                .await
+                .unwrap();
+            } else if let Some(pg_address) = &options.pg_address {
+                start_test_indexer(IndexerConfig {
+                    db_url: pg_address.clone(),
+                    ..config
+                })
+                .await
+                .unwrap();
+            }
         }
 
         // Let nodes connect to one another
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
+        let (config_directory, temp_dir) = match &options.config_dir {
+            Some(dir) => {
+                let faucet_dir = dir.join(FAUCET_DIR);
+                std::fs::create_dir_all(&faucet_dir)?;
+                (faucet_dir, None)
+            }
+            None => {
+                let temp_dir = tempfile::tempdir()?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
+
         // TODO: test connectivity before proceeding?
         Ok(Self {
             test_cluster,
             fullnode_url,
             faucet_key,
-            config_directory: tempfile::tempdir()?,
+            config_directory,
+            _temp_dir: temp_dir,
             indexer_url: options.indexer_address.clone(),
         })
     }
@@ -251,10 +326,42 @@ impl Cluster for LocalNewCluster {
     }
 
     fn config_directory(&self) -> &Path {
-        self.config_directory.path()
+        &self.config_directory
     }
 }
 
+/// Objects of the state snapshot of `epoch` stored in `snapshot_dir` that can be added to the
+/// genesis of a local network, once checked against the last checkpoint of the epoch in the
+/// checkpoint store at `checkpoint_store_dir`. The system packages and system state are left out
+/// as the local network creates its own, so objects tied to the validators of the forked network,
+/// such as staked SUI, are not usable.
+async fn objects_from_snapshot(
+    snapshot_dir: &Path,
+    epoch: EpochId,
+    checkpoint_store_dir: &Path,
+) -> Result<Vec<Object>, anyhow::Error> {
+    let config = ObjectStoreConfig::local(snapshot_dir);
+    let checkpoint_store = CheckpointStore::new(checkpoint_store_dir);
+    let live_objects = StateSnapshotReader::new(epoch, &config)?
+        .read_live_objects(&checkpoint_store)
+        .await?;
+    let system_state_owner = Owner::ObjectOwner(SUI_SYSTEM_STATE_OBJECT_ID.into());
+    Ok(live_objects
+        .into_iter()
+        .filter_map(|live_object| match live_object {
+            LiveObject::Normal(object) => Some(object),
+            LiveObject::Wrapped(_) => None,
+        })
+        .filter(|object| {
+            let id = object.id();
+            !is_system_package(id)
+                && id != SUI_SYSTEM_STATE_OBJECT_ID
+                && id != SUI_CLOCK_OBJECT_ID
+                && object.owner != system_state_owner
+        })
+        .collect())
+}
+
 // Make linter happy
 #[async_trait]
 impl Cluster for Box<dyn Cluster + Send + Sync> {
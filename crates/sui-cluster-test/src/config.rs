@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use clap::*;
+use std::path::PathBuf;
 
 #[derive(Parser, Clone, ArgEnum)]
 pub enum Env {
@@ -30,9 +31,25 @@ pub struct ClusterTestOpt {
     /// URL for the Indexer Postgres DB
     #[clap(long)]
     pub pg_address: Option<String>,
+    /// Path of a SQLite database file for the indexer, used instead of `pg_address` when set
+    #[clap(long)]
+    pub indexer_sqlite_path: Option<String>,
     /// TODO(gegao): remove this after indexer migration is complete.
     #[clap(long)]
     pub use_indexer_experimental_methods: bool,
+    /// Directory to keep a local network in, so that it can be stopped and restarted
+    #[clap(long)]
+    pub config_dir: Option<PathBuf>,
+    /// Directory of a state snapshot store to seed the genesis of a new local network with
+    #[clap(long)]
+    pub fork_from_snapshot: Option<PathBuf>,
+    /// Epoch of the state snapshot to fork from, required with `fork_from_snapshot`
+    #[clap(long)]
+    pub fork_epoch: Option<u64>,
+    /// Checkpoint store holding the last checkpoint of the forked epoch, which the snapshot is
+    /// verified against, required with `fork_from_snapshot`
+    #[clap(long)]
+    pub fork_checkpoint_store: Option<PathBuf>,
 }
 
 impl ClusterTestOpt {
@@ -44,7 +61,12 @@ impl ClusterTestOpt {
             epoch_duration_ms: None,
             indexer_address: None,
             pg_address: None,
+            indexer_sqlite_path: None,
             use_indexer_experimental_methods: false,
+            config_dir: None,
+            fork_from_snapshot: None,
+            fork_epoch: None,
+            fork_checkpoint_store: None,
         }
     }
 }
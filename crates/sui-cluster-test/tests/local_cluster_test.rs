@@ -1,7 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::Path;
+use std::time::Duration;
+
+use sui_cluster_test::cluster::{Cluster, LocalNewCluster};
 use sui_cluster_test::{config::ClusterTestOpt, ClusterTest};
+use sui_config::utils::get_available_port;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_snapshot::writer::StateSnapshotWriter;
+use sui_indexer::models::webhook_cursors::WebhookCursor;
+use sui_indexer::new_sqlite_connection_pool;
+use sui_indexer::store::{IndexerStore, SqliteIndexerStore};
+use sui_json_rpc_types::{
+    SuiObjectDataOptions, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::SuiClient;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::base_types::ObjectID;
+use sui_types::digests::TransactionDigest;
+use test_utils::network::TestClusterBuilder;
+use test_utils::transaction::publish_basics_package;
 
 #[tokio::test]
 async fn cluster_test() {
@@ -9,3 +29,207 @@ async fn cluster_test() {
 
     ClusterTest::run(ClusterTestOpt::new_local()).await;
 }
+
+/// Options of a local cluster persisted in `config_dir`, with a SQLite indexer.
+fn persisted_cluster_options(config_dir: &Path, indexer_port: u16) -> ClusterTestOpt {
+    ClusterTestOpt {
+        indexer_address: Some(format!("127.0.0.1:{indexer_port}")),
+        indexer_sqlite_path: Some(indexer_db_url(config_dir)),
+        use_indexer_experimental_methods: true,
+        config_dir: Some(config_dir.to_path_buf()),
+        ..ClusterTestOpt::new_local()
+    }
+}
+
+fn indexer_db_url(config_dir: &Path) -> String {
+    config_dir.join("indexer.db").display().to_string()
+}
+
+/// Publish the basics package from the first account of the wallet, returning the package ID
+/// along with the digest of the publish transaction.
+async fn publish_package(cluster: &LocalNewCluster) -> (ObjectID, TransactionDigest) {
+    let wallet = cluster.wallet();
+    let sender = wallet.config.keystore.addresses()[0];
+    let package_id = publish_basics_package(wallet, sender).await.0;
+    let digest = wallet
+        .get_client()
+        .await
+        .unwrap()
+        .read_api()
+        .get_object_with_options(
+            package_id,
+            SuiObjectDataOptions::new().with_previous_transaction(),
+        )
+        .await
+        .unwrap()
+        .into_object()
+        .unwrap()
+        .previous_transaction
+        .unwrap();
+    (package_id, digest)
+}
+
+async fn wait_until_indexed(store: &SqliteIndexerStore, digest: TransactionDigest) {
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while store
+            .get_transaction_by_digest(&digest.base58_encode())
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("transaction not indexed in time");
+}
+
+async fn assert_object_exists(client: &SuiClient, object_id: ObjectID) {
+    let object = client
+        .read_api()
+        .get_object_with_options(object_id, SuiObjectDataOptions::new())
+        .await
+        .unwrap()
+        .into_object()
+        .unwrap();
+    assert_eq!(object.object_id, object_id);
+}
+
+// Each run of a cluster gets a runtime of its own, so that stopping the cluster also stops the
+// fullnode and indexer tasks holding its databases.
+#[test]
+fn test_restart_persisted_cluster() {
+    telemetry_subscribers::init_for_testing();
+    let config_dir = tempfile::tempdir().unwrap();
+    let options = persisted_cluster_options(config_dir.path(), get_available_port("127.0.0.1"));
+    let store = SqliteIndexerStore::new(
+        new_sqlite_connection_pool(&indexer_db_url(config_dir.path())).unwrap(),
+    );
+    // A row the indexer can't rebuild from the fullnode, telling whether its database was reset.
+    let marker = WebhookCursor {
+        name: "restart".to_string(),
+        cursor: "null".to_string(),
+        updated_at: 0,
+    };
+
+    let (package_id, digest) = tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let cluster = LocalNewCluster::start(&options).await.unwrap();
+        let (package_id, digest) = publish_package(&cluster).await;
+        wait_until_indexed(&store, digest).await;
+        store.persist_webhook_cursor(&marker).await.unwrap();
+        (package_id, digest)
+    });
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let cluster = LocalNewCluster::start(&options).await.unwrap();
+        let client = cluster.wallet().get_client().await.unwrap();
+        assert_object_exists(&client, package_id).await;
+        let response = client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(response.digest, digest);
+
+        // The indexed history is kept rather than indexed again.
+        assert_eq!(
+            store.get_webhook_cursor(&marker.name).await.unwrap(),
+            Some(marker)
+        );
+        store
+            .get_transaction_by_digest(&digest.base58_encode())
+            .await
+            .unwrap();
+
+        // The restarted network keeps going.
+        publish_package(&cluster).await;
+    });
+}
+
+#[test]
+fn test_fork_from_snapshot() {
+    telemetry_subscribers::init_for_testing();
+    let source_dir = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+
+    // A network whose fullnode takes a db checkpoint at the end of each epoch, from which the
+    // state snapshot of the epoch the package was published in is written. It is kept in a
+    // directory of its own so that the db checkpoint outlives it.
+    let (package_id, epoch, db_checkpoint) =
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let source = TestClusterBuilder::new()
+                .with_config_dir(source_dir.path().join("network"))
+                .with_epoch_duration_ms(5000)
+                .with_enable_db_checkpoints_fullnodes()
+                .build()
+                .await
+                .unwrap();
+            let sender = source.get_address_0();
+            let package_id = publish_basics_package(&source.wallet, sender).await.0;
+            let digest = source
+                .sui_client()
+                .read_api()
+                .get_object_with_options(
+                    package_id,
+                    SuiObjectDataOptions::new().with_previous_transaction(),
+                )
+                .await
+                .unwrap()
+                .into_object()
+                .unwrap()
+                .previous_transaction
+                .unwrap();
+            let epoch = source
+                .sui_client()
+                .read_api()
+                .get_transaction_with_options(
+                    digest,
+                    SuiTransactionBlockResponseOptions::new().with_effects(),
+                )
+                .await
+                .unwrap()
+                .effects
+                .unwrap()
+                .executed_epoch();
+            source.wait_for_epoch(Some(epoch + 1)).await;
+
+            let db_checkpoint = source
+                .fullnode_handle
+                .sui_node
+                .db_checkpoint_path()
+                .join(format!("epoch_{epoch}"));
+            tokio::time::timeout(Duration::from_secs(60), async {
+                while !db_checkpoint.exists() {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            })
+            .await
+            .expect("no db checkpoint at the end of the epoch");
+            let perpetual_db = AuthorityPerpetualTables::open(&db_checkpoint.join("store"), None);
+            let checkpoint_store = CheckpointStore::new(&db_checkpoint.join("checkpoints"));
+            StateSnapshotWriter::new(&ObjectStoreConfig::local(snapshot_dir.path()), usize::MAX)
+                .unwrap()
+                .write(epoch, &perpetual_db, &checkpoint_store)
+                .await
+                .unwrap();
+            (package_id, epoch, db_checkpoint)
+        });
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let options = ClusterTestOpt {
+            fork_from_snapshot: Some(snapshot_dir.path().to_path_buf()),
+            fork_epoch: Some(epoch),
+            fork_checkpoint_store: Some(db_checkpoint.join("checkpoints")),
+            ..ClusterTestOpt::new_local()
+        };
+        let cluster = LocalNewCluster::start(&options).await.unwrap();
+        let client = cluster.wallet().get_client().await.unwrap();
+        assert_object_exists(&client, package_id).await;
+
+        // A snapshot is only forked from once checked against its checkpoint.
+        let options = ClusterTestOpt {
+            fork_checkpoint_store: None,
+            ..options
+        };
+        assert!(LocalNewCluster::start(&options).await.is_err());
+    });
+}
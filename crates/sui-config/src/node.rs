@@ -100,6 +100,11 @@ pub struct NodeConfig {
     #[serde(default)]
     pub db_checkpoint_config: DBCheckpointConfig,
 
+    /// If set, certified checkpoints and their contents are continuously archived into the
+    /// configured object store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_archive_config: Option<CheckpointArchiveConfig>,
+
     #[serde(default)]
     pub indirect_objects_threshold: usize,
 
@@ -381,6 +386,16 @@ pub struct AuthorityStorePruningConfig {
     /// pruner deletion method. If set to `true`, range deletion is utilized (recommended).
     /// Use `false` for point deletes.
     pub use_range_deletion: bool,
+    /// number of epochs to keep transactions, effects, events and checkpoint contents for.
+    /// History is kept forever if neither this nor `num_checkpoints_to_retain_for_history` is
+    /// set, and history of the current epoch is always kept. History is only pruned on fullnodes,
+    /// validators refuse to start with either limit set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_epochs_to_retain_for_history: Option<u64>,
+    /// number of checkpoints to keep transactions, effects, events and checkpoint contents for.
+    /// If both retention limits are set, history is only pruned once it is outside of both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_checkpoints_to_retain_for_history: Option<u64>,
 }
 
 impl Default for AuthorityStorePruningConfig {
@@ -393,6 +408,8 @@ impl Default for AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_history: None,
+            num_checkpoints_to_retain_for_history: None,
         }
     }
 }
@@ -407,8 +424,16 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_history: None,
+            num_checkpoints_to_retain_for_history: None,
         }
     }
+    /// Whether transactions, effects, events and checkpoint contents are pruned.
+    pub fn prunes_history(&self) -> bool {
+        self.num_epochs_to_retain_for_history.is_some()
+            || self.num_checkpoints_to_retain_for_history.is_some()
+    }
+
     pub fn fullnode_config() -> Self {
         Self {
             num_latest_epoch_dbs_to_retain: 3,
@@ -418,6 +443,8 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_history: None,
+            num_checkpoints_to_retain_for_history: None,
         }
     }
 }
@@ -442,6 +469,29 @@ pub struct DBCheckpointConfig {
     pub object_store_config: Option<ObjectStoreConfig>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CheckpointArchiveConfig {
+    pub object_store_config: ObjectStoreConfig,
+    /// Checkpoints are committed into a new archive file once their uncompressed size reaches
+    /// this many bytes...
+    #[serde(default = "default_archive_commit_file_size")]
+    pub commit_file_size: usize,
+    /// ...or once the oldest of them was read this many seconds ago, whichever comes first.
+    #[serde(default = "default_archive_commit_duration_seconds")]
+    pub commit_duration_seconds: u64,
+}
+
+fn default_archive_commit_file_size() -> usize {
+    // 128 MiB
+    128 * 1024 * 1024
+}
+
+fn default_archive_commit_duration_seconds() -> u64 {
+    // 10 minutes
+    600
+}
+
 /// Publicly known information about a validator
 /// TODO read most of this from on-chain
 #[serde_as]
@@ -4,6 +4,7 @@
 use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::multiaddr::Multiaddr;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -131,6 +132,15 @@ pub struct StateSyncConfig {
     /// If unspecified, this will default to no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub get_checkpoint_contents_rate_limit: Option<NonZeroU32>,
+
+    /// Object store holding an archive of checkpoints, as written by
+    /// `sui_storage::archive::writer::ArchiveWriter`. When syncing from peers stops making
+    /// progress, e.g. because they have pruned the checkpoints we're missing, checkpoints are
+    /// synced from this archive instead.
+    ///
+    /// If unspecified, checkpoints are only synced from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_object_store_config: Option<ObjectStoreConfig>,
 }
 
 impl StateSyncConfig {
@@ -284,6 +284,7 @@ impl<'a> FullnodeConfigBuilder<'a> {
             metrics: None,
             supported_protocol_versions: Some(supported_protocol_versions),
             db_checkpoint_config: self.db_checkpoint_config,
+            checkpoint_archive_config: None,
             indirect_objects_threshold: usize::MAX,
             // Copy the expensive safety check config from the first validator config.
             expensive_safety_check_config: validator_config.expensive_safety_check_config.clone(),
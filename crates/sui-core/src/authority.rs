@@ -2,6 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::cmp::{max, min};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -15,7 +16,7 @@ use fastcrypto::encoding::Encoding;
 use fastcrypto::traits::KeyPair;
 use itertools::Itertools;
 use move_binary_format::CompiledModule;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, TypeTag};
 use parking_lot::Mutex;
 use prometheus::{
     register_histogram_with_registry, register_int_counter_vec_with_registry,
@@ -52,8 +53,9 @@ use sui_json_rpc_types::{
 };
 use sui_macros::{fail_point, fail_point_async, nondeterministic};
 use sui_protocol_config::SupportedProtocolVersions;
-use sui_storage::indexes::ObjectIndexChanges;
+use sui_storage::indexes::{CoinBalance, CoinBalanceKey, ObjectIndexChanges};
 use sui_storage::IndexStore;
+use sui_types::coin::Coin;
 use sui_types::committee::{EpochId, ProtocolVersion};
 use sui_types::crypto::{
     default_hash, AggregateAuthoritySignature, AuthorityKeyPair, AuthoritySignInfo, NetworkKeyPair,
@@ -100,12 +102,12 @@ use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::epoch::epoch_metrics::EpochMetrics;
-use crate::event_handler::EventHandler;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::signature_verifier::SignatureVerifierMetrics;
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::StateAccumulator;
+use crate::subscription_handler::SubscriptionHandler;
 use crate::{transaction_input_checker, transaction_manager::TransactionManager};
 
 #[cfg(test)]
@@ -474,7 +476,7 @@ pub struct AuthorityState {
 
     indexes: Option<Arc<IndexStore>>,
 
-    pub event_handler: Arc<EventHandler>,
+    pub subscription_handler: Arc<SubscriptionHandler>,
     pub(crate) checkpoint_store: Arc<CheckpointStore>,
 
     committee_store: Arc<CommitteeStore>,
@@ -1287,6 +1289,18 @@ impl AuthorityState {
             .cloned()
             .collect::<HashMap<_, _>>();
 
+        // Every coin owned by an address before the transaction is removed from the balance of its
+        // owner, and added back to the balance of its owner after the transaction, if any.
+        let mut coin_balance_changes = vec![];
+        for (id, old_version) in effects.modified_at_versions() {
+            let Some(old_object) = self.database.get_object_by_key(id, *old_version)? else {
+                continue;
+            };
+            if let Some((key, balance)) = Self::owned_coin_balance(&old_object)? {
+                coin_balance_changes.push((key, -(balance as i128), -1));
+            }
+        }
+
         let mut deleted_owners = vec![];
         let mut deleted_dynamic_fields = vec![];
         for (id, _, _) in effects.deleted() {
@@ -1336,6 +1350,10 @@ impl AuthorityState {
                         continue;
                     };
 
+                    if let Some((key, balance)) = Self::owned_coin_balance(&o)? {
+                        coin_balance_changes.push((key, balance as i128, 1));
+                    }
+
                     let type_ = o
                         .type_()
                         .map(|type_| ObjectType::Struct(type_.clone()))
@@ -1372,9 +1390,33 @@ impl AuthorityState {
             deleted_dynamic_fields,
             new_owners,
             new_dynamic_fields,
+            coin_balance_changes,
         })
     }
 
+    /// The balance of `object` if it is a coin owned by an address, along with its owner and coin
+    /// type.
+    fn owned_coin_balance(object: &Object) -> SuiResult<Option<(CoinBalanceKey, u64)>> {
+        let Owner::AddressOwner(owner) = object.owner else {
+            return Ok(None);
+        };
+        let Some(balance) = Coin::extract_balance_if_coin(object).map_err(|e| {
+            SuiError::ObjectDeserializationError {
+                error: e.to_string(),
+            }
+        })? else {
+            return Ok(None);
+        };
+        // Coins have a single type parameter, the type of the coin.
+        let Some(coin_type) = object
+            .type_()
+            .and_then(|type_| type_.type_params().into_iter().next())
+        else {
+            return Ok(None);
+        };
+        Ok(Some(((owner, coin_type), balance)))
+    }
+
     fn try_create_dynamic_field_info(
         &self,
         o: &Object,
@@ -1475,8 +1517,9 @@ impl AuthorityState {
 
             // Emit events
             if res.is_ok() {
-                self.event_handler
-                    .process_events(
+                self.subscription_handler
+                    .process_tx(
+                        certificate.data().transaction_data(),
                         &effects.clone().try_into()?,
                         &SuiTransactionBlockEvents::try_from(
                             events.clone(),
@@ -1659,7 +1702,9 @@ impl AuthorityState {
         let _objects_pruner = AuthorityStorePruner::new(
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
+            indexes.clone(),
             store.objects_lock_table.clone(),
+            store.events_lock_table.clone(),
             pruning_config,
             epoch_store.epoch_start_state().epoch_duration_ms(),
             prometheus_registry,
@@ -1670,7 +1715,7 @@ impl AuthorityState {
             epoch_store: ArcSwap::new(epoch_store.clone()),
             database: store,
             indexes,
-            event_handler: Arc::new(EventHandler::default()),
+            subscription_handler: Arc::new(SubscriptionHandler::default()),
             checkpoint_store,
             committee_store,
             transaction_manager,
@@ -1693,6 +1738,7 @@ impl AuthorityState {
         state
             .create_owner_index_if_empty(genesis_objects, &epoch_store)
             .expect("Error indexing genesis objects.");
+        state.spawn_coin_balance_index_backfill();
 
         state
     }
@@ -1835,9 +1881,123 @@ impl AuthorityState {
             deleted_dynamic_fields: vec![],
             new_owners,
             new_dynamic_fields,
+            coin_balance_changes: vec![],
         })
     }
 
+    /// Build the coin balance index from the owner index in the background, for new databases and
+    /// for databases created before the balance index existed. Balances are computed from the
+    /// coins of their owner until the index is built.
+    fn spawn_coin_balance_index_backfill(&self) {
+        let Some(index_store) = self.indexes.clone() else {
+            return;
+        };
+        let database = self.database.clone();
+        tokio::task::spawn_blocking(move || {
+            match index_store.needs_coin_balance_backfill() {
+                Ok(false) => return,
+                Ok(true) => info!("Building coin balance index from owner index"),
+                Err(e) => {
+                    error!("Failed to read coin balance index: {e}");
+                    return;
+                }
+            }
+            let result = index_store.backfill_coin_balances(|info| {
+                // The version of the coin in the owner index is read, rather than the live
+                // version, as transactions which are not indexed yet are not counted.
+                let Some(object) = database.get_object_by_key(&info.object_id, info.version)? else {
+                    return Ok(None);
+                };
+                if object.owner != info.owner {
+                    return Ok(None);
+                }
+                Coin::extract_balance_if_coin(&object).map_err(|e| {
+                    SuiError::ObjectDeserializationError {
+                        error: e.to_string(),
+                    }
+                })
+            });
+            match result {
+                Ok(()) => info!("Built coin balance index"),
+                Err(e) => error!("Failed to build coin balance index: {e}"),
+            }
+        });
+    }
+
+    /// Total balance and number of the coins of type `coin_type` owned by `owner`.
+    pub fn get_coin_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: TypeTag,
+    ) -> SuiResult<CoinBalance> {
+        if let Some(balance) = self
+            .get_indexes()?
+            .get_coin_balance(owner, coin_type.clone())?
+        {
+            return Ok(balance);
+        }
+        Ok(self
+            .sum_coin_balances(owner, Some(&coin_type))?
+            .remove(&coin_type)
+            .unwrap_or_default())
+    }
+
+    /// Total balance and number of the coins owned by `owner`, for every coin type it owns.
+    pub fn get_coin_balances(&self, owner: SuiAddress) -> SuiResult<Vec<(TypeTag, CoinBalance)>> {
+        if let Some(balances) = self.get_indexes()?.get_coin_balances(owner)? {
+            return Ok(balances);
+        }
+        Ok(self.sum_coin_balances(owner, None)?.into_iter().collect())
+    }
+
+    /// Sum up the coins of `owner` in the owner index, of type `coin_type` if provided, while the
+    /// coin balance index is being built.
+    fn sum_coin_balances(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<&TypeTag>,
+    ) -> SuiResult<BTreeMap<TypeTag, CoinBalance>> {
+        let coins = self
+            .get_owner_objects_iterator(owner, None, None)?
+            .filter(|info| match coin_type {
+                Some(coin_type) => info.type_.is_coin_t(coin_type),
+                None => info.type_.is_coin(),
+            })
+            .map(|info| ObjectKey(info.object_id, info.version))
+            .collect::<Vec<_>>();
+
+        let mut balances: BTreeMap<TypeTag, CoinBalance> = BTreeMap::new();
+        for (object, key) in self
+            .database
+            .multi_get_object_by_key(&coins)?
+            .into_iter()
+            .zip(&coins)
+        {
+            let object = object.ok_or(UserInputError::ObjectNotFound {
+                object_id: key.0,
+                version: Some(key.1),
+            })?;
+            let Some(value) = Coin::extract_balance_if_coin(&object).map_err(|e| {
+                SuiError::ObjectDeserializationError {
+                    error: e.to_string(),
+                }
+            })? else {
+                continue;
+            };
+            // Coins have a single type parameter, the type of the coin.
+            let Some(coin_type) = object
+                .type_()
+                .and_then(|type_| type_.type_params().into_iter().next())
+            else {
+                continue;
+            };
+            let balance = balances.entry(coin_type).or_default();
+            balance.total_balance += value as u128;
+            balance.coin_count += 1;
+        }
+        Ok(balances)
+    }
+
     pub async fn reconfigure(
         &self,
         cur_epoch_store: &AuthorityPerEpochStore,
@@ -2183,6 +2343,37 @@ impl AuthorityState {
         }
     }
 
+    /// Read the latest version of an object as of `checkpoint`, i.e. the version written by the
+    /// last transaction of checkpoints up to and including `checkpoint` which changed it.
+    pub async fn get_object_read_at_checkpoint(
+        &self,
+        object_id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<PastObjectRead> {
+        let indexes = self.get_indexes()?;
+        match indexes.get_object_version_at_checkpoint(*object_id, checkpoint)? {
+            None => Ok(PastObjectRead::ObjectNotExists(*object_id)),
+            Some((obj_ref, None)) => Ok(PastObjectRead::ObjectDeleted(obj_ref)),
+            Some(((_, version, _), Some(_))) => self.get_past_object_read(object_id, version).await,
+        }
+    }
+
+    /// The objects owned by `owner` as of `checkpoint`, along with the version they had then.
+    pub fn get_owner_objects_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        checkpoint: CheckpointSequenceNumber,
+        // If `Some`, the query will start from the next item after the specified cursor
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<Vec<ObjectRef>> {
+        if let Some(indexes) = &self.indexes {
+            indexes.get_owner_objects_at_checkpoint(owner, checkpoint, cursor, limit)
+        } else {
+            Err(SuiError::IndexStoreNotAvailable)
+        }
+    }
+
     fn get_owner_at_version(
         &self,
         object_id: &ObjectID,
@@ -2301,7 +2492,7 @@ impl AuthorityState {
         let effects = self.database.get_executed_effects(&digest)?;
         match (transaction, effects) {
             (Some(transaction), Some(effects)) => Ok((transaction, effects)),
-            _ => Err(anyhow!(SuiError::TransactionNotFound { digest })),
+            _ => Err(anyhow!(self.transaction_not_found(digest)?)),
         }
     }
 
@@ -2309,16 +2500,42 @@ impl AuthorityState {
         &self,
         digest: TransactionDigest,
     ) -> Result<VerifiedTransaction, anyhow::Error> {
-        let transaction = self.database.get_transaction_block(&digest)?;
-        transaction.ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest }))
+        match self.database.get_transaction_block(&digest)? {
+            Some(transaction) => Ok(transaction),
+            None => Err(anyhow!(self.transaction_not_found(digest)?)),
+        }
     }
 
     pub fn get_executed_effects(
         &self,
         digest: TransactionDigest,
     ) -> Result<TransactionEffects, anyhow::Error> {
-        let effects = self.database.get_executed_effects(&digest)?;
-        effects.ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest }))
+        match self.database.get_executed_effects(&digest)? {
+            Some(effects) => Ok(effects),
+            None => Err(anyhow!(self.transaction_not_found(digest)?)),
+        }
+    }
+
+    /// The error for an executed transaction that is missing from the store, which tells apart
+    /// transactions whose history was pruned from transactions that were never executed.
+    pub fn transaction_not_found(&self, digest: TransactionDigest) -> SuiResult<SuiError> {
+        let lowest_available_checkpoint = self.get_lowest_available_checkpoint()?;
+        Ok(match self.database.get_transaction_checkpoint(&digest)? {
+            Some((_, checkpoint)) if checkpoint < lowest_available_checkpoint => {
+                SuiError::TransactionPruned {
+                    digest,
+                    lowest_available_checkpoint,
+                }
+            }
+            _ => SuiError::TransactionNotFound { digest },
+        })
+    }
+
+    /// The lowest checkpoint whose contents, transactions, effects and events are available.
+    pub fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+        Ok(self
+            .get_checkpoint_store()
+            .get_lowest_available_checkpoint()?)
     }
 
     pub fn multi_get_executed_transactions(
@@ -2367,7 +2584,7 @@ impl AuthorityState {
             .ok_or(SuiError::TransactionEventsNotFound { digest: *digest })
     }
 
-    fn get_indexes(&self) -> SuiResult<Arc<IndexStore>> {
+    pub fn get_indexes(&self) -> SuiResult<Arc<IndexStore>> {
         match &self.indexes {
             Some(i) => Ok(i.clone()),
             None => Err(SuiError::UnsupportedFeatureError {
@@ -2401,8 +2618,95 @@ impl AuthorityState {
                 return Ok(iter.take(limit.unwrap_or(usize::max_value())).collect());
             }
         }
+        if let Some(TransactionFilter::CheckpointRange {
+            start_checkpoint,
+            end_checkpoint,
+        }) = filter
+        {
+            return self.get_transactions_in_checkpoint_range(
+                start_checkpoint,
+                end_checkpoint,
+                cursor,
+                limit,
+                reverse,
+            );
+        }
         self.get_indexes()?
-            .get_transactions(filter, cursor, limit, reverse)
+            .get_transactions(filter, cursor, limit, reverse, |digest| {
+                Ok(self
+                    .database
+                    .get_transaction_checkpoint(digest)?
+                    .map(|(_, checkpoint)| checkpoint))
+            })
+    }
+
+    fn get_transactions_in_checkpoint_range(
+        &self,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        let end_checkpoint = min(
+            end_checkpoint,
+            self.get_latest_checkpoint_sequence_number()?,
+        );
+        // Resume from the checkpoint containing the cursor rather than the start of the range.
+        let cursor_checkpoint = match cursor {
+            Some(cursor) => Some(
+                self.database
+                    .get_transaction_checkpoint(&cursor)?
+                    .ok_or_else(|| anyhow!("Transaction [{cursor:?}] not found in a checkpoint."))?
+                    .1,
+            ),
+            None => None,
+        };
+        // Otherwise the cursor would never be reached, and the page would look like the end of
+        // the results.
+        if let (Some(cursor), Some(checkpoint)) = (cursor, cursor_checkpoint) {
+            if checkpoint < start_checkpoint || checkpoint > end_checkpoint {
+                return Err(
+                    SuiError::from(UserInputError::CursorOutsideCheckpointRange {
+                        cursor,
+                        start_checkpoint,
+                        end_checkpoint,
+                    })
+                    .into(),
+                );
+            }
+        }
+        let checkpoints: Box<dyn Iterator<Item = CheckpointSequenceNumber>> = if reverse {
+            let end = cursor_checkpoint.map_or(end_checkpoint, |c| min(c, end_checkpoint));
+            Box::new((start_checkpoint..=end).rev())
+        } else {
+            let start = cursor_checkpoint.map_or(start_checkpoint, |c| max(c, start_checkpoint));
+            Box::new(start..=end_checkpoint)
+        };
+
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut digests = vec![];
+        let mut past_cursor = cursor.is_none();
+        for checkpoint in checkpoints {
+            let contents = self.get_checkpoint_contents_by_sequence_number(checkpoint)?;
+            let transactions: Vec<_> = contents.iter().map(|c| c.transaction).collect();
+            let transactions: Box<dyn Iterator<Item = TransactionDigest>> = if reverse {
+                Box::new(transactions.into_iter().rev())
+            } else {
+                Box::new(transactions.into_iter())
+            };
+            for digest in transactions {
+                if digests.len() >= limit {
+                    return Ok(digests);
+                }
+                if past_cursor {
+                    digests.push(digest);
+                } else {
+                    past_cursor = Some(digest) == cursor;
+                }
+            }
+        }
+        Ok(digests)
     }
 
     fn get_checkpoint_store(&self) -> Arc<CheckpointStore> {
@@ -2494,6 +2798,13 @@ impl AuthorityState {
         &self,
         sequence_number: CheckpointSequenceNumber,
     ) -> Result<CheckpointContents, anyhow::Error> {
+        let lowest_available_checkpoint = self.get_lowest_available_checkpoint()?;
+        if sequence_number < lowest_available_checkpoint {
+            return Err(anyhow!(SuiError::CheckpointContentsPruned {
+                sequence_number,
+                lowest_available_checkpoint,
+            }));
+        }
         let verified_checkpoint = self
             .get_checkpoint_store()
             .get_checkpoint_by_sequence_number(sequence_number)?;
@@ -2546,14 +2857,27 @@ impl AuthorityState {
         let checkpoint_contents = self
             .get_checkpoint_store()
             .multi_get_checkpoint_content(checkpoint_contents_digest.as_slice())?;
-        let contents: Vec<CheckpointContents> = checkpoint_contents.into_iter().flatten().collect();
+        let lowest_available_checkpoint = self.get_lowest_available_checkpoint()?;
 
         let mut checkpoints: Vec<Checkpoint> = vec![];
 
         for (summary_and_sig, content) in checkpoint_summaries_and_signatures
             .into_iter()
-            .zip(contents.into_iter())
+            .zip(checkpoint_contents.into_iter())
         {
+            let sequence_number = summary_and_sig.0.sequence_number;
+            let Some(content) = content else {
+                if sequence_number < lowest_available_checkpoint {
+                    return Err(anyhow!(SuiError::CheckpointContentsPruned {
+                        sequence_number,
+                        lowest_available_checkpoint,
+                    }));
+                }
+                return Err(anyhow!(
+                    "Checkpoint contents not found for sequence number {}",
+                    sequence_number
+                ));
+            };
             checkpoints.push(Checkpoint::from((
                 summary_and_sig.0,
                 content,
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::iter;
 use std::ops::Not;
 use std::path::Path;
@@ -113,6 +114,9 @@ pub struct AuthorityStore {
     /// Guards reference count updates to `indirect_move_objects` table
     pub(crate) objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
 
+    /// Guards updates to `event_references` against the removal of the events they refer to
+    pub(crate) events_lock_table: Arc<RwLockTable<TransactionEventsDigest>>,
+
     indirect_objects_threshold: usize,
 
     /// Whether to enable expensive SUI conservation check at epoch boundaries.
@@ -201,6 +205,7 @@ impl AuthorityStore {
                 NotifyRead::<EpochId, (CheckpointSequenceNumber, Accumulator)>::new(),
             execution_lock: RwLock::new(epoch),
             objects_lock_table: Arc::new(RwLockTable::new(NUM_SHARDS)),
+            events_lock_table: Arc::new(RwLockTable::new(NUM_SHARDS)),
             indirect_objects_threshold,
             enable_epoch_sui_conservation_check,
             metrics: AuthorityStoreMetrics::new(registry),
@@ -240,6 +245,13 @@ impl AuthorityStore {
                 .enumerate()
                 .map(|(i, e)| ((event_digests, i), e));
             store.perpetual_tables.events.multi_insert(events).unwrap();
+            if let Some(events_digest) = genesis.effects().events_digest() {
+                store
+                    .perpetual_tables
+                    .event_references
+                    .insert(&(*events_digest, *transaction.digest()), &())
+                    .unwrap();
+            }
             // When we are opening the db table, the only time when it's safe to
             // check SUI conservation is at genesis. Otherwise we may be in the middle of
             // an epoch and the SUI conservation check will fail. This also initialize
@@ -762,6 +774,10 @@ impl AuthorityStore {
         let _locks = self
             .acquire_read_locks_for_indirect_objects(&inner_temporary_store)
             .await;
+        let _events_locks = self
+            .events_lock_table
+            .acquire_read_locks(effects.events_digest().into_iter().copied().collect())
+            .await;
         // Extract the new state from the execution
         let mut write_batch = self.perpetual_tables.transactions.batch();
 
@@ -787,6 +803,12 @@ impl AuthorityStore {
                 &self.perpetual_tables.executed_effects,
                 [(transaction_digest, effects_digest)],
             )?;
+        if let Some(events_digest) = effects.events_digest() {
+            write_batch.insert_batch(
+                &self.perpetual_tables.event_references,
+                [((*events_digest, *transaction_digest), ())],
+            )?;
+        }
 
         // test crashing before writing the batch
         fail_point_async!("crash");
@@ -1226,11 +1248,25 @@ impl AuthorityStore {
             &self.perpetual_tables.executed_effects,
             iter::once(tx_digest),
         )?;
+        let _events_locks = self
+            .events_lock_table
+            .acquire_locks(effects.events_digest().into_iter().copied())
+            .await;
         if let Some(events_digest) = effects.events_digest() {
-            write_batch.delete_range(
-                &self.perpetual_tables.events,
-                &(*events_digest, usize::MIN),
-                &(*events_digest, usize::MAX),
+            // Other transactions may have emitted the same events.
+            if !self
+                .perpetual_tables
+                .events_referenced_by_others(events_digest, &HashSet::from([*tx_digest]))?
+            {
+                write_batch.delete_range(
+                    &self.perpetual_tables.events,
+                    &(*events_digest, usize::MIN),
+                    &(*events_digest, usize::MAX),
+                )?;
+            }
+            write_batch.delete_batch(
+                &self.perpetual_tables.event_references,
+                iter::once((*events_digest, *tx_digest)),
             )?;
         }
 
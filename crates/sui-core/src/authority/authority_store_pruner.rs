@@ -9,12 +9,17 @@ use prometheus::{
     Registry,
 };
 use std::cmp::{max, min};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{sync::Arc, time::Duration};
 use sui_config::node::AuthorityStorePruningConfig;
+use sui_storage::indexes::PrunedTransaction;
 use sui_storage::mutex_table::RwLockTable;
-use sui_types::messages::{TransactionEffects, TransactionEffectsAPI};
-use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_storage::IndexStore;
+use sui_types::digests::TransactionEventsDigest;
+use sui_types::messages::{
+    TransactionDataAPI, TransactionEffects, TransactionEffectsAPI, TransactionEvents,
+};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
 use sui_types::{
     base_types::{ObjectID, VersionNumber},
     storage::ObjectKey,
@@ -33,6 +38,8 @@ pub struct AuthorityStorePruner {
 pub struct AuthorityStorePruningMetrics {
     pub last_pruned_checkpoint: IntGauge,
     pub num_pruned_objects: IntCounter,
+    pub last_pruned_history_checkpoint: IntGauge,
+    pub num_pruned_transactions: IntCounter,
 }
 
 impl AuthorityStorePruningMetrics {
@@ -50,6 +57,18 @@ impl AuthorityStorePruningMetrics {
                 registry
             )
             .unwrap(),
+            last_pruned_history_checkpoint: register_int_gauge_with_registry!(
+                "last_pruned_history_checkpoint",
+                "Last checkpoint whose transactions, effects and events were pruned",
+                registry
+            )
+            .unwrap(),
+            num_pruned_transactions: register_int_counter_with_registry!(
+                "num_pruned_transactions",
+                "Number of transactions pruned along with their effects and events",
+                registry
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
@@ -162,10 +181,16 @@ impl AuthorityStorePruner {
             "Starting object pruning. Current epoch: {}. Latest pruned checkpoint: {}",
             current_epoch, checkpoint_number
         );
+        // The effects of checkpoints whose history was pruned are no longer available, which is
+        // only the case if history pruning ran while objects pruning was disabled.
+        let start = max(
+            checkpoint_number + 1,
+            checkpoint_store.get_lowest_available_checkpoint()?,
+        );
         let iter = checkpoint_store
             .certified_checkpoints
             .iter()
-            .skip_to(&(checkpoint_number + 1))?
+            .skip_to(&start)?
             .map(|(k, ckpt)| (k, ckpt.into_inner()));
 
         #[allow(clippy::explicit_counter_loop)]
@@ -231,12 +256,243 @@ impl AuthorityStorePruner {
         Ok(())
     }
 
+    /// Prunes the transactions, effects and events of `checkpoints`, along with their contents and
+    /// their entries in the indexes. `checkpoints` must directly follow the highest pruned
+    /// checkpoint, in order.
+    async fn prune_history(
+        checkpoints: Vec<VerifiedCheckpoint>,
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
+        indexes: Option<&Arc<IndexStore>>,
+        events_lock_table: &Arc<RwLockTable<TransactionEventsDigest>>,
+        metrics: Arc<AuthorityStorePruningMetrics>,
+    ) -> anyhow::Result<()> {
+        let _scope = monitored_scope("HistoryPruner");
+        let Some(last_checkpoint) = checkpoints.last().cloned() else {
+            return Ok(());
+        };
+
+        let mut contents_digests = vec![];
+        let mut execution_digests = vec![];
+        for checkpoint in &checkpoints {
+            // Contents may be missing if pruning was interrupted after they were removed.
+            if let Some(contents) =
+                checkpoint_store.get_checkpoint_contents(&checkpoint.content_digest)?
+            {
+                execution_digests.extend(contents.iter().copied());
+            }
+            contents_digests.push(checkpoint.content_digest);
+        }
+        let tx_digests: Vec<_> = execution_digests.iter().map(|d| d.transaction).collect();
+        let transactions = perpetual_db.transactions.multi_get(&tx_digests)?;
+        let effects = perpetual_db
+            .effects
+            .multi_get(execution_digests.iter().map(|d| d.effects))?;
+
+        // Transactions emitting identical events share them, so events are only removed along
+        // with the last transaction referencing them. Holding the locks prevents transactions
+        // being executed from referencing the events in the meantime.
+        let pruned_digests: HashSet<_> = tx_digests.iter().copied().collect();
+        let _locks = events_lock_table
+            .acquire_locks(
+                effects
+                    .iter()
+                    .flatten()
+                    .filter_map(|effects| effects.events_digest().copied()),
+            )
+            .await;
+
+        let mut pruned_transactions = vec![];
+        let mut wb = perpetual_db.transactions.batch();
+        for (transaction, effects) in transactions.into_iter().zip(effects) {
+            // Transactions may be missing if pruning was interrupted after they were removed, in
+            // which case they were already removed from the indexes.
+            let (Some(transaction), Some(effects)) = (transaction, effects) else {
+                continue;
+            };
+            let events = match effects.events_digest() {
+                Some(events_digest) => {
+                    let data: Vec<_> = perpetual_db
+                        .events
+                        .iter()
+                        .skip_to(&(*events_digest, 0))?
+                        .take_while(|((digest, _), _)| digest == events_digest)
+                        .map(|(_, event)| event)
+                        .collect();
+                    let reference = (*events_digest, *transaction.digest());
+                    // Events of transactions executed before references were recorded are kept,
+                    // as there is no telling which other transactions emitted them.
+                    if perpetual_db.event_references.contains_key(&reference)?
+                        && !perpetual_db
+                            .events_referenced_by_others(events_digest, &pruned_digests)?
+                    {
+                        wb.delete_range(
+                            &perpetual_db.events,
+                            &(*events_digest, 0),
+                            &(*events_digest, data.len()),
+                        )?;
+                    }
+                    wb.delete_batch(&perpetual_db.event_references, [reference])?;
+                    TransactionEvents { data }
+                }
+                None => TransactionEvents::default(),
+            };
+            if indexes.is_some() {
+                let tx_data = &transaction.data().intent_message().value;
+                pruned_transactions.push(PrunedTransaction {
+                    digest: *transaction.digest(),
+                    sender: tx_data.sender(),
+                    input_objects: tx_data
+                        .input_objects()?
+                        .iter()
+                        .map(|o| o.object_id())
+                        .collect(),
+                    mutated_objects: effects
+                        .all_changed_objects()
+                        .into_iter()
+                        .map(|(obj_ref, owner, _)| (*obj_ref, *owner))
+                        .collect(),
+                    move_functions: tx_data
+                        .move_calls()
+                        .into_iter()
+                        .map(|(package, module, function)| {
+                            (*package, module.to_owned(), function.to_owned())
+                        })
+                        .collect(),
+                    events,
+                });
+            }
+        }
+        wb.delete_batch(&perpetual_db.transactions, &tx_digests)?;
+        wb.delete_batch(&perpetual_db.executed_effects, &tx_digests)?;
+        wb.delete_batch(
+            &perpetual_db.effects,
+            execution_digests.iter().map(|d| d.effects),
+        )?;
+
+        // Indexes are pruned first, as the transactions are needed to find their entries.
+        if let Some(indexes) = indexes {
+            indexes.prune_transactions(&pruned_transactions)?;
+        }
+        wb.write()?;
+        checkpoint_store.prune_checkpoint_contents(contents_digests, &last_checkpoint)?;
+
+        metrics
+            .num_pruned_transactions
+            .inc_by(tx_digests.len() as u64);
+        metrics
+            .last_pruned_history_checkpoint
+            .set(*last_checkpoint.sequence_number() as i64);
+        Ok(())
+    }
+
+    /// Prunes the history of all checkpoints outside of the configured retention limits
+    async fn prune_history_for_eligible_checkpoints(
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
+        indexes: Option<&Arc<IndexStore>>,
+        events_lock_table: &Arc<RwLockTable<TransactionEventsDigest>>,
+        config: AuthorityStorePruningConfig,
+        metrics: Arc<AuthorityStorePruningMetrics>,
+    ) -> anyhow::Result<()> {
+        let (highest_executed_checkpoint, current_epoch) = checkpoint_store
+            .get_highest_executed_checkpoint()?
+            .map(|c| (c.sequence_number, c.epoch()))
+            .unwrap_or_default();
+        // Effects are needed by the objects pruner, so history is only pruned once the objects
+        // pruner is done with it, unless objects are not pruned at all.
+        let highest_prunable_checkpoint = if config.num_epochs_to_retain == u64::MAX {
+            highest_executed_checkpoint
+        } else {
+            min(
+                highest_executed_checkpoint,
+                perpetual_db.get_highest_pruned_checkpoint()?,
+            )
+        };
+        let start = checkpoint_store.get_lowest_available_checkpoint()?;
+        debug!(
+            "Starting history pruning. Current epoch: {}. Lowest available checkpoint: {}",
+            current_epoch, start
+        );
+
+        // Summaries are retained, so the transaction count of the last pruned checkpoint is known.
+        let mut network_total_transactions = match start.checked_sub(1) {
+            Some(seq) => checkpoint_store
+                .certified_checkpoints
+                .get(&seq)?
+                .map_or(0, |c| c.into_inner().network_total_transactions),
+            None => 0,
+        };
+        let mut batch = vec![];
+        let mut transactions_in_batch = 0;
+        for (_, checkpoint) in checkpoint_store
+            .certified_checkpoints
+            .iter()
+            .skip_to(&start)?
+        {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            // History of the current epoch is always retained.
+            let retained_by_epochs = config
+                .num_epochs_to_retain_for_history
+                .map_or(true, |epochs| {
+                    current_epoch < checkpoint.epoch() + max(epochs, 1)
+                });
+            let retained_by_checkpoints =
+                config
+                    .num_checkpoints_to_retain_for_history
+                    .map_or(true, |checkpoints| {
+                        highest_executed_checkpoint < *checkpoint.sequence_number() + checkpoints
+                    });
+            if (retained_by_epochs && retained_by_checkpoints)
+                || checkpoint.epoch() == current_epoch
+                || *checkpoint.sequence_number() > highest_prunable_checkpoint
+            {
+                break;
+            }
+
+            transactions_in_batch +=
+                checkpoint.network_total_transactions - network_total_transactions;
+            network_total_transactions = checkpoint.network_total_transactions;
+            batch.push(checkpoint);
+            if batch.len() >= config.max_checkpoints_in_batch
+                || transactions_in_batch as usize >= config.max_transactions_in_batch
+            {
+                Self::prune_history(
+                    std::mem::take(&mut batch),
+                    perpetual_db,
+                    checkpoint_store,
+                    indexes,
+                    events_lock_table,
+                    metrics.clone(),
+                )
+                .await?;
+                transactions_in_batch = 0;
+            }
+        }
+        Self::prune_history(
+            batch,
+            perpetual_db,
+            checkpoint_store,
+            indexes,
+            events_lock_table,
+            metrics,
+        )
+        .await?;
+        debug!(
+            "Finished history pruning. Lowest available checkpoint: {}",
+            checkpoint_store.get_lowest_available_checkpoint()?
+        );
+        Ok(())
+    }
+
     fn setup_objects_pruning(
         config: AuthorityStorePruningConfig,
         epoch_duration_ms: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
+        indexes: Option<Arc<IndexStore>>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
+        events_lock_table: Arc<RwLockTable<TransactionEventsDigest>>,
         metrics: Arc<AuthorityStorePruningMetrics>,
     ) -> Sender<()> {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
@@ -257,9 +513,16 @@ impl AuthorityStorePruner {
         tokio::task::spawn(async move {
             loop {
                 tokio::select! {
-                    _ = prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX => {
-                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone()).await {
-                            error!("Failed to prune objects: {:?}", err);
+                    _ = prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX || config.prunes_history() => {
+                        if config.num_epochs_to_retain != u64::MAX {
+                            if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone()).await {
+                                error!("Failed to prune objects: {:?}", err);
+                            }
+                        }
+                        if config.prunes_history() {
+                            if let Err(err) = Self::prune_history_for_eligible_checkpoints(&perpetual_db, &checkpoint_store, indexes.as_ref(), &events_lock_table, config, metrics.clone()).await {
+                                error!("Failed to prune history: {:?}", err);
+                            }
                         }
                     },
                     _ = &mut recv => break,
@@ -271,7 +534,9 @@ impl AuthorityStorePruner {
     pub fn new(
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
+        indexes: Option<Arc<IndexStore>>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
+        events_lock_table: Arc<RwLockTable<TransactionEventsDigest>>,
         pruning_config: AuthorityStorePruningConfig,
         epoch_duration_ms: u64,
         registry: &Registry,
@@ -282,13 +547,19 @@ impl AuthorityStorePruner {
                 epoch_duration_ms,
                 perpetual_db,
                 checkpoint_store,
+                indexes,
                 objects_lock_table,
+                events_lock_table,
                 AuthorityStorePruningMetrics::new(registry),
             ),
         }
     }
 }
 
+#[cfg(test)]
+#[path = "../unit_tests/authority_store_pruner_tests.rs"]
+mod authority_store_pruner_tests;
+
 #[cfg(test)]
 mod tests {
     use fs_extra::dir::get_size;
@@ -4,22 +4,26 @@
 use super::*;
 use crate::authority::authority_store::LockDetailsWrapper;
 use rocksdb::Options;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::storage::ObjectStore;
 use typed_store::metrics::SamplingInterval;
-use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
+use typed_store::rocks::util::{
+    empty_compaction_filter, is_ref_count_value, reference_count_merge_operator,
+};
 use typed_store::rocks::{
     optimized_for_high_throughput_options, read_size_from_env, DBBatch, DBMap, DBOptions,
-    MetricConf, ReadWriteOptions,
+    MetricConf, ReadWriteOptions, TypedStoreError,
 };
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
 use crate::authority::authority_store_types::{
-    MigratedStoreObjectPair, ObjectContentDigest, StoreData, StoreMoveObjectWrapper, StoreObject,
-    StoreObjectValue, StoreObjectWrapper,
+    get_store_object_pair, MigratedStoreObjectPair, ObjectContentDigest, StoreData,
+    StoreMoveObjectWrapper, StoreObject, StoreObjectPair, StoreObjectValue, StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use typed_store_derive::DBMapUtils;
@@ -87,10 +91,15 @@ pub struct AuthorityPerpetualTables {
     // Currently this is needed in the validator for returning events during process certificates.
     // We could potentially remove this if we decided not to provide events in the execution path.
     // TODO: Figure out what to do with this table in the long run.
-    // Also we need a pruning policy for this table. We can prune this table along with tx/effects.
+    // Events are pruned along with tx/effects, once no transaction in `event_references` emitted them.
     #[default_options_override_fn = "events_table_default_config"]
     pub(crate) events: DBMap<(TransactionEventsDigest, usize), Event>,
 
+    /// The transactions which emitted each set of events. Transactions emitting identical events
+    /// share their entries in the `events` table, so these can only be removed along with the last
+    /// of these transactions.
+    pub(crate) event_references: DBMap<(TransactionEventsDigest, TransactionDigest), ()>,
+
     /// When transaction is executed via checkpoint executor, we store association here
     /// TODO: Eventually be able to prune this table.
     pub(crate) executed_transactions_to_checkpoint:
@@ -264,6 +273,89 @@ impl AuthorityPerpetualTables {
         }
     }
 
+    /// Insert the latest versions of objects restored from a state snapshot, see
+    /// [crate::state_snapshot]. As for genesis objects, locks are initialized for all objects
+    /// which are not owned by another object.
+    pub(crate) fn insert_live_objects(
+        &self,
+        live_objects: &[LiveObject],
+        indirect_objects_threshold: usize,
+    ) -> SuiResult {
+        let mut store_objects = vec![];
+        let mut indirect_objects = vec![];
+        let mut locks = vec![];
+        for live_object in live_objects {
+            match live_object {
+                LiveObject::Normal(object) => {
+                    let object_ref = object.compute_object_reference();
+                    let StoreObjectPair(store_object, indirect_object) =
+                        get_store_object_pair(object.clone(), indirect_objects_threshold);
+                    store_objects.push((ObjectKey::from(object_ref), store_object));
+                    if let Some(indirect_object) = indirect_object {
+                        indirect_objects.push((indirect_object.inner().digest(), indirect_object));
+                    }
+                    if !object.is_child_object() {
+                        locks.push(object_ref);
+                    }
+                }
+                LiveObject::Wrapped(key) => {
+                    store_objects.push((*key, StoreObject::Wrapped.into()));
+                }
+            }
+        }
+
+        // Objects with the same contents share an indirect object, only the first of them needs
+        // a full merge and the others increment its ref count.
+        let existing_digests = self
+            .indirect_move_objects
+            .multi_get_raw_bytes(indirect_objects.iter().map(|(digest, _)| digest))?;
+        let mut seen_digests = HashSet::new();
+        let (new_indirect_objects, existing_indirect_objects): (Vec<_>, Vec<_>) = indirect_objects
+            .into_iter()
+            .zip(existing_digests)
+            .partition(|((digest, _), existing)| {
+                seen_digests.insert(*digest)
+                    && !matches!(existing, Some(value) if !is_ref_count_value(value))
+            });
+
+        let mut batch = self.objects.batch();
+        batch.insert_batch(&self.objects, store_objects)?;
+        if !new_indirect_objects.is_empty() {
+            batch.merge_batch(
+                &self.indirect_move_objects,
+                new_indirect_objects.into_iter().map(|(pair, _)| pair),
+            )?;
+        }
+        if !existing_indirect_objects.is_empty() {
+            batch.partial_merge_batch(
+                &self.indirect_move_objects,
+                existing_indirect_objects
+                    .into_iter()
+                    .map(|((digest, _), _)| (digest, 1_u64.to_le_bytes())),
+            )?;
+        }
+        batch.insert_batch(
+            &self.owned_object_transaction_locks,
+            locks.iter().map(|object_ref| (object_ref, None)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Whether transactions other than `excluded` emitted the events with `events_digest`.
+    pub(crate) fn events_referenced_by_others(
+        &self,
+        events_digest: &TransactionEventsDigest,
+        excluded: &HashSet<TransactionDigest>,
+    ) -> Result<bool, TypedStoreError> {
+        Ok(self
+            .event_references
+            .iter()
+            .skip_to(&(*events_digest, TransactionDigest::ZERO))?
+            .take_while(|((digest, _), _)| digest == events_digest)
+            .any(|((_, tx_digest), _)| !excluded.contains(&tx_digest)))
+    }
+
     pub fn checkpoint_db(&self, path: &Path) -> SuiResult {
         // This checkpoints the entire db and not just objects table
         self.objects
@@ -278,6 +370,7 @@ impl AuthorityPerpetualTables {
         self.owned_object_transaction_locks.clear()?;
         self.executed_effects.clear()?;
         self.events.clear()?;
+        self.event_references.clear()?;
         self.executed_transactions_to_checkpoint.clear()?;
         self.root_state_hash_by_epoch.clear()?;
         self.epoch_start_configuration.clear()?;
@@ -326,6 +419,7 @@ pub struct LiveSetIter<'a> {
     prev: Option<(ObjectKey, StoreObjectWrapper)>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LiveObject {
     Normal(Object),
     Wrapped(ObjectKey),
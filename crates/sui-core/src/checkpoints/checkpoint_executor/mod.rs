@@ -30,6 +30,7 @@ use mysten_metrics::{spawn_monitored_task, MonitoredFutureExt};
 use prometheus::Registry;
 use sui_config::node::CheckpointExecutorConfig;
 use sui_macros::{fail_point, fail_point_async};
+use sui_storage::IndexStore;
 use sui_types::message_envelope::Message;
 use sui_types::messages::VerifiedExecutableTransaction;
 use sui_types::{
@@ -68,6 +69,8 @@ pub struct CheckpointExecutor {
     authority_store: Arc<AuthorityStore>,
     tx_manager: Arc<TransactionManager>,
     accumulator: Arc<StateAccumulator>,
+    /// Indexes of the node, if it maintains any.
+    indexes: Option<Arc<IndexStore>>,
     config: CheckpointExecutorConfig,
     metrics: Arc<CheckpointExecutorMetrics>,
 }
@@ -79,6 +82,7 @@ impl CheckpointExecutor {
         authority_store: Arc<AuthorityStore>,
         tx_manager: Arc<TransactionManager>,
         accumulator: Arc<StateAccumulator>,
+        indexes: Option<Arc<IndexStore>>,
         config: CheckpointExecutorConfig,
         prometheus_registry: &Registry,
     ) -> Self {
@@ -88,6 +92,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            indexes,
             config,
             metrics: CheckpointExecutorMetrics::new(prometheus_registry),
         }
@@ -106,6 +111,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            indexes: None,
             config: Default::default(),
             metrics: CheckpointExecutorMetrics::new_for_tests(),
         }
@@ -174,7 +180,7 @@ impl CheckpointExecutor {
                 // be processed (added to FuturesOrdered) in seq_number order, using FuturesOrdered
                 // guarantees that we will also ratchet the watermarks in order.
                 Some(Ok(checkpoint)) = pending.next() => {
-                    self.process_executed_checkpoint(&checkpoint);
+                    self.process_executed_checkpoint(&checkpoint).await;
                     highest_executed = Some(checkpoint);
 
                     // Estimate TPS every 10k transactions or 30 sec
@@ -226,7 +232,7 @@ impl CheckpointExecutor {
 
     /// Post processing and plumbing after we executed a checkpoint. This function is guaranteed
     /// to be called in the order of checkpoint sequence number.
-    fn process_executed_checkpoint(&self, checkpoint: &VerifiedCheckpoint) {
+    async fn process_executed_checkpoint(&self, checkpoint: &VerifiedCheckpoint) {
         // Ensure that we are not skipping checkpoints at any point
         let seq = *checkpoint.sequence_number();
         if let Some(prev_highest) = self
@@ -245,12 +251,55 @@ impl CheckpointExecutor {
 
         fail_point!("highest-executed-checkpoint");
 
+        // The change epoch transaction of the last checkpoint of the epoch is only executed
+        // afterwards, the checkpoint is indexed once it is.
+        if checkpoint.end_of_epoch_data.is_none() {
+            self.index_checkpoint_objects(checkpoint).await;
+        }
         self.checkpoint_store
             .update_highest_executed_checkpoint(checkpoint)
             .unwrap();
         self.metrics.last_executed_checkpoint.set(seq as i64);
     }
 
+    /// Index the objects changed by an executed checkpoint, if the node maintains indexes.
+    /// Checkpoints must be indexed in order of sequence number, so indexing is retried until it
+    /// succeeds.
+    async fn index_checkpoint_objects(&self, checkpoint: &VerifiedCheckpoint) {
+        let Some(indexes) = &self.indexes else {
+            return;
+        };
+        while let Err(err) = self.try_index_checkpoint_objects(indexes, checkpoint) {
+            error!(
+                seq = checkpoint.sequence_number(),
+                "Error while indexing objects of checkpoint, will retry in 1s: {:?}", err
+            );
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            self.metrics.checkpoint_exec_errors.inc();
+        }
+    }
+
+    fn try_index_checkpoint_objects(
+        &self,
+        indexes: &IndexStore,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> SuiResult {
+        let tx_digests: Vec<_> = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .expect("Checkpoint contents should exist")
+            .iter()
+            .map(|digests| digests.transaction)
+            .collect();
+        let effects: Vec<_> = self
+            .authority_store
+            .multi_get_executed_effects(&tx_digests)?
+            .into_iter()
+            .map(|effects| effects.expect("Executed checkpoint must have effects"))
+            .collect();
+        indexes.index_checkpoint_objects(*checkpoint.sequence_number(), &effects)
+    }
+
     async fn schedule_synced_checkpoints(
         &self,
         pending: &mut CheckpointExecutionBuffer,
@@ -547,6 +596,7 @@ impl CheckpointExecutor {
                         effects,
                     )
                     .expect("Finalizing checkpoint cannot fail");
+                    self.index_checkpoint_objects(checkpoint).await;
 
                     self.accumulator
                         .accumulate_epoch(
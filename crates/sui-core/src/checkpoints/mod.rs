@@ -272,6 +272,45 @@ impl CheckpointStore {
         self.checkpoint_content.get(digest)
     }
 
+    /// Sequence number of the last checkpoint whose contents and transactions were pruned.
+    pub fn get_highest_pruned_checkpoint_seq_number(
+        &self,
+    ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
+        Ok(self
+            .watermarks
+            .get(&CheckpointWatermark::HighestPruned)?
+            .map(|(seq, _)| seq))
+    }
+
+    /// Sequence number of the lowest checkpoint whose contents and transactions are available on
+    /// this node.
+    pub fn get_lowest_available_checkpoint(
+        &self,
+    ) -> Result<CheckpointSequenceNumber, TypedStoreError> {
+        Ok(self
+            .get_highest_pruned_checkpoint_seq_number()?
+            .map_or(0, |seq| seq + 1))
+    }
+
+    /// Remove the contents of checkpoints up to `checkpoint` and mark it as the highest pruned
+    /// checkpoint. Summaries of pruned checkpoints are kept.
+    pub fn prune_checkpoint_contents(
+        &self,
+        contents_digests: impl IntoIterator<Item = CheckpointContentsDigest>,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), TypedStoreError> {
+        let mut batch = self.checkpoint_content.batch();
+        batch.delete_batch(&self.checkpoint_content, contents_digests)?;
+        batch.insert_batch(
+            &self.watermarks,
+            [(
+                CheckpointWatermark::HighestPruned,
+                (*checkpoint.sequence_number(), *checkpoint.digest()),
+            )],
+        )?;
+        batch.write()
+    }
+
     pub fn insert_certified_checkpoint(
         &self,
         checkpoint: &VerifiedCheckpoint,
@@ -416,6 +455,7 @@ pub enum CheckpointWatermark {
     HighestVerified,
     HighestSynced,
     HighestExecuted,
+    HighestPruned,
 }
 
 pub struct CheckpointBuilder {
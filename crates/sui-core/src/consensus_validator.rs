@@ -1,9 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use eyre::WrapErr;
+use eyre::{eyre, WrapErr};
 use mysten_metrics::monitored_scope;
-use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
+    IntCounterVec, Registry,
+};
 use std::sync::Arc;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
@@ -11,6 +14,7 @@ use crate::transaction_manager::TransactionManager;
 use async_trait::async_trait;
 use narwhal_types::BatchAPI;
 use narwhal_worker::TransactionValidator;
+use sui_types::error::SuiError;
 use sui_types::messages::{ConsensusTransaction, ConsensusTransactionKind};
 use tap::TapFallible;
 use tokio::runtime::Handle;
@@ -47,6 +51,84 @@ fn tx_from_bytes(tx: &[u8]) -> Result<ConsensusTransaction, eyre::Report> {
         .wrap_err("Malformed transaction (failed to deserialize)")
 }
 
+/// Why a batch from another validator was rejected, used as the label of the rejection metric.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BatchRejection {
+    Malformed,
+    Oversize,
+    WrongEpoch,
+    InvalidSignature,
+}
+
+impl BatchRejection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BatchRejection::Malformed => "malformed",
+            BatchRejection::Oversize => "oversize",
+            BatchRejection::WrongEpoch => "wrong_epoch",
+            BatchRejection::InvalidSignature => "invalid_signature",
+        }
+    }
+}
+
+impl SuiTxValidator {
+    fn reject(&self, reason: BatchRejection, err: eyre::Report) -> eyre::Report {
+        warn!(reason = reason.as_str(), "Rejecting batch: {err:#}");
+        self.metrics
+            .batches_rejected
+            .with_label_values(&[reason.as_str()])
+            .inc();
+        err
+    }
+
+    /// Checks that do not need signature verification: the epoch of certificates and checkpoint
+    /// signatures, and the size of user transactions, which are bounded the same way as when they
+    /// are submitted to this validator.
+    fn check_transaction(&self, tx: &ConsensusTransaction) -> Result<(), eyre::Report> {
+        let epoch = self.epoch_store.epoch();
+        match &tx.kind {
+            ConsensusTransactionKind::UserTransaction(certificate) => {
+                if certificate.epoch() != epoch {
+                    return Err(self.reject(
+                        BatchRejection::WrongEpoch,
+                        eyre!(
+                            "Certificate {} is from epoch {}, expected {epoch}",
+                            certificate.digest(),
+                            certificate.epoch()
+                        ),
+                    ));
+                }
+                certificate
+                    .data()
+                    .check_size(self.epoch_store.protocol_config())
+                    .map_err(|e| {
+                        let reason = match e {
+                            SuiError::TransactionSerializationError { .. } => {
+                                BatchRejection::Malformed
+                            }
+                            _ => BatchRejection::Oversize,
+                        };
+                        self.reject(reason, eyre!("Certificate {}: {e}", certificate.digest()))
+                    })?;
+            }
+            ConsensusTransactionKind::CheckpointSignature(signature) => {
+                if signature.summary.epoch() != epoch {
+                    return Err(self.reject(
+                        BatchRejection::WrongEpoch,
+                        eyre!(
+                            "Checkpoint signature is from epoch {}, expected {epoch}",
+                            signature.summary.epoch()
+                        ),
+                    ));
+                }
+            }
+            ConsensusTransactionKind::EndOfPublish(_)
+            | ConsensusTransactionKind::CapabilityNotification(_) => {}
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl TransactionValidator for SuiTxValidator {
     type Error = eyre::Report;
@@ -62,7 +144,11 @@ impl TransactionValidator for SuiTxValidator {
             .transactions()
             .iter()
             .map(|tx| tx_from_bytes(tx))
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| self.reject(BatchRejection::Malformed, e))?;
+        for tx in &txs {
+            self.check_transaction(tx)?;
+        }
 
         let mut cert_batch = Vec::new();
         let mut ckpt_batch = Vec::new();
@@ -97,7 +183,8 @@ impl TransactionValidator for SuiTxValidator {
                     .tap_err(|e| warn!("batch verification error: {}", e))
                     .wrap_err("Malformed batch (failed to verify)")
             })
-            .await??;
+            .await?
+            .map_err(|e| self.reject(BatchRejection::InvalidSignature, e))?;
         self.metrics
             .certificate_signatures_verified
             .inc_by(cert_count as u64);
@@ -120,6 +207,7 @@ impl TransactionValidator for SuiTxValidator {
 pub struct SuiTxValidatorMetrics {
     certificate_signatures_verified: IntCounter,
     checkpoint_signatures_verified: IntCounter,
+    batches_rejected: IntCounterVec,
 }
 
 impl SuiTxValidatorMetrics {
@@ -137,6 +225,13 @@ impl SuiTxValidatorMetrics {
                 registry
             )
             .unwrap(),
+            batches_rejected: register_int_counter_vec_with_registry!(
+                "narwhal_batches_rejected",
+                "Number of batches rejected by the narwhal batch verifier, by reason",
+                &["reason"],
+                registry
+            )
+            .unwrap(),
         })
     }
 }
@@ -156,6 +251,7 @@ mod tests {
     };
 
     use sui_macros::sim_test;
+    use sui_protocol_config::ProtocolConfig;
     use sui_types::crypto::Ed25519SuiSignature;
     use sui_types::object::Object;
     #[sim_test]
@@ -206,6 +302,19 @@ mod tests {
         let res_batch = validator.validate_batch(&batch).await;
         assert!(res_batch.is_ok(), "{res_batch:?}");
 
+        let wrong_epoch_transaction_bytes: Vec<_> = certificates
+            .clone()
+            .into_iter()
+            .map(|mut cert| {
+                cert.auth_sig_mut_for_testing().epoch += 1;
+                bcs::to_bytes(&ConsensusTransaction::new_certificate_message(&name1, cert)).unwrap()
+            })
+            .collect();
+
+        let batch = Batch::new(wrong_epoch_transaction_bytes);
+        let res_batch = validator.validate_batch(&batch).await;
+        assert!(res_batch.is_err());
+
         let bogus_transaction_bytes: Vec<_> = certificates
             .into_iter()
             .map(|mut cert| {
@@ -221,5 +330,77 @@ mod tests {
         let batch = Batch::new(bogus_transaction_bytes);
         let res_batch = validator.validate_batch(&batch).await;
         assert!(res_batch.is_err());
+
+        let batch = Batch::new(vec![vec![0xff; 16]]);
+        let res_batch = validator.validate_batch(&batch).await;
+        assert!(res_batch.is_err());
+
+        for reason in ["wrong_epoch", "invalid_signature", "malformed"] {
+            assert_eq!(
+                validator
+                    .metrics
+                    .batches_rejected
+                    .with_label_values(&[reason])
+                    .get(),
+                1,
+                "{reason}"
+            );
+        }
+    }
+
+    #[sim_test]
+    async fn reject_oversize_transaction() {
+        // Any signed transaction is larger than this.
+        let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+            config.set_max_tx_size_bytes_for_testing(64);
+            config
+        });
+        let mut objects = test_gas_objects();
+        objects.push(Object::shared_for_testing());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = sui_config::builder::ConfigBuilder::new(&dir)
+            .with_objects(objects.clone())
+            .build();
+        let genesis = network_config.genesis;
+
+        let sec1 = network_config.validator_configs[0]
+            .protocol_key_pair()
+            .copy();
+        let name1: AuthorityName = sec1.public().into();
+
+        let state = init_state_with_objects_and_committee(objects, &genesis, &sec1).await;
+        let certificates = test_certificates(&state).await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        // A validator would have refused these transactions if they were submitted to it.
+        assert!(certificates[0]
+            .data()
+            .check_size(epoch_store.protocol_config())
+            .is_err());
+
+        let metrics = SuiTxValidatorMetrics::new(&Default::default());
+        let validator =
+            SuiTxValidator::new(epoch_store, state.transaction_manager().clone(), metrics);
+        let transaction_bytes: Vec<_> = certificates
+            .into_iter()
+            .map(|cert| {
+                bcs::to_bytes(&ConsensusTransaction::new_certificate_message(&name1, cert)).unwrap()
+            })
+            .collect();
+        let batch = Batch::new(transaction_bytes);
+        let res_batch = validator.validate_batch(&batch).await;
+        assert!(res_batch.is_err());
+
+        assert_eq!(
+            validator
+                .metrics
+                .batches_rejected
+                .with_label_values(&["oversize"])
+                .get(),
+            1
+        );
+        // The batch is rejected before its signatures are verified.
+        assert_eq!(validator.metrics.certificate_signatures_verified.get(), 0);
     }
 }
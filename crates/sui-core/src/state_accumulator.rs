@@ -303,17 +303,7 @@ impl StateAccumulator {
     pub fn accumulate_live_object_set(&self) -> Accumulator {
         let mut acc = Accumulator::default();
         for live_object in self.authority_store.iter_live_object_set() {
-            match live_object {
-                LiveObject::Normal(object) => {
-                    acc.insert(object.compute_object_reference().2);
-                }
-                LiveObject::Wrapped(key) => {
-                    acc.insert(
-                        bcs::to_bytes(&WrappedObject::new(key.0, key.1))
-                            .expect("Failed to serialize WrappedObject"),
-                    );
-                }
-            }
+            accumulate_live_object(&mut acc, &live_object);
         }
         acc
     }
@@ -336,3 +326,19 @@ impl StateAccumulator {
             .into())
     }
 }
+
+/// Insert a single object of the live object set into `acc`, exactly as
+/// [StateAccumulator::accumulate_live_object_set] does.
+pub fn accumulate_live_object(acc: &mut Accumulator, live_object: &LiveObject) {
+    match live_object {
+        LiveObject::Normal(object) => {
+            acc.insert(object.compute_object_reference().2);
+        }
+        LiveObject::Wrapped(key) => {
+            acc.insert(
+                bcs::to_bytes(&WrappedObject::new(key.0, key.1))
+                    .expect("Failed to serialize WrappedObject"),
+            );
+        }
+    }
+}
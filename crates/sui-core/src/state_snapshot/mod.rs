@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! State snapshots of the live object set at the end of an epoch.
+//!
+//! Unlike db checkpoints, which are raw copies of the RocksDB instances of a node, a state
+//! snapshot only contains the latest version of every live object (and a tombstone for every
+//! wrapped object), so it does not depend on the RocksDB version or the column family layout of
+//! the node which produced it. The [writer::StateSnapshotWriter] exports the live object set of
+//! an epoch from a node's perpetual tables and the [reader::StateSnapshotReader] restores it into
+//! an empty [AuthorityPerpetualTables]. Snapshots are stored in any of the backends supported by
+//! [sui_storage::object_store::ObjectStoreConfig], laid out as follows:
+//! ```text
+//! <root>
+//!  ├── epoch_0
+//!  │    ├── MANIFEST
+//!  │    ├── 0.obj
+//!  │    ├── 1.obj
+//!  │    └── ...
+//!  └── epoch_1
+//!       └── ...
+//! ```
+//! Live objects are sorted by object ID and chunked into `*.obj` files, each encoded like the
+//! files of the checkpoint archive (see [sui_storage::archive]) with one [LiveObject] per blob.
+//!
+//! The MANIFEST of an epoch lists its object files along with their checksum, and commits to the
+//! whole live object set with its [ECMHLiveObjectSetDigest], i.e. the root state hash computed by
+//! [crate::state_accumulator] at the end of the epoch. It is encoded as:
+//! ```text
+//! ┌──────────────┬─────────────────────────────┬───────────────────┐
+//! │ magic <4 B>  │ BCS(SnapshotManifest) <var> │ checksum <32 B>   │
+//! └──────────────┴─────────────────────────────┴───────────────────┘
+//! ```
+//! The MANIFEST is uploaded once all object files are, and only after the live object set has
+//! been checked against the root state hash, so a snapshot without a MANIFEST is incomplete.
+
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use serde::{Deserialize, Serialize};
+use sui_storage::archive::{
+    compute_checksum, read_manifest_file, write_manifest_file, CHECKSUM_LENGTH,
+};
+use sui_types::base_types::EpochId;
+use sui_types::messages_checkpoint::{
+    CheckpointDigest, CheckpointSequenceNumber, ECMHLiveObjectSetDigest,
+};
+use typed_store::traits::Map;
+
+use crate::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+
+pub mod reader;
+pub mod writer;
+
+#[cfg(test)]
+mod tests;
+
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const EPOCH_DIR_PREFIX: &str = "epoch_";
+pub const OBJECT_FILE_SUFFIX: &str = "obj";
+
+pub const MANIFEST_FILE_MAGIC: u32 = 0x0005_7A7E;
+pub const OBJECT_FILE_MAGIC: u32 = 0x0000_0B1E;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectFileMetadata {
+    pub file_num: u64,
+    pub num_objects: u64,
+    pub checksum: [u8; CHECKSUM_LENGTH],
+}
+
+impl ObjectFileMetadata {
+    pub fn new(file_num: u64, num_objects: u64, bytes: &[u8]) -> Self {
+        Self {
+            file_num,
+            num_objects,
+            checksum: compute_checksum(bytes),
+        }
+    }
+
+    pub fn file_path(&self, epoch: EpochId) -> Path {
+        epoch_dir(epoch).child(format!("{}.{OBJECT_FILE_SUFFIX}", self.file_num))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotManifest {
+    V1(SnapshotManifestV1),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifestV1 {
+    pub epoch: EpochId,
+    pub last_checkpoint_of_epoch: CheckpointSequenceNumber,
+    /// Digest of the last checkpoint of the epoch, which the next epoch starts from.
+    pub last_checkpoint_digest: CheckpointDigest,
+    /// Root state hash of the epoch, committing to all the objects of the snapshot.
+    pub root_state_hash: ECMHLiveObjectSetDigest,
+    pub file_metadata: Vec<ObjectFileMetadata>,
+}
+
+impl SnapshotManifest {
+    pub fn epoch(&self) -> EpochId {
+        match self {
+            SnapshotManifest::V1(manifest) => manifest.epoch,
+        }
+    }
+
+    pub fn last_checkpoint_of_epoch(&self) -> CheckpointSequenceNumber {
+        match self {
+            SnapshotManifest::V1(manifest) => manifest.last_checkpoint_of_epoch,
+        }
+    }
+
+    pub fn last_checkpoint_digest(&self) -> CheckpointDigest {
+        match self {
+            SnapshotManifest::V1(manifest) => manifest.last_checkpoint_digest,
+        }
+    }
+
+    pub fn root_state_hash(&self) -> &ECMHLiveObjectSetDigest {
+        match self {
+            SnapshotManifest::V1(manifest) => &manifest.root_state_hash,
+        }
+    }
+
+    pub fn files(&self) -> &[ObjectFileMetadata] {
+        match self {
+            SnapshotManifest::V1(manifest) => &manifest.file_metadata,
+        }
+    }
+
+    pub fn num_objects(&self) -> u64 {
+        self.files().iter().map(|f| f.num_objects).sum()
+    }
+}
+
+pub fn epoch_dir(epoch: EpochId) -> Path {
+    Path::from(format!("{EPOCH_DIR_PREFIX}{epoch}"))
+}
+
+/// Read the MANIFEST of the snapshot of `epoch`, if the snapshot is complete.
+pub async fn read_manifest(
+    store: Arc<DynObjectStore>,
+    epoch: EpochId,
+) -> Result<Option<SnapshotManifest>> {
+    let Some(manifest) = read_manifest_file::<SnapshotManifest>(
+        store,
+        &epoch_dir(epoch).child(MANIFEST_FILENAME),
+        MANIFEST_FILE_MAGIC,
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+    ensure!(
+        manifest.epoch() == epoch,
+        "Snapshot MANIFEST of epoch {epoch} is for epoch {}",
+        manifest.epoch()
+    );
+    Ok(Some(manifest))
+}
+
+pub async fn write_manifest(manifest: &SnapshotManifest, store: Arc<DynObjectStore>) -> Result<()> {
+    write_manifest_file(
+        manifest,
+        &epoch_dir(manifest.epoch()).child(MANIFEST_FILENAME),
+        MANIFEST_FILE_MAGIC,
+        store,
+    )
+    .await
+}
+
+/// The root state hash recorded by the node at the end of `epoch`, along with the last checkpoint
+/// of the epoch.
+pub fn root_state_hash(
+    perpetual_db: &AuthorityPerpetualTables,
+    epoch: EpochId,
+) -> Result<Option<(CheckpointSequenceNumber, ECMHLiveObjectSetDigest)>> {
+    Ok(perpetual_db
+        .root_state_hash_by_epoch
+        .get(&epoch)?
+        .map(|(last_checkpoint_of_epoch, acc)| (last_checkpoint_of_epoch, acc.digest().into())))
+}
+
+/// Objects of the live object set must be strictly ordered by ID.
+fn ensure_ordered(previous: Option<&LiveObject>, next: &LiveObject) -> Result<()> {
+    if let Some(previous) = previous {
+        ensure!(
+            previous.object_id() < next.object_id(),
+            "Live objects are not sorted: {} is followed by {}",
+            previous.object_id(),
+            next.object_id()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,237 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bytes::Bytes;
+use object_store::DynObjectStore;
+use sui_storage::archive::{compute_checksum, decode_blobs};
+use sui_storage::object_store::util::put;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::EpochId;
+use sui_types::messages_checkpoint::{CheckpointCommitment, ECMHLiveObjectSetDigest};
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use tempfile::TempDir;
+use tracing::{debug, info};
+use typed_store::traits::Map;
+
+use super::{
+    ensure_ordered, read_manifest, ObjectFileMetadata, SnapshotManifest, OBJECT_FILE_MAGIC,
+};
+use crate::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::accumulate_live_object;
+
+/// Restores the live object set of a node from a state snapshot written by
+/// [super::writer::StateSnapshotWriter].
+pub struct StateSnapshotReader {
+    epoch: EpochId,
+    remote_store: Arc<DynObjectStore>,
+}
+
+impl StateSnapshotReader {
+    pub fn new(epoch: EpochId, remote_store_config: &ObjectStoreConfig) -> Result<Self> {
+        Ok(Self {
+            epoch,
+            remote_store: remote_store_config.make()?,
+        })
+    }
+
+    /// The MANIFEST of the snapshot, failing if the snapshot is missing or incomplete.
+    pub async fn manifest(&self) -> Result<SnapshotManifest> {
+        read_manifest(self.remote_store.clone(), self.epoch)
+            .await?
+            .ok_or_else(|| anyhow!("No complete state snapshot for epoch {}", self.epoch))
+    }
+
+    /// Restore the snapshot into the empty `perpetual_db`, leaving it ready to start the epoch
+    /// following the one of the snapshot. The last checkpoint of the epoch must already be in
+    /// `checkpoint_store`, e.g. synced from peers or from a checkpoint archive, as objects are
+    /// checked against the root state hash it commits to.
+    ///
+    /// Object files are downloaded into a staging directory under `staging_dir` and checked
+    /// before any object is inserted, so `perpetual_db` is left untouched if the snapshot doesn't
+    /// match the commitment. The staging directory is removed in any case.
+    pub async fn restore(
+        &self,
+        perpetual_db: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+        staging_dir: &Path,
+        indirect_objects_threshold: usize,
+    ) -> Result<SnapshotManifest> {
+        ensure!(
+            perpetual_db.database_is_empty()?,
+            "Cannot restore a state snapshot into a non empty database"
+        );
+        let manifest = self.manifest().await?;
+        let root_state_hash = self.committed_root_state_hash(&manifest, checkpoint_store)?;
+        info!(
+            epoch = self.epoch,
+            num_files = manifest.files().len(),
+            num_objects = manifest.num_objects(),
+            "Restoring state snapshot"
+        );
+
+        let staging_dir = TempDir::new_in(staging_dir)?;
+        let staging_store = ObjectStoreConfig::local(staging_dir.path()).make()?;
+        let acc = self
+            .stage_files(&manifest, staging_store.clone(), &root_state_hash)
+            .await?;
+        for file in manifest.files() {
+            let (_, live_objects) = self.read_file(staging_store.clone(), file).await?;
+            perpetual_db.insert_live_objects(&live_objects, indirect_objects_threshold)?;
+            debug!(
+                epoch = self.epoch,
+                file_num = file.file_num,
+                "Restored state snapshot file"
+            );
+        }
+        perpetual_db
+            .root_state_hash_by_epoch
+            .insert(&self.epoch, &(manifest.last_checkpoint_of_epoch(), acc))?;
+
+        let system_state = get_sui_system_state(perpetual_db)?;
+        ensure!(
+            system_state.epoch() == self.epoch + 1,
+            "Restored system state is at epoch {}, expected {}",
+            system_state.epoch(),
+            self.epoch + 1
+        );
+        let epoch_start_configuration = EpochStartConfiguration::new_v1(
+            system_state.into_epoch_start_state(),
+            manifest.last_checkpoint_digest(),
+        );
+        perpetual_db
+            .set_epoch_start_configuration(&epoch_start_configuration)
+            .await?;
+        info!(epoch = self.epoch, "Restored state snapshot");
+        Ok(manifest)
+    }
+
+    /// The root state hash committed to by the verified last checkpoint of the epoch, which must
+    /// be the checkpoint the MANIFEST claims to end the epoch with.
+    fn committed_root_state_hash(
+        &self,
+        manifest: &SnapshotManifest,
+        checkpoint_store: &CheckpointStore,
+    ) -> Result<ECMHLiveObjectSetDigest> {
+        let sequence_number = manifest.last_checkpoint_of_epoch();
+        let checkpoint = checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Checkpoint {sequence_number}, the last of epoch {}, must be synced before \
+                    restoring its state snapshot",
+                    self.epoch
+                )
+            })?;
+        ensure!(
+            checkpoint.epoch() == self.epoch
+                && *checkpoint.digest() == manifest.last_checkpoint_digest(),
+            "Snapshot MANIFEST doesn't match checkpoint {sequence_number}"
+        );
+        let root_state_hash = checkpoint
+            .end_of_epoch_data
+            .as_ref()
+            .ok_or_else(|| {
+                anyhow!("Checkpoint {sequence_number} is not the last checkpoint of an epoch")
+            })?
+            .epoch_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                CheckpointCommitment::ECMHLiveObjectSetDigest(digest) => Some(digest.clone()),
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Checkpoint {sequence_number} doesn't commit to the root state hash of epoch {}",
+                    self.epoch
+                )
+            })?;
+        ensure!(
+            manifest.root_state_hash() == &root_state_hash,
+            "Snapshot MANIFEST doesn't match the root state hash of epoch {}",
+            self.epoch
+        );
+        Ok(root_state_hash)
+    }
+
+    /// Copy the object files of the snapshot into `staging_store`, checking the objects against
+    /// `root_state_hash`.
+    async fn stage_files(
+        &self,
+        manifest: &SnapshotManifest,
+        staging_store: Arc<DynObjectStore>,
+        root_state_hash: &ECMHLiveObjectSetDigest,
+    ) -> Result<Accumulator> {
+        let mut acc = Accumulator::default();
+        let mut previous: Option<LiveObject> = None;
+        for file in manifest.files() {
+            let (bytes, live_objects) = self.read_file(self.remote_store.clone(), file).await?;
+            for live_object in live_objects {
+                ensure_ordered(previous.as_ref(), &live_object)?;
+                accumulate_live_object(&mut acc, &live_object);
+                previous = Some(live_object);
+            }
+            put(&file.file_path(self.epoch), bytes, staging_store.clone()).await?;
+        }
+        let staged_root_state_hash: ECMHLiveObjectSetDigest = acc.digest().into();
+        ensure!(
+            &staged_root_state_hash == root_state_hash,
+            "Snapshot objects don't match the root state hash of epoch {}",
+            self.epoch
+        );
+        Ok(acc)
+    }
+
+    /// Read the whole live object set of the snapshot in memory, checked against the root state
+    /// hash committed to by the last checkpoint of the epoch in `checkpoint_store`, as for
+    /// [Self::restore]. Meant for small networks, e.g. to seed the genesis of a local fork.
+    pub async fn read_live_objects(
+        &self,
+        checkpoint_store: &CheckpointStore,
+    ) -> Result<Vec<LiveObject>> {
+        let manifest = self.manifest().await?;
+        let root_state_hash = self.committed_root_state_hash(&manifest, checkpoint_store)?;
+        let mut acc = Accumulator::default();
+        let mut result: Vec<LiveObject> = Vec::with_capacity(manifest.num_objects() as usize);
+        for file in manifest.files() {
+            let (_, live_objects) = self.read_file(self.remote_store.clone(), file).await?;
+            for live_object in live_objects {
+                ensure_ordered(result.last(), &live_object)?;
+                accumulate_live_object(&mut acc, &live_object);
+                result.push(live_object);
+            }
+        }
+        let read_root_state_hash: ECMHLiveObjectSetDigest = acc.digest().into();
+        ensure!(
+            read_root_state_hash == root_state_hash,
+            "Snapshot objects don't match the root state hash of epoch {}",
+            self.epoch
+        );
+        Ok(result)
+    }
+
+    async fn read_file(
+        &self,
+        store: Arc<DynObjectStore>,
+        metadata: &ObjectFileMetadata,
+    ) -> Result<(Bytes, Vec<LiveObject>)> {
+        let path = metadata.file_path(self.epoch);
+        let bytes = store.get(&path).await?.bytes().await?;
+        ensure!(
+            compute_checksum(&bytes) == metadata.checksum,
+            "Checksum mismatch for state snapshot file {path}"
+        );
+        let live_objects: Vec<LiveObject> = decode_blobs(OBJECT_FILE_MAGIC, &bytes)
+            .with_context(|| format!("Failed to decode state snapshot file {path}"))?;
+        ensure!(
+            live_objects.len() as u64 == metadata.num_objects,
+            "Unexpected number of objects in state snapshot file {path}"
+        );
+        Ok((bytes, live_objects))
+    }
+}
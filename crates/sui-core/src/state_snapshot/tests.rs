@@ -0,0 +1,272 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use fastcrypto::traits::KeyPair;
+use sui_network::state_sync::test_utils::CommitteeFixture;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::committee::ProtocolVersion;
+use sui_types::gas::GasCostSummary;
+use sui_types::messages::{TransactionEffectsAPI, VerifiedExecutableTransaction};
+use sui_types::messages_checkpoint::{ECMHLiveObjectSetDigest, EndOfEpochData, VerifiedCheckpoint};
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+use tempfile::TempDir;
+
+use super::reader::StateSnapshotReader;
+use super::writer::StateSnapshotWriter;
+use super::*;
+use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
+use crate::authority::AuthorityState;
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::StateAccumulator;
+
+/// An authority at the end of epoch 0, along with the committee of the epoch and the last
+/// checkpoint of the epoch, committing to the root state hash of the authority.
+async fn end_of_epoch_authority(
+    checkpoint_store: &CheckpointStore,
+) -> (Arc<AuthorityState>, CommitteeFixture, VerifiedCheckpoint) {
+    let dir = TempDir::new().unwrap();
+    let network_config = sui_config::builder::ConfigBuilder::new(&dir).build();
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let keypair = network_config.validator_configs[0]
+        .protocol_key_pair()
+        .copy();
+    let state = AuthorityState::new_for_testing(
+        committee.committee().clone(),
+        &keypair,
+        None,
+        &network_config.genesis,
+    )
+    .await;
+
+    let (checkpoints, _, _) = committee.make_checkpoints(1, None);
+    let last_checkpoint_of_epoch = checkpoints.last().unwrap().sequence_number + 1;
+    // Execute the change epoch transaction, as the checkpoint executor would.
+    let epoch_store = state.epoch_store_for_testing().clone();
+    let (_, effects) = state
+        .create_and_execute_advance_epoch_tx(
+            &epoch_store,
+            &GasCostSummary::new(0, 0, 0, 0),
+            last_checkpoint_of_epoch,
+            0, // epoch_start_timestamp_ms
+        )
+        .await
+        .unwrap();
+    let transaction = state
+        .database
+        .get_transaction_block(effects.transaction_digest())
+        .unwrap()
+        .unwrap();
+    let executable = VerifiedExecutableTransaction::new_from_checkpoint(
+        transaction,
+        0,
+        last_checkpoint_of_epoch,
+    );
+    epoch_store
+        .acquire_shared_locks_from_effects(&executable, &effects, state.db())
+        .await
+        .unwrap();
+    state
+        .try_execute_immediately(&executable, &epoch_store)
+        .await
+        .unwrap();
+
+    let acc = StateAccumulator::new(state.database.clone()).accumulate_live_object_set();
+    let checkpoint = end_of_epoch_checkpoint(&committee, &checkpoints[0], acc.digest().into());
+    assert_eq!(checkpoint.sequence_number, last_checkpoint_of_epoch);
+    checkpoint_store
+        .insert_verified_checkpoint(checkpoint.clone())
+        .unwrap();
+    state
+        .database
+        .perpetual_tables
+        .root_state_hash_by_epoch
+        .insert(&0, &(last_checkpoint_of_epoch, acc))
+        .unwrap();
+    (state, committee, checkpoint)
+}
+
+fn end_of_epoch_checkpoint(
+    committee: &CommitteeFixture,
+    previous_checkpoint: &VerifiedCheckpoint,
+    root_state_hash: ECMHLiveObjectSetDigest,
+) -> VerifiedCheckpoint {
+    let next_committee = CommitteeFixture::generate(rand::rngs::OsRng, 1, 4);
+    let (_, _, checkpoint) = committee.make_end_of_epoch_checkpoint(
+        previous_checkpoint.clone(),
+        Some(EndOfEpochData {
+            next_epoch_committee: next_committee.committee().voting_rights.clone(),
+            next_epoch_protocol_version: ProtocolVersion::MIN,
+            epoch_commitments: vec![root_state_hash.into()],
+        }),
+    );
+    checkpoint
+}
+
+#[tokio::test]
+async fn test_snapshot_write_and_restore() -> Result<()> {
+    let checkpoint_dir = TempDir::new()?;
+    let checkpoint_store = CheckpointStore::new(checkpoint_dir.path());
+    let (state, _, last_checkpoint) = end_of_epoch_authority(&checkpoint_store).await;
+    let source = &state.database.perpetual_tables;
+
+    let snapshot_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(snapshot_dir.path());
+    // A tiny file size puts every object into its own file.
+    let manifest = StateSnapshotWriter::new(&config, 1)?
+        .write(0, source, &checkpoint_store)
+        .await?;
+    assert_eq!(manifest.last_checkpoint_digest(), *last_checkpoint.digest());
+    assert_eq!(
+        Some((
+            manifest.last_checkpoint_of_epoch(),
+            manifest.root_state_hash().clone()
+        )),
+        root_state_hash(source, 0)?
+    );
+    assert_eq!(
+        manifest.files().len() as u64,
+        source.iter_live_object_set().count() as u64
+    );
+
+    let restore_dir = TempDir::new()?;
+    let target = AuthorityPerpetualTables::open(restore_dir.path(), None);
+    let reader = StateSnapshotReader::new(0, &config)?;
+    assert_eq!(
+        reader.read_live_objects(&checkpoint_store).await?,
+        source.iter_live_object_set().collect::<Vec<_>>()
+    );
+    let staging_dir = TempDir::new()?;
+    assert_eq!(
+        reader
+            .restore(&target, &checkpoint_store, staging_dir.path(), 0)
+            .await?,
+        manifest
+    );
+    // The staged object files are discarded.
+    assert_eq!(std::fs::read_dir(staging_dir.path())?.count(), 0);
+    assert_eq!(
+        source.iter_live_object_set().collect::<Vec<_>>(),
+        target.iter_live_object_set().collect::<Vec<_>>()
+    );
+    assert_eq!(root_state_hash(&target, 0)?, root_state_hash(source, 0)?);
+    let epoch_start_configuration = target.epoch_start_configuration.get(&())?.unwrap();
+    assert_eq!(epoch_start_configuration.epoch_start_state().epoch(), 1);
+    assert_eq!(
+        epoch_start_configuration.epoch_digest(),
+        *last_checkpoint.digest()
+    );
+
+    // Restoring requires an empty database.
+    assert!(reader
+        .restore(&target, &checkpoint_store, staging_dir.path(), 0)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_rejects_mismatched_state() -> Result<()> {
+    let checkpoint_dir = TempDir::new()?;
+    let checkpoint_store = CheckpointStore::new(checkpoint_dir.path());
+    let (state, _, _) = end_of_epoch_authority(&checkpoint_store).await;
+    let source = &state.database.perpetual_tables;
+
+    // A live object set which moved past the end of the epoch cannot be snapshotted.
+    let (last_checkpoint_of_epoch, _) = root_state_hash(source, 0)?.unwrap();
+    source
+        .root_state_hash_by_epoch
+        .insert(&0, &(last_checkpoint_of_epoch, Default::default()))?;
+    let snapshot_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(snapshot_dir.path());
+    assert!(StateSnapshotWriter::new(&config, usize::MAX)?
+        .write(0, source, &checkpoint_store)
+        .await
+        .is_err());
+    let reader = StateSnapshotReader::new(0, &config)?;
+    assert!(reader.manifest().await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_restore_requires_committed_root_state_hash() -> Result<()> {
+    let checkpoint_dir = TempDir::new()?;
+    let checkpoint_store = CheckpointStore::new(checkpoint_dir.path());
+    let (state, committee, last_checkpoint) = end_of_epoch_authority(&checkpoint_store).await;
+    let source = &state.database.perpetual_tables;
+    let snapshot_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(snapshot_dir.path());
+    let manifest = StateSnapshotWriter::new(&config, 1)?
+        .write(0, source, &checkpoint_store)
+        .await?;
+    let reader = StateSnapshotReader::new(0, &config)?;
+    let restore_dir = TempDir::new()?;
+    let target = AuthorityPerpetualTables::open(restore_dir.path(), None);
+    let staging_dir = TempDir::new()?;
+
+    // The last checkpoint of the epoch must be synced first.
+    let empty_checkpoint_dir = TempDir::new()?;
+    let empty_checkpoint_store = CheckpointStore::new(empty_checkpoint_dir.path());
+    assert!(reader
+        .restore(&target, &empty_checkpoint_store, staging_dir.path(), 0)
+        .await
+        .is_err());
+    assert!(reader
+        .read_live_objects(&empty_checkpoint_store)
+        .await
+        .is_err());
+
+    // A MANIFEST claiming another last checkpoint than the synced one is rejected.
+    let (previous_checkpoint, _, _) = committee.make_checkpoints(1, None);
+    let forged_checkpoint = end_of_epoch_checkpoint(
+        &committee,
+        &previous_checkpoint[0],
+        ECMHLiveObjectSetDigest::default(),
+    );
+    let forged_checkpoint_dir = TempDir::new()?;
+    let forged_checkpoint_store = CheckpointStore::new(forged_checkpoint_dir.path());
+    forged_checkpoint_store.insert_verified_checkpoint(forged_checkpoint.clone())?;
+    assert!(reader
+        .restore(&target, &forged_checkpoint_store, staging_dir.path(), 0)
+        .await
+        .is_err());
+    assert!(reader
+        .read_live_objects(&forged_checkpoint_store)
+        .await
+        .is_err());
+
+    // Objects not matching the root state hash claimed by the MANIFEST are only staged, and
+    // discarded.
+    let SnapshotManifest::V1(mut forged_manifest) = manifest;
+    forged_manifest.root_state_hash = ECMHLiveObjectSetDigest::default();
+    forged_manifest.last_checkpoint_digest = *forged_checkpoint.digest();
+    write_manifest(&SnapshotManifest::V1(forged_manifest), config.make()?).await?;
+    assert!(reader
+        .restore(&target, &forged_checkpoint_store, staging_dir.path(), 0)
+        .await
+        .is_err());
+    assert!(target.database_is_empty()?);
+    assert_eq!(std::fs::read_dir(staging_dir.path())?.count(), 0);
+    assert!(reader
+        .read_live_objects(&forged_checkpoint_store)
+        .await
+        .is_err());
+
+    // The genuine snapshot is still accepted.
+    StateSnapshotWriter::new(&config, 1)?
+        .write(0, source, &checkpoint_store)
+        .await?;
+    reader
+        .restore(&target, &checkpoint_store, staging_dir.path(), 0)
+        .await?;
+    assert_eq!(
+        target
+            .epoch_start_configuration
+            .get(&())?
+            .unwrap()
+            .epoch_digest(),
+        *last_checkpoint.digest()
+    );
+    Ok(())
+}
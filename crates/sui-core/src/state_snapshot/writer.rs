@@ -0,0 +1,120 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+use object_store::DynObjectStore;
+use sui_storage::archive::{encode_blobs, FileCompression};
+use sui_storage::object_store::util::put;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::EpochId;
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
+use tracing::{debug, info};
+
+use super::{
+    ensure_ordered, root_state_hash, write_manifest, ObjectFileMetadata, SnapshotManifest,
+    SnapshotManifestV1, OBJECT_FILE_MAGIC,
+};
+use crate::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::accumulate_live_object;
+
+/// Exports the live object set of a node at the end of an epoch as a state snapshot, see
+/// [crate::state_snapshot] for the layout of the snapshot.
+pub struct StateSnapshotWriter {
+    remote_store: Arc<DynObjectStore>,
+    /// Objects are chunked into files of roughly this uncompressed size.
+    file_size: usize,
+}
+
+impl StateSnapshotWriter {
+    pub fn new(remote_store_config: &ObjectStoreConfig, file_size: usize) -> Result<Self> {
+        Ok(Self {
+            remote_store: remote_store_config.make()?,
+            file_size,
+        })
+    }
+
+    /// Write the snapshot of `epoch` from the tables of a node whose live object set is the one
+    /// at the end of the epoch, e.g. a db checkpoint taken at the epoch boundary. Fails without
+    /// writing the MANIFEST if the live object set doesn't match the root state hash of the epoch.
+    pub async fn write(
+        &self,
+        epoch: EpochId,
+        perpetual_db: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+    ) -> Result<SnapshotManifest> {
+        let (last_checkpoint_of_epoch, expected_root_state_hash) =
+            root_state_hash(perpetual_db, epoch)?
+                .ok_or_else(|| anyhow!("No root state hash for epoch {epoch}"))?;
+        let last_checkpoint = checkpoint_store
+            .get_checkpoint_by_sequence_number(last_checkpoint_of_epoch)?
+            .ok_or_else(|| {
+                anyhow!("Missing checkpoint {last_checkpoint_of_epoch}, the last of epoch {epoch}")
+            })?;
+        ensure!(
+            last_checkpoint.epoch() == epoch && last_checkpoint.end_of_epoch_data.is_some(),
+            "Checkpoint {last_checkpoint_of_epoch} is not the last checkpoint of epoch {epoch}"
+        );
+        info!(epoch, last_checkpoint_of_epoch, "Writing state snapshot");
+
+        let mut acc = Accumulator::default();
+        let mut files = vec![];
+        let mut blobs = vec![];
+        let mut size = 0;
+        let mut previous: Option<LiveObject> = None;
+        for live_object in perpetual_db.iter_live_object_set() {
+            ensure_ordered(previous.as_ref(), &live_object)?;
+            accumulate_live_object(&mut acc, &live_object);
+            let blob = bcs::to_bytes(&live_object)?;
+            size += blob.len();
+            blobs.push(blob);
+            if size >= self.file_size {
+                files.push(self.write_file(epoch, files.len() as u64, &blobs).await?);
+                blobs.clear();
+                size = 0;
+            }
+            previous = Some(live_object);
+        }
+        if !blobs.is_empty() {
+            files.push(self.write_file(epoch, files.len() as u64, &blobs).await?);
+        }
+
+        let root_state_hash: ECMHLiveObjectSetDigest = acc.digest().into();
+        ensure!(
+            root_state_hash == expected_root_state_hash,
+            "Live object set doesn't match the root state hash of epoch {epoch}, \
+             is the database at the end of the epoch?"
+        );
+        let manifest = SnapshotManifest::V1(SnapshotManifestV1 {
+            epoch,
+            last_checkpoint_of_epoch,
+            last_checkpoint_digest: *last_checkpoint.digest(),
+            root_state_hash,
+            file_metadata: files,
+        });
+        write_manifest(&manifest, self.remote_store.clone()).await?;
+        info!(
+            epoch,
+            num_files = manifest.files().len(),
+            num_objects = manifest.num_objects(),
+            "Wrote state snapshot"
+        );
+        Ok(manifest)
+    }
+
+    async fn write_file(
+        &self,
+        epoch: EpochId,
+        file_num: u64,
+        blobs: &[Vec<u8>],
+    ) -> Result<ObjectFileMetadata> {
+        let bytes = encode_blobs(OBJECT_FILE_MAGIC, FileCompression::Snappy, blobs)?;
+        let metadata = ObjectFileMetadata::new(file_num, blobs.len() as u64, &bytes);
+        put(&metadata.file_path(epoch), bytes, self.remote_store.clone()).await?;
+        debug!(epoch, file_num, "Wrote state snapshot file");
+        Ok(metadata)
+    }
+}
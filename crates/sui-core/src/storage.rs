@@ -81,6 +81,10 @@ impl ReadStore for RocksDbStore {
             })
     }
 
+    fn get_lowest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber, Self::Error> {
+        self.checkpoint_store.get_lowest_available_checkpoint()
+    }
+
     fn get_full_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::event_handler::EVENT_DISPATCH_BUFFER_SIZE;
+use crate::subscription_handler::EVENT_DISPATCH_BUFFER_SIZE;
 use futures::Stream;
 use mysten_metrics::spawn_monitored_task;
 use std::collections::BTreeMap;
@@ -20,14 +20,17 @@ type Subscribers<T, F> = Arc<RwLock<BTreeMap<String, (Sender<T>, F)>>>;
 
 /// The Streamer splits a mpsc channel into multiple mpsc channels using the subscriber's `Filter<T>` object.
 /// Data will be sent to the subscribers in parallel and the subscription will be dropped if it received a send error.
-pub struct Streamer<T, F: Filter<T>> {
+/// The filter is evaluated against `T`, while subscribers receive `S`, this allows filtering on data
+/// (e.g. transaction input) that is not part of the streamed item.
+pub struct Streamer<T, S, F: Filter<T>> {
     streamer_queue: Sender<T>,
-    subscribers: Subscribers<T, F>,
+    subscribers: Subscribers<S, F>,
 }
 
-impl<T, F> Streamer<T, F>
+impl<T, S, F> Streamer<T, S, F>
 where
-    T: Clone + Debug + Send + Sync + 'static,
+    T: Into<S> + Clone + Debug + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
     F: Filter<T> + Clone + Send + Sync + 'static + Clone,
 {
     pub fn spawn(buffer: usize) -> Self {
@@ -46,21 +49,21 @@ where
         streamer
     }
 
-    async fn send_to_all_subscribers(subscribers: Subscribers<T, F>, data: T) {
+    async fn send_to_all_subscribers(subscribers: Subscribers<S, F>, data: T) {
         for (id, (subscriber, filter)) in subscribers.read().await.clone() {
             if !(filter.matches(&data)) {
                 continue;
             }
-            let data = data.clone();
+            let data: S = data.clone().into();
             let subscribers = subscribers.clone();
             spawn_monitored_task!(async move {
                 match subscriber.send(data).await {
                     Ok(_) => {
-                        debug!("Sending Move event to subscriber [{id}].")
+                        debug!("Sending data to subscriber [{id}].")
                     }
                     Err(e) => {
                         subscribers.write().await.remove(&id);
-                        warn!("Error sending event, removing subscriber [{id}] from subscriber list. Error: {e}");
+                        warn!("Error sending data, removing subscriber [{id}] from subscriber list. Error: {e}");
                     }
                 }
             });
@@ -68,11 +71,11 @@ where
     }
 
     /// Subscribe to the data stream filtered by the filter object.
-    pub fn subscribe(&self, filter: F) -> impl Stream<Item = T> {
+    pub fn subscribe(&self, filter: F) -> impl Stream<Item = S> {
         let handle = Handle::current();
         let _ = handle.enter();
         let mut subscribers = futures::executor::block_on(async { self.subscribers.write().await });
-        let (tx, rx) = mpsc::channel::<T>(EVENT_DISPATCH_BUFFER_SIZE);
+        let (tx, rx) = mpsc::channel::<S>(EVENT_DISPATCH_BUFFER_SIZE);
         subscribers.insert(ObjectID::random().to_string(), (tx, filter));
         ReceiverStream::new(rx)
     }
@@ -0,0 +1,271 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+use std::sync::Arc;
+
+use move_core_types::identifier::Identifier;
+use prometheus::Registry;
+use sui_config::node::AuthorityStorePruningConfig;
+use sui_storage::mutex_table::RwLockTable;
+use sui_types::base_types::{AuthorityName, SuiAddress, TransactionDigest};
+use sui_types::committee::{Committee, EpochId};
+use sui_types::crypto::{AuthorityKeyPair, KeypairTraits};
+use sui_types::event::Event;
+use sui_types::gas::GasCostSummary;
+use sui_types::gas_coin::GasCoin;
+use sui_types::message_envelope::Message;
+use sui_types::messages::{TransactionEffects, TransactionEffectsV1, TransactionEvents};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+    VerifiedCheckpoint,
+};
+use sui_types::utils::{create_fake_transaction, make_committee_key};
+use sui_types::{base_types::ExecutionDigests, SUI_FRAMEWORK_OBJECT_ID};
+use typed_store::Map;
+
+use super::{AuthorityStorePruner, AuthorityStorePruningMetrics};
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+use crate::checkpoints::CheckpointStore;
+
+/// Checkpoints, along with the transactions, effects and events they contain, as they are stored
+/// by a node executing them.
+struct History {
+    perpetual_db: Arc<AuthorityPerpetualTables>,
+    checkpoint_store: Arc<CheckpointStore>,
+    keys: Vec<AuthorityKeyPair>,
+    checkpoints: Vec<VerifiedCheckpoint>,
+}
+
+impl History {
+    fn new(path: &Path) -> Self {
+        let (keys, _) = make_committee_key(&mut rand::thread_rng());
+        Self {
+            perpetual_db: Arc::new(AuthorityPerpetualTables::open(path, None)),
+            checkpoint_store: CheckpointStore::new(&path.join("checkpoints")),
+            keys,
+            checkpoints: vec![],
+        }
+    }
+
+    /// Execute a checkpoint of `epoch` made of a transaction emitting each of `events`, returning
+    /// the digests of these transactions.
+    fn execute_checkpoint(
+        &mut self,
+        epoch: EpochId,
+        events: Vec<TransactionEvents>,
+    ) -> Vec<TransactionDigest> {
+        let db = &self.perpetual_db;
+        let mut execution_digests = vec![];
+        for events in events {
+            let transaction = create_fake_transaction();
+            let events_digest = events.digest();
+            let effects = TransactionEffects::V1(TransactionEffectsV1 {
+                transaction_digest: *transaction.digest(),
+                events_digest: Some(events_digest),
+                ..Default::default()
+            });
+            db.transactions
+                .insert(transaction.digest(), transaction.serializable_ref())
+                .unwrap();
+            db.effects.insert(&effects.digest(), &effects).unwrap();
+            db.executed_effects
+                .insert(transaction.digest(), &effects.digest())
+                .unwrap();
+            db.events
+                .multi_insert(
+                    events
+                        .data
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, e)| ((events_digest, i), e)),
+                )
+                .unwrap();
+            db.event_references
+                .insert(&(events_digest, *transaction.digest()), &())
+                .unwrap();
+            execution_digests.push(ExecutionDigests::new(
+                *transaction.digest(),
+                effects.digest(),
+            ));
+        }
+        let tx_digests = execution_digests.iter().map(|d| d.transaction).collect();
+
+        let previous = self.checkpoints.last();
+        let contents =
+            CheckpointContents::new_with_causally_ordered_transactions(execution_digests);
+        let summary = CheckpointSummary::new(
+            epoch,
+            previous.map_or(0, |c| c.sequence_number() + 1),
+            previous.map_or(0, |c| c.network_total_transactions) + contents.size() as u64,
+            &contents,
+            previous.map(|c| *c.digest()),
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        let checkpoint = self.certify(summary);
+        self.checkpoint_store
+            .insert_checkpoint_contents(contents)
+            .unwrap();
+        self.checkpoint_store
+            .insert_verified_checkpoint(checkpoint.clone())
+            .unwrap();
+        self.checkpoint_store
+            .update_highest_executed_checkpoint(&checkpoint)
+            .unwrap();
+        self.checkpoints.push(checkpoint);
+        tx_digests
+    }
+
+    fn certify(&self, summary: CheckpointSummary) -> VerifiedCheckpoint {
+        let committee = Committee::new_for_testing_with_normalized_voting_power(
+            summary.epoch,
+            self.keys
+                .iter()
+                .map(|k| (AuthorityName::from(k.public()), 1))
+                .collect(),
+        );
+        let sign_infos = self
+            .keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(summary.epoch, &summary, k, k.public().into()))
+            .collect();
+        VerifiedCheckpoint::new_unchecked(
+            CertifiedCheckpointSummary::new(summary, sign_infos, &committee).unwrap(),
+        )
+    }
+
+    /// Prune the history of all epochs but the current one.
+    async fn prune(&self) {
+        let config = AuthorityStorePruningConfig {
+            num_epochs_to_retain: u64::MAX,
+            num_epochs_to_retain_for_history: Some(1),
+            ..Default::default()
+        };
+        AuthorityStorePruner::prune_history_for_eligible_checkpoints(
+            &self.perpetual_db,
+            &self.checkpoint_store,
+            None,
+            &Arc::new(RwLockTable::new(1)),
+            config,
+            AuthorityStorePruningMetrics::new(&Registry::default()),
+        )
+        .await
+        .unwrap();
+    }
+
+    fn has_transaction(&self, digest: &TransactionDigest) -> bool {
+        let db = &self.perpetual_db;
+        let effects_digest = db.executed_effects.get(digest).unwrap();
+        let has_effects =
+            effects_digest.map_or(false, |digest| db.effects.contains_key(&digest).unwrap());
+        assert_eq!(db.transactions.contains_key(digest).unwrap(), has_effects);
+        has_effects
+    }
+
+    fn has_events(&self, events: &TransactionEvents) -> bool {
+        self.perpetual_db
+            .events
+            .contains_key(&(events.digest(), 0))
+            .unwrap()
+    }
+
+    fn has_contents(&self, checkpoint: usize) -> bool {
+        self.checkpoint_store
+            .get_checkpoint_contents(&self.checkpoints[checkpoint].content_digest)
+            .unwrap()
+            .is_some()
+    }
+}
+
+fn events(contents: u8) -> TransactionEvents {
+    TransactionEvents {
+        data: vec![Event {
+            package_id: SUI_FRAMEWORK_OBJECT_ID,
+            transaction_module: Identifier::new("test").unwrap(),
+            sender: SuiAddress::ZERO,
+            type_: GasCoin::type_(),
+            contents: vec![contents],
+        }],
+    }
+}
+
+#[tokio::test]
+async fn test_prune_history() {
+    let path = tempfile::tempdir().unwrap();
+    let mut history = History::new(path.path());
+    let pruned = history.execute_checkpoint(0, vec![events(0), events(1)]);
+    let pruned = [pruned, history.execute_checkpoint(0, vec![events(2)])].concat();
+    let retained = history.execute_checkpoint(1, vec![events(3)]);
+
+    history.prune().await;
+
+    for digest in &pruned {
+        assert!(!history.has_transaction(digest));
+    }
+    for digest in &retained {
+        assert!(history.has_transaction(digest));
+    }
+    assert!(!history.has_contents(0));
+    assert!(!history.has_contents(1));
+    assert!(history.has_contents(2));
+    assert_eq!(
+        history
+            .checkpoint_store
+            .get_lowest_available_checkpoint()
+            .unwrap(),
+        2
+    );
+    // Summaries of pruned checkpoints are kept.
+    assert!(history
+        .checkpoint_store
+        .get_checkpoint_by_sequence_number(0)
+        .unwrap()
+        .is_some());
+
+    assert!(!history.has_events(&events(0)));
+    assert!(!history.has_events(&events(1)));
+    assert!(!history.has_events(&events(2)));
+    assert!(history.has_events(&events(3)));
+}
+
+#[tokio::test]
+async fn test_prune_history_with_shared_events() {
+    let path = tempfile::tempdir().unwrap();
+    let mut history = History::new(path.path());
+    // The first checkpoint has two transactions emitting the same events, and a transaction of a
+    // later epoch emits them again.
+    let pruned = history.execute_checkpoint(0, vec![events(0), events(0), events(1)]);
+    let retained = history.execute_checkpoint(1, vec![events(0)]);
+
+    history.prune().await;
+
+    for digest in &pruned {
+        assert!(!history.has_transaction(digest));
+    }
+    assert!(history.has_transaction(&retained[0]));
+    assert!(!history.has_events(&events(1)));
+    // The events are still referenced by the retained transaction.
+    assert!(history.has_events(&events(0)));
+    assert_eq!(
+        history
+            .perpetual_db
+            .event_references
+            .keys()
+            .collect::<Vec<_>>(),
+        vec![(events(0).digest(), retained[0])]
+    );
+
+    // They are removed along with the last transaction referencing them.
+    history.execute_checkpoint(2, vec![]);
+    history.prune().await;
+    assert!(!history.has_transaction(&retained[0]));
+    assert!(!history.has_events(&events(0)));
+    assert!(history
+        .perpetual_db
+        .event_references
+        .keys()
+        .next()
+        .is_none());
+}
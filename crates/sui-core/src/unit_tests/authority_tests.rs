@@ -3167,6 +3167,105 @@ async fn test_transfer_sui_no_amount() {
     );
 }
 
+#[tokio::test]
+async fn test_coin_balance_index() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let init_balance = sui_types::gas::get_gas_balance(&gas_object).unwrap();
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let network_config = sui_config::builder::ConfigBuilder::new(&dir)
+        .with_objects(vec![gas_object.clone()])
+        .build();
+    let keypair = network_config.validator_configs[0]
+        .protocol_key_pair()
+        .copy();
+    let authority_state = init_state_with_committee(&network_config.genesis, &keypair).await;
+    let gas_type = sui_types::gas_coin::GAS::type_tag();
+
+    // Balances are summed up from the coins of their owner until the index is built in the
+    // background.
+    assert_eq!(
+        authority_state
+            .get_coin_balance(sender, gas_type.clone())
+            .unwrap(),
+        CoinBalance {
+            total_balance: init_balance as u128,
+            coin_count: 1,
+        }
+    );
+    let indexes = authority_state.get_indexes().unwrap();
+    while indexes.needs_coin_balance_backfill().unwrap() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(
+        indexes.get_coin_balance(sender, gas_type.clone()).unwrap(),
+        Some(CoinBalance {
+            total_balance: init_balance as u128,
+            coin_count: 1,
+        })
+    );
+
+    let epoch_store = authority_state.load_epoch_store_one_call_per_task();
+    let rgp = epoch_store.reference_gas_price();
+    let tx_data = TransactionData::new_transfer_sui(
+        recipient,
+        sender,
+        Some(1000),
+        gas_object.compute_object_reference(),
+        rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        rgp,
+    );
+    let transaction = to_sender_signed_transaction(tx_data, &sender_key);
+    let certificate = init_certified_transaction(transaction, &authority_state);
+    let effects = authority_state
+        .execute_certificate(&certificate, &epoch_store)
+        .await
+        .unwrap()
+        .into_message();
+    assert!(effects.status().is_ok());
+
+    let check_balances = || {
+        let sender_balance =
+            (init_balance as i64 - 1000 - effects.gas_cost_summary().net_gas_usage()) as u128;
+        assert_eq!(
+            authority_state.get_coin_balances(sender).unwrap(),
+            vec![(
+                gas_type.clone(),
+                CoinBalance {
+                    total_balance: sender_balance,
+                    coin_count: 1,
+                }
+            )]
+        );
+        assert_eq!(
+            authority_state
+                .get_coin_balance(recipient, gas_type.clone())
+                .unwrap(),
+            CoinBalance {
+                total_balance: 1000,
+                coin_count: 1,
+            }
+        );
+    };
+    check_balances();
+
+    // Indexing the transaction again, e.g. if the node crashed before post-processing completed,
+    // does not count its coins twice.
+    let executable = VerifiedExecutableTransaction::new_from_certificate(certificate);
+    authority_state
+        .post_process_one_tx(
+            &executable,
+            &effects,
+            &TransactionEvents::default(),
+            &epoch_store,
+        )
+        .await
+        .unwrap();
+    check_balances();
+}
+
 #[tokio::test]
 async fn test_transfer_sui_with_amount() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
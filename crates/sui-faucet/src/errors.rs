@@ -3,7 +3,7 @@
 
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum FaucetError {
     #[error("Faucet cannot read objects from fullnode: {0}")]
     FullnodeReadingError(String),
@@ -31,6 +31,9 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Too many requests for {0}, try again later")]
+    TooManyRequests(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -10,7 +10,8 @@ mod simple_faucet;
 mod write_ahead_log;
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -25,15 +26,73 @@ pub struct CoinInfo {
     pub transfer_tx_digest: TransactionDigest,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchSendStatusType {
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+/// Progress of a request, which can be polled while the coins are sent in the background.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSendStatus {
+    pub status: BatchSendStatusType,
+    pub transferred_gas_objects: Option<FaucetReceipt>,
+    pub error: Option<String>,
+}
+
+impl BatchSendStatus {
+    pub fn in_progress() -> Self {
+        Self {
+            status: BatchSendStatusType::InProgress,
+            transferred_gas_objects: None,
+            error: None,
+        }
+    }
+}
+
+impl From<&Result<FaucetReceipt, FaucetError>> for BatchSendStatus {
+    fn from(result: &Result<FaucetReceipt, FaucetError>) -> Self {
+        match result {
+            Ok(receipt) => Self {
+                status: BatchSendStatusType::Succeeded,
+                transferred_gas_objects: Some(receipt.clone()),
+                error: None,
+            },
+            Err(e) => Self {
+                status: BatchSendStatusType::Failed,
+                transferred_gas_objects: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
 #[async_trait]
 pub trait Faucet {
-    /// Send `Coin<SUI>` of the specified amount to the recipient
+    /// Send `Coin<SUI>` of the specified amount to the recipient, recording the outcome as the
+    /// status of request `id`.
     async fn send(
         &self,
         id: Uuid,
         recipient: SuiAddress,
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// Count request `id` against the quotas of the recipient and of the IP it came from, and
+    /// record it as in progress. Fails with `FaucetError::TooManyRequests` if either quota is
+    /// exhausted, in which case the request is not counted.
+    async fn admit(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<(), FaucetError>;
+
+    /// Status of request `id`, if it is known to the faucet.
+    async fn status(&self, id: Uuid) -> Result<Option<BatchSendStatus>, FaucetError>;
 }
 
 #[derive(Parser, Clone)]
@@ -69,6 +128,30 @@ pub struct FaucetConfig {
 
     #[clap(long, default_value_t = 300)]
     pub wal_retry_interval: u64,
+
+    /// Maximum number of requests for the same recipient within the rate limit window. Unlimited
+    /// if not set.
+    #[clap(long)]
+    pub max_requests_per_address: Option<u64>,
+
+    /// Maximum number of requests from the same IP within the rate limit window. Unlimited if not
+    /// set.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Length of the sliding window over which requests count against the quotas.
+    #[clap(long, default_value_t = 86400)]
+    pub rate_limit_window_secs: u64,
+
+    /// How long the status of a request can be polled for after it was received.
+    #[clap(long, default_value_t = 3600)]
+    pub request_status_retention_secs: u64,
+
+    /// IPs of the reverse proxies in front of the faucet. The IP a request counts against is read
+    /// from the `X-Forwarded-For` header of requests coming through them, and is the IP of the
+    /// peer otherwise.
+    #[clap(long, value_delimiter = ',')]
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl Default for FaucetConfig {
@@ -83,6 +166,11 @@ impl Default for FaucetConfig {
             wallet_client_timeout_secs: 60,
             write_ahead_log: Default::default(),
             wal_retry_interval: 300,
+            max_requests_per_address: None,
+            max_requests_per_ip: None,
+            rate_limit_window_secs: 86400,
+            request_status_retention_secs: 3600,
+            trusted_proxies: vec![],
         }
     }
 }
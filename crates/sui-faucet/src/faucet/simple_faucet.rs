@@ -8,9 +8,10 @@ use prometheus::Registry;
 use tap::tap::TapFallible;
 
 use shared_crypto::intent::Intent;
-#[cfg(test)]
 use std::collections::HashSet;
+use std::net::IpAddr;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 use typed_store::Map;
 
 use sui::client_commands::WalletContext;
@@ -33,8 +34,8 @@ use tokio::time::{timeout, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use super::write_ahead_log::WriteAheadLog;
-use crate::{CoinInfo, Faucet, FaucetConfig, FaucetError, FaucetReceipt};
+use super::write_ahead_log::{RateLimitKey, WriteAheadLog};
+use crate::{BatchSendStatus, CoinInfo, Faucet, FaucetConfig, FaucetError, FaucetReceipt};
 
 pub struct SimpleFaucet {
     wallet: WalletContext,
@@ -43,6 +44,10 @@ pub struct SimpleFaucet {
     consumer: Mutex<Receiver<ObjectID>>,
     metrics: FaucetMetrics,
     wal: Mutex<WriteAheadLog>,
+    max_requests_per_address: Option<u64>,
+    max_requests_per_ip: Option<u64>,
+    rate_limit_window: Duration,
+    request_status_retention: Duration,
 }
 
 enum GasCoinResponse {
@@ -81,9 +86,15 @@ impl SimpleFaucet {
             .collect::<Vec<GasCoin>>();
         let metrics = FaucetMetrics::new(prometheus_registry);
 
-        let wal = WriteAheadLog::open(wal_path);
+        let mut wal = WriteAheadLog::open(wal_path);
         let mut pending = vec![];
 
+        // Requests are only served after restarting if their transaction made it to the WAL, the
+        // others will never complete.
+        let in_flight: HashSet<_> = wal.log.values().map(|entry| entry.uuid).collect();
+        wal.fail_interrupted_requests(&in_flight, "request interrupted by a faucet restart")
+            .map_err(FaucetError::internal)?;
+
         let (producer, consumer) = mpsc::channel(coins.len());
         for coin in &coins {
             let coin_id = *coin.id();
@@ -117,13 +128,17 @@ impl SimpleFaucet {
             consumer: Mutex::new(consumer),
             metrics,
             wal: Mutex::new(wal),
+            max_requests_per_address: config.max_requests_per_address,
+            max_requests_per_ip: config.max_requests_per_ip,
+            rate_limit_window: Duration::from_secs(config.rate_limit_window_secs),
+            request_status_retention: Duration::from_secs(config.request_status_retention_secs),
         };
 
         // Retrying all the pending transactions from the WAL, before continuing.  Ignore return
         // values -- if the executions failed, the pending coins will simply remain in the WAL, and
         // not recycled.
         futures::future::join_all(pending.into_iter().map(|(uuid, recipient, coin_id, tx)| {
-            faucet.sign_and_execute_txn(uuid, recipient, coin_id, tx)
+            faucet.retry_wal_txn(uuid, recipient, coin_id, tx)
         }))
         .await;
 
@@ -247,7 +262,7 @@ impl SimpleFaucet {
 
         let transfer_results =
             futures::future::join_all(pending.into_iter().map(|(uuid, recipient, coin_id, tx)| {
-                self.sign_and_execute_txn(uuid, recipient, coin_id, tx)
+                self.retry_wal_txn(uuid, recipient, coin_id, tx)
             }))
             .await;
 
@@ -260,6 +275,60 @@ impl SimpleFaucet {
         Ok(())
     }
 
+    /// Forget requests which no longer count against the quotas, and statuses past their
+    /// retention.
+    pub async fn prune_request_history(&self) -> Result<(), FaucetError> {
+        self.wal
+            .lock()
+            .await
+            .prune(
+                self.rate_limit_window,
+                self.request_status_retention,
+                now_ms(),
+            )
+            .map_err(FaucetError::internal)
+    }
+
+    /// Execute the transaction of request `uuid` found in the WAL, and record the outcome as the
+    /// status of the request. A request failing here may still succeed on a later retry, as its
+    /// transaction stays in the WAL.
+    async fn retry_wal_txn(
+        &self,
+        uuid: Uuid,
+        recipient: SuiAddress,
+        coin_id: ObjectID,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockResponse, FaucetError> {
+        let result = self
+            .sign_and_execute_txn(uuid, recipient, coin_id, tx_data)
+            .await;
+        let receipt = match &result {
+            Ok(response) => match &response.effects {
+                Some(effects) => {
+                    let coin_ids = effects
+                        .created()
+                        .iter()
+                        .filter(|created| created.owner == recipient)
+                        .map(|created| created.reference.object_id)
+                        .collect();
+                    Ok(self
+                        .describe_sent_coins(uuid, recipient, response.digest, coin_ids)
+                        .await)
+                }
+                None => Err(FaucetError::ParseTransactionResponseError(format!(
+                    "effects field missing for txn {}",
+                    response.digest
+                ))),
+            },
+            Err(e) => Err(e.clone()),
+        };
+        let status = BatchSendStatus::from(&receipt);
+        if let Err(e) = self.wal.lock().await.set_status(uuid, status, now_ms()) {
+            error!(?uuid, "Failed to record request status: {e:?}");
+        }
+        result
+    }
+
     /// Sign an already created transaction (in `tx_data`) and keep trying to execute it until
     /// fullnode returns a definite response or a timeout is hit.
     async fn sign_and_execute_txn(
@@ -535,6 +604,55 @@ impl SimpleFaucet {
         Ok((res.digest, coin_ids))
     }
 
+    /// Send the coins, and describe them in the receipt.
+    async fn send_inner(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError> {
+        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
+
+        info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
+        Ok(self
+            .describe_sent_coins(id, recipient, digest, coin_ids)
+            .await)
+    }
+
+    /// Look up the coins sent to `recipient` by transaction `digest`, to describe them in the
+    /// receipt.
+    async fn describe_sent_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        digest: TransactionDigest,
+        coin_ids: Vec<ObjectID>,
+    ) -> FaucetReceipt {
+        let mut sent = Vec::with_capacity(coin_ids.len());
+        let coin_results =
+            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
+        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
+            let amount = if let Ok(Some((_, coin))) = res {
+                coin.value()
+            } else {
+                info!(
+                    ?recipient,
+                    ?coin_id,
+                    uuid = ?id,
+                    "Could not find coin after successful transaction, error: {:?}",
+                    &res,
+                );
+                0
+            };
+            sent.push(CoinInfo {
+                transfer_tx_digest: digest,
+                amount,
+                id: coin_id,
+            });
+        }
+        FaucetReceipt { sent }
+    }
+
     #[cfg(test)]
     async fn drain_gas_queue(&mut self, expected_gas_count: usize) -> HashSet<ObjectID> {
         use tokio::sync::mpsc::error::TryRecvError;
@@ -571,42 +689,77 @@ impl Faucet for SimpleFaucet {
     ) -> Result<FaucetReceipt, FaucetError> {
         info!(?recipient, uuid = ?id, "Getting faucet requests");
 
-        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
+        let result = self.send_inner(id, recipient, amounts).await;
+        // The coins were sent or not regardless of whether the status is recorded, so only log
+        // failures to record it.
+        let status = BatchSendStatus::from(&result);
+        let mut wal = self.wal.lock().await;
+        if let Err(e) = wal.set_status(id, status, now_ms()) {
+            error!(uuid = ?id, "Failed to record request status: {e:?}");
+        }
+        result
+    }
 
-        info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
-        let mut sent = Vec::with_capacity(coin_ids.len());
-        let coin_results =
-            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
-        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
-            let amount = if let Ok(Some((_, coin))) = res {
-                coin.value()
-            } else {
-                info!(
-                    ?recipient,
-                    ?coin_id,
-                    uuid = ?id,
-                    "Could not find coin after successful transaction, error: {:?}",
-                    &res,
-                );
-                0
-            };
-            sent.push(CoinInfo {
-                transfer_tx_digest: digest,
-                amount,
-                id: coin_id,
-            });
+    async fn admit(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<(), FaucetError> {
+        let quotas: Vec<_> = self
+            .max_requests_per_address
+            .map(|max| (RateLimitKey::Address(recipient), max))
+            .into_iter()
+            .chain(
+                ip.zip(self.max_requests_per_ip)
+                    .map(|(ip, max)| (RateLimitKey::Ip(ip), max)),
+            )
+            .collect();
+        let exhausted = self
+            .wal
+            .lock()
+            .await
+            .admit(id, &quotas, self.rate_limit_window, now_ms())
+            .map_err(FaucetError::internal)?;
+        match exhausted {
+            None => Ok(()),
+            Some(key) => {
+                warn!(uuid = ?id, ?key, "Request exceeds quota");
+                self.metrics.total_requests_rate_limited.inc();
+                Err(FaucetError::TooManyRequests(match key {
+                    RateLimitKey::Address(address) => address.to_string(),
+                    RateLimitKey::Ip(ip) => ip.to_string(),
+                }))
+            }
         }
-        Ok(FaucetReceipt { sent })
     }
+
+    async fn status(&self, id: Uuid) -> Result<Option<BatchSendStatus>, FaucetError> {
+        self.wal
+            .lock()
+            .await
+            .status(id)
+            .map_err(FaucetError::internal)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the unix epoch")
+        .as_millis() as u64
 }
 
 #[cfg(test)]
 mod tests {
-    use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+    use sui::client_commands::{
+        SuiClientCommandResult, SuiClientCommands, UnsignedTransactionArgs,
+    };
     use sui_json_rpc_types::SuiExecutionStatus;
     use test_utils::network::TestClusterBuilder;
 
     use super::*;
+    use crate::BatchSendStatusType;
 
     #[tokio::test]
     async fn simple_faucet_basic_interface_should_work() {
@@ -737,6 +890,7 @@ mod tests {
             input_coins: vec![*bad_gas.id()],
             recipient: SuiAddress::random_for_testing_only(),
             gas_budget: 2_000_000,
+            unsigned_transaction: UnsignedTransactionArgs::default(),
         }
         .execute(faucet.wallet_mut())
         .await
@@ -846,6 +1000,7 @@ mod tests {
             gas_budget: 50000000,
             gas: None,
             count: None,
+            unsigned_transaction: UnsignedTransactionArgs::default(),
         }
         .execute(&mut context)
         .await;
@@ -908,6 +1063,65 @@ mod tests {
         assert!(candidates.get(&tiny_coin_id).is_none());
     }
 
+    #[tokio::test]
+    async fn test_request_quotas_and_status() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(1),
+            max_requests_per_ip: Some(2),
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let recipient = SuiAddress::random_for_testing_only();
+        let id = Uuid::new_v4();
+        assert!(faucet.status(id).await.unwrap().is_none());
+        faucet.admit(id, recipient, Some(ip)).await.unwrap();
+        assert_eq!(
+            faucet.status(id).await.unwrap().unwrap().status,
+            BatchSendStatusType::InProgress
+        );
+
+        let receipt = faucet.send(id, recipient, &[1, 2]).await.unwrap();
+        let status = faucet.status(id).await.unwrap().unwrap();
+        assert_eq!(status.status, BatchSendStatusType::Succeeded);
+        assert_eq!(
+            status.transferred_gas_objects.unwrap().sent.len(),
+            receipt.sent.len()
+        );
+
+        // The recipient exhausted its quota, then the IP exhausts its own.
+        assert!(matches!(
+            faucet.admit(Uuid::new_v4(), recipient, Some(ip)).await,
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        let other = SuiAddress::random_for_testing_only();
+        faucet.admit(Uuid::new_v4(), other, Some(ip)).await.unwrap();
+        assert!(matches!(
+            faucet
+                .admit(
+                    Uuid::new_v4(),
+                    SuiAddress::random_for_testing_only(),
+                    Some(ip)
+                )
+                .await,
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        assert_eq!(faucet.metrics.total_requests_rate_limited.get(), 2);
+    }
+
     async fn test_basic_interface(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
+use std::net::IpAddr;
 use std::path::Path;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::SuiAddress;
@@ -13,6 +16,8 @@ use typed_store::Map;
 use typed_store_derive::DBMapUtils;
 use uuid::Uuid;
 
+use crate::{BatchSendStatus, BatchSendStatusType};
+
 /// Persistent log of transactions paying out sui from the faucet, keyed by the coin serving the
 /// request.  Transactions are expected to be written to the log before they are sent to full-node,
 /// and removed after receiving a response back, before the coin becomes available for subsequent
@@ -20,9 +25,29 @@ use uuid::Uuid;
 ///
 /// This allows the faucet to go down and back up, and not forget which requests were in-flight that
 /// it needs to confirm succeeded or failed.
+///
+/// The log also keeps the recent requests counting against the quotas of each recipient and IP, and
+/// the status of recent requests, so that neither is lost across restarts.
 #[derive(DBMapUtils, Clone)]
 pub struct WriteAheadLog {
     pub log: DBMap<ObjectID, Entry>,
+    /// Timestamps (in ms) of the requests within the rate limit window, in increasing order.
+    pub request_history: DBMap<RateLimitKey, Vec<u64>>,
+    pub request_status: DBMap<uuid::Bytes, RequestStatusEntry>,
+}
+
+/// Who a quota applies to.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum RateLimitKey {
+    Address(SuiAddress),
+    Ip(IpAddr),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequestStatusEntry {
+    /// When the request was received, in ms since the unix epoch.
+    pub timestamp_ms: u64,
+    pub status: BatchSendStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -92,6 +117,139 @@ impl WriteAheadLog {
         }
         Ok(())
     }
+
+    /// Count request `uuid`, received at `now_ms`, against each of `quotas`, which limit the
+    /// number of requests within `window`, and record it as in progress. If a quota is exhausted,
+    /// nothing is recorded and its key is returned.
+    pub(crate) fn admit(
+        &mut self,
+        uuid: Uuid,
+        quotas: &[(RateLimitKey, u64)],
+        window: Duration,
+        now_ms: u64,
+    ) -> Result<Option<RateLimitKey>, TypedStoreError> {
+        let window_start = now_ms.saturating_sub(window.as_millis() as u64);
+        let mut histories = Vec::with_capacity(quotas.len());
+        for (key, max_requests) in quotas {
+            let mut history = self.request_history.get(key)?.unwrap_or_default();
+            history.retain(|timestamp_ms| *timestamp_ms > window_start);
+            if history.len() as u64 >= *max_requests {
+                return Ok(Some(*key));
+            }
+            history.push(now_ms);
+            histories.push((*key, history));
+        }
+
+        let mut batch = self.request_history.batch();
+        batch.insert_batch(&self.request_history, histories)?;
+        batch.insert_batch(
+            &self.request_status,
+            [(
+                *uuid.as_bytes(),
+                RequestStatusEntry {
+                    timestamp_ms: now_ms,
+                    status: BatchSendStatus::in_progress(),
+                },
+            )],
+        )?;
+        batch.write()?;
+        Ok(None)
+    }
+
+    /// Update the status of request `uuid`, received at `now_ms` if it was not admitted first.
+    pub(crate) fn set_status(
+        &mut self,
+        uuid: Uuid,
+        status: BatchSendStatus,
+        now_ms: u64,
+    ) -> Result<(), TypedStoreError> {
+        let key = *uuid.as_bytes();
+        let timestamp_ms = self
+            .request_status
+            .get(&key)?
+            .map_or(now_ms, |entry| entry.timestamp_ms);
+        self.request_status.insert(
+            &key,
+            &RequestStatusEntry {
+                timestamp_ms,
+                status,
+            },
+        )
+    }
+
+    /// Mark the requests which are still in progress as failed with `error`, unless they are in
+    /// `in_flight`. Called on startup, when the only requests still being served are those whose
+    /// transactions are in the log.
+    pub(crate) fn fail_interrupted_requests(
+        &mut self,
+        in_flight: &HashSet<uuid::Bytes>,
+        error: &str,
+    ) -> Result<(), TypedStoreError> {
+        let interrupted = self
+            .request_status
+            .iter()
+            .filter(|(uuid, entry)| {
+                entry.status.status == BatchSendStatusType::InProgress && !in_flight.contains(uuid)
+            })
+            .map(|(uuid, entry)| {
+                (
+                    uuid,
+                    RequestStatusEntry {
+                        timestamp_ms: entry.timestamp_ms,
+                        status: BatchSendStatus {
+                            status: BatchSendStatusType::Failed,
+                            transferred_gas_objects: None,
+                            error: Some(error.to_string()),
+                        },
+                    },
+                )
+            });
+        let mut batch = self.request_status.batch();
+        batch.insert_batch(&self.request_status, interrupted)?;
+        batch.write()
+    }
+
+    pub(crate) fn status(&self, uuid: Uuid) -> Result<Option<BatchSendStatus>, TypedStoreError> {
+        Ok(self
+            .request_status
+            .get(uuid.as_bytes())?
+            .map(|entry| entry.status))
+    }
+
+    /// Forget requests which no longer count against the quotas as of `now_ms`, and statuses of
+    /// requests received longer than `status_retention` ago.
+    pub(crate) fn prune(
+        &mut self,
+        window: Duration,
+        status_retention: Duration,
+        now_ms: u64,
+    ) -> Result<(), TypedStoreError> {
+        let window_start = now_ms.saturating_sub(window.as_millis() as u64);
+        let status_start = now_ms.saturating_sub(status_retention.as_millis() as u64);
+
+        let mut batch = self.request_history.batch();
+        let mut expired_keys = vec![];
+        let mut updated_histories = vec![];
+        for (key, mut history) in self.request_history.iter() {
+            let len = history.len();
+            history.retain(|timestamp_ms| *timestamp_ms > window_start);
+            if history.is_empty() {
+                expired_keys.push(key);
+            } else if history.len() != len {
+                updated_histories.push((key, history));
+            }
+        }
+        batch.delete_batch(&self.request_history, expired_keys)?;
+        batch.insert_batch(&self.request_history, updated_histories)?;
+
+        let expired_statuses = self
+            .request_status
+            .iter()
+            .filter(|(_, entry)| entry.timestamp_ms <= status_start)
+            .map(|(uuid, _)| uuid);
+        batch.delete_batch(&self.request_status, expired_statuses)?;
+        batch.write()
+    }
 }
 
 #[cfg(test)]
@@ -216,6 +374,100 @@ mod tests {
         wal.reserve(uuid, coin.0, recv1, tx1).unwrap();
     }
 
+    #[tokio::test]
+    async fn admit_within_quotas() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        let window = Duration::from_secs(10);
+
+        let address = RateLimitKey::Address(SuiAddress::random_for_testing_only());
+        let ip = RateLimitKey::Ip("127.0.0.1".parse().unwrap());
+        let quotas = [(address, 2), (ip, 3)];
+
+        let uuid = Uuid::new_v4();
+        assert_eq!(Ok(None), wal.admit(uuid, &quotas, window, 1_000));
+        assert_eq!(
+            wal.status(uuid).unwrap().unwrap().status,
+            BatchSendStatusType::InProgress
+        );
+        assert_eq!(Ok(None), wal.admit(Uuid::new_v4(), &quotas, window, 2_000));
+
+        // The address exhausted its quota, so the request is not counted against the IP.
+        let rejected = Uuid::new_v4();
+        assert_eq!(
+            Ok(Some(address)),
+            wal.admit(rejected, &quotas, window, 3_000)
+        );
+        assert!(wal.status(rejected).unwrap().is_none());
+        let other = RateLimitKey::Address(SuiAddress::random_for_testing_only());
+        assert_eq!(
+            Ok(None),
+            wal.admit(Uuid::new_v4(), &[(other, 2), (ip, 3)], window, 4_000)
+        );
+        assert_eq!(
+            Ok(Some(ip)),
+            wal.admit(Uuid::new_v4(), &[(other, 2), (ip, 3)], window, 5_000)
+        );
+
+        // The first request falls out of the window.
+        assert_eq!(Ok(None), wal.admit(Uuid::new_v4(), &quotas, window, 11_000));
+
+        // Quotas survive restarts, and pruning keeps requests within the window.
+        drop(wal);
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        wal.prune(window, window, 13_500).unwrap();
+        assert!(wal.status(uuid).unwrap().is_none());
+        assert_eq!(Ok(None), wal.admit(Uuid::new_v4(), &quotas, window, 13_500));
+        assert_eq!(
+            Ok(Some(address)),
+            wal.admit(Uuid::new_v4(), &quotas, window, 13_600)
+        );
+        wal.prune(window, window, 30_000).unwrap();
+        assert_eq!(0, wal.request_history.iter().count());
+        assert_eq!(0, wal.request_status.iter().count());
+    }
+
+    #[tokio::test]
+    async fn fail_interrupted_requests() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        let window = Duration::from_secs(10);
+
+        let interrupted = Uuid::new_v4();
+        let in_flight = Uuid::new_v4();
+        let served = Uuid::new_v4();
+        for uuid in [interrupted, in_flight, served] {
+            assert_eq!(Ok(None), wal.admit(uuid, &[], window, 1_000));
+        }
+        let succeeded = BatchSendStatus {
+            status: BatchSendStatusType::Succeeded,
+            transferred_gas_objects: None,
+            error: None,
+        };
+        wal.set_status(served, succeeded, 2_000).unwrap();
+
+        drop(wal);
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        wal.fail_interrupted_requests(&HashSet::from([*in_flight.as_bytes()]), "restarted")
+            .unwrap();
+
+        let status = wal.status(interrupted).unwrap().unwrap();
+        assert_eq!(status.status, BatchSendStatusType::Failed);
+        assert_eq!(status.error.as_deref(), Some("restarted"));
+        assert_eq!(
+            wal.status(in_flight).unwrap().unwrap().status,
+            BatchSendStatusType::InProgress
+        );
+        assert_eq!(
+            wal.status(served).unwrap().unwrap().status,
+            BatchSendStatusType::Succeeded
+        );
+
+        // The statuses are still pruned according to when the requests were received.
+        wal.prune(window, window, 11_000).unwrap();
+        assert_eq!(0, wal.request_status.iter().count());
+    }
+
     fn random_request(coin: ObjectRef) -> (SuiAddress, TransactionData) {
         let gas_price = 1;
         let send = SuiAddress::random_for_testing_only();
@@ -3,13 +3,14 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
+    extract::{ConnectInfo, Path},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
 use clap::Parser;
-use http::Method;
+use http::{HeaderMap, Method};
 use mysten_metrics::spawn_monitored_task;
 use std::env;
 use std::{
@@ -21,11 +22,12 @@ use std::{
 use sui::client_commands::WalletContext;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
 use sui_faucet::{
-    Faucet, FaucetConfig, FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
+    BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
+    FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
 };
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
@@ -91,6 +93,8 @@ async fn main() -> Result<(), anyhow::Error> {
     let app = Router::new()
         .route("/", get(health))
         .route("/gas", post(request_gas))
+        .route("/v1/gas", post(batch_request_gas))
+        .route("/v1/status/:task_id", get(request_status))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -113,13 +117,16 @@ async fn main() -> Result<(), anyhow::Error> {
             // Every 300 seconds we try to clear the wal coins
             tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
             app_state.faucet.retry_wal_coins().await.unwrap();
+            if let Err(e) = app_state.faucet.prune_request_history().await {
+                error!("Failed to prune request history: {e:?}");
+            }
         }
     });
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -132,13 +139,19 @@ async fn health() -> &'static str {
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
+    let ip = client_ip(addr.ip(), &headers, &state.config.trusted_proxies);
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            if let Err(e) = state.faucet.admit(id, requests.recipient, Some(ip)).await {
+                return (error_status(&e), Json(FaucetResponse::from(e)));
+            }
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the reclycing of coins
             spawn_monitored_task!(async move {
@@ -170,6 +183,102 @@ async fn request_gas(
     }
 }
 
+/// handler for requests served in the background, which return the id of the task to poll for
+/// the status of the request
+async fn batch_request_gas(
+    Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new batch gas request.");
+    let ip = client_ip(addr.ip(), &headers, &state.config.trusted_proxies);
+    let FaucetRequest::FixedAmountRequest(request) = payload;
+    if let Err(e) = state.faucet.admit(id, request.recipient, Some(ip)).await {
+        warn!(uuid = ?id, "Failed to admit gas request: {:?}", e);
+        return (error_status(&e), Json(BatchFaucetResponse::from(e)));
+    }
+
+    spawn_monitored_task!(async move {
+        let result = state
+            .faucet
+            .send(
+                id,
+                request.recipient,
+                &vec![state.config.amount; state.config.num_coins],
+            )
+            .await;
+        match result {
+            Ok(_) => info!(uuid = ?id, "Batch request is successfully served"),
+            Err(e) => warn!(uuid = ?id, "Failed to serve batch gas request: {:?}", e),
+        }
+    });
+    (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id)))
+}
+
+/// handler for polling the status of a request
+async fn request_status(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> impl IntoResponse {
+    let Ok(id) = Uuid::parse_str(&task_id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(BatchStatusFaucetResponse {
+                status: None,
+                error: Some(format!("invalid task id {task_id}")),
+            }),
+        );
+    };
+    match state.faucet.status(id).await {
+        Ok(Some(status)) => (
+            StatusCode::OK,
+            Json(BatchStatusFaucetResponse::from(status)),
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(BatchStatusFaucetResponse {
+                status: None,
+                error: Some(format!("unknown task {task_id}")),
+            }),
+        ),
+        Err(e) => (error_status(&e), Json(BatchStatusFaucetResponse::from(e))),
+    }
+}
+
+/// The IP of the client a request came from. Requests coming through a trusted proxy are attributed
+/// to the right-most address in their `X-Forwarded-For` header that is not a trusted proxy itself,
+/// as addresses left of it can be forged by the client.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+    let forwarded_for = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|ip| ip.trim().parse::<IpAddr>())
+        .collect::<Vec<_>>();
+    for ip in forwarded_for.into_iter().rev() {
+        match ip {
+            Ok(ip) if trusted_proxies.contains(&ip) => continue,
+            Ok(ip) => return ip,
+            // The header was not written by a proxy we trust, so nothing left of it can be used.
+            Err(_) => break,
+        }
+    }
+    peer
+}
+
+fn error_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -189,3 +298,43 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let client: IpAddr = "1.2.3.4".parse().unwrap();
+        let forged: IpAddr = "5.6.7.8".parse().unwrap();
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let inner_proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let trusted = [proxy, inner_proxy];
+
+        // The header is ignored unless the peer is a trusted proxy.
+        let headers = forwarded_for(&["5.6.7.8"]);
+        assert_eq!(client, client_ip(client, &headers, &trusted));
+        assert_eq!(client, client_ip(client, &forwarded_for(&[]), &[]));
+
+        // Addresses prepended by the client are ignored, as are trusted proxies.
+        let headers = forwarded_for(&["5.6.7.8, 1.2.3.4", "10.0.0.2"]);
+        assert_eq!(client, client_ip(proxy, &headers, &trusted));
+        let headers = forwarded_for(&["5.6.7.8, 1.2.3.4, 10.0.0.2"]);
+        assert_eq!(client, client_ip(proxy, &headers, &trusted));
+        let headers = forwarded_for(&["5.6.7.8"]);
+        assert_eq!(forged, client_ip(proxy, &headers, &trusted));
+
+        // Without a usable address, the request is attributed to the proxy.
+        assert_eq!(proxy, client_ip(proxy, &forwarded_for(&[]), &trusted));
+        let headers = forwarded_for(&["1.2.3.4, garbage"]);
+        assert_eq!(proxy, client_ip(proxy, &headers, &trusted));
+    }
+}
@@ -27,6 +27,7 @@ pub struct FaucetMetrics {
     pub(crate) current_executions_in_flight: IntGauge,
     pub(crate) total_available_coins: IntGauge,
     pub(crate) total_discarded_coins: IntGauge,
+    pub(crate) total_requests_rate_limited: IntCounter,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -105,6 +106,12 @@ impl FaucetMetrics {
                 registry,
             )
             .unwrap(),
+            total_requests_rate_limited: register_int_counter_with_registry!(
+                "total_requests_rate_limited",
+                "Total number of requests rejected because a recipient or IP exhausted its quota",
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
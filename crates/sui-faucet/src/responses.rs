@@ -3,6 +3,7 @@
 
 use crate::*;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -28,3 +29,54 @@ impl From<FaucetReceipt> for FaucetResponse {
         }
     }
 }
+
+/// Response to a request served in the background, identifying the task to poll for its status.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFaucetResponse {
+    pub task: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for BatchFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            task: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<Uuid> for BatchFaucetResponse {
+    fn from(id: Uuid) -> Self {
+        Self {
+            task: Some(id.to_string()),
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusFaucetResponse {
+    pub status: Option<BatchSendStatus>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for BatchStatusFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            status: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<BatchSendStatus> for BatchStatusFaucetResponse {
+    fn from(status: BatchSendStatus) -> Self {
+        Self {
+            status: Some(status),
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS dynamic_fields;
+DROP TYPE IF EXISTS dynamic_field_type;
@@ -0,0 +1,30 @@
+DO
+$$
+    BEGIN
+        CREATE TYPE dynamic_field_type AS ENUM ('dynamic_field', 'dynamic_object');
+    EXCEPTION
+        WHEN duplicate_object THEN
+            -- Type already exists, do nothing
+            NULL;
+    END
+$$;
+
+-- Latest version of the dynamic fields of each parent object, a row per
+-- sui::dynamic_field::Field object, deleted when the field object is deleted or wrapped.
+CREATE TABLE dynamic_fields
+(
+    object_id        address            PRIMARY KEY,
+    parent_object_id address            NOT NULL,
+    version          BIGINT             NOT NULL,
+    object_digest    base58digest       NOT NULL,
+    object_type      VARCHAR            NOT NULL,
+    -- type of the field name, for dynamic object fields the type wrapped by
+    -- sui::dynamic_object_field::Wrapper.
+    name_type        VARCHAR            NOT NULL,
+    field_type       dynamic_field_type NOT NULL,
+    checkpoint       BIGINT             NOT NULL,
+    -- BCS of the field object, the name value is decoded from it on reads
+    -- as its layout needs the package of the name type.
+    bcs              bytea              NOT NULL
+);
+CREATE INDEX dynamic_fields_parent_object_id ON dynamic_fields (parent_object_id, object_id);
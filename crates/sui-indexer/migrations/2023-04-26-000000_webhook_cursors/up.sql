@@ -0,0 +1,8 @@
+-- Delivery progress of each webhook subscription, the cursor is the id of the
+-- last event or the digest of the last transaction acknowledged by the endpoint.
+CREATE TABLE webhook_cursors
+(
+    name       VARCHAR PRIMARY KEY,
+    cursor     VARCHAR NOT NULL,
+    updated_at BIGINT  NOT NULL
+);
@@ -0,0 +1,18 @@
+DROP VIEW IF EXISTS epoch_move_call_metrics;
+DROP VIEW IF EXISTS network_metrics;
+DROP TABLE IF EXISTS validators;
+DROP TABLE IF EXISTS system_states;
+DROP TABLE IF EXISTS epochs;
+DROP TABLE IF EXISTS input_objects;
+DROP TABLE IF EXISTS recipients;
+DROP TABLE IF EXISTS move_calls;
+DROP TABLE IF EXISTS packages;
+DROP TRIGGER IF EXISTS objects_history_delete;
+DROP TRIGGER IF EXISTS objects_history_update;
+DROP TRIGGER IF EXISTS objects_history_insert;
+DROP TABLE IF EXISTS objects_history;
+DROP TABLE IF EXISTS objects;
+DROP TABLE IF EXISTS addresses;
+DROP TABLE IF EXISTS events;
+DROP TABLE IF EXISTS transactions;
+DROP TABLE IF EXISTS checkpoints;
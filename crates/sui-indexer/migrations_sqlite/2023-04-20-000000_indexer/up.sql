@@ -0,0 +1,232 @@
+-- SQLite flavor of the indexer schema. Columns that queries filter or sort on are stored as
+-- regular columns, the complete row is stored BCS-encoded in the `data` column.
+
+CREATE TABLE checkpoints
+(
+    sequence_number            INTEGER PRIMARY KEY,
+    checkpoint_digest          TEXT    NOT NULL UNIQUE,
+    epoch                      INTEGER NOT NULL,
+    total_transactions         INTEGER NOT NULL,
+    network_total_transactions INTEGER NOT NULL,
+    timestamp_ms               INTEGER NOT NULL,
+    data                       BLOB    NOT NULL
+);
+CREATE INDEX checkpoints_epoch ON checkpoints (epoch);
+CREATE INDEX checkpoints_timestamp ON checkpoints (timestamp_ms);
+
+CREATE TABLE transactions
+(
+    id                         INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_digest         TEXT    NOT NULL UNIQUE,
+    sender                     TEXT    NOT NULL,
+    checkpoint_sequence_number INTEGER,
+    timestamp_ms               INTEGER,
+    transaction_kind           TEXT    NOT NULL,
+    transaction_count          INTEGER NOT NULL,
+    -- JSON array of the created, mutated and unwrapped object IDs.
+    changed_objects            TEXT    NOT NULL,
+    data                       BLOB    NOT NULL
+);
+CREATE INDEX transactions_timestamp_ms ON transactions (timestamp_ms);
+CREATE INDEX transactions_sender ON transactions (sender);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+CREATE INDEX transactions_transaction_kind ON transactions (transaction_kind);
+
+CREATE TABLE events
+(
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_digest TEXT    NOT NULL,
+    event_sequence     INTEGER NOT NULL,
+    sender             TEXT    NOT NULL,
+    package            TEXT    NOT NULL,
+    module             TEXT    NOT NULL,
+    event_type         TEXT    NOT NULL,
+    event_time_ms      INTEGER,
+    data               BLOB    NOT NULL,
+    UNIQUE (transaction_digest, event_sequence)
+);
+CREATE INDEX events_sender ON events (sender);
+CREATE INDEX events_package ON events (package, module);
+CREATE INDEX events_event_type ON events (event_type);
+CREATE INDEX events_event_time_ms ON events (event_time_ms);
+
+CREATE TABLE addresses
+(
+    account_address       TEXT PRIMARY KEY,
+    first_appearance_tx   TEXT    NOT NULL,
+    first_appearance_time INTEGER NOT NULL
+);
+
+CREATE TABLE objects
+(
+    object_id     TEXT PRIMARY KEY,
+    version       INTEGER NOT NULL,
+    checkpoint    INTEGER NOT NULL,
+    owner_type    TEXT    NOT NULL,
+    owner_address TEXT,
+    object_type   TEXT    NOT NULL,
+    object_status TEXT    NOT NULL,
+    data          BLOB    NOT NULL
+);
+CREATE INDEX objects_owner_address ON objects (owner_type, owner_address);
+
+CREATE TABLE objects_history
+(
+    object_id         TEXT    NOT NULL,
+    version           INTEGER NOT NULL,
+    checkpoint        INTEGER NOT NULL,
+    owner_type        TEXT    NOT NULL,
+    owner_address     TEXT,
+    old_owner_type    TEXT,
+    old_owner_address TEXT,
+    object_type       TEXT    NOT NULL,
+    object_status     TEXT    NOT NULL,
+    data              BLOB    NOT NULL,
+    PRIMARY KEY (object_id, version, checkpoint)
+);
+CREATE INDEX objects_history_checkpoint_index ON objects_history (checkpoint);
+CREATE INDEX objects_history_owner_index ON objects_history (owner_type, owner_address);
+CREATE INDEX objects_history_old_owner_index ON objects_history (old_owner_type, old_owner_address);
+
+-- Same as the objects_history trigger of the Postgres schema, every write to the objects table
+-- is archived in objects_history, together with the previous owner on updates.
+CREATE TRIGGER objects_history_insert
+    AFTER INSERT
+    ON objects
+BEGIN
+    INSERT OR IGNORE INTO objects_history
+    VALUES (NEW.object_id, NEW.version, NEW.checkpoint, NEW.owner_type, NEW.owner_address, NULL, NULL,
+            NEW.object_type, NEW.object_status, NEW.data);
+END;
+
+CREATE TRIGGER objects_history_update
+    AFTER UPDATE
+    ON objects
+BEGIN
+    INSERT OR IGNORE INTO objects_history
+    VALUES (NEW.object_id, NEW.version, NEW.checkpoint, NEW.owner_type, NEW.owner_address,
+            OLD.owner_type, OLD.owner_address, NEW.object_type, NEW.object_status, NEW.data);
+END;
+
+CREATE TRIGGER objects_history_delete
+    AFTER DELETE
+    ON objects
+BEGIN
+    DELETE FROM objects_history WHERE object_id = OLD.object_id;
+END;
+
+CREATE TABLE packages
+(
+    package_id TEXT    NOT NULL,
+    version    INTEGER NOT NULL,
+    author     TEXT    NOT NULL,
+    data       BLOB    NOT NULL,
+    PRIMARY KEY (package_id, version)
+);
+
+CREATE TABLE move_calls
+(
+    id                         INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_digest         TEXT    NOT NULL,
+    checkpoint_sequence_number INTEGER NOT NULL,
+    epoch                      INTEGER NOT NULL,
+    sender                     TEXT    NOT NULL,
+    move_package               TEXT    NOT NULL,
+    move_module                TEXT    NOT NULL,
+    move_function              TEXT    NOT NULL
+);
+CREATE INDEX move_calls_transaction_digest ON move_calls (transaction_digest);
+CREATE INDEX move_calls_move_package ON move_calls (move_package, move_module, move_function);
+
+CREATE TABLE recipients
+(
+    id                         INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_digest         TEXT    NOT NULL,
+    checkpoint_sequence_number INTEGER NOT NULL,
+    epoch                      INTEGER NOT NULL,
+    sender                     TEXT    NOT NULL,
+    recipient                  TEXT    NOT NULL
+);
+CREATE INDEX recipients_transaction_digest ON recipients (transaction_digest);
+CREATE INDEX recipients_recipient ON recipients (recipient);
+
+CREATE TABLE input_objects
+(
+    id                         INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_digest         TEXT    NOT NULL,
+    checkpoint_sequence_number INTEGER NOT NULL,
+    epoch                      INTEGER NOT NULL,
+    object_id                  TEXT    NOT NULL,
+    object_version             INTEGER
+);
+CREATE INDEX input_objects_transaction_digest ON input_objects (transaction_digest);
+CREATE INDEX input_objects_object_id ON input_objects (object_id);
+
+CREATE TABLE epochs
+(
+    epoch                    INTEGER PRIMARY KEY,
+    epoch_start_timestamp    INTEGER NOT NULL,
+    -- kept out of `data`, it is incremented as checkpoints of the epoch are committed.
+    epoch_total_transactions INTEGER NOT NULL,
+    data                     BLOB    NOT NULL
+);
+
+CREATE TABLE system_states
+(
+    epoch INTEGER PRIMARY KEY,
+    data  BLOB NOT NULL
+);
+
+CREATE TABLE validators
+(
+    epoch       INTEGER NOT NULL,
+    sui_address TEXT    NOT NULL,
+    data        BLOB    NOT NULL,
+    PRIMARY KEY (epoch, sui_address)
+);
+
+CREATE VIEW network_metrics AS
+SELECT (SELECT CAST(COALESCE(SUM(transaction_count), 0) AS REAL) / 10
+        FROM transactions
+        WHERE timestamp_ms >
+              (SELECT timestamp_ms FROM checkpoints ORDER BY sequence_number DESC LIMIT 1) - 10000) AS current_tps,
+       (SELECT COALESCE(CAST(SUM(total_transactions) AS REAL) * 1000 /
+                        NULLIF(MAX(timestamp_ms) - MIN(timestamp_ms), 0), 0.0)
+        FROM checkpoints
+        WHERE timestamp_ms > (CAST(strftime('%s', 'now') AS INTEGER) - 30 * 86400) * 1000)  AS tps_30_days,
+       (SELECT COUNT(1) FROM addresses)                                                      AS total_addresses,
+       (SELECT COUNT(1) FROM objects)                                                        AS total_objects,
+       (SELECT COUNT(1) FROM packages)                                                       AS total_packages,
+       (SELECT COALESCE(MAX(epoch), 0) FROM epochs)                                          AS current_epoch,
+       (SELECT COALESCE(MAX(sequence_number), 0) FROM checkpoints)                           AS current_checkpoint;
+
+CREATE VIEW epoch_move_call_metrics AS
+SELECT *
+FROM (SELECT 3 AS day, move_package, move_module, move_function, COUNT(*) AS count
+      FROM move_calls
+      WHERE epoch >= (SELECT MIN(epoch)
+                      FROM epochs
+                      WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS INTEGER) - 3 * 86400) * 1000)
+      GROUP BY move_package, move_module, move_function
+      ORDER BY count DESC
+      LIMIT 10)
+UNION ALL
+SELECT *
+FROM (SELECT 7 AS day, move_package, move_module, move_function, COUNT(*) AS count
+      FROM move_calls
+      WHERE epoch >= (SELECT MIN(epoch)
+                      FROM epochs
+                      WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS INTEGER) - 7 * 86400) * 1000)
+      GROUP BY move_package, move_module, move_function
+      ORDER BY count DESC
+      LIMIT 10)
+UNION ALL
+SELECT *
+FROM (SELECT 30 AS day, move_package, move_module, move_function, COUNT(*) AS count
+      FROM move_calls
+      WHERE epoch >= (SELECT MIN(epoch)
+                      FROM epochs
+                      WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS INTEGER) - 30 * 86400) * 1000)
+      GROUP BY move_package, move_module, move_function
+      ORDER BY count DESC
+      LIMIT 10);
@@ -0,0 +1,8 @@
+CREATE TABLE dynamic_fields
+(
+    object_id        TEXT PRIMARY KEY,
+    parent_object_id TEXT    NOT NULL,
+    version          INTEGER NOT NULL,
+    data             BLOB    NOT NULL
+);
+CREATE INDEX dynamic_fields_parent_object_id ON dynamic_fields (parent_object_id, object_id);
@@ -0,0 +1,6 @@
+CREATE TABLE webhook_cursors
+(
+    name       TEXT PRIMARY KEY,
+    cursor     TEXT    NOT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -9,11 +9,11 @@ use futures::executor::block_on;
 use futures::future::join_all;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 
-use move_core_types::identifier::Identifier;
-use sui_core::event_handler::EventHandler;
+use sui_core::subscription_handler::SubscriptionHandler;
 use sui_json_rpc::api::{
     validate_limit, IndexerApiClient, IndexerApiServer, QUERY_MAX_RESULT_LIMIT,
     QUERY_MAX_RESULT_LIMIT_OBJECTS,
@@ -21,9 +21,9 @@ use sui_json_rpc::api::{
 use sui_json_rpc::indexer_api::spawn_subscription;
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataFilter,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponseQuery,
-    TransactionBlocksPage,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, PastObjectsPage,
+    SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    SuiPastObjectResponse, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -31,14 +31,18 @@ use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
 use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 use crate::errors::IndexerError;
-use crate::store::IndexerStore;
+use crate::store::{
+    get_dynamic_field_object_id, get_dynamic_field_page, get_transaction_page_by_query,
+    IndexerStore,
+};
 
 pub(crate) struct IndexerApi<S> {
     state: S,
     fullnode: HttpClient,
-    event_handler: Arc<EventHandler>,
+    subscription_handler: Arc<SubscriptionHandler>,
     migrated_methods: Vec<String>,
 }
 
@@ -46,13 +50,13 @@ impl<S: IndexerStore> IndexerApi<S> {
     pub fn new(
         state: S,
         fullnode_client: HttpClient,
-        event_handler: Arc<EventHandler>,
+        subscription_handler: Arc<SubscriptionHandler>,
         migrated_methods: Vec<String>,
     ) -> Self {
         Self {
             state,
             fullnode: fullnode_client,
-            event_handler,
+            subscription_handler,
             migrated_methods,
         }
     }
@@ -69,6 +73,32 @@ impl<S: IndexerStore> IndexerApi<S> {
             .await
     }
 
+    async fn get_dynamic_fields_internal(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<DynamicFieldPage, IndexerError> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
+        get_dynamic_field_page(&self.state, parent_object_id, cursor, limit).await
+    }
+
+    async fn get_dynamic_field_object_internal(
+        &self,
+        parent_object_id: ObjectID,
+        name: DynamicFieldName,
+    ) -> Result<SuiObjectResponse, IndexerError> {
+        let object_id = get_dynamic_field_object_id(&self.state, parent_object_id, &name)
+            .await?
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Cannot find dynamic field [{name:?}] for object [{parent_object_id}]."
+                ))
+            })?;
+        let read = self.state.get_object(object_id, None).await?;
+        Ok((read, SuiObjectDataOptions::full_content()).try_into()?)
+    }
+
     async fn query_transaction_blocks_internal(
         &self,
         query: SuiTransactionBlockResponseQuery,
@@ -79,155 +109,14 @@ impl<S: IndexerStore> IndexerApi<S> {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
         let is_descending = descending_order.unwrap_or_default();
         let cursor_str = cursor.map(|digest| digest.to_string());
-        let mut tx_vec_from_db = match query.filter {
-            None => {
-                let indexer_seq_number = self
-                    .state
-                    .get_transaction_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_all_transaction_page(indexer_seq_number, limit + 1, is_descending)
-                    .await
-            }
-            Some(TransactionFilter::Checkpoint(checkpoint_id)) => {
-                let indexer_seq_number = self
-                    .state
-                    .get_transaction_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_checkpoint(
-                        checkpoint_id as i64,
-                        indexer_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::MoveFunction {
-                package,
-                module,
-                function,
-            }) => {
-                let module = if let Some(m) = module {
-                    Some(
-                        Identifier::new(m)
-                            .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?,
-                    )
-                } else {
-                    None
-                };
-                let function = if let Some(f) = function {
-                    Some(
-                        Identifier::new(f)
-                            .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?,
-                    )
-                } else {
-                    None
-                };
-                let move_call_seq_number = self
-                    .state
-                    .get_move_call_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_move_call(
-                        package,
-                        module,
-                        function,
-                        move_call_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::InputObject(input_obj_id)) => {
-                let input_obj_seq = self
-                    .state
-                    .get_input_object_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_input_object(
-                        input_obj_id,
-                        /* version */ None,
-                        input_obj_seq,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::ChangedObject(mutated_obj_id)) => {
-                let indexer_seq_number = self
-                    .state
-                    .get_transaction_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_mutated_object(
-                        mutated_obj_id.to_string(),
-                        indexer_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            // NOTE: more efficient to run this query over transactions table
-            Some(TransactionFilter::FromAddress(sender_address)) => {
-                let indexer_seq_number = self
-                    .state
-                    .get_transaction_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_sender_address(
-                        sender_address.to_string(),
-                        indexer_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::ToAddress(recipient_address)) => {
-                let recipient_seq_number = self
-                    .state
-                    .get_recipient_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_sender_recipient_address(
-                        /* from */ None,
-                        recipient_address,
-                        recipient_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::FromAndToAddress { from, to }) => {
-                let recipient_seq_number = self
-                    .state
-                    .get_recipient_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_sender_recipient_address(
-                        Some(from),
-                        to,
-                        recipient_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-            Some(TransactionFilter::TransactionKind(tx_kind_name)) => {
-                let indexer_seq_number = self
-                    .state
-                    .get_transaction_sequence_by_digest(cursor_str, is_descending)
-                    .await?;
-                self.state
-                    .get_transaction_page_by_transaction_kind(
-                        tx_kind_name,
-                        indexer_seq_number,
-                        limit + 1,
-                        is_descending,
-                    )
-                    .await
-            }
-        }?;
+        let mut tx_vec_from_db = get_transaction_page_by_query(
+            &self.state,
+            query.filter,
+            cursor_str,
+            limit + 1,
+            is_descending,
+        )
+        .await?;
 
         let has_next_page = tx_vec_from_db.len() > limit;
         tx_vec_from_db.truncate(limit);
@@ -311,6 +200,53 @@ impl<S: IndexerStore> IndexerApi<S> {
             has_next_page,
         })
     }
+
+    async fn get_owned_objects_at_checkpoint_internal(
+        &self,
+        address: SuiAddress,
+        checkpoint: u64,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<PastObjectsPage> {
+        let options = options.unwrap_or_default();
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
+        let latest_checkpoint = self.state.get_latest_checkpoint_sequence_number().await? as u64;
+        if checkpoint > latest_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Checkpoint {checkpoint} is not indexed yet, the latest indexed checkpoint is {latest_checkpoint}"
+            ))
+            .into());
+        }
+
+        // NOTE: fetch one more object to check if there is next page
+        let mut objects = self
+            .state
+            .query_objects_history(
+                SuiObjectDataFilter::AddressOwner(address),
+                checkpoint,
+                cursor,
+                limit + 1,
+            )
+            .await?;
+
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+        let next_cursor = objects
+            .last()
+            .map_or(cursor, |o_read| Some(o_read.object_id()));
+
+        let data: Vec<SuiPastObjectResponse> = objects
+            .into_iter()
+            .map(|o| (o, options.clone()).try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
 }
 
 #[async_trait]
@@ -337,6 +273,27 @@ where
         block_on(self.get_owned_objects_internal(address, query, cursor, limit))
     }
 
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        checkpoint: BigInt<u64>,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<PastObjectsPage> {
+        if !self
+            .migrated_methods
+            .contains(&"get_owned_objects_at_checkpoint".to_string())
+        {
+            return self
+                .fullnode
+                .get_owned_objects_at_checkpoint(address, checkpoint, options, cursor, limit)
+                .await;
+        }
+        self.get_owned_objects_at_checkpoint_internal(address, *checkpoint, options, cursor, limit)
+            .await
+    }
+
     fn query_transaction_blocks(
         &self,
         query: SuiTransactionBlockResponseQuery,
@@ -391,10 +348,20 @@ where
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> RpcResult<DynamicFieldPage> {
-        block_on(
-            self.fullnode
-                .get_dynamic_fields(parent_object_id, cursor, limit),
-        )
+        if !self
+            .migrated_methods
+            .contains(&"get_dynamic_fields".to_string())
+        {
+            return block_on(
+                self.fullnode
+                    .get_dynamic_fields(parent_object_id, cursor, limit),
+            );
+        }
+        Ok(block_on(self.get_dynamic_fields_internal(
+            parent_object_id,
+            cursor,
+            limit,
+        ))?)
     }
 
     fn get_dynamic_field_object(
@@ -402,14 +369,41 @@ where
         parent_object_id: ObjectID,
         name: DynamicFieldName,
     ) -> RpcResult<SuiObjectResponse> {
-        block_on(
-            self.fullnode
-                .get_dynamic_field_object(parent_object_id, name),
-        )
+        if !self
+            .migrated_methods
+            .contains(&"get_dynamic_field_object".to_string())
+        {
+            return block_on(
+                self.fullnode
+                    .get_dynamic_field_object(parent_object_id, name),
+            );
+        }
+        Ok(block_on(self.get_dynamic_field_object_internal(
+            parent_object_id,
+            name,
+        ))?)
     }
 
     fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.event_handler.subscribe(filter));
+        spawn_subscription(sink, self.subscription_handler.subscribe_events(filter));
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        mut sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        if filter.requires_checkpoint() {
+            sink.reject(CallError::InvalidParams(anyhow!(
+                "Checkpoint and time range filters are not supported for transaction subscription"
+            )))?;
+            return Ok(());
+        }
+        spawn_subscription(
+            sink,
+            self.subscription_handler.subscribe_transactions(filter),
+        );
         Ok(())
     }
 }
@@ -114,6 +114,27 @@ impl<S: IndexerStore> ReadApi<S> {
         Ok((read, options.unwrap_or_default()).try_into()?)
     }
 
+    async fn get_object_at_checkpoint_internal(
+        &self,
+        object_id: ObjectID,
+        checkpoint: u64,
+        options: Option<SuiObjectDataOptions>,
+    ) -> Result<SuiPastObjectResponse, IndexerError> {
+        let latest_checkpoint = self
+            .get_latest_checkpoint_sequence_number_internal()
+            .await?;
+        if checkpoint > latest_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Checkpoint {checkpoint} is not indexed yet, the latest indexed checkpoint is {latest_checkpoint}"
+            )));
+        }
+        let read = self
+            .state
+            .get_object_at_checkpoint(object_id, checkpoint)
+            .await?;
+        Ok((read, options.unwrap_or_default()).try_into()?)
+    }
+
     async fn get_latest_checkpoint_sequence_number_internal(&self) -> Result<u64, IndexerError> {
         self.state
             .get_latest_checkpoint_sequence_number()
@@ -211,6 +232,26 @@ where
         )
     }
 
+    async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: BigInt<u64>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        if !self
+            .migrated_methods
+            .contains(&"get_object_at_checkpoint".to_string())
+        {
+            return self
+                .fullnode
+                .get_object_at_checkpoint(object_id, checkpoint, options)
+                .await;
+        }
+        Ok(self
+            .get_object_at_checkpoint_internal(object_id, *checkpoint, options)
+            .await?)
+    }
+
     async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
         if !self
             .migrated_methods
@@ -1,17 +1,37 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
-use clap::Parser;
-use tracing::{error, info, warn};
+//! Walks a checkpoint range and compares what a fullnode and an indexer serve for it over
+//! JSON-RPC: checkpoints, transactions with their effects, events and object and balance
+//! changes, the event index, the object versions written and the owner indexes as of each
+//! checkpoint. Diffs are reported by category as JSON lines, and the next checkpoint to check is
+//! saved after each checkpoint so that an interrupted run can be resumed.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::{ArgEnum, Parser};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
 
 use sui_indexer::new_rpc_client;
 use sui_json_rpc_types::{
-    CheckpointId, ObjectChange, SuiObjectDataOptions, SuiTransactionBlockResponseOptions,
+    CheckpointId, ObjectChange, SuiGetPastObjectRequest, SuiObjectDataOptions,
+    SuiPastObjectResponse, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
+use sui_sdk::error::SuiRpcResult;
 use sui_sdk::SuiClient;
-use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
 use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Owner;
+
+// Number of differing JSON paths kept in a diff, the full values are in the diff anyway.
+const MAX_DIFF_PATHS: usize = 20;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -21,190 +41,744 @@ async fn main() -> Result<()> {
         .init();
     info!("Running correctness check for indexer...");
     let config = TestConfig::parse();
-    let fn_rpc_client = new_rpc_client(&config.fn_rpc_client_url).await?;
-    let indexer_rpc_client = new_rpc_client(&config.indexer_rpc_client_url).await?;
+    let fullnode = new_rpc_client(&config.fn_rpc_client_url).await?;
+    let indexer = new_rpc_client(&config.indexer_rpc_client_url).await?;
 
-    let fn_latest_checkpoint = fn_rpc_client
+    let fn_latest_checkpoint = fullnode
         .read_api()
         .get_latest_checkpoint_sequence_number()
         .await?;
-    let indexer_latest_checkpoint = indexer_rpc_client
+    let indexer_latest_checkpoint = indexer
         .read_api()
         .get_latest_checkpoint_sequence_number()
         .await?;
+    let latest_checkpoint = fn_latest_checkpoint.min(indexer_latest_checkpoint);
+    let end_checkpoint = config.end_checkpoint.unwrap_or(latest_checkpoint);
+    if end_checkpoint > latest_checkpoint {
+        return Err(anyhow!(
+            "End checkpoint {end_checkpoint} is not available in both FN and Indexer, FN latest: {fn_latest_checkpoint}, indexer latest: {indexer_latest_checkpoint}"
+        ));
+    }
+    let cursor_path = config.cursor_path.as_deref();
+    let start_checkpoint = resume_checkpoint(cursor_path, config.start_checkpoint)?;
 
-    let end_checkpoint = config.end_checkpoint;
-    if end_checkpoint <= fn_latest_checkpoint && end_checkpoint <= indexer_latest_checkpoint {
-        for checkpoint in config.start_checkpoint..=end_checkpoint {
-            info!("Checking checkpoint {}", checkpoint);
-            check_checkpoint(&config, &fn_rpc_client, &indexer_rpc_client, checkpoint).await?;
-        }
-    } else {
-        error!("Start checkpoint is not available in both FN and Indexer, start: {}, FN latest: {}, indexer latest: {}", 
-        end_checkpoint, fn_latest_checkpoint, indexer_latest_checkpoint);
+    let report = Report::new(config.report_path.as_deref())?;
+    let mut validator = Validator {
+        checks: config.checks.iter().copied().collect(),
+        fullnode,
+        indexer,
+        report,
+    };
+    info!(
+        "Checking checkpoints {} to {}",
+        start_checkpoint, end_checkpoint
+    );
+    let mut checkpoint = start_checkpoint;
+    while checkpoint <= end_checkpoint {
+        validator.check_checkpoint(checkpoint).await?;
+        validator.report.flush()?;
+        checkpoint = checkpoint_checked(cursor_path, checkpoint)?;
     }
+    validator.report.summarize();
     Ok(())
 }
 
-pub async fn check_checkpoint(
-    config: &TestConfig,
-    fn_client: &SuiClient,
-    indexer_client: &SuiClient,
-    checkpoint: u64,
-) -> Result<(), anyhow::Error> {
-    let fn_checkpoint = fn_client
-        .read_api()
-        .get_checkpoint(CheckpointId::SequenceNumber(checkpoint))
-        .await?;
-    let indexer_checkpoint = indexer_client
-        .read_api()
-        .get_checkpoint(CheckpointId::SequenceNumber(checkpoint))
-        .await?;
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Check {
+    /// Checkpoint summaries and contents.
+    Checkpoints,
+    /// Transactions with their effects, events, object changes and balance changes.
+    Transactions,
+    /// Events of each transaction as queried from the event index.
+    Events,
+    /// Object versions written by each transaction.
+    Objects,
+    /// Objects owned as of each checkpoint by the addresses it changed objects of.
+    Owners,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffCategory {
+    /// Read by one source only, the other one failing or not having the data.
+    Missing,
+    Checkpoint,
+    /// Transaction input data, checkpoint and timestamp.
+    Transaction,
+    Effects,
+    Events,
+    ObjectChanges,
+    BalanceChanges,
+    EventIndex,
+    ObjectVersion,
+    OwnerIndex,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diff {
+    pub category: DiffCategory,
+    pub checkpoint: CheckpointSequenceNumber,
+    /// What was compared, e.g. the transaction digest.
+    pub key: String,
+    /// The JSON paths at which the values differ.
+    pub paths: Vec<String>,
+    pub fullnode: Value,
+    pub indexer: Value,
+}
+
+/// Counts the diffs by category and writes them as JSON lines.
+struct Report {
+    writer: Option<File>,
+    counts: BTreeMap<DiffCategory, u64>,
+}
 
-    if fn_checkpoint != indexer_checkpoint {
-        error!("Checkpoint mismatch found in {}", checkpoint);
+impl Report {
+    fn new(path: Option<&Path>) -> Result<Self> {
+        // appending, so that resumed runs keep the diffs found before.
+        let writer = path
+            .map(|path| OpenOptions::new().create(true).append(true).open(path))
+            .transpose()?;
+        Ok(Self {
+            writer,
+            counts: BTreeMap::new(),
+        })
+    }
+
+    fn add(&mut self, diff: Diff) -> Result<()> {
         warn!(
-            "Checkpoint mismatch with checkpoint seq {:?}:\nFN:\n{:?}\nIndexer:\n{:?} ",
-            checkpoint, fn_checkpoint, indexer_checkpoint
+            "{:?} mismatch in checkpoint {} for {} at {:?}",
+            diff.category, diff.checkpoint, diff.key, diff.paths
         );
+        *self.counts.entry(diff.category).or_default() += 1;
+        if let Some(writer) = &mut self.writer {
+            serde_json::to_writer(&mut *writer, &diff)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
     }
-    if config.check_transactions {
-        check_transactions(
-            config,
-            fn_client,
-            indexer_client,
-            fn_checkpoint.transactions,
-        )
-        .await?;
+
+    /// Returns both values when both sources could be read, records a [DiffCategory::Missing]
+    /// diff when only one of them could.
+    fn both_read<T: Serialize>(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        key: &str,
+        fullnode: SuiRpcResult<T>,
+        indexer: SuiRpcResult<T>,
+    ) -> Result<Option<(T, T)>> {
+        match (fullnode, indexer) {
+            (Ok(fullnode), Ok(indexer)) => Ok(Some((fullnode, indexer))),
+            (Err(fn_err), Err(indexer_err)) => Err(anyhow!(
+                "Failed reading {key} from both FN: {fn_err} and Indexer: {indexer_err}"
+            )),
+            (fullnode, indexer) => {
+                self.add(Diff {
+                    category: DiffCategory::Missing,
+                    checkpoint,
+                    key: key.to_string(),
+                    paths: vec![],
+                    fullnode: read_result_to_json(fullnode)?,
+                    indexer: read_result_to_json(indexer)?,
+                })?;
+                Ok(None)
+            }
+        }
     }
-    Ok(())
-}
 
-pub async fn check_transactions(
-    config: &TestConfig,
-    fn_client: &SuiClient,
-    indexer_client: &SuiClient,
-    tx_vec: Vec<TransactionDigest>,
-) -> Result<(), anyhow::Error> {
-    for digest in tx_vec {
-        let fetch_options = SuiTransactionBlockResponseOptions::full_content();
-        let fn_sui_tx_response = fn_client
-            .read_api()
-            .get_transaction_with_options(digest, fetch_options.clone())
-            .await?;
-        let indexer_sui_tx_response = indexer_client
-            .read_api()
-            .get_transaction_with_options(digest, fetch_options)
-            .await?;
-        if fn_sui_tx_response != indexer_sui_tx_response {
-            error!("Checkpoint transactions mismatch found in {}", digest);
-            warn!(
-                "Transaction response mismatch with digest {:?}:\nFN:\n{:?}\nIndexer:\n{:?} ",
-                digest, fn_sui_tx_response, indexer_sui_tx_response
-            );
-            continue;
+    fn compare<T: Serialize>(
+        &mut self,
+        category: DiffCategory,
+        checkpoint: CheckpointSequenceNumber,
+        key: &str,
+        fullnode: &T,
+        indexer: &T,
+    ) -> Result<()> {
+        let (fullnode, indexer) = (
+            serde_json::to_value(fullnode)?,
+            serde_json::to_value(indexer)?,
+        );
+        if fullnode == indexer {
+            return Ok(());
+        }
+        let mut paths = vec![];
+        diff_paths(&fullnode, &indexer, "$".to_string(), &mut paths);
+        self.add(Diff {
+            category,
+            checkpoint,
+            key: key.to_string(),
+            paths,
+            fullnode,
+            indexer,
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = &mut self.writer {
+            writer.flush()?;
         }
-        if config.check_events {
-            check_events(fn_client, indexer_client, digest).await?;
+        Ok(())
+    }
+
+    fn summarize(&self) {
+        if self.counts.is_empty() {
+            info!("No mismatch found");
         }
-        if config.check_objects {
-            let objects = fn_sui_tx_response.object_changes.unwrap();
-            check_objects(fn_client, indexer_client, objects).await?;
+        for (category, count) in &self.counts {
+            info!("{:?} mismatches: {}", category, count);
         }
     }
-    Ok(())
 }
 
-pub async fn check_events(
-    fn_client: &SuiClient,
-    indexer_client: &SuiClient,
-    tx_digest: TransactionDigest,
-) -> Result<(), anyhow::Error> {
-    let mut fn_events = fn_client.event_api().get_events(tx_digest).await?;
-    fn_events.sort_by(|a, b| a.id.event_seq.cmp(&b.id.event_seq));
-    let mut indexer_events = indexer_client.event_api().get_events(tx_digest).await?;
-    indexer_events.sort_by(|a, b| a.id.event_seq.cmp(&b.id.event_seq));
-
-    for (fn_event, indexer_event) in fn_events.iter().zip(indexer_events.iter()) {
-        if fn_event != indexer_event {
-            error!(
-                "Checkpoint events mismatch found in {:?}, with sequence number: {}",
-                tx_digest, fn_event.id.event_seq
-            );
-            warn!(
-                "Event mismatch with digest {:?} and seq nunber:\nFN:\n{:?}\nIndexer:\n{:?} ",
-                tx_digest, fn_event, indexer_event
-            );
+struct Validator {
+    checks: BTreeSet<Check>,
+    fullnode: SuiClient,
+    indexer: SuiClient,
+    report: Report,
+}
+
+impl Validator {
+    async fn check_checkpoint(&mut self, checkpoint: CheckpointSequenceNumber) -> Result<()> {
+        info!("Checking checkpoint {}", checkpoint);
+        let id = CheckpointId::SequenceNumber(checkpoint);
+        let (fn_checkpoint, indexer_checkpoint) = tokio::join!(
+            self.fullnode.read_api().get_checkpoint(id.clone()),
+            self.indexer.read_api().get_checkpoint(id)
+        );
+        let key = format!("checkpoint {checkpoint}");
+        // the transactions to check are those of the fullnode checkpoint.
+        let Some((fn_checkpoint, indexer_checkpoint)) =
+            self.report.both_read(checkpoint, &key, fn_checkpoint, indexer_checkpoint)? else {
+            return Ok(());
+        };
+        if self.checks.contains(&Check::Checkpoints) {
+            self.report.compare(
+                DiffCategory::Checkpoint,
+                checkpoint,
+                &key,
+                &fn_checkpoint,
+                &indexer_checkpoint,
+            )?;
+        }
+
+        let mut owners = BTreeSet::new();
+        for digest in fn_checkpoint.transactions {
+            if let Some(object_changes) = self.check_transaction(checkpoint, digest).await? {
+                owners.extend(object_changes.iter().filter_map(changed_address_owner));
+            }
+        }
+        if self.checks.contains(&Check::Owners) {
+            for owner in owners {
+                self.check_owned_objects(checkpoint, owner).await?;
+            }
         }
+        Ok(())
+    }
+
+    /// Returns the object changes of the transaction according to the fullnode.
+    async fn check_transaction(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+    ) -> Result<Option<Vec<ObjectChange>>> {
+        let options = SuiTransactionBlockResponseOptions::full_content();
+        let (fn_tx, indexer_tx) = tokio::join!(
+            self.fullnode
+                .read_api()
+                .get_transaction_with_options(digest, options.clone()),
+            self.indexer
+                .read_api()
+                .get_transaction_with_options(digest, options)
+        );
+        let key = format!("transaction {digest}");
+        let Some((fn_tx, indexer_tx)) =
+            self.report.both_read(checkpoint, &key, fn_tx, indexer_tx)? else {
+            return Ok(None);
+        };
+        if self.checks.contains(&Check::Transactions) {
+            self.compare_transactions(checkpoint, &key, &fn_tx, &indexer_tx)?;
+        }
+        if self.checks.contains(&Check::Events) {
+            self.check_events(checkpoint, digest).await?;
+        }
+        let object_changes = fn_tx.object_changes.unwrap_or_default();
+        if self.checks.contains(&Check::Objects) {
+            self.check_objects(checkpoint, digest, &object_changes)
+                .await?;
+        }
+        Ok(Some(object_changes))
+    }
+
+    fn compare_transactions(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        key: &str,
+        fn_tx: &SuiTransactionBlockResponse,
+        indexer_tx: &SuiTransactionBlockResponse,
+    ) -> Result<()> {
+        self.report.compare(
+            DiffCategory::Transaction,
+            checkpoint,
+            key,
+            &(&fn_tx.transaction, fn_tx.checkpoint, fn_tx.timestamp_ms),
+            &(
+                &indexer_tx.transaction,
+                indexer_tx.checkpoint,
+                indexer_tx.timestamp_ms,
+            ),
+        )?;
+        self.report.compare(
+            DiffCategory::Effects,
+            checkpoint,
+            key,
+            &fn_tx.effects,
+            &indexer_tx.effects,
+        )?;
+        self.report.compare(
+            DiffCategory::Events,
+            checkpoint,
+            key,
+            &fn_tx.events,
+            &indexer_tx.events,
+        )?;
+        self.report.compare(
+            DiffCategory::ObjectChanges,
+            checkpoint,
+            key,
+            &fn_tx.object_changes,
+            &indexer_tx.object_changes,
+        )?;
+        self.report.compare(
+            DiffCategory::BalanceChanges,
+            checkpoint,
+            key,
+            &fn_tx.balance_changes,
+            &indexer_tx.balance_changes,
+        )
+    }
+
+    async fn check_events(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+    ) -> Result<()> {
+        let (fn_events, indexer_events) = tokio::join!(
+            self.fullnode.event_api().get_events(digest),
+            self.indexer.event_api().get_events(digest)
+        );
+        let key = format!("events of transaction {digest}");
+        let Some((mut fn_events, mut indexer_events)) =
+            self.report.both_read(checkpoint, &key, fn_events, indexer_events)? else {
+            return Ok(());
+        };
+        fn_events.sort_by_key(|e| e.id.event_seq);
+        indexer_events.sort_by_key(|e| e.id.event_seq);
+        self.report.compare(
+            DiffCategory::EventIndex,
+            checkpoint,
+            &key,
+            &fn_events,
+            &indexer_events,
+        )
+    }
+
+    async fn check_objects(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+        object_changes: &[ObjectChange],
+    ) -> Result<()> {
+        let requests = object_changes
+            .iter()
+            .filter_map(changed_object_version)
+            .map(|(object_id, version)| SuiGetPastObjectRequest { object_id, version })
+            .collect::<Vec<_>>();
+        if requests.is_empty() {
+            return Ok(());
+        }
+        let options = SuiObjectDataOptions::full_content();
+        let (fn_objects, indexer_objects) = tokio::join!(
+            self.fullnode
+                .read_api()
+                .try_multi_get_parsed_past_object(requests.clone(), options.clone()),
+            self.indexer
+                .read_api()
+                .try_multi_get_parsed_past_object(requests, options)
+        );
+        let key = format!("objects changed by transaction {digest}");
+        let Some((fn_objects, indexer_objects)) =
+            self.report.both_read(checkpoint, &key, fn_objects, indexer_objects)? else {
+            return Ok(());
+        };
+        self.report.compare(
+            DiffCategory::ObjectVersion,
+            checkpoint,
+            &key,
+            &fn_objects,
+            &indexer_objects,
+        )
+    }
+
+    async fn check_owned_objects(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        owner: SuiAddress,
+    ) -> Result<()> {
+        let (fn_objects, indexer_objects) = tokio::join!(
+            owned_objects_at_checkpoint(&self.fullnode, owner, checkpoint),
+            owned_objects_at_checkpoint(&self.indexer, owner, checkpoint)
+        );
+        let key = format!("objects owned by {owner}");
+        let Some((fn_objects, indexer_objects)) =
+            self.report.both_read(checkpoint, &key, fn_objects, indexer_objects)? else {
+            return Ok(());
+        };
+        self.report.compare(
+            DiffCategory::OwnerIndex,
+            checkpoint,
+            &key,
+            &fn_objects,
+            &indexer_objects,
+        )
     }
-    Ok(())
 }
 
-pub async fn check_objects(
-    fn_client: &SuiClient,
-    indexer_client: &SuiClient,
-    objects: Vec<ObjectChange>,
-) -> Result<(), anyhow::Error> {
-    let options = SuiObjectDataOptions::full_content();
-    let object_id_and_version_vec = objects
-        .iter()
-        .filter_map(get_object_id_and_version)
-        .collect::<Vec<(ObjectID, SequenceNumber)>>();
-    for (object_id, version) in object_id_and_version_vec {
-        let fn_object = fn_client
-            .read_api()
-            .try_get_parsed_past_object(object_id, version, options.clone())
-            .await?;
-        let indexer_object = indexer_client
+async fn owned_objects_at_checkpoint(
+    client: &SuiClient,
+    owner: SuiAddress,
+    checkpoint: CheckpointSequenceNumber,
+) -> SuiRpcResult<Vec<SuiPastObjectResponse>> {
+    let mut objects = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
             .read_api()
-            .try_get_parsed_past_object(object_id, version, options.clone())
+            .get_owned_objects_at_checkpoint(
+                owner,
+                checkpoint,
+                SuiObjectDataOptions::new(),
+                cursor,
+                None,
+            )
             .await?;
-        if fn_object != indexer_object {
-            error!(
-                "Checkpoint objects mismatch found with object id: {:?} and version: {:?}",
-                object_id, version
-            );
-            warn!(
-                "Object mismatch with digest {:?} and object id:\nFN:\n{:?}\nIndexer:\n{:?} ",
-                version, fn_object, indexer_object
-            );
+        objects.extend(page.data);
+        if !page.has_next_page {
+            return Ok(objects);
         }
+        cursor = page.next_cursor;
+    }
+}
+
+fn read_result_to_json<T: Serialize>(result: SuiRpcResult<T>) -> Result<Value> {
+    Ok(match result {
+        Ok(value) => serde_json::to_value(value)?,
+        Err(e) => json!({ "error": e.to_string() }),
+    })
+}
+
+/// Collects into `paths` the JSON paths under `path` at which `fullnode` and `indexer` differ,
+/// descending into the objects and the arrays of the same length found on both sides.
+fn diff_paths(fullnode: &Value, indexer: &Value, path: String, paths: &mut Vec<String>) {
+    if paths.len() >= MAX_DIFF_PATHS || fullnode == indexer {
+        return;
+    }
+    match (fullnode, indexer) {
+        (Value::Object(fn_map), Value::Object(indexer_map)) => {
+            let keys = fn_map
+                .keys()
+                .chain(indexer_map.keys())
+                .collect::<BTreeSet<_>>();
+            for key in keys {
+                diff_paths(
+                    fn_map.get(key).unwrap_or(&Value::Null),
+                    indexer_map.get(key).unwrap_or(&Value::Null),
+                    format!("{path}.{key}"),
+                    paths,
+                );
+            }
+        }
+        (Value::Array(fn_vec), Value::Array(indexer_vec)) if fn_vec.len() == indexer_vec.len() => {
+            for (i, (fn_value, indexer_value)) in fn_vec.iter().zip(indexer_vec).enumerate() {
+                diff_paths(fn_value, indexer_value, format!("{path}[{i}]"), paths);
+            }
+        }
+        _ => paths.push(path),
     }
-    Ok(())
 }
 
-fn get_object_id_and_version(object_change: &ObjectChange) -> Option<(ObjectID, SequenceNumber)> {
+fn changed_object_version(object_change: &ObjectChange) -> Option<(ObjectID, SequenceNumber)> {
     match object_change {
         ObjectChange::Transferred {
             object_id, version, ..
-        } => Some((*object_id, *version)),
-        ObjectChange::Mutated {
+        }
+        | ObjectChange::Mutated {
             object_id, version, ..
-        } => Some((*object_id, *version)),
-        ObjectChange::Created {
+        }
+        | ObjectChange::Created {
+            object_id, version, ..
+        }
+        | ObjectChange::Deleted {
+            object_id, version, ..
+        }
+        | ObjectChange::Wrapped {
             object_id, version, ..
         } => Some((*object_id, *version)),
         // TODO(gegaowp): needs separate checks for packages and modules publishing
-        // TODO(gegaowp): ?? needs separate checks for deleted and wrapped objects
+        ObjectChange::Published { .. } => None,
+    }
+}
+
+fn changed_address_owner(object_change: &ObjectChange) -> Option<SuiAddress> {
+    match object_change {
+        ObjectChange::Transferred {
+            recipient: owner, ..
+        }
+        | ObjectChange::Mutated { owner, .. }
+        | ObjectChange::Created { owner, .. } => match owner {
+            Owner::AddressOwner(address) => Some(*address),
+            _ => None,
+        },
         _ => None,
     }
 }
 
+/// The first checkpoint to check, the one saved at `cursor_path` by an interrupted run if any,
+/// `start_checkpoint` otherwise.
+fn resume_checkpoint(
+    cursor_path: Option<&Path>,
+    start_checkpoint: CheckpointSequenceNumber,
+) -> Result<CheckpointSequenceNumber> {
+    Ok(match cursor_path {
+        Some(path) => read_cursor(path)?.unwrap_or(start_checkpoint),
+        None => start_checkpoint,
+    })
+}
+
+/// Records that `checkpoint` was fully checked, returning the next checkpoint to check.
+fn checkpoint_checked(
+    cursor_path: Option<&Path>,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<CheckpointSequenceNumber> {
+    let next_checkpoint = checkpoint + 1;
+    if let Some(path) = cursor_path {
+        write_cursor(path, next_checkpoint)?;
+    }
+    Ok(next_checkpoint)
+}
+
+/// Returns the next checkpoint to check saved at `path`, if any.
+fn read_cursor(path: &Path) -> Result<Option<CheckpointSequenceNumber>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let cursor = std::fs::read_to_string(path)?.trim().parse()?;
+    info!("Resuming from checkpoint {} saved at {:?}", cursor, path);
+    Ok(Some(cursor))
+}
+
+fn write_cursor(path: &Path, next_checkpoint: CheckpointSequenceNumber) -> Result<()> {
+    // replaced by a rename so that an interrupted write leaves the previous cursor.
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, next_checkpoint.to_string())?;
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
 #[derive(Parser)]
-#[clap(name = "Transactions Test")]
+#[clap(name = "Indexer data validation")]
 pub struct TestConfig {
     #[clap(long)]
     pub fn_rpc_client_url: String,
     #[clap(long)]
     pub indexer_rpc_client_url: String,
-    #[clap(long)]
+    #[clap(long, default_value = "0")]
     pub start_checkpoint: u64,
+    /// Defaults to the latest checkpoint available on both sides.
     #[clap(long)]
-    pub end_checkpoint: u64,
+    pub end_checkpoint: Option<u64>,
+    #[clap(
+        long,
+        arg_enum,
+        multiple_values = true,
+        default_values = &["checkpoints", "transactions", "events", "objects", "owners"]
+    )]
+    pub checks: Vec<Check>,
+    /// File the diffs are appended to, as JSON lines.
     #[clap(long)]
-    pub check_transactions: bool,
+    pub report_path: Option<PathBuf>,
+    /// File holding the next checkpoint to check, updated as checkpoints are checked. When it
+    /// exists, checking resumes from it instead of `--start-checkpoint`.
     #[clap(long)]
-    pub check_events: bool,
-    #[clap(long)]
-    pub check_objects: bool,
+    pub cursor_path: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::digests::ObjectDigest;
+    use sui_types::gas_coin::GasCoin;
+
+    #[test]
+    fn test_diff_paths() {
+        let fullnode = json!({
+            "digest": "a",
+            "effects": { "status": "success", "gasUsed": [1, 2, 3] },
+            "events": [1, 2],
+        });
+        let indexer = json!({
+            "digest": "a",
+            "effects": { "status": "failure", "gasUsed": [1, 5, 3] },
+            "events": [1],
+            "checkpoint": 7,
+        });
+        let mut paths = vec![];
+        diff_paths(&fullnode, &indexer, "$".to_string(), &mut paths);
+        assert_eq!(
+            paths,
+            vec![
+                "$.checkpoint",
+                "$.effects.gasUsed[1]",
+                "$.effects.status",
+                "$.events"
+            ]
+        );
+
+        let mut paths = vec![];
+        diff_paths(&fullnode, &fullnode, "$".to_string(), &mut paths);
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn test_resume_after_interrupted_run() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cursor_path = dir.path().join("cursor");
+        let cursor_path = Some(cursor_path.as_path());
+
+        // a first run starts at the configured checkpoint.
+        assert_eq!(resume_checkpoint(cursor_path, 5)?, 5);
+        assert_eq!(resume_checkpoint(None, 5)?, 5);
+
+        // checkpoints 5 and 6 are checked, the run is interrupted while checking 7.
+        assert_eq!(checkpoint_checked(cursor_path, 5)?, 6);
+        assert_eq!(checkpoint_checked(cursor_path, 6)?, 7);
+        assert_eq!(resume_checkpoint(cursor_path, 5)?, 7);
+        assert_eq!(read_cursor(cursor_path.unwrap())?, Some(7));
+
+        // the cursor is written through a temporary file renamed in place.
+        let files = std::fs::read_dir(dir.path())?.count();
+        assert_eq!(files, 1);
+
+        std::fs::write(cursor_path.unwrap(), "not a checkpoint")?;
+        assert!(resume_checkpoint(cursor_path, 5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_both_read() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let report_path = dir.path().join("report.jsonl");
+        let mut report = Report::new(Some(&report_path))?;
+
+        let read = report.both_read(1, "both", Ok(1), Ok(2))?;
+        assert_eq!(read, Some((1, 2)));
+        assert!(report.counts.is_empty());
+
+        // read by one source only, recorded as missing.
+        let read = report.both_read(
+            2,
+            "indexer only",
+            Err(sui_sdk::error::Error::DataError("not found".to_string())),
+            Ok(3),
+        )?;
+        assert_eq!(read, None);
+        assert_eq!(report.counts.get(&DiffCategory::Missing), Some(&1));
+
+        // read by neither source, the run is aborted rather than recording a diff.
+        let read = report.both_read::<u64>(
+            3,
+            "neither",
+            Err(sui_sdk::error::Error::DataError("fn down".to_string())),
+            Err(sui_sdk::error::Error::DataError("indexer down".to_string())),
+        );
+        assert!(read.is_err());
+        assert_eq!(report.counts.get(&DiffCategory::Missing), Some(&1));
+
+        report.flush()?;
+        let lines = std::fs::read_to_string(&report_path)?;
+        let diffs = lines
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?;
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0]["category"], "missing");
+        assert_eq!(diffs[0]["checkpoint"], 2);
+        assert_eq!(diffs[0]["fullnode"]["error"], "Data error: not found");
+        assert_eq!(diffs[0]["indexer"], 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_address_owner() {
+        let sender = SuiAddress::random_for_testing_only();
+        let address = SuiAddress::random_for_testing_only();
+        let object_type = GasCoin::type_();
+        let object_id = ObjectID::random();
+        let version = SequenceNumber::from(2);
+        let digest = ObjectDigest::random();
+        let created = |owner| ObjectChange::Created {
+            sender,
+            owner,
+            object_type: object_type.clone(),
+            object_id,
+            version,
+            digest,
+        };
+
+        assert_eq!(
+            changed_address_owner(&created(Owner::AddressOwner(address))),
+            Some(address)
+        );
+        let mutated = ObjectChange::Mutated {
+            sender,
+            owner: Owner::AddressOwner(address),
+            object_type: object_type.clone(),
+            object_id,
+            version,
+            previous_version: SequenceNumber::from(1),
+            digest,
+        };
+        assert_eq!(changed_address_owner(&mutated), Some(address));
+        let transferred = ObjectChange::Transferred {
+            sender,
+            recipient: Owner::AddressOwner(address),
+            object_type: object_type.clone(),
+            object_id,
+            version,
+            digest,
+        };
+        assert_eq!(changed_address_owner(&transferred), Some(address));
+
+        for owner in [
+            Owner::ObjectOwner(address),
+            Owner::Shared {
+                initial_shared_version: SequenceNumber::from(1),
+            },
+            Owner::Immutable,
+        ] {
+            assert_eq!(changed_address_owner(&created(owner)), None);
+        }
+        let deleted = ObjectChange::Deleted {
+            sender,
+            object_type: object_type.clone(),
+            object_id,
+            version,
+        };
+        assert_eq!(changed_address_owner(&deleted), None);
+        let wrapped = ObjectChange::Wrapped {
+            sender,
+            object_type,
+            object_id,
+            version,
+        };
+        assert_eq!(changed_address_owner(&wrapped), None);
+    }
 }
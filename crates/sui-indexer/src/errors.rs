@@ -47,9 +47,32 @@ pub enum IndexerError {
     #[error(transparent)]
     PostgresError(#[from] diesel::result::Error),
 
+    #[error("Indexer failed to build SQLite connection pool with error: `{0}`")]
+    SqliteConnectionPoolInitError(String),
+
+    #[error(
+        "Indexer failed to get a pool connection from SQLite connection pool with error: `{0}`"
+    )]
+    SqlitePoolConnectionError(String),
+
+    #[error("Indexer failed to read SQLite DB with error: `{0}`")]
+    SqliteReadError(String),
+
+    #[error("Indexer failed to commit changes to SQLite DB with error: `{0}`")]
+    SqliteWriteError(String),
+
+    #[error("Indexer failed to run SQLite DB migrations with error: `{0}`")]
+    SqliteMigrationError(String),
+
     #[error("Indexer failed to initialize fullnode Http client with error: `{0}`")]
     HttpClientInitError(String),
 
+    #[error("Indexer failed to serve GraphQL with error: `{0}`")]
+    GraphQLServerError(String),
+
+    #[error("Indexer failed to deliver webhook with error: `{0}`")]
+    WebhookDeliveryError(String),
+
     #[error("Indexer failed to serialize/deserialize with error: `{0}`")]
     SerdeError(String),
 
@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! GraphQL query service over the indexer tables. Unlike the JSON-RPC apis, which mirror the
+//! fullnode methods one by one, the schema lets clients follow the relations between
+//! checkpoints, transactions, objects, events, addresses and epochs in a single request.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Extension, Router};
+use tracing::info;
+
+use crate::errors::IndexerError;
+use crate::store::IndexerStore;
+use crate::IndexerConfig;
+
+pub use query::Query;
+
+mod query;
+mod reader;
+mod types;
+
+pub type IndexerSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Builds the GraphQL schema over `store`, rejecting queries deeper or more complex than allowed
+/// by `config` before any of their resolvers runs.
+pub fn build_schema<S>(store: S, config: &IndexerConfig) -> IndexerSchema
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    let store: reader::Store = Arc::new(store);
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(store)
+        .limit_depth(config.graphql_max_query_depth)
+        .limit_complexity(config.graphql_max_query_complexity)
+        .finish()
+}
+
+/// Serves the GraphQL schema at `/graphql` on the GraphQL server port, with a GraphiQL IDE on
+/// `GET` requests.
+pub async fn start_graphql_server<S>(
+    store: S,
+    config: &IndexerConfig,
+    port: u16,
+) -> Result<(), IndexerError>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    let schema = build_schema(store, config);
+    let app = Router::new()
+        .route("/graphql", get(graphiql).post(graphql_handler))
+        .layer(Extension(schema));
+
+    let addr = SocketAddr::new(
+        IpAddr::V4(
+            Ipv4Addr::from_str(&config.rpc_server_url)
+                .map_err(|e| IndexerError::GraphQLServerError(e.to_string()))?,
+        ),
+        port,
+    );
+    info!("Starting GraphQL server on {}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| IndexerError::GraphQLServerError(e.to_string()))
+}
+
+async fn graphql_handler(
+    Extension(schema): Extension<IndexerSchema>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    schema.execute(request.into_inner()).await.into()
+}
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,435 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Error, InputObject, Object, Result};
+
+use move_core_types::language_storage::StructTag;
+use sui_json_rpc_types::{CheckpointId, EventFilter};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+
+use super::types::{
+    epoch_by_id, page_limit, store, transaction_connection, Address, Checkpoint, Epoch, Event,
+    SuiObject, Transaction, DEFAULT_PAGE_SIZE,
+};
+
+/// Root of the GraphQL queries.
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// A checkpoint by sequence number or digest, the latest checkpoint when neither is given
+    async fn checkpoint(
+        &self,
+        ctx: &Context<'_>,
+        sequence_number: Option<u64>,
+        digest: Option<String>,
+    ) -> Result<Checkpoint> {
+        let id = match (sequence_number, digest) {
+            (Some(sequence_number), None) => CheckpointId::SequenceNumber(sequence_number),
+            (None, Some(digest)) => CheckpointId::Digest(CheckpointDigest::from_str(&digest)?),
+            (None, None) => CheckpointId::SequenceNumber(latest_checkpoint(ctx).await?),
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    "Only one of `sequenceNumber` and `digest` can be given",
+                ))
+            }
+        };
+        Ok(Checkpoint(store(ctx).checkpoint(id).await?))
+    }
+
+    /// Checkpoints in sequence order, the cursor is the checkpoint sequence number
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn checkpoints(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Checkpoint>> {
+        let limit = page_limit(first)?;
+        let latest = latest_checkpoint(ctx).await?;
+        let after = after.as_deref().map(u64::from_str).transpose()?;
+        // fetch one more sequence number to tell if there is a next page
+        let sequence_numbers: Vec<u64> = match (after, descending) {
+            (None, false) => (0..=latest).take(limit + 1).collect(),
+            (Some(after), false) => (after + 1..=latest).take(limit + 1).collect(),
+            (None, true) => (0..=latest).rev().take(limit + 1).collect(),
+            (Some(after), true) => (0..after.min(latest + 1)).rev().take(limit + 1).collect(),
+        };
+        let has_next_page = sequence_numbers.len() > limit;
+
+        let mut connection = Connection::new(after.is_some(), has_next_page);
+        for sequence_number in sequence_numbers.into_iter().take(limit) {
+            let checkpoint = store(ctx)
+                .checkpoint(CheckpointId::SequenceNumber(sequence_number))
+                .await?;
+            connection.edges.push(Edge::new(
+                sequence_number.to_string(),
+                Checkpoint(checkpoint),
+            ));
+        }
+        Ok(connection)
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, digest: String) -> Result<Transaction> {
+        Ok(Transaction(store(ctx).transaction(&digest).await?))
+    }
+
+    /// Transactions matching `filter`, the cursor is the transaction digest
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionFilterInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Transaction>> {
+        let filter = filter
+            .map(TransactionFilterInput::into_filter)
+            .transpose()?;
+        transaction_connection(ctx, filter, first, after, descending).await
+    }
+
+    /// An object at `version`, or its latest version when no version is given. None if the
+    /// object doesn't exist or is deleted at that version.
+    async fn object(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        version: Option<u64>,
+    ) -> Result<Option<SuiObject>> {
+        let object_id = ObjectID::from_str(&id)?;
+        let read = store(ctx)
+            .object(object_id, version.map(SequenceNumber::from_u64))
+            .await?;
+        Ok(SuiObject::from_read(read))
+    }
+
+    async fn address(&self, address: String) -> Result<Address> {
+        Address::from_str(&address)
+    }
+
+    /// Events matching `filter`, the cursor is `<transaction digest>:<event sequence>`
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilterInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Event>> {
+        let limit = page_limit(first)?;
+        let filter = filter
+            .map(EventFilterInput::into_filter)
+            .transpose()?
+            .unwrap_or(EventFilter::All(vec![]));
+        let cursor = after.as_deref().map(parse_event_cursor).transpose()?;
+        let page = store(ctx)
+            .events(filter, cursor, Some(limit), descending)
+            .await?;
+
+        let mut connection = Connection::new(after.is_some(), page.has_next_page);
+        connection.edges.extend(page.data.into_iter().map(|event| {
+            let event = Event(event);
+            Edge::new(event.cursor(), event)
+        }));
+        Ok(connection)
+    }
+
+    /// An epoch by number, the current epoch when no number is given
+    async fn epoch(&self, ctx: &Context<'_>, id: Option<u64>) -> Result<Option<Epoch>> {
+        match id {
+            Some(id) => epoch_by_id(ctx, id).await,
+            None => Ok(Some(Epoch(store(ctx).current_epoch().await?))),
+        }
+    }
+
+    /// Epochs in order, the cursor is the epoch number
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn epochs(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Epoch>> {
+        let limit = page_limit(first)?;
+        let cursor = after.as_deref().map(u64::from_str).transpose()?;
+        let mut epochs = store(ctx).epochs(cursor, limit + 1, descending).await?;
+        let has_next_page = epochs.len() > limit;
+        epochs.truncate(limit);
+
+        let mut connection = Connection::new(after.is_some(), has_next_page);
+        connection.edges.extend(
+            epochs
+                .into_iter()
+                .map(|epoch| Edge::new(epoch.epoch.to_string(), Epoch(epoch))),
+        );
+        Ok(connection)
+    }
+}
+
+async fn latest_checkpoint(ctx: &Context<'_>) -> Result<u64> {
+    let latest = store(ctx).latest_checkpoint_sequence_number().await?;
+    u64::try_from(latest).map_err(|_| Error::new("No checkpoint has been indexed yet"))
+}
+
+fn parse_event_cursor(cursor: &str) -> Result<EventID> {
+    let (digest, sequence) = cursor
+        .split_once(':')
+        .ok_or_else(|| Error::new(format!("Invalid event cursor {cursor}")))?;
+    Ok(EventID {
+        tx_digest: TransactionDigest::from_str(digest)?,
+        event_seq: sequence.parse()?,
+    })
+}
+
+/// Transaction filter, at most one criterion can be set except for `sender` and `recipient`,
+/// which can be combined.
+#[derive(InputObject)]
+pub(crate) struct TransactionFilterInput {
+    checkpoint: Option<u64>,
+    sender: Option<String>,
+    recipient: Option<String>,
+    input_object: Option<String>,
+    /// Created, mutated or unwrapped object
+    changed_object: Option<String>,
+    /// Move call, `package`, `package::module` or `package::module::function`
+    move_function: Option<String>,
+    /// Transaction kind name, e.g. `ProgrammableTransaction`
+    kind: Option<String>,
+}
+
+impl TransactionFilterInput {
+    fn into_filter(self) -> Result<TransactionFilter> {
+        let Self {
+            checkpoint,
+            sender,
+            recipient,
+            input_object,
+            changed_object,
+            move_function,
+            kind,
+        } = self;
+        let sender = sender.as_deref().map(SuiAddress::from_str).transpose()?;
+        let recipient = recipient.as_deref().map(SuiAddress::from_str).transpose()?;
+
+        let mut filters = vec![];
+        match (sender, recipient) {
+            (Some(from), Some(to)) => {
+                filters.push(TransactionFilter::FromAndToAddress { from, to })
+            }
+            (Some(from), None) => filters.push(TransactionFilter::FromAddress(from)),
+            (None, Some(to)) => filters.push(TransactionFilter::ToAddress(to)),
+            (None, None) => {}
+        }
+        if let Some(checkpoint) = checkpoint {
+            filters.push(TransactionFilter::Checkpoint(checkpoint));
+        }
+        if let Some(object_id) = input_object {
+            filters.push(TransactionFilter::InputObject(ObjectID::from_str(
+                &object_id,
+            )?));
+        }
+        if let Some(object_id) = changed_object {
+            filters.push(TransactionFilter::ChangedObject(ObjectID::from_str(
+                &object_id,
+            )?));
+        }
+        if let Some(move_function) = move_function {
+            let mut parts = move_function.split("::");
+            filters.push(TransactionFilter::MoveFunction {
+                package: ObjectID::from_str(parts.next().unwrap_or_default())?,
+                module: parts.next().map(str::to_string),
+                function: parts.next().map(str::to_string),
+            });
+        }
+        if let Some(kind) = kind {
+            filters.push(TransactionFilter::TransactionKind(kind));
+        }
+
+        match filters.len() {
+            1 => Ok(filters.remove(0)),
+            0 => Err(Error::new("Transaction filter must set one criterion")),
+            _ => Err(Error::new(
+                "Transaction filter can only set one criterion, besides sender with recipient",
+            )),
+        }
+    }
+}
+
+/// Event filter, exactly one criterion must be set, `startTimeMs` and `endTimeMs` together.
+#[derive(InputObject)]
+pub(crate) struct EventFilterInput {
+    transaction: Option<String>,
+    sender: Option<String>,
+    /// Emitting module, `package::module`
+    module: Option<String>,
+    /// Move struct type of the event
+    event_type: Option<String>,
+    start_time_ms: Option<u64>,
+    end_time_ms: Option<u64>,
+}
+
+impl EventFilterInput {
+    fn into_filter(self) -> Result<EventFilter> {
+        let Self {
+            transaction,
+            sender,
+            module,
+            event_type,
+            start_time_ms,
+            end_time_ms,
+        } = self;
+
+        let mut filters = vec![];
+        if let Some(digest) = transaction {
+            filters.push(EventFilter::Transaction(TransactionDigest::from_str(
+                &digest,
+            )?));
+        }
+        if let Some(sender) = sender {
+            filters.push(EventFilter::Sender(SuiAddress::from_str(&sender)?));
+        }
+        if let Some(module) = module {
+            let (package, module) = module
+                .split_once("::")
+                .ok_or_else(|| Error::new(format!("Invalid module {module}")))?;
+            filters.push(EventFilter::MoveModule {
+                package: ObjectID::from_str(package)?,
+                module: module.parse()?,
+            });
+        }
+        if let Some(event_type) = event_type {
+            filters.push(EventFilter::MoveEventType(StructTag::from_str(
+                &event_type,
+            )?));
+        }
+        match (start_time_ms, end_time_ms) {
+            (Some(start_time), Some(end_time)) => filters.push(EventFilter::TimeRange {
+                start_time,
+                end_time,
+            }),
+            (None, None) => {}
+            _ => {
+                return Err(Error::new(
+                    "`startTimeMs` and `endTimeMs` must be given together",
+                ))
+            }
+        }
+
+        match filters.len() {
+            1 => Ok(filters.remove(0)),
+            0 => Err(Error::new("Event filter must set one criterion")),
+            _ => Err(Error::new("Event filter can only set one criterion")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::build_schema;
+    use crate::store::SqliteIndexerStore;
+    use crate::{new_sqlite_connection_pool, IndexerConfig};
+
+    #[test]
+    fn test_transaction_filter_input() {
+        let sender = SuiAddress::random_for_testing_only();
+        let recipient = SuiAddress::random_for_testing_only();
+        let input = TransactionFilterInput {
+            checkpoint: None,
+            sender: Some(sender.to_string()),
+            recipient: Some(recipient.to_string()),
+            input_object: None,
+            changed_object: None,
+            move_function: None,
+            kind: None,
+        };
+        assert!(matches!(
+            input.into_filter().unwrap(),
+            TransactionFilter::FromAndToAddress { from, to } if from == sender && to == recipient
+        ));
+
+        let input = TransactionFilterInput {
+            checkpoint: None,
+            sender: None,
+            recipient: None,
+            input_object: None,
+            changed_object: None,
+            move_function: Some("0x2::coin::transfer".to_string()),
+            kind: None,
+        };
+        assert!(matches!(
+            input.into_filter().unwrap(),
+            TransactionFilter::MoveFunction { package, module, function }
+                if package == ObjectID::from_str("0x2").unwrap()
+                    && module.as_deref() == Some("coin")
+                    && function.as_deref() == Some("transfer")
+        ));
+
+        let input = TransactionFilterInput {
+            checkpoint: Some(1),
+            sender: Some(sender.to_string()),
+            recipient: None,
+            input_object: None,
+            changed_object: None,
+            move_function: None,
+            kind: None,
+        };
+        assert!(input.into_filter().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_limits() {
+        const TOO_COMPLEX: &str = "{ transactions(first: 50) { edges { node { sender { \
+             transactionsSent(first: 50) { edges { node { digest } } } } } } } }";
+        const TOO_DEEP: &str = "{ transaction(digest: \"\") { effects { created { object { \
+             owner { address { objects { edges { node { owner { address { address } } } } } } \
+             } } } } } }";
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_url = db_dir.path().join("indexer.db");
+        let pool = new_sqlite_connection_pool(db_url.to_str().unwrap()).unwrap();
+        let store = SqliteIndexerStore::new(pool);
+
+        // rejected during validation, before any resolver reads the store
+        let config = IndexerConfig {
+            graphql_max_query_depth: 10,
+            graphql_max_query_complexity: 1000,
+            ..Default::default()
+        };
+        let schema = build_schema(store.clone(), &config);
+
+        let response = schema.execute(TOO_COMPLEX).await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too complex"));
+
+        let response = schema.execute(TOO_DEEP).await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too deep"));
+
+        // within the limits of a more permissive config
+        let config = IndexerConfig {
+            graphql_max_query_depth: 20,
+            graphql_max_query_complexity: 1_000_000,
+            ..Default::default()
+        };
+        let schema = build_schema(store, &config);
+        for query in [TOO_COMPLEX, TOO_DEEP] {
+            let response = schema.execute(query).await;
+            assert!(response
+                .errors
+                .iter()
+                .all(|e| !e.message.contains("too complex") && !e.message.contains("too deep")));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use sui_json_rpc_types::{
+    Checkpoint as RpcCheckpoint, CheckpointId, EpochInfo, EventFilter, EventPage,
+    SuiObjectDataFilter,
+};
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::event::EventID;
+use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
+
+use crate::errors::IndexerError;
+use crate::models::transactions::Transaction;
+use crate::store::{get_transaction_page_by_query, IndexerStore};
+
+/// The store as seen by the resolvers, kept in the schema data.
+pub(crate) type Store = Arc<dyn Reader>;
+
+/// The reads of [IndexerStore] the resolvers need, as an object safe trait so that the schema
+/// types don't depend on the store implementation.
+#[async_trait]
+pub(crate) trait Reader: Send + Sync {
+    async fn checkpoint(&self, id: CheckpointId) -> Result<RpcCheckpoint, IndexerError>;
+
+    async fn latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError>;
+
+    async fn transaction(&self, digest: &str) -> Result<Transaction, IndexerError>;
+
+    async fn transaction_page(
+        &self,
+        filter: Option<TransactionFilter>,
+        cursor: Option<String>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError>;
+
+    async fn object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError>;
+
+    async fn owned_objects(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError>;
+
+    async fn events(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        is_descending: bool,
+    ) -> Result<EventPage, IndexerError>;
+
+    async fn epochs(
+        &self,
+        cursor: Option<EpochId>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<EpochInfo>, IndexerError>;
+
+    async fn current_epoch(&self) -> Result<EpochInfo, IndexerError>;
+}
+
+#[async_trait]
+impl<S: IndexerStore + Sync + Send> Reader for S {
+    async fn checkpoint(&self, id: CheckpointId) -> Result<RpcCheckpoint, IndexerError> {
+        self.get_checkpoint(id).await
+    }
+
+    async fn latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
+        self.get_latest_checkpoint_sequence_number().await
+    }
+
+    async fn transaction(&self, digest: &str) -> Result<Transaction, IndexerError> {
+        self.get_transaction_by_digest(digest).await
+    }
+
+    async fn transaction_page(
+        &self,
+        filter: Option<TransactionFilter>,
+        cursor: Option<String>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        get_transaction_page_by_query(self, filter, cursor, limit, is_descending).await
+    }
+
+    async fn object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        self.get_object(object_id, version).await
+    }
+
+    async fn owned_objects(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        self.query_latest_objects(SuiObjectDataFilter::AddressOwner(owner), cursor, limit)
+            .await
+    }
+
+    async fn events(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        is_descending: bool,
+    ) -> Result<EventPage, IndexerError> {
+        self.get_events(filter, cursor, limit, is_descending).await
+    }
+
+    async fn epochs(
+        &self,
+        cursor: Option<EpochId>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<EpochInfo>, IndexerError> {
+        self.get_epochs(cursor, limit, Some(is_descending)).await
+    }
+
+    async fn current_epoch(&self) -> Result<EpochInfo, IndexerError> {
+        self.get_current_epoch().await
+    }
+}
@@ -0,0 +1,652 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Enum, Error, Json, Object, Result, SimpleObject};
+
+use sui_json_rpc_types::{
+    Checkpoint as RpcCheckpoint, CheckpointId, EpochInfo, EventFilter, OwnedObjectRef, SuiEvent,
+    SuiExecutionStatus, SuiObjectRef, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+};
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::object::{ObjectRead, Owner as SuiOwner};
+use sui_types::query::TransactionFilter;
+use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
+
+use crate::models::transactions::Transaction as DBTransaction;
+
+use super::reader::Store;
+
+/// Page size of connections when `first` is not given.
+pub(crate) const DEFAULT_PAGE_SIZE: usize = 20;
+/// Largest page a connection returns, which also bounds the cost of nested connections.
+pub(crate) const MAX_PAGE_SIZE: usize = 50;
+
+pub(crate) fn store<'a>(ctx: &'a Context<'_>) -> &'a Store {
+    ctx.data_unchecked::<Store>()
+}
+
+pub(crate) fn page_limit(first: Option<usize>) -> Result<usize> {
+    match first.unwrap_or(DEFAULT_PAGE_SIZE) {
+        0 => Err(Error::new("`first` must be greater than 0")),
+        limit if limit > MAX_PAGE_SIZE => Err(Error::new(format!(
+            "`first` must not exceed {MAX_PAGE_SIZE}"
+        ))),
+        limit => Ok(limit),
+    }
+}
+
+/// Returns the page of transactions matching `filter` after the transaction with digest `after`.
+pub(crate) async fn transaction_connection(
+    ctx: &Context<'_>,
+    filter: Option<TransactionFilter>,
+    first: Option<usize>,
+    after: Option<String>,
+    descending: bool,
+) -> Result<Connection<String, Transaction>> {
+    let limit = page_limit(first)?;
+    // fetch one more transaction to tell if there is a next page
+    let mut transactions = store(ctx)
+        .transaction_page(filter, after.clone(), limit + 1, descending)
+        .await?;
+    let has_next_page = transactions.len() > limit;
+    transactions.truncate(limit);
+
+    let mut connection = Connection::new(after.is_some(), has_next_page);
+    connection.edges.extend(
+        transactions
+            .into_iter()
+            .map(|tx| Edge::new(tx.transaction_digest.clone(), Transaction(tx))),
+    );
+    Ok(connection)
+}
+
+pub(crate) async fn epoch_by_id(ctx: &Context<'_>, epoch: EpochId) -> Result<Option<Epoch>> {
+    // epochs are paged after the cursor
+    let mut epochs = store(ctx)
+        .epochs(epoch.checked_sub(1), 1, /* is_descending */ false)
+        .await?;
+    Ok(epochs.pop().filter(|e| e.epoch == epoch).map(Epoch))
+}
+
+pub(crate) async fn latest_object(
+    ctx: &Context<'_>,
+    object_id: ObjectID,
+) -> Result<Option<SuiObject>> {
+    Ok(SuiObject::from_read(
+        store(ctx).object(object_id, None).await?,
+    ))
+}
+
+pub(crate) struct Checkpoint(pub(crate) RpcCheckpoint);
+
+#[Object]
+impl Checkpoint {
+    async fn sequence_number(&self) -> u64 {
+        self.0.sequence_number
+    }
+
+    async fn digest(&self) -> String {
+        self.0.digest.base58_encode()
+    }
+
+    async fn previous_digest(&self) -> Option<String> {
+        self.0.previous_digest.map(|digest| digest.base58_encode())
+    }
+
+    async fn timestamp_ms(&self) -> u64 {
+        self.0.timestamp_ms
+    }
+
+    /// Total number of transactions in the network up to and including this checkpoint
+    async fn network_total_transactions(&self) -> u64 {
+        self.0.network_total_transactions
+    }
+
+    /// Whether this is the last checkpoint of its epoch
+    async fn end_of_epoch(&self) -> bool {
+        self.0.end_of_epoch_data.is_some()
+    }
+
+    async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
+        epoch_by_id(ctx, self.0.epoch).await
+    }
+
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Transaction>> {
+        transaction_connection(
+            ctx,
+            Some(TransactionFilter::Checkpoint(self.0.sequence_number)),
+            first,
+            after,
+            false,
+        )
+        .await
+    }
+}
+
+pub(crate) struct Transaction(pub(crate) DBTransaction);
+
+#[Object]
+impl Transaction {
+    async fn digest(&self) -> &str {
+        &self.0.transaction_digest
+    }
+
+    async fn sender(&self) -> Result<Address> {
+        Address::from_str(&self.0.sender)
+    }
+
+    /// Name of the transaction kind, e.g. `ProgrammableTransaction`
+    async fn kind(&self) -> &str {
+        &self.0.transaction_kind
+    }
+
+    async fn timestamp_ms(&self) -> Option<i64> {
+        self.0.timestamp_ms
+    }
+
+    async fn gas_budget(&self) -> i64 {
+        self.0.gas_budget
+    }
+
+    async fn gas_price(&self) -> i64 {
+        self.0.gas_price
+    }
+
+    async fn computation_cost(&self) -> i64 {
+        self.0.computation_cost
+    }
+
+    async fn storage_cost(&self) -> i64 {
+        self.0.storage_cost
+    }
+
+    async fn storage_rebate(&self) -> i64 {
+        self.0.storage_rebate
+    }
+
+    /// The checkpoint including this transaction, none until the transaction is checkpointed
+    async fn checkpoint(&self, ctx: &Context<'_>) -> Result<Option<Checkpoint>> {
+        let Some(sequence_number) = self.0.checkpoint_sequence_number else {
+            return Ok(None);
+        };
+        let checkpoint = store(ctx)
+            .checkpoint(CheckpointId::SequenceNumber(sequence_number as u64))
+            .await?;
+        Ok(Some(Checkpoint(checkpoint)))
+    }
+
+    async fn effects(&self) -> Result<TransactionEffects> {
+        let effects = serde_json::from_str(&self.0.transaction_effects_content)?;
+        Ok(TransactionEffects(effects))
+    }
+
+    async fn events(&self, ctx: &Context<'_>) -> Result<Vec<Event>> {
+        let digest = TransactionDigest::from_str(&self.0.transaction_digest)?;
+        let page = store(ctx)
+            .events(EventFilter::Transaction(digest), None, None, false)
+            .await?;
+        Ok(page.data.into_iter().map(Event).collect())
+    }
+
+    async fn move_calls(&self) -> Vec<MoveCall> {
+        self.0
+            .move_calls
+            .iter()
+            .flatten()
+            .filter_map(|move_call| {
+                let mut parts = move_call.split("::");
+                Some(MoveCall {
+                    package: parts.next()?.to_string(),
+                    module: parts.next()?.to_string(),
+                    function: parts.next()?.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct MoveCall {
+    package: String,
+    module: String,
+    function: String,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum ExecutionStatus {
+    Success,
+    Failure,
+}
+
+pub(crate) struct TransactionEffects(SuiTransactionBlockEffects);
+
+#[Object]
+impl TransactionEffects {
+    async fn status(&self) -> ExecutionStatus {
+        match self.0.status() {
+            SuiExecutionStatus::Success => ExecutionStatus::Success,
+            SuiExecutionStatus::Failure { .. } => ExecutionStatus::Failure,
+        }
+    }
+
+    /// The execution error of failed transactions
+    async fn error(&self) -> Option<&str> {
+        match self.0.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some(error),
+        }
+    }
+
+    async fn executed_epoch(&self) -> u64 {
+        self.0.executed_epoch()
+    }
+
+    async fn gas_object(&self) -> ObjectChange {
+        ObjectChange(self.0.gas_object().clone())
+    }
+
+    async fn created(&self) -> Vec<ObjectChange> {
+        object_changes(self.0.created())
+    }
+
+    async fn mutated(&self) -> Vec<ObjectChange> {
+        object_changes(self.0.mutated())
+    }
+
+    async fn unwrapped(&self) -> Vec<ObjectChange> {
+        object_changes(self.0.unwrapped())
+    }
+
+    async fn deleted(&self) -> Vec<ObjectRef> {
+        object_refs(self.0.deleted())
+    }
+
+    async fn wrapped(&self) -> Vec<ObjectRef> {
+        object_refs(self.0.wrapped())
+    }
+
+    /// Digests of the transactions this transaction depends on
+    async fn dependencies(&self) -> Vec<String> {
+        self.0
+            .dependencies()
+            .iter()
+            .map(|digest| digest.base58_encode())
+            .collect()
+    }
+}
+
+fn object_changes(refs: &[OwnedObjectRef]) -> Vec<ObjectChange> {
+    refs.iter().cloned().map(ObjectChange).collect()
+}
+
+fn object_refs(refs: &[SuiObjectRef]) -> Vec<ObjectRef> {
+    refs.iter()
+        .map(|r| ObjectRef {
+            object_id: r.object_id.to_string(),
+            version: r.version.value(),
+            digest: r.digest.base58_encode(),
+        })
+        .collect()
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct ObjectRef {
+    object_id: String,
+    version: u64,
+    digest: String,
+}
+
+/// An object written by a transaction
+pub(crate) struct ObjectChange(OwnedObjectRef);
+
+#[Object]
+impl ObjectChange {
+    async fn object_id(&self) -> String {
+        self.0.reference.object_id.to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.0.reference.version.value()
+    }
+
+    async fn digest(&self) -> String {
+        self.0.reference.digest.base58_encode()
+    }
+
+    async fn owner(&self) -> Owner {
+        Owner(self.0.owner)
+    }
+
+    /// The object as written by the transaction
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<SuiObject>> {
+        let read = store(ctx)
+            .object(self.0.reference.object_id, Some(self.0.reference.version))
+            .await?;
+        Ok(SuiObject::from_read(read))
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum OwnerKind {
+    Address,
+    Object,
+    Shared,
+    Immutable,
+}
+
+pub(crate) struct Owner(SuiOwner);
+
+#[Object]
+impl Owner {
+    async fn kind(&self) -> OwnerKind {
+        match self.0 {
+            SuiOwner::AddressOwner(_) => OwnerKind::Address,
+            SuiOwner::ObjectOwner(_) => OwnerKind::Object,
+            SuiOwner::Shared { .. } => OwnerKind::Shared,
+            SuiOwner::Immutable => OwnerKind::Immutable,
+        }
+    }
+
+    /// The owning address, or the ID of the owning object, of owned objects
+    async fn address(&self) -> Option<Address> {
+        match self.0 {
+            SuiOwner::AddressOwner(address) | SuiOwner::ObjectOwner(address) => {
+                Some(Address(address))
+            }
+            SuiOwner::Shared { .. } | SuiOwner::Immutable => None,
+        }
+    }
+
+    /// The owning object of objects owned by another object
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<SuiObject>> {
+        match self.0 {
+            SuiOwner::ObjectOwner(address) => latest_object(ctx, address.into()).await,
+            _ => Ok(None),
+        }
+    }
+
+    async fn initial_shared_version(&self) -> Option<u64> {
+        match self.0 {
+            SuiOwner::Shared {
+                initial_shared_version,
+            } => Some(initial_shared_version.value()),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) struct SuiObject(sui_types::object::Object);
+
+impl SuiObject {
+    pub(crate) fn from_read(read: ObjectRead) -> Option<Self> {
+        match read {
+            ObjectRead::Exists(_, object, _) => Some(Self(object)),
+            ObjectRead::Deleted(_) | ObjectRead::NotExists(_) => None,
+        }
+    }
+}
+
+#[Object(name = "Object")]
+impl SuiObject {
+    async fn object_id(&self) -> String {
+        self.0.id().to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.0.version().value()
+    }
+
+    async fn digest(&self) -> String {
+        self.0.digest().base58_encode()
+    }
+
+    /// The Move type of the object, none for packages
+    #[graphql(name = "type")]
+    async fn type_(&self) -> Option<String> {
+        self.0.type_().map(|t| t.to_string())
+    }
+
+    async fn is_package(&self) -> bool {
+        self.0.is_package()
+    }
+
+    async fn owner(&self) -> Owner {
+        Owner(self.0.owner)
+    }
+
+    async fn storage_rebate(&self) -> u64 {
+        self.0.storage_rebate
+    }
+
+    /// The transaction that last wrote the object
+    async fn previous_transaction(&self, ctx: &Context<'_>) -> Result<Transaction> {
+        let tx = store(ctx)
+            .transaction(&self.0.previous_transaction.base58_encode())
+            .await?;
+        Ok(Transaction(tx))
+    }
+}
+
+pub(crate) struct Address(pub(crate) SuiAddress);
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Self(SuiAddress::from_str(s)?))
+    }
+}
+
+#[Object]
+impl Address {
+    async fn address(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Objects currently owned by the address, ordered by object ID
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn objects(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<Connection<String, SuiObject>> {
+        let limit = page_limit(first)?;
+        let cursor = after.as_deref().map(ObjectID::from_str).transpose()?;
+        let mut objects = store(ctx).owned_objects(self.0, cursor, limit + 1).await?;
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+
+        let mut connection = Connection::new(after.is_some(), has_next_page);
+        connection.edges.extend(
+            objects
+                .into_iter()
+                .filter_map(SuiObject::from_read)
+                .map(|object| Edge::new(object.0.id().to_string(), object)),
+        );
+        Ok(connection)
+    }
+
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn transactions_sent(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Transaction>> {
+        let filter = TransactionFilter::FromAddress(self.0);
+        transaction_connection(ctx, Some(filter), first, after, descending).await
+    }
+
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) * child_complexity")]
+    async fn transactions_received(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        #[graphql(default)] descending: bool,
+    ) -> Result<Connection<String, Transaction>> {
+        let filter = TransactionFilter::ToAddress(self.0);
+        transaction_connection(ctx, Some(filter), first, after, descending).await
+    }
+}
+
+pub(crate) struct Event(pub(crate) SuiEvent);
+
+impl Event {
+    /// Cursor of the event in connections, `<transaction digest>:<event sequence>`.
+    pub(crate) fn cursor(&self) -> String {
+        format!("{}:{}", self.0.id.tx_digest, self.0.id.event_seq)
+    }
+}
+
+#[Object]
+impl Event {
+    /// Position of the event among the events of its transaction
+    async fn sequence(&self) -> u64 {
+        self.0.id.event_seq
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<Transaction> {
+        let tx = store(ctx)
+            .transaction(&self.0.id.tx_digest.base58_encode())
+            .await?;
+        Ok(Transaction(tx))
+    }
+
+    async fn sender(&self) -> Address {
+        Address(self.0.sender)
+    }
+
+    async fn package_id(&self) -> String {
+        self.0.package_id.to_string()
+    }
+
+    async fn module(&self) -> String {
+        self.0.transaction_module.to_string()
+    }
+
+    #[graphql(name = "type")]
+    async fn type_(&self) -> String {
+        self.0.type_.to_string()
+    }
+
+    async fn timestamp_ms(&self) -> Option<u64> {
+        self.0.timestamp_ms
+    }
+
+    /// The event's fields as JSON
+    async fn json(&self) -> Json<serde_json::Value> {
+        Json(self.0.parsed_json.clone())
+    }
+}
+
+pub(crate) struct Epoch(pub(crate) EpochInfo);
+
+#[Object]
+impl Epoch {
+    async fn epoch(&self) -> u64 {
+        self.0.epoch
+    }
+
+    async fn first_checkpoint(&self) -> u64 {
+        self.0.first_checkpoint_id
+    }
+
+    /// None while the epoch is ongoing
+    async fn last_checkpoint(&self) -> Option<u64> {
+        self.0
+            .end_of_epoch_info
+            .as_ref()
+            .map(|info| info.last_checkpoint_id)
+    }
+
+    async fn start_timestamp_ms(&self) -> u64 {
+        self.0.epoch_start_timestamp
+    }
+
+    async fn end_timestamp_ms(&self) -> Option<u64> {
+        self.0
+            .end_of_epoch_info
+            .as_ref()
+            .map(|info| info.epoch_end_timestamp)
+    }
+
+    async fn total_transactions(&self) -> u64 {
+        self.0.epoch_total_transactions
+    }
+
+    async fn reference_gas_price(&self) -> Option<u64> {
+        self.0
+            .end_of_epoch_info
+            .as_ref()
+            .map(|info| info.reference_gas_price)
+    }
+
+    async fn total_gas_fees(&self) -> Option<u64> {
+        self.0
+            .end_of_epoch_info
+            .as_ref()
+            .map(|info| info.total_gas_fees)
+    }
+
+    async fn validators(&self) -> Vec<Validator> {
+        self.0.validators.iter().cloned().map(Validator).collect()
+    }
+}
+
+pub(crate) struct Validator(SuiValidatorSummary);
+
+#[Object]
+impl Validator {
+    async fn address(&self) -> Address {
+        Address(self.0.sui_address)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    async fn image_url(&self) -> &str {
+        &self.0.image_url
+    }
+
+    async fn project_url(&self) -> &str {
+        &self.0.project_url
+    }
+
+    async fn voting_power(&self) -> u64 {
+        self.0.voting_power
+    }
+
+    async fn gas_price(&self) -> u64 {
+        self.0.gas_price
+    }
+
+    async fn commission_rate(&self) -> u64 {
+        self.0.commission_rate
+    }
+
+    async fn next_epoch_stake(&self) -> u64 {
+        self.0.next_epoch_stake
+    }
+
+    async fn staking_pool_sui_balance(&self) -> u64 {
+        self.0.staking_pool_sui_balance
+    }
+}
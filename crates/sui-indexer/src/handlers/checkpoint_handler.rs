@@ -18,7 +18,7 @@ use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 
 use mysten_metrics::spawn_monitored_task;
-use sui_core::event_handler::EventHandler;
+use sui_core::subscription_handler::SubscriptionHandler;
 use sui_json_rpc::api::{GovernanceReadApiClient, ReadApiClient};
 use sui_json_rpc_types::{
     OwnedObjectRef, SuiGetPastObjectRequest, SuiObjectData, SuiObjectDataOptions, SuiRawData,
@@ -27,6 +27,7 @@ use sui_json_rpc_types::{
 use sui_sdk::error::Error;
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::committee::EpochId;
+use sui_types::messages::SenderSignedData;
 use sui_types::messages_checkpoint::{CheckpointCommitment, CheckpointSequenceNumber};
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_types::SUI_SYSTEM_ADDRESS;
@@ -57,7 +58,7 @@ const EPOCH_QUEUE_LIMIT: usize = 2;
 pub struct CheckpointHandler<S> {
     state: S,
     http_client: HttpClient,
-    event_handler: Arc<EventHandler>,
+    subscription_handler: Arc<SubscriptionHandler>,
     metrics: IndexerCheckpointHandlerMetrics,
     config: IndexerConfig,
     checkpoint_sender: Arc<Mutex<Sender<TemporaryCheckpointStore>>>,
@@ -76,7 +77,7 @@ where
     pub fn new(
         state: S,
         http_client: HttpClient,
-        event_handler: Arc<EventHandler>,
+        subscription_handler: Arc<SubscriptionHandler>,
         prometheus_registry: &Registry,
         config: &IndexerConfig,
     ) -> Self {
@@ -87,7 +88,7 @@ where
         Self {
             state,
             http_client,
-            event_handler,
+            subscription_handler,
             metrics: IndexerCheckpointHandlerMetrics::new(prometheus_registry),
             config: config.clone(),
             checkpoint_sender: Arc::new(Mutex::new(checkpoint_sender)),
@@ -377,8 +378,9 @@ where
             for checkpoint in &downloaded_checkpoints {
                 let ws_guard = self.metrics.subscription_process_latency.start_timer();
                 for tx in &checkpoint.transactions {
-                    self.event_handler
-                        .process_events(&tx.effects, &tx.events)
+                    let data: SenderSignedData = bcs::from_bytes(&tx.raw_transaction)?;
+                    self.subscription_handler
+                        .process_tx(data.transaction_data(), &tx.effects, &tx.events)
                         .await?;
                 }
                 ws_guard.stop_and_record();
@@ -0,0 +1,568 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Delivers the indexed events and transactions matching each configured webhook subscription to
+//! its endpoint. Pages are POSTed in order and the cursor of a subscription only advances once
+//! its endpoint acknowledged a page, so delivery is at-least-once across indexer restarts.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use backoff::ExponentialBackoff;
+use hmac::{Hmac, Mac};
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use url::Url;
+
+use mysten_metrics::spawn_monitored_task;
+use sui_json_rpc_types::{EventFilter, SuiTransactionBlockResponseOptions};
+use sui_types::digests::TransactionDigest;
+use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerWebhookMetrics;
+use crate::models::webhook_cursors::WebhookCursor;
+use crate::store::{get_transaction_page_by_query, IndexerStore};
+
+pub const SIGNATURE_HEADER: &str = "X-Sui-Webhook-Signature";
+pub const SUBSCRIPTION_HEADER: &str = "X-Sui-Webhook-Subscription";
+
+const PAGE_SIZE: usize = 50;
+const POLL_INTERVAL_IN_MILLIS: u64 = 1000;
+const REQUEST_TIMEOUT_IN_SECS: u64 = 30;
+const MAX_RETRY_INTERVAL_IN_SECS: u64 = 60;
+
+/// A webhook subscription, as listed in the file passed with `--webhooks-config`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WebhookConfig {
+    /// Identifies the subscription in the database, renaming a subscription restarts its
+    /// delivery from the first indexed item.
+    pub name: String,
+    pub url: Url,
+    /// Key of the HMAC-SHA256 signature of the payloads.
+    pub secret: String,
+    pub filter: WebhookFilter,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFilter {
+    Event(EventFilter),
+    /// All transactions when no filter is given.
+    Transaction(Option<TransactionFilter>),
+}
+
+/// Body of the requests sent to the webhook endpoints.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload<'a> {
+    subscription: &'a str,
+    data: serde_json::Value,
+    next_cursor: serde_json::Value,
+}
+
+/// Reads the webhook subscriptions from a YAML file.
+pub fn load_webhooks_config(path: &Path) -> Result<Vec<WebhookConfig>, IndexerError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        IndexerError::InvalidArgumentError(format!(
+            "Failed to read webhooks config {}: {e}",
+            path.display()
+        ))
+    })?;
+    let webhooks: Vec<WebhookConfig> = serde_yaml::from_str(&content).map_err(|e| {
+        IndexerError::InvalidArgumentError(format!(
+            "Failed to parse webhooks config {}: {e}",
+            path.display()
+        ))
+    })?;
+    let mut names = std::collections::HashSet::new();
+    if let Some(webhook) = webhooks.iter().find(|w| !names.insert(&w.name)) {
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "Duplicate webhook name {} in {}",
+            webhook.name,
+            path.display()
+        )));
+    }
+    Ok(webhooks)
+}
+
+/// Hex encoded HMAC-SHA256 of `body` keyed by `secret`, sent as `sha256=<hex>` in the
+/// signature header.
+pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[derive(Clone)]
+pub struct WebhookHandler<S> {
+    state: S,
+    client: reqwest::Client,
+    webhooks: Vec<WebhookConfig>,
+    metrics: IndexerWebhookMetrics,
+}
+
+impl<S> WebhookHandler<S>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(
+        state: S,
+        webhooks: Vec<WebhookConfig>,
+        prometheus_registry: &Registry,
+    ) -> Result<Self, IndexerError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_IN_SECS))
+            .build()
+            .map_err(|e| IndexerError::WebhookDeliveryError(e.to_string()))?;
+        Ok(Self {
+            state,
+            client,
+            webhooks,
+            metrics: IndexerWebhookMetrics::new(prometheus_registry),
+        })
+    }
+
+    /// Starts a delivery task per subscription.
+    pub fn spawn(self) -> Vec<JoinHandle<()>> {
+        info!(
+            "Indexer webhook handler started with {} subscriptions...",
+            self.webhooks.len()
+        );
+        self.webhooks
+            .iter()
+            .cloned()
+            .map(|webhook| {
+                let handler = self.clone();
+                spawn_monitored_task!(async move {
+                    if let Err(e) = handler.deliver(&webhook).await {
+                        handler
+                            .metrics
+                            .webhook_stopped
+                            .with_label_values(&[&webhook.name])
+                            .set(1);
+                        error!("Webhook {} stopped with error: {:?}", webhook.name, e);
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Delivers the pages of `webhook` forever, only returning on errors that retrying the same
+    /// page cannot fix.
+    async fn deliver(&self, webhook: &WebhookConfig) -> Result<(), IndexerError> {
+        let mut cursor = self
+            .state
+            .get_webhook_cursor(&webhook.name)
+            .await?
+            .map(|c| c.cursor);
+        loop {
+            let page = match self.next_page(webhook, cursor.as_deref()).await {
+                Ok(page) => page,
+                Err(e @ (IndexerError::NotSupportedError(_) | IndexerError::SerdeError(_))) => {
+                    return Err(e)
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed reading the next page of webhook {}: {:?}",
+                        webhook.name, e
+                    );
+                    None
+                }
+            };
+            let Some((data, next_cursor, count)) = page else {
+                tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_IN_MILLIS)).await;
+                continue;
+            };
+
+            let body = serde_json::to_vec(&WebhookPayload {
+                subscription: &webhook.name,
+                data,
+                next_cursor: serde_json::from_str(&next_cursor).map_err(serde_error)?,
+            })
+            .map_err(serde_error)?;
+            self.post_with_retry(webhook, body).await?;
+            self.metrics
+                .total_webhook_item_delivered
+                .with_label_values(&[&webhook.name])
+                .inc_by(count as u64);
+
+            let webhook_cursor = WebhookCursor {
+                name: webhook.name.clone(),
+                cursor: next_cursor,
+                updated_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64,
+            };
+            let (state, webhook_cursor_ref) = (&self.state, &webhook_cursor);
+            backoff::future::retry(retry_policy(), move || async move {
+                state
+                    .persist_webhook_cursor(webhook_cursor_ref)
+                    .await
+                    .map_err(backoff::Error::transient)
+            })
+            .await?;
+            cursor = Some(webhook_cursor.cursor);
+        }
+    }
+
+    /// Reads the items of `webhook` after `cursor`, returning them as JSON along with the cursor
+    /// of the last one and their count, or `None` when there is nothing new.
+    async fn next_page(
+        &self,
+        webhook: &WebhookConfig,
+        cursor: Option<&str>,
+    ) -> Result<Option<(serde_json::Value, String, usize)>, IndexerError> {
+        match &webhook.filter {
+            WebhookFilter::Event(filter) => {
+                let cursor = cursor
+                    .map(serde_json::from_str::<EventID>)
+                    .transpose()
+                    .map_err(serde_error)?;
+                let page = self
+                    .state
+                    .get_events(filter.clone(), cursor, Some(PAGE_SIZE), false)
+                    .await?;
+                let Some(next_cursor) = page.next_cursor else {
+                    return Ok(None);
+                };
+                Ok(Some((
+                    serde_json::to_value(&page.data).map_err(serde_error)?,
+                    serde_json::to_string(&next_cursor).map_err(serde_error)?,
+                    page.data.len(),
+                )))
+            }
+            WebhookFilter::Transaction(filter) => {
+                let cursor = cursor
+                    .map(serde_json::from_str::<TransactionDigest>)
+                    .transpose()
+                    .map_err(serde_error)?;
+                let txs = get_transaction_page_by_query(
+                    &self.state,
+                    filter.clone(),
+                    cursor.map(|digest| digest.base58_encode()),
+                    PAGE_SIZE,
+                    false,
+                )
+                .await?;
+                let Some(last) = txs.last() else {
+                    return Ok(None);
+                };
+                let next_cursor: TransactionDigest =
+                    last.transaction_digest.parse().map_err(|e| {
+                        IndexerError::InsertableParsingError(format!(
+                            "Failed to parse transaction digest {}: {e:?}",
+                            last.transaction_digest
+                        ))
+                    })?;
+                let options = SuiTransactionBlockResponseOptions::full_content();
+                let mut responses = Vec::with_capacity(txs.len());
+                for tx in txs {
+                    responses.push(
+                        self.state
+                            .compose_sui_transaction_block_response(tx, Some(&options))
+                            .await?,
+                    );
+                }
+                Ok(Some((
+                    serde_json::to_value(&responses).map_err(serde_error)?,
+                    serde_json::to_string(&next_cursor).map_err(serde_error)?,
+                    responses.len(),
+                )))
+            }
+        }
+    }
+
+    /// POSTs `body` to the endpoint of `webhook` until it responds with a success status. Client
+    /// errors are permanent, except for request timeouts and rate limiting: the endpoint will
+    /// reject the page however many times it is sent.
+    async fn post_with_retry(
+        &self,
+        webhook: &WebhookConfig,
+        body: Vec<u8>,
+    ) -> Result<(), IndexerError> {
+        let signature = format!("sha256={}", sign_payload(webhook.secret.as_bytes(), &body));
+        let (client, signature, body) = (&self.client, &signature, &body);
+        let post = move || async move {
+            let response = client
+                .post(webhook.url.clone())
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, signature)
+                .header(SUBSCRIPTION_HEADER, &webhook.name)
+                .body(body.clone())
+                .send()
+                .await
+                .map_err(|e| {
+                    backoff::Error::transient(IndexerError::WebhookDeliveryError(e.to_string()))
+                })?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            let e = IndexerError::WebhookDeliveryError(format!(
+                "{} responded with {status}",
+                webhook.url
+            ));
+            if is_permanent_failure(status) {
+                Err(backoff::Error::permanent(e))
+            } else {
+                Err(backoff::Error::transient(e))
+            }
+        };
+        let notify = |e: IndexerError, retry_in: Duration| {
+            self.metrics
+                .total_webhook_delivery_failure
+                .with_label_values(&[&webhook.name])
+                .inc();
+            warn!(
+                "Failed delivering webhook {} to {}: {:?}, retrying in {:?}",
+                webhook.name, webhook.url, e, retry_in
+            );
+        };
+        // the policy never gives up on transient errors, so that no page is skipped.
+        backoff::future::retry_notify(retry_policy(), post, notify).await
+    }
+}
+
+fn is_permanent_failure(status: reqwest::StatusCode) -> bool {
+    status.is_client_error()
+        && status != reqwest::StatusCode::REQUEST_TIMEOUT
+        && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+fn serde_error(e: serde_json::Error) -> IndexerError {
+    IndexerError::SerdeError(e.to_string())
+}
+
+fn retry_policy() -> ExponentialBackoff {
+    ExponentialBackoff {
+        max_interval: Duration::from_secs(MAX_RETRY_INTERVAL_IN_SECS),
+        max_elapsed_time: None,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use ntest::timeout;
+
+    use ::test_utils::network::TestClusterBuilder;
+    use ::test_utils::transaction::transfer_sui;
+    use sui_config::utils::get_available_port;
+
+    use super::*;
+    use crate::store::SqliteIndexerStore;
+    use crate::test_utils::start_test_sqlite_indexer;
+    use crate::{DbBackend, IndexerConfig};
+
+    /// A webhook endpoint failing requests until `accept` is set, then recording the pages.
+    #[derive(Clone, Default)]
+    struct Endpoint {
+        accept: Arc<AtomicBool>,
+        attempts: Arc<AtomicUsize>,
+        pages: Arc<Mutex<Vec<serde_json::Value>>>,
+    }
+
+    impl Endpoint {
+        /// Serves the endpoint on `/`, along with `/gone` which rejects every page.
+        fn start(&self) -> Url {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+            let app = Router::new()
+                .route("/", post(receive_page))
+                .route("/gone", post(|| async { StatusCode::GONE }))
+                .with_state(self.clone());
+            tokio::spawn(
+                axum::Server::from_tcp(listener)
+                    .unwrap()
+                    .serve(app.into_make_service()),
+            );
+            url
+        }
+
+        /// Digests of the transactions received since the `skip` first pages.
+        fn digests(&self, skip: usize) -> Vec<String> {
+            self.pages.lock().unwrap()[skip..]
+                .iter()
+                .flat_map(|page| page["data"].as_array().unwrap().clone())
+                .map(|tx| tx["digest"].as_str().unwrap().to_string())
+                .collect()
+        }
+    }
+
+    async fn receive_page(
+        State(endpoint): State<Endpoint>,
+        Json(page): Json<serde_json::Value>,
+    ) -> StatusCode {
+        endpoint.attempts.fetch_add(1, Ordering::SeqCst);
+        if !endpoint.accept.load(Ordering::SeqCst) {
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        endpoint.pages.lock().unwrap().push(page);
+        StatusCode::OK
+    }
+
+    async fn wait_until(mut condition: impl FnMut() -> bool) {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    async fn wait_for_cursor(store: &SqliteIndexerStore, name: &str, digest: TransactionDigest) {
+        let expected = serde_json::to_string(&digest).unwrap();
+        loop {
+            let cursor = store.get_webhook_cursor(name).await.unwrap();
+            if cursor.map_or(false, |c| c.cursor == expected) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[tokio::test]
+    #[timeout(120000)]
+    async fn test_deliver_webhook() {
+        let mut test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+        let config = IndexerConfig {
+            db_url: db_dir.path().join("indexer.db").display().to_string(),
+            db_backend: DbBackend::Sqlite,
+            rpc_client_url: test_cluster.rpc_url().to_string(),
+            rpc_server_url: "127.0.0.1".to_string(),
+            rpc_server_port: get_available_port("127.0.0.1"),
+            migrated_methods: IndexerConfig::all_implemented_methods(),
+            reset_db: true,
+            ..Default::default()
+        };
+        let (store, _indexer_handle) = start_test_sqlite_indexer(config).await.unwrap();
+        let (_, sender, _, first_digest) = transfer_sui(&mut test_cluster.wallet, None, None)
+            .await
+            .unwrap();
+
+        let endpoint = Endpoint::default();
+        let url = endpoint.start();
+        let webhooks = vec![
+            WebhookConfig {
+                name: "sender".to_string(),
+                url: url.clone(),
+                secret: "secret".to_string(),
+                filter: WebhookFilter::Transaction(Some(TransactionFilter::FromAddress(sender))),
+            },
+            WebhookConfig {
+                name: "gone".to_string(),
+                url: url.join("gone").unwrap(),
+                secret: "secret".to_string(),
+                filter: WebhookFilter::Transaction(None),
+            },
+        ];
+        let handler =
+            WebhookHandler::new(store.clone(), webhooks.clone(), &Registry::default()).unwrap();
+        let mut handles = handler.clone().spawn();
+
+        // The cursor does not advance while the endpoint fails the requests.
+        wait_until(|| endpoint.attempts.load(Ordering::SeqCst) >= 2).await;
+        assert_eq!(store.get_webhook_cursor("sender").await.unwrap(), None);
+        endpoint.accept.store(true, Ordering::SeqCst);
+        wait_for_cursor(&store, "sender", first_digest).await;
+        assert_eq!(endpoint.digests(0), vec![first_digest.base58_encode()]);
+
+        // The subscription to the rejecting endpoint stopped without advancing.
+        let gone = handles.pop().unwrap();
+        gone.await.unwrap();
+        assert_eq!(
+            handler
+                .metrics
+                .webhook_stopped
+                .with_label_values(&["gone"])
+                .get(),
+            1
+        );
+        assert_eq!(store.get_webhook_cursor("gone").await.unwrap(), None);
+        assert_eq!(
+            handler
+                .metrics
+                .webhook_stopped
+                .with_label_values(&["sender"])
+                .get(),
+            0
+        );
+
+        // After a restart, delivery resumes after the acknowledged pages.
+        for handle in handles {
+            handle.abort();
+        }
+        let received = endpoint.pages.lock().unwrap().len();
+        let (_, _, _, second_digest) = transfer_sui(&mut test_cluster.wallet, Some(sender), None)
+            .await
+            .unwrap();
+        let _handles =
+            WebhookHandler::new(store.clone(), webhooks[..1].to_vec(), &Registry::default())
+                .unwrap()
+                .spawn();
+        wait_for_cursor(&store, "sender", second_digest).await;
+        assert_eq!(
+            endpoint.digests(received),
+            vec![second_digest.base58_encode()]
+        );
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // test case 2 of RFC 4231
+        assert_eq!(
+            sign_payload(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_parse_webhooks_config() {
+        let config = r#"
+- name: coin-events
+  url: https://example.com/hooks/events
+  secret: event-secret
+  filter:
+    event:
+      MoveEventType: "0x2::coin::CurrencyCreated<0x2::sui::SUI>"
+- name: all-transactions
+  url: https://example.com/hooks/transactions
+  secret: transaction-secret
+  filter:
+    transaction: ~
+- name: sender-transactions
+  url: https://example.com/hooks/sender
+  secret: sender-secret
+  filter:
+    transaction:
+      FromAddress: "0x0000000000000000000000000000000000000000000000000000000000000002"
+"#;
+        let webhooks: Vec<WebhookConfig> = serde_yaml::from_str(config).unwrap();
+        assert_eq!(webhooks.len(), 3);
+        assert!(matches!(
+            webhooks[0].filter,
+            WebhookFilter::Event(EventFilter::MoveEventType(_))
+        ));
+        assert!(matches!(
+            webhooks[1].filter,
+            WebhookFilter::Transaction(None)
+        ));
+        assert!(matches!(
+            webhooks[2].filter,
+            WebhookFilter::Transaction(Some(TransactionFilter::FromAddress(_)))
+        ));
+        assert_eq!(webhooks[2].url.path(), "/hooks/sender");
+    }
+}
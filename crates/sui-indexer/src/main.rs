@@ -2,12 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
+use prometheus::Registry;
 use tracing::{error, info};
 
 use sui_indexer::errors::IndexerError;
-use sui_indexer::store::PgIndexerStore;
-use sui_indexer::utils::reset_database;
-use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, Indexer, IndexerConfig};
+use sui_indexer::store::{PgIndexerStore, SqliteIndexerStore};
+use sui_indexer::utils::{reset_database, run_sqlite_migrations};
+use sui_indexer::{
+    get_pg_pool_connection, get_sqlite_pool_connection, new_pg_connection_pool,
+    new_sqlite_connection_pool, DbBackend, Indexer, IndexerConfig,
+};
 use sui_node::metrics::start_prometheus_server;
 
 #[tokio::main]
@@ -31,6 +35,16 @@ async fn main() -> Result<(), IndexerError> {
     );
 
     let registry = registry_service.default_registry();
+    match indexer_config.db_backend {
+        DbBackend::Postgres => start_pg_indexer(&indexer_config, &registry).await,
+        DbBackend::Sqlite => start_sqlite_indexer(&indexer_config, &registry).await,
+    }
+}
+
+async fn start_pg_indexer(
+    indexer_config: &IndexerConfig,
+    registry: &Registry,
+) -> Result<(), IndexerError> {
     let (blocking_cp, async_cp) = new_pg_connection_pool(&indexer_config.db_url)
         .await
         .map_err(|e| {
@@ -60,5 +74,33 @@ async fn main() -> Result<(), IndexerError> {
     }
     let store = PgIndexerStore::new(async_cp, blocking_cp).await;
 
-    Indexer::start(&indexer_config, &registry, store).await
+    Indexer::start(indexer_config, registry, store).await
+}
+
+async fn start_sqlite_indexer(
+    indexer_config: &IndexerConfig,
+    registry: &Registry,
+) -> Result<(), IndexerError> {
+    let cp = new_sqlite_connection_pool(&indexer_config.db_url).map_err(|e| {
+        error!("Failed creating SQLite connection pool with error {:?}", e);
+        e
+    })?;
+    let mut conn = get_sqlite_pool_connection(&cp).map_err(|e| {
+        error!(
+            "Failed getting SQLite connection from connection pool with error {:?}",
+            e
+        );
+        e
+    })?;
+    run_sqlite_migrations(&mut conn, indexer_config.reset_db).map_err(|e| {
+        error!(
+            "Failed running migrations on SQLite database {:?} with error {:?}",
+            indexer_config.db_url, e
+        );
+        e
+    })?;
+    drop(conn);
+    let store = SqliteIndexerStore::new(cp);
+
+    Indexer::start(indexer_config, registry, store).await
 }
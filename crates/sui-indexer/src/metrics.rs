@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry, Histogram, IntCounter,
-    Registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry, Histogram,
+    IntCounter, IntCounterVec, IntGaugeVec, Registry,
 };
 
 /// Prometheus metrics for sui-indexer.
@@ -190,3 +191,38 @@ impl IndexerObjectProcessorMetrics {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct IndexerWebhookMetrics {
+    pub total_webhook_item_delivered: IntCounterVec,
+    pub total_webhook_delivery_failure: IntCounterVec,
+    pub webhook_stopped: IntGaugeVec,
+}
+
+impl IndexerWebhookMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_webhook_item_delivered: register_int_counter_vec_with_registry!(
+                "total_webhook_item_delivered",
+                "Total number of events or transactions delivered per webhook",
+                &["webhook"],
+                registry,
+            )
+            .unwrap(),
+            total_webhook_delivery_failure: register_int_counter_vec_with_registry!(
+                "total_webhook_delivery_failure",
+                "Total number of failed webhook requests per webhook",
+                &["webhook"],
+                registry,
+            )
+            .unwrap(),
+            webhook_stopped: register_int_gauge_vec_with_registry!(
+                "webhook_stopped",
+                "Set to 1 when the delivery of a webhook stopped on an error retrying cannot fix",
+                &["webhook"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use diesel::prelude::*;
 
 use crate::schema::addresses;
 
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Debug, Clone)]
 #[diesel(table_name = addresses, primary_key(account_address))]
 pub struct Address {
     pub account_address: String,
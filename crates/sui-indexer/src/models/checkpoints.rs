@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use fastcrypto::traits::EncodeDecodeBase64;
 use sui_json_rpc_types::Checkpoint as RpcCheckpoint;
@@ -14,7 +15,7 @@ use sui_types::messages_checkpoint::EndOfEpochData;
 use crate::errors::IndexerError;
 use crate::schema::checkpoints::{self};
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Debug, Clone, Default, Serialize, Deserialize)]
 #[diesel(table_name = checkpoints)]
 pub struct Checkpoint {
     pub sequence_number: i64,
@@ -0,0 +1,341 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use move_bytecode_utils::layout::TypeLayoutBuilder;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::value::MoveStruct;
+use serde::{Deserialize, Serialize};
+
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::SuiMoveValue;
+use sui_types::base_types::ObjectID;
+use sui_types::dynamic_field::{
+    derive_dynamic_field_id, DynamicFieldInfo, DynamicFieldName, DynamicFieldType,
+};
+use sui_types::object::{MoveObject, ObjectFormatOptions};
+use sui_types::{parse_sui_struct_tag, SUI_FRAMEWORK_ADDRESS};
+
+use crate::errors::IndexerError;
+use crate::models::objects::Object;
+use crate::models::owners::OwnerType;
+use crate::schema::dynamic_fields;
+use crate::store::TransactionObjectChanges;
+
+const DYNAMIC_OBJECT_FIELD_MODULE_NAME: &IdentStr = ident_str!("dynamic_object_field");
+const WRAPPER_STRUCT_NAME: &IdentStr = ident_str!("Wrapper");
+
+// NOTE: the name value is decoded from `bcs` on reads instead of being stored, because its layout
+// needs the package of the name type, which can be committed after the field is indexed.
+#[derive(Queryable, Insertable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = dynamic_fields, primary_key(object_id))]
+pub struct DynamicField {
+    // id of the sui::dynamic_field::Field object.
+    pub object_id: String,
+    pub parent_object_id: String,
+    pub version: i64,
+    pub object_digest: String,
+    pub object_type: String,
+    pub name_type: String,
+    pub field_type: DBDynamicFieldType,
+    // checkpoint seq number in which this field got updated,
+    // it can be temp -1 for field updates from fast path, same as objects.
+    pub checkpoint: i64,
+    pub bcs: Vec<u8>,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[ExistingTypePath = "crate::schema::sql_types::DynamicFieldType"]
+#[serde(rename_all = "snake_case")]
+pub enum DBDynamicFieldType {
+    DynamicField,
+    DynamicObject,
+}
+
+impl From<DBDynamicFieldType> for DynamicFieldType {
+    fn from(field_type: DBDynamicFieldType) -> Self {
+        match field_type {
+            DBDynamicFieldType::DynamicField => DynamicFieldType::DynamicField,
+            DBDynamicFieldType::DynamicObject => DynamicFieldType::DynamicObject,
+        }
+    }
+}
+
+pub struct DecodedDynamicField {
+    pub name: DynamicFieldName,
+    pub bcs_name: Vec<u8>,
+    pub field_type: DynamicFieldType,
+    // id of the field object for dynamic fields, of the wrapped object for dynamic object fields.
+    pub object_id: ObjectID,
+}
+
+impl DynamicField {
+    /// Returns `None` if `object` is not a dynamic field object owned by its parent.
+    pub fn try_from_object(object: &Object) -> Result<Option<Self>, IndexerError> {
+        let (OwnerType::ObjectOwner, Some(parent_object_id)) =
+            (&object.owner_type, &object.owner_address) else {
+            return Ok(None);
+        };
+        let tag = parse_sui_struct_tag(&object.object_type)?;
+        if !DynamicFieldInfo::is_dynamic_field(&tag) {
+            return Ok(None);
+        }
+        let field_type = if is_dynamic_object_field(&tag) {
+            DBDynamicFieldType::DynamicObject
+        } else {
+            DBDynamicFieldType::DynamicField
+        };
+        let name_type = DynamicFieldInfo::try_extract_field_name(&tag, &field_type.into())?;
+        let bcs = object.bcs.first().ok_or_else(|| {
+            IndexerError::InsertableParsingError(format!(
+                "Dynamic field object {} has no BCS contents",
+                object.object_id
+            ))
+        })?;
+        Ok(Some(Self {
+            object_id: object.object_id.clone(),
+            parent_object_id: parent_object_id.clone(),
+            version: object.version,
+            object_digest: object.object_digest.clone(),
+            object_type: object.object_type.clone(),
+            name_type: name_type.to_string(),
+            field_type,
+            checkpoint: object.checkpoint,
+            bcs: bcs.1.clone(),
+        }))
+    }
+
+    pub fn decode(
+        &self,
+        module_cache: &impl GetModule,
+    ) -> Result<DecodedDynamicField, IndexerError> {
+        let tag = parse_sui_struct_tag(&self.object_type)?;
+        let name_type = DynamicFieldInfo::try_extract_field_name(&tag, &self.field_type.into())?;
+        let layout = MoveObject::get_layout_from_struct_tag(
+            tag,
+            ObjectFormatOptions::default(),
+            module_cache,
+        )?;
+        let move_struct = MoveStruct::simple_deserialize(&self.bcs, &layout).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to deserialize dynamic field {} with error: {:?}",
+                self.object_id, e
+            ))
+        })?;
+        let (name_value, field_type, object_id) =
+            DynamicFieldInfo::parse_move_object(&move_struct)?;
+        let bcs_name = bcs::to_bytes(&name_value.clone().undecorate())?;
+        Ok(DecodedDynamicField {
+            name: DynamicFieldName {
+                type_: name_type,
+                value: SuiMoveValue::from(name_value).to_json_value(),
+            },
+            bcs_name,
+            field_type,
+            object_id,
+        })
+    }
+
+    /// Type of the field value, reported as the object type of dynamic fields.
+    pub fn value_type(&self) -> Result<String, IndexerError> {
+        parse_sui_struct_tag(&self.object_type)?
+            .type_params
+            .get(1)
+            .map(|type_| type_.to_string())
+            .ok_or_else(|| {
+                IndexerError::SerdeError(format!(
+                    "Failed to get the value type of dynamic field {}",
+                    self.object_id
+                ))
+            })
+    }
+}
+
+/// Returns the dynamic fields to upsert and the ids of the field objects to delete.
+/// Only the latest change of each field is kept, because a field removed and added back under
+/// the same name gets the same object id.
+pub fn get_dynamic_field_changes(
+    tx_object_changes: &[TransactionObjectChanges],
+) -> Result<(Vec<DynamicField>, Vec<String>), IndexerError> {
+    let mut latest_changes = BTreeMap::<String, (i64, Option<DynamicField>)>::new();
+    let mut add_change = |object_id: &String, version: i64, field: Option<DynamicField>| {
+        if !matches!(latest_changes.get(object_id), Some((latest, _)) if *latest > version) {
+            latest_changes.insert(object_id.clone(), (version, field));
+        }
+    };
+    for changes in tx_object_changes {
+        for object in &changes.changed_objects {
+            if let Some(field) = DynamicField::try_from_object(object)? {
+                add_change(&object.object_id, object.version, Some(field));
+            }
+        }
+        // NOTE: deleted objects are not all fields, deleting the other ones is a no-op.
+        for deleted_object in &changes.deleted_objects {
+            add_change(&deleted_object.object_id, deleted_object.version, None);
+        }
+    }
+
+    let mut changed_fields = vec![];
+    let mut deleted_field_ids = vec![];
+    for (object_id, (_, field)) in latest_changes {
+        match field {
+            Some(field) => changed_fields.push(field),
+            None => deleted_field_ids.push(object_id),
+        }
+    }
+    Ok((changed_fields, deleted_field_ids))
+}
+
+/// The ids that the field object of `name` has under `parent_object_id`, as a dynamic field and
+/// as a dynamic object field. Returns `None` if the name cannot be encoded from its JSON value.
+pub fn derive_dynamic_field_ids(
+    parent_object_id: ObjectID,
+    name: &DynamicFieldName,
+    module_cache: &impl GetModule,
+) -> Option<Vec<ObjectID>> {
+    let layout = TypeLayoutBuilder::build_with_types(&name.type_, module_cache).ok()?;
+    let bcs_name = SuiJsonValue::new(name.value.clone())
+        .and_then(|value| value.to_bcs_bytes(&layout))
+        .ok()?;
+    let wrapper_type = TypeTag::Struct(Box::new(StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: DYNAMIC_OBJECT_FIELD_MODULE_NAME.to_owned(),
+        name: WRAPPER_STRUCT_NAME.to_owned(),
+        type_params: vec![name.type_.clone()],
+    }));
+    [&name.type_, &wrapper_type]
+        .into_iter()
+        .map(|key_type| derive_dynamic_field_id(parent_object_id, key_type, &bcs_name).ok())
+        .collect()
+}
+
+fn is_dynamic_object_field(tag: &StructTag) -> bool {
+    matches!(
+        tag.type_params.first(),
+        Some(TypeTag::Struct(name)) if name.address == SUI_FRAMEWORK_ADDRESS
+            && name.module.as_ident_str() == DYNAMIC_OBJECT_FIELD_MODULE_NAME
+            && name.name.as_ident_str() == WRAPPER_STRUCT_NAME
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::objects::{DeletedObject, NamedBcsBytes, ObjectStatus};
+
+    const PARENT: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    const FIELD: &str = "0x0000000000000000000000000000000000000000000000000000000000000002";
+    const OTHER: &str = "0x0000000000000000000000000000000000000000000000000000000000000003";
+
+    fn object(object_id: &str, version: i64, object_type: &str) -> Object {
+        Object {
+            epoch: 0,
+            checkpoint: 1,
+            object_id: object_id.to_string(),
+            version,
+            object_digest: "digest".to_string(),
+            owner_type: OwnerType::ObjectOwner,
+            owner_address: Some(PARENT.to_string()),
+            initial_shared_version: None,
+            previous_transaction: "tx".to_string(),
+            object_type: object_type.to_string(),
+            object_status: ObjectStatus::Created,
+            has_public_transfer: false,
+            storage_rebate: 0,
+            bcs: vec![NamedBcsBytes("object".to_string(), vec![0; 48])],
+        }
+    }
+
+    fn deleted_object(object_id: &str, version: i64) -> DeletedObject {
+        DeletedObject {
+            epoch: 0,
+            checkpoint: Some(1),
+            object_id: object_id.to_string(),
+            version,
+            object_digest: "DELETED".to_string(),
+            owner_type: OwnerType::AddressOwner,
+            previous_transaction: "tx".to_string(),
+            object_type: "DELETED".to_string(),
+            object_status: ObjectStatus::Deleted,
+            has_public_transfer: false,
+        }
+    }
+
+    fn changes(
+        changed_objects: Vec<Object>,
+        deleted_objects: Vec<DeletedObject>,
+    ) -> TransactionObjectChanges {
+        TransactionObjectChanges {
+            changed_objects,
+            deleted_objects,
+        }
+    }
+
+    #[test]
+    fn test_dynamic_field_from_object() {
+        let field = DynamicField::try_from_object(&object(
+            FIELD,
+            1,
+            "0x2::dynamic_field::Field<u64, bool>",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(field.parent_object_id, PARENT);
+        assert_eq!(field.name_type, "u64");
+        assert!(matches!(field.field_type, DBDynamicFieldType::DynamicField));
+        assert_eq!(field.value_type().unwrap(), "bool");
+
+        let field = DynamicField::try_from_object(&object(
+            FIELD,
+            1,
+            "0x2::dynamic_field::Field<0x2::dynamic_object_field::Wrapper<u64>, 0x2::object::ID>",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(field.name_type, "u64");
+        assert!(matches!(
+            field.field_type,
+            DBDynamicFieldType::DynamicObject
+        ));
+
+        // Child objects which are not fields, like the objects of dynamic object fields.
+        assert!(
+            DynamicField::try_from_object(&object(OTHER, 1, "0x2::coin::Coin<0x2::sui::SUI>"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_dynamic_field_changes() {
+        let field_type = "0x2::dynamic_field::Field<u64, bool>";
+
+        // Added, then removed in a later transaction.
+        let (changed, deleted) = get_dynamic_field_changes(&[
+            changes(vec![object(FIELD, 1, field_type)], vec![]),
+            changes(
+                vec![],
+                vec![deleted_object(FIELD, 2), deleted_object(OTHER, 2)],
+            ),
+        ])
+        .unwrap();
+        assert!(changed.is_empty());
+        assert_eq!(deleted, vec![FIELD.to_string(), OTHER.to_string()]);
+
+        // Removed, then added back under the same name and object id.
+        let (changed, deleted) = get_dynamic_field_changes(&[
+            changes(vec![], vec![deleted_object(FIELD, 2)]),
+            changes(vec![object(FIELD, 3, field_type)], vec![]),
+        ])
+        .unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].version, 3);
+        assert!(deleted.is_empty());
+    }
+}
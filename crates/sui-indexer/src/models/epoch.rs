@@ -1,16 +1,25 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use diesel::{Insertable, Queryable};
-use jsonrpsee::core::__reexports::serde::Deserialize;
+use fastcrypto::hash::Digest;
+use fastcrypto::traits::ToFromBytes;
+use serde::{Deserialize, Serialize};
 
 use sui_json_rpc_types::{EndOfEpochInfo, EpochInfo};
+use sui_types::committee::ProtocolVersion;
+use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::messages_checkpoint::{
+    CheckpointCommitment, ECMHLiveObjectSetDigest, EndOfEpochData,
+};
 
 use crate::errors::IndexerError;
 use crate::models::system_state::DBValidatorSummary;
 use crate::schema::epochs;
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Debug, Clone, Default, Serialize, Deserialize)]
 #[diesel(table_name = epochs)]
 pub struct DBEpochInfo {
     pub epoch: i64,
@@ -109,6 +118,66 @@ impl DBEpochInfo {
     }
 }
 
+/// Builds the infos of `epochs` with their validators, `validators` holding those of any of the
+/// epochs.
+pub fn to_epoch_infos(
+    epochs: Vec<DBEpochInfo>,
+    validators: Vec<DBValidatorSummary>,
+) -> Result<Vec<EpochInfo>, IndexerError> {
+    let mut validators =
+        validators
+            .into_iter()
+            .fold(BTreeMap::<i64, Vec<_>>::new(), |mut acc, v| {
+                acc.entry(v.epoch).or_default().push(v);
+                acc
+            });
+    epochs
+        .into_iter()
+        .map(|info| {
+            let epoch = info.epoch;
+            info.to_epoch_info(validators.remove(&epoch).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Rebuilds the end of epoch data of the last checkpoint of an epoch from its `epochs` row.
+pub fn to_end_of_epoch_data(
+    next_epoch_version: Option<i64>,
+    next_epoch_committee: &[Option<Vec<u8>>],
+    next_epoch_committee_stake: &[Option<i64>],
+    epoch_commitments: &[Option<Vec<u8>>],
+) -> Result<Option<EndOfEpochData>, IndexerError> {
+    let next_epoch_committee = next_epoch_committee
+        .iter()
+        .flatten()
+        .zip(next_epoch_committee_stake.iter().flatten())
+        .map(|(name, vote)| {
+            AuthorityPublicKeyBytes::from_bytes(name.as_slice()).map(|b| (b, (*vote) as u64))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let epoch_commitments = epoch_commitments
+        .iter()
+        .flatten()
+        .flat_map(|v| {
+            if let Ok(v) = v.clone().try_into() {
+                return Some(CheckpointCommitment::ECMHLiveObjectSetDigest(
+                    ECMHLiveObjectSetDigest::from(Digest::new(v)),
+                ));
+            }
+            None
+        })
+        .collect::<Vec<_>>();
+
+    Ok(
+        next_epoch_version.map(|next_epoch_protocol_version| EndOfEpochData {
+            next_epoch_committee,
+            next_epoch_protocol_version: ProtocolVersion::from(next_epoch_protocol_version as u64),
+            epoch_commitments,
+        }),
+    )
+}
+
 #[derive(Deserialize)]
 pub struct SystemEpochInfoEvent {
     pub epoch: i64,
@@ -7,6 +7,7 @@ use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
 use move_core_types::value::MoveStruct;
+use serde::{Deserialize, Serialize};
 
 use sui_json_rpc_types::{SuiEvent, SuiMoveStruct};
 use sui_types::base_types::TransactionDigest;
@@ -17,7 +18,7 @@ use sui_types::parse_sui_struct_tag;
 use crate::errors::IndexerError;
 use crate::schema::events;
 
-#[derive(Queryable, Insertable, Debug, Clone)]
+#[derive(Queryable, Insertable, Debug, Clone, Serialize, Deserialize)]
 #[diesel(table_name = events)]
 pub struct Event {
     #[diesel(deserialize_as = i64)]
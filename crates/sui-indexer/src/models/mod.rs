@@ -3,6 +3,7 @@
 
 pub mod addresses;
 pub mod checkpoints;
+pub mod dynamic_fields;
 pub mod epoch;
 pub mod events;
 pub mod network_metrics;
@@ -12,3 +13,4 @@ pub mod packages;
 pub mod system_state;
 pub mod transaction_index;
 pub mod transactions;
+pub mod webhook_cursors;
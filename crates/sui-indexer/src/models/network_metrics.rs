@@ -1,12 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::str::FromStr;
+
 use diesel::sql_types::BigInt;
 use diesel::sql_types::Double;
 use diesel::sql_types::Text;
 use diesel::QueryableByName;
+use move_core_types::identifier::Identifier;
 
-use sui_json_rpc_types::NetworkMetrics;
+use sui_json_rpc_types::{MoveCallMetrics, MoveFunctionName, NetworkMetrics};
+use sui_types::base_types::ObjectID;
 
 #[derive(QueryableByName, Debug, Clone, Default)]
 pub struct DBNetworkMetrics {
@@ -53,3 +57,34 @@ impl From<DBNetworkMetrics> for NetworkMetrics {
         }
     }
 }
+
+/// Ranks the move calls of the last 3, 7 and 30 days, skipping the calls that do not name a
+/// valid Move function.
+pub fn to_move_call_metrics(metrics: Vec<DBMoveCallMetrics>) -> MoveCallMetrics {
+    let mut d3 = vec![];
+    let mut d7 = vec![];
+    let mut d30 = vec![];
+    for m in metrics {
+        let package = ObjectID::from_str(&m.move_package);
+        let module = Identifier::from_str(m.move_module.as_str());
+        let function = Identifier::from_str(m.move_function.as_str());
+        if let (Ok(package), Ok(module), Ok(function)) = (package, module, function) {
+            let fun = MoveFunctionName {
+                package,
+                module,
+                function,
+            };
+            match m.day {
+                3 => d3.push((fun, m.count as usize)),
+                7 => d7.push((fun, m.count as usize)),
+                30 => d30.push((fun, m.count as usize)),
+                _ => {}
+            }
+        }
+    }
+    MoveCallMetrics {
+        rank_3_days: d3,
+        rank_7_days: d7,
+        rank_30_days: d30,
+    }
+}
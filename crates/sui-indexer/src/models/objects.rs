@@ -31,7 +31,9 @@ const OBJECT: &str = "object";
 // NOTE: please add updating statement like below in pg_indexer_store.rs,
 // if new columns are added here:
 // objects::epoch.eq(excluded(objects::epoch))
-#[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
+#[derive(
+    Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName, Serialize, Deserialize,
+)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct Object {
     // epoch id in which this object got update.
@@ -53,7 +55,7 @@ pub struct Object {
     pub storage_rebate: i64,
     pub bcs: Vec<NamedBcsBytes>,
 }
-#[derive(SqlType, Debug, Clone)]
+#[derive(SqlType, Debug, Clone, Serialize, Deserialize)]
 #[diesel(sql_type = crate::schema::sql_types::BcsBytes)]
 pub struct NamedBcsBytes(pub String, pub Vec<u8>);
 
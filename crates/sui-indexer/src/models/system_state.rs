@@ -4,6 +4,7 @@
 use std::str::FromStr;
 
 use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
 
 use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
 use sui_types::sui_system_state::sui_system_state_summary::{
@@ -13,7 +14,7 @@ use sui_types::sui_system_state::sui_system_state_summary::{
 use crate::errors::IndexerError;
 use crate::schema::{at_risk_validators, system_states, validators};
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Debug, Clone, Default, Serialize, Deserialize)]
 #[diesel(table_name = system_states)]
 pub struct DBSystemStateSummary {
     pub epoch: i64,
@@ -70,7 +71,7 @@ impl From<SuiSystemStateSummary> for DBSystemStateSummary {
     }
 }
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Debug, Clone, Default, Serialize, Deserialize)]
 #[diesel(table_name = validators)]
 pub struct DBValidatorSummary {
     pub epoch: i64,
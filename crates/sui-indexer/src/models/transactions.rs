@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use sui_json_rpc_types::{
     OwnedObjectRef, SuiObjectRef, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
@@ -11,7 +12,7 @@ use crate::errors::IndexerError;
 use crate::schema::transactions;
 use crate::types::TemporaryTransactionBlockResponseStore;
 
-#[derive(Clone, Debug, Queryable, Insertable)]
+#[derive(Clone, Debug, Queryable, Insertable, Serialize, Deserialize)]
 #[diesel(table_name = transactions)]
 pub struct Transaction {
     #[diesel(deserialize_as = i64)]
@@ -0,0 +1,17 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::webhook_cursors;
+
+/// Delivery progress of a webhook subscription, `cursor` is the JSON of the event id or of the
+/// transaction digest last acknowledged by the endpoint.
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[diesel(table_name = webhook_cursors, primary_key(name))]
+pub struct WebhookCursor {
+    pub name: String,
+    pub cursor: String,
+    pub updated_at: i64,
+}
@@ -7,6 +7,10 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "bcs_bytes"))]
     pub struct BcsBytes;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "dynamic_field_type"))]
+    pub struct DynamicFieldType;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "object_status"))]
     pub struct ObjectStatus;
@@ -53,6 +57,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DynamicFieldType;
+
+    dynamic_fields (object_id) {
+        object_id -> Varchar,
+        parent_object_id -> Varchar,
+        version -> Int8,
+        object_digest -> Varchar,
+        object_type -> Varchar,
+        name_type -> Varchar,
+        field_type -> DynamicFieldType,
+        checkpoint -> Int8,
+        bcs -> Bytea,
+    }
+}
+
 diesel::table! {
     epochs (epoch) {
         epoch -> Int8,
@@ -295,10 +316,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webhook_cursors (name) {
+        name -> Varchar,
+        cursor -> Varchar,
+        updated_at -> Int8,
+    }
+}
+
 diesel::allow_tables_to_appear_in_same_query!(
     addresses,
     at_risk_validators,
     checkpoints,
+    dynamic_fields,
     epochs,
     events,
     input_objects,
@@ -310,4 +340,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     system_states,
     transactions,
     validators,
+    webhook_cursors,
 );
@@ -0,0 +1,131 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schema of the SQLite database, see `migrations_sqlite`.
+
+// The tables without BCS-encoded `data` are the same as in Postgres, and their rows are read and
+// written through the same models.
+pub use crate::schema::{addresses, input_objects, move_calls, recipients, webhook_cursors};
+
+diesel::table! {
+    checkpoints (sequence_number) {
+        sequence_number -> BigInt,
+        checkpoint_digest -> Text,
+        epoch -> BigInt,
+        total_transactions -> BigInt,
+        network_total_transactions -> BigInt,
+        timestamp_ms -> BigInt,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    dynamic_fields (object_id) {
+        object_id -> Text,
+        parent_object_id -> Text,
+        version -> BigInt,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    epochs (epoch) {
+        epoch -> BigInt,
+        epoch_start_timestamp -> BigInt,
+        epoch_total_transactions -> BigInt,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    events (id) {
+        id -> BigInt,
+        transaction_digest -> Text,
+        event_sequence -> BigInt,
+        sender -> Text,
+        package -> Text,
+        module -> Text,
+        event_type -> Text,
+        event_time_ms -> Nullable<BigInt>,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    objects (object_id) {
+        object_id -> Text,
+        version -> BigInt,
+        checkpoint -> BigInt,
+        owner_type -> Text,
+        owner_address -> Nullable<Text>,
+        object_type -> Text,
+        object_status -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    objects_history (object_id, version, checkpoint) {
+        object_id -> Text,
+        version -> BigInt,
+        checkpoint -> BigInt,
+        owner_type -> Text,
+        owner_address -> Nullable<Text>,
+        old_owner_type -> Nullable<Text>,
+        old_owner_address -> Nullable<Text>,
+        object_type -> Text,
+        object_status -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    packages (package_id, version) {
+        package_id -> Text,
+        version -> BigInt,
+        author -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    system_states (epoch) {
+        epoch -> BigInt,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    transactions (id) {
+        id -> BigInt,
+        transaction_digest -> Text,
+        sender -> Text,
+        checkpoint_sequence_number -> Nullable<BigInt>,
+        timestamp_ms -> Nullable<BigInt>,
+        transaction_kind -> Text,
+        transaction_count -> BigInt,
+        changed_objects -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    validators (epoch, sui_address) {
+        epoch -> BigInt,
+        sui_address -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(
+    checkpoints,
+    dynamic_fields,
+    epochs,
+    events,
+    objects,
+    objects_history,
+    packages,
+    system_states,
+    transactions,
+    validators,
+);
@@ -1,26 +1,35 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use async_trait::async_trait;
 use prometheus::Histogram;
 
+use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
+use sui_json_rpc::{ObjectProvider, ObjectProviderCache};
 use sui_json_rpc_types::{
-    Checkpoint as RpcCheckpoint, CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics,
-    NetworkMetrics, SuiObjectData, SuiObjectDataFilter, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    Checkpoint as RpcCheckpoint, CheckpointId, DynamicFieldPage, EpochInfo, EventFilter, EventPage,
+    MoveCallMetrics, NetworkMetrics, Page, SuiEvent, SuiObjectData, SuiObjectDataFilter,
+    SuiTransactionBlock, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_types::base_types::{EpochId, ObjectID, SequenceNumber, SuiAddress, VersionNumber};
-use sui_types::digests::CheckpointDigest;
-use sui_types::error::SuiError;
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
+use sui_types::error::{SuiError, UserInputError};
 use sui_types::event::EventID;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
 use sui_types::storage::ObjectStore;
 
 use crate::errors::IndexerError;
 use crate::models::addresses::Address;
 use crate::models::checkpoints::Checkpoint;
+use crate::models::dynamic_fields::{derive_dynamic_field_ids, DynamicField};
 use crate::models::epoch::DBEpochInfo;
 use crate::models::events::Event;
 use crate::models::objects::{DeletedObject, Object, ObjectStatus};
@@ -28,11 +37,13 @@ use crate::models::packages::Package;
 use crate::models::system_state::{DBSystemStateSummary, DBValidatorSummary};
 use crate::models::transaction_index::{InputObject, MoveCall, Recipient};
 use crate::models::transactions::Transaction;
+use crate::models::webhook_cursors::WebhookCursor;
 use crate::types::CheckpointTransactionBlockResponse;
+use crate::utils::{get_balance_changes_from_effect, get_object_changes};
 
 #[async_trait]
 pub trait IndexerStore {
-    type ModuleCache;
+    type ModuleCache: GetModule;
 
     async fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError>;
     async fn get_checkpoint(&self, id: CheckpointId) -> Result<RpcCheckpoint, IndexerError>;
@@ -56,6 +67,13 @@ pub trait IndexerStore {
         version: Option<SequenceNumber>,
     ) -> Result<ObjectRead, IndexerError>;
 
+    /// The latest version of an object as of `checkpoint`, from the objects history.
+    async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<ObjectRead, IndexerError>;
+
     async fn query_objects_history(
         &self,
         filter: SuiObjectDataFilter,
@@ -71,6 +89,21 @@ pub trait IndexerStore {
         limit: usize,
     ) -> Result<Vec<ObjectRead>, IndexerError>;
 
+    /// The dynamic fields of `parent_object_id` in field object id order, after `cursor`.
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<DynamicField>, IndexerError>;
+
+    /// The dynamic fields of `parent_object_id` among the field objects `object_ids`.
+    async fn multi_get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        object_ids: &[ObjectID],
+    ) -> Result<Vec<DynamicField>, IndexerError>;
+
     async fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError>;
 
     // TODO: combine all get_transaction* methods
@@ -154,6 +187,14 @@ pub trait IndexerStore {
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError>;
 
+    async fn get_transaction_page_by_filter(
+        &self,
+        filter: TransactionFilter,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError>;
+
     async fn get_transaction_sequence_by_digest(
         &self,
         tx_digest: Option<String>,
@@ -226,6 +267,10 @@ pub trait IndexerStore {
 
     async fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError>;
 
+    /// The delivery cursor of the webhook subscription `name`, `None` before its first delivery.
+    async fn get_webhook_cursor(&self, name: &str) -> Result<Option<WebhookCursor>, IndexerError>;
+    async fn persist_webhook_cursor(&self, cursor: &WebhookCursor) -> Result<(), IndexerError>;
+
     fn module_cache(&self) -> &Self::ModuleCache;
 }
 
@@ -297,3 +342,430 @@ pub struct TemporaryEpochStore {
     pub system_state: DBSystemStateSummary,
     pub validators: Vec<DBValidatorSummary>,
 }
+
+/// Builds the RPC response of an indexed transaction, shared by the store implementations.
+pub(crate) async fn compose_transaction_block_response<S>(
+    store: &S,
+    tx: Transaction,
+    options: Option<&SuiTransactionBlockResponseOptions>,
+) -> Result<SuiTransactionBlockResponse, IndexerError>
+where
+    S: IndexerStore + ObjectProvider<Error = IndexerError> + Clone + Sync + Send,
+{
+    let transaction: SuiTransactionBlock =
+        serde_json::from_str(&tx.transaction_content).map_err(|err| {
+            IndexerError::InsertableParsingError(format!(
+                "Failed converting transaction JSON {:?} to SuiTransactionBlock with error: {:?}",
+                tx.transaction_content, err
+            ))
+        })?;
+    let effects: SuiTransactionBlockEffects = serde_json::from_str(&tx.transaction_effects_content).map_err(|err| {
+    IndexerError::InsertableParsingError(format!(
+        "Failed converting transaction effect JSON {:?} to SuiTransactionBlockEffects with error: {:?}",
+        tx.transaction_effects_content, err
+    ))
+    })?;
+
+    let tx_digest: TransactionDigest = tx.transaction_digest.parse().map_err(|e| {
+        IndexerError::InsertableParsingError(format!(
+            "Failed to parse transaction digest {} : {:?}",
+            tx.transaction_digest, e
+        ))
+    })?;
+    let sender = SuiAddress::from_str(tx.sender.as_str())?;
+
+    let (mut tx_opt, mut effects_opt, mut raw_tx) = (None, None, vec![]);
+    let (mut object_changes, mut balance_changes, mut events) = (None, None, None);
+    if let Some(options) = options {
+        if options.show_balance_changes {
+            let object_cache = ObjectProviderCache::new(store.clone());
+            balance_changes = Some(get_balance_changes_from_effect(&object_cache, &effects).await?);
+        }
+        if options.show_object_changes {
+            let object_cache = ObjectProviderCache::new(store.clone());
+            object_changes = Some(
+                get_object_changes(
+                    &object_cache,
+                    sender,
+                    &effects.modified_at_versions(),
+                    effects.all_changed_objects(),
+                    effects.all_deleted_objects(),
+                )
+                .await?,
+            );
+        }
+        if options.show_events {
+            let event_page = store
+                .get_events(
+                    EventFilter::Transaction(tx_digest),
+                    None,
+                    None,
+                    /* descending_order */ false,
+                )
+                .await?;
+            events = Some(SuiTransactionBlockEvents {
+                data: event_page.data,
+            });
+        }
+        if options.show_input {
+            tx_opt = Some(transaction);
+        }
+        if options.show_raw_input {
+            raw_tx = tx.raw_transaction;
+        }
+        if options.show_effects {
+            effects_opt = Some(effects);
+        }
+    }
+
+    Ok(SuiTransactionBlockResponse {
+        digest: tx_digest,
+        transaction: tx_opt,
+        raw_transaction: raw_tx,
+        effects: effects_opt,
+        confirmed_local_execution: tx.confirmed_local_execution,
+        timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
+        checkpoint: tx.checkpoint_sequence_number.map(|c| c as u64),
+        events,
+        object_changes,
+        balance_changes,
+        errors: vec![],
+    })
+}
+
+const MAX_EVENT_PAGE_SIZE: usize = 1000;
+
+/// Checks the requested size of an event page, the maximum page size by default.
+pub(crate) fn event_page_limit(limit: Option<usize>) -> Result<usize, IndexerError> {
+    let page_limit = limit.unwrap_or(MAX_EVENT_PAGE_SIZE);
+    if page_limit > MAX_EVENT_PAGE_SIZE {
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "Limit {} exceeds the maximum page size {}",
+            page_limit, MAX_EVENT_PAGE_SIZE
+        )));
+    }
+    Ok(page_limit)
+}
+
+/// Builds a page of up to `page_limit` events, `events` being read with one more item to tell if
+/// there is a next page.
+pub(crate) fn to_event_page(mut events: Vec<SuiEvent>, page_limit: usize) -> EventPage {
+    let has_next_page = events.len() > page_limit;
+    events.truncate(page_limit);
+    let next_cursor = events.last().map(|e| e.id.clone());
+    EventPage {
+        data: events,
+        next_cursor,
+        has_next_page,
+    }
+}
+
+/// Reads the transactions of `tx_digests` in the order of the digests, as returned by the
+/// queries over the transaction index tables.
+pub(crate) async fn get_transactions_in_digest_order<S: IndexerStore + Sync>(
+    store: &S,
+    tx_digests: Vec<String>,
+) -> Result<Vec<Transaction>, IndexerError> {
+    let mut transactions = store
+        .multi_get_transactions_by_digests(&tx_digests)
+        .await?
+        .into_iter()
+        .map(|tx| (tx.transaction_digest.clone(), tx))
+        .collect::<HashMap<_, _>>();
+    Ok(tx_digests
+        .iter()
+        .filter_map(|digest| transactions.remove(digest))
+        .collect())
+}
+
+/// Reads a page of transactions matching `filter`, starting after the transaction with digest
+/// `cursor_str`. Filters served by the transaction index tables page over those tables' sequence.
+pub(crate) async fn get_transaction_page_by_query<S: IndexerStore>(
+    store: &S,
+    filter: Option<TransactionFilter>,
+    cursor_str: Option<String>,
+    limit: usize,
+    is_descending: bool,
+) -> Result<Vec<Transaction>, IndexerError> {
+    match filter {
+        None => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_all_transaction_page(indexer_seq_number, limit, is_descending)
+                .await
+        }
+        Some(TransactionFilter::Checkpoint(checkpoint_id)) => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_checkpoint(
+                    checkpoint_id as i64,
+                    indexer_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::MoveFunction {
+            package,
+            module,
+            function,
+        }) => {
+            let module = if let Some(m) = module {
+                Some(
+                    Identifier::new(m)
+                        .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?,
+                )
+            } else {
+                None
+            };
+            let function = if let Some(f) = function {
+                Some(
+                    Identifier::new(f)
+                        .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?,
+                )
+            } else {
+                None
+            };
+            let move_call_seq_number = store
+                .get_move_call_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_move_call(
+                    package,
+                    module,
+                    function,
+                    move_call_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::InputObject(input_obj_id)) => {
+            let input_obj_seq = store
+                .get_input_object_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_input_object(
+                    input_obj_id,
+                    /* version */ None,
+                    input_obj_seq,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::ChangedObject(mutated_obj_id)) => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_mutated_object(
+                    mutated_obj_id.to_string(),
+                    indexer_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        // NOTE: more efficient to run this query over transactions table
+        Some(TransactionFilter::FromAddress(sender_address)) => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_sender_address(
+                    sender_address.to_string(),
+                    indexer_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::ToAddress(recipient_address)) => {
+            let recipient_seq_number = store
+                .get_recipient_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_sender_recipient_address(
+                    /* from */ None,
+                    recipient_address,
+                    recipient_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::FromAndToAddress { from, to }) => {
+            let recipient_seq_number = store
+                .get_recipient_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_sender_recipient_address(
+                    Some(from),
+                    to,
+                    recipient_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(TransactionFilter::TransactionKind(tx_kind_name)) => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_transaction_kind(
+                    tx_kind_name,
+                    indexer_seq_number,
+                    limit,
+                    is_descending,
+                )
+                .await
+        }
+        Some(
+            filter @ (TransactionFilter::CheckpointRange { .. }
+            | TransactionFilter::TimeRange { .. }
+            | TransactionFilter::And(..)
+            | TransactionFilter::Or(..)
+            | TransactionFilter::Not(_)),
+        ) => {
+            let indexer_seq_number = store
+                .get_transaction_sequence_by_digest(cursor_str, is_descending)
+                .await?;
+            store
+                .get_transaction_page_by_filter(filter, indexer_seq_number, limit, is_descending)
+                .await
+        }
+    }
+}
+
+/// Page size of the scan for dynamic field names that cannot be encoded from their JSON value.
+const DYNAMIC_FIELD_SCAN_PAGE_SIZE: usize = 1000;
+
+/// Reads a page of the dynamic fields of `parent_object_id`, with the field object id of the
+/// last field as the next cursor.
+pub(crate) async fn get_dynamic_field_page<S: IndexerStore>(
+    store: &S,
+    parent_object_id: ObjectID,
+    cursor: Option<ObjectID>,
+    limit: usize,
+) -> Result<DynamicFieldPage, IndexerError> {
+    // NOTE: fetch one more field to check if there is next page
+    let mut fields = store
+        .get_dynamic_fields(parent_object_id, cursor, limit + 1)
+        .await?;
+    let has_next_page = fields.len() > limit;
+    fields.truncate(limit);
+    let next_cursor = match fields.last() {
+        Some(field) => Some(ObjectID::from_str(&field.object_id)?),
+        None => cursor,
+    };
+
+    let mut data = vec![];
+    for field in fields {
+        data.push(to_dynamic_field_info(store, field).await?);
+    }
+    Ok(Page {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// Returns the id of the object behind the dynamic field `name` of `parent_object_id`: the field
+/// object for dynamic fields, the wrapped object for dynamic object fields.
+pub(crate) async fn get_dynamic_field_object_id<S: IndexerStore>(
+    store: &S,
+    parent_object_id: ObjectID,
+    name: &DynamicFieldName,
+) -> Result<Option<ObjectID>, IndexerError> {
+    let field = match derive_dynamic_field_ids(parent_object_id, name, store.module_cache()) {
+        Some(object_ids) => store
+            .multi_get_dynamic_fields(parent_object_id, &object_ids)
+            .await?
+            .pop(),
+        // NOTE: the name cannot be encoded to derive the field ids, fall back to decoding the
+        // fields of the parent with the same name type, like the fullnode index does.
+        None => find_dynamic_field_by_name(store, parent_object_id, name).await?,
+    };
+    field
+        .map(|field| Ok(field.decode(store.module_cache())?.object_id))
+        .transpose()
+}
+
+async fn find_dynamic_field_by_name<S: IndexerStore>(
+    store: &S,
+    parent_object_id: ObjectID,
+    name: &DynamicFieldName,
+) -> Result<Option<DynamicField>, IndexerError> {
+    let name_type = name.type_.to_string();
+    let mut cursor = None;
+    loop {
+        let fields = store
+            .get_dynamic_fields(parent_object_id, cursor, DYNAMIC_FIELD_SCAN_PAGE_SIZE)
+            .await?;
+        let Some(last) = fields.last() else {
+            return Ok(None);
+        };
+        cursor = Some(ObjectID::from_str(&last.object_id)?);
+        for field in fields {
+            if field.name_type == name_type
+                && field.decode(store.module_cache())?.name.value == name.value
+            {
+                return Ok(Some(field));
+            }
+        }
+    }
+}
+
+async fn to_dynamic_field_info<S: IndexerStore>(
+    store: &S,
+    field: DynamicField,
+) -> Result<DynamicFieldInfo, IndexerError> {
+    let decoded = field.decode(store.module_cache())?;
+    let (object_id, version, digest, object_type) = match decoded.field_type {
+        DynamicFieldType::DynamicField => (
+            decoded.object_id,
+            SequenceNumber::from(field.version as u64),
+            field.object_digest.parse().map_err(|e| {
+                IndexerError::SerdeError(format!(
+                    "Failed to parse object digest: {}, error: {}",
+                    field.object_digest, e
+                ))
+            })?,
+            field.value_type()?,
+        ),
+        // NOTE: the wrapped object can change without its field, so report its latest version.
+        DynamicFieldType::DynamicObject => match store.get_object(decoded.object_id, None).await? {
+            ObjectRead::Exists((object_id, version, digest), object, _) => (
+                object_id,
+                version,
+                digest,
+                object
+                    .type_()
+                    .map(|type_| type_.to_string())
+                    .unwrap_or_default(),
+            ),
+            _ => {
+                return Err(UserInputError::ObjectNotFound {
+                    object_id: decoded.object_id,
+                    version: None,
+                }
+                .into())
+            }
+        },
+    };
+    Ok(DynamicFieldInfo {
+        name: decoded.name,
+        bcs_name: decoded.bcs_name,
+        type_: decoded.field_type,
+        object_type,
+        object_id,
+        version,
+        digest,
+    })
+}
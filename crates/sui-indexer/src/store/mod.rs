@@ -3,11 +3,13 @@
 
 pub use indexer_store::*;
 pub use pg_indexer_store::PgIndexerStore;
+pub use sqlite_indexer_store::SqliteIndexerStore;
 
 mod indexer_store;
 mod module_resolver;
 mod pg_indexer_store;
 mod query;
+mod sqlite_indexer_store;
 
 mod diesel_marco {
     macro_rules! read_only_blocking {
@@ -57,8 +59,82 @@ mod diesel_marco {
                 .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))
         }};
     }
+    // The macros below build the queries shared by the Postgres and SQLite stores, whose tables
+    // share the column names that are filtered and sorted on.
+
+    /// Narrows the boxed query `$query` on the `$events` table to the events matching `$filter`.
+    macro_rules! filter_events {
+        ($events:ident, $query:expr, $filter:expr) => {{
+            let query = $query;
+            match $filter {
+                sui_json_rpc_types::EventFilter::All(..) => Ok(query),
+                sui_json_rpc_types::EventFilter::Transaction(digest) => {
+                    Ok(query.filter($events::transaction_digest.eq(digest.base58_encode())))
+                }
+                sui_json_rpc_types::EventFilter::MoveModule { package, module } => Ok(query
+                    .filter($events::package.eq(package.to_string()))
+                    .filter($events::module.eq(module.to_string()))),
+                sui_json_rpc_types::EventFilter::MoveEventType(struct_name) => {
+                    Ok(query.filter($events::event_type.eq(struct_name.to_string())))
+                }
+                sui_json_rpc_types::EventFilter::Sender(sender) => {
+                    Ok(query.filter($events::sender.eq(sender.to_string())))
+                }
+                sui_json_rpc_types::EventFilter::TimeRange {
+                    start_time,
+                    end_time,
+                } => Ok(query
+                    .filter($events::event_time_ms.ge(start_time as i64))
+                    .filter($events::event_time_ms.lt(end_time as i64))),
+                // TODO: Implement EventFilter to SQL
+                filter => Err(crate::errors::IndexerError::NotSupportedError(format!(
+                    "Filter type [{filter:?}] not supported by the Indexer."
+                ))),
+            }
+        }};
+    }
+
+    /// Restricts the boxed query `$query` to the rows after `$start_sequence` in `$id` order,
+    /// sorted in that order.
+    macro_rules! page_by_id {
+        ($query:expr, $id:expr, $start_sequence:expr, $is_descending:expr) => {{
+            let (mut query, is_descending) = ($query, $is_descending);
+            if let Some(start_sequence) = $start_sequence {
+                query = if is_descending {
+                    query.filter($id.lt(start_sequence))
+                } else {
+                    query.filter($id.gt(start_sequence))
+                };
+            }
+            if is_descending {
+                query.order($id.desc())
+            } else {
+                query.order($id.asc())
+            }
+        }};
+    }
+
+    /// Selects the `id` of the first row of the `$table` index written by the transaction with
+    /// digest `$digest`, in the paging order.
+    macro_rules! sequence_by_digest {
+        ($table:ident, $digest:expr, $is_descending:expr) => {{
+            let query = $table::table
+                .select($table::id)
+                .filter($table::transaction_digest.eq($digest))
+                .into_boxed();
+            if $is_descending {
+                query.order($table::id.desc())
+            } else {
+                query.order($table::id.asc())
+            }
+        }};
+    }
+
+    pub(crate) use filter_events;
+    pub(crate) use page_by_id;
     pub(crate) use read_only;
     pub(crate) use read_only_blocking;
+    pub(crate) use sequence_by_digest;
     pub(crate) use transactional;
     pub(crate) use transactional_blocking;
 }
@@ -5,14 +5,17 @@ use diesel::pg::sql_types::Bytea;
 use diesel::sql_types::Text;
 use diesel::QueryableByName;
 use diesel::RunQueryDsl;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, QueryDsl};
 use move_core_types::language_storage::ModuleId;
 use move_core_types::resolver::ModuleResolver;
 
 use sui_types::base_types::ObjectID;
 
 use crate::errors::{Context, IndexerError};
+use crate::models::packages::Package;
+use crate::sqlite_schema::packages;
 use crate::store::diesel_marco::read_only_blocking;
-use crate::PgConnectionPool;
+use crate::{get_sqlite_pool_connection, PgConnectionPool, SqliteConnectionPool};
 
 pub struct IndexerModuleResolver {
     cp: PgConnectionPool,
@@ -54,3 +57,44 @@ impl ModuleResolver for IndexerModuleResolver {
         Ok(Some(module_bytes.data))
     }
 }
+
+pub struct SqliteModuleResolver {
+    cp: SqliteConnectionPool,
+}
+
+impl SqliteModuleResolver {
+    pub fn new(cp: SqliteConnectionPool) -> Self {
+        Self { cp }
+    }
+}
+
+impl ModuleResolver for SqliteModuleResolver {
+    type Error = IndexerError;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        let package_id = ObjectID::from(*id.address()).to_string();
+        let module_name = id.name().to_string();
+
+        let mut conn = get_sqlite_pool_connection(&self.cp)?;
+        let package = conn
+            .transaction(|conn| {
+                packages::table
+                    .select(packages::data)
+                    .filter(packages::package_id.eq(&package_id))
+                    .order(packages::version.desc())
+                    .first::<Vec<u8>>(conn)
+                    .optional()
+            })
+            .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+            .context("Error reading module.")?;
+        let Some(package) = package else {
+            return Ok(None);
+        };
+        let package: Package = bcs::from_bytes(&package)?;
+        Ok(package
+            .data
+            .into_iter()
+            .find(|module| module.0 == module_name)
+            .map(|module| module.1))
+    }
+}
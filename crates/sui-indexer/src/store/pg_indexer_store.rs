@@ -17,65 +17,64 @@ use diesel::{ExpressionMethods, PgArrayExpressionMethods};
 use diesel::{OptionalExtension, QueryableByName};
 use diesel_async::scoped_futures::ScopedFutureExt;
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
-use fastcrypto::hash::Digest;
-use fastcrypto::traits::ToFromBytes;
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::identifier::Identifier;
 use prometheus::Histogram;
 use tracing::info;
 
-use sui_json_rpc::{ObjectProvider, ObjectProviderCache};
+use sui_json_rpc::ObjectProvider;
 use sui_json_rpc_types::{
-    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, MoveFunctionName,
-    NetworkMetrics, SuiEvent, SuiObjectDataFilter,
-};
-use sui_json_rpc_types::{
-    SuiTransactionBlock, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
-    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, NetworkMetrics, SuiEvent,
+    SuiObjectDataFilter,
 };
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
-use sui_types::committee::{EpochId, ProtocolVersion};
-use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::committee::EpochId;
 use sui_types::digests::CheckpointDigest;
-use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
-use sui_types::messages_checkpoint::{
-    CheckpointCommitment, CheckpointSequenceNumber, ECMHLiveObjectSetDigest, EndOfEpochData,
-};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
 
 use crate::errors::{Context, IndexerError};
 use crate::models::addresses::Address;
 use crate::models::checkpoints::Checkpoint;
-use crate::models::epoch::DBEpochInfo;
+use crate::models::dynamic_fields::{get_dynamic_field_changes, DynamicField};
+use crate::models::epoch::{to_end_of_epoch_data, to_epoch_infos, DBEpochInfo};
 use crate::models::events::Event;
-use crate::models::network_metrics::{DBMoveCallMetrics, DBNetworkMetrics};
+use crate::models::network_metrics::{to_move_call_metrics, DBMoveCallMetrics, DBNetworkMetrics};
 use crate::models::objects::{
-    compose_object_bulk_insert_update_query, group_and_sort_objects, Object,
+    compose_object_bulk_insert_update_query, group_and_sort_objects, Object, ObjectStatus,
 };
 use crate::models::packages::Package;
 use crate::models::system_state::DBValidatorSummary;
 use crate::models::transaction_index::{InputObject, MoveCall, Recipient};
 use crate::models::transactions::Transaction;
+use crate::models::webhook_cursors::WebhookCursor;
 use crate::schema::{
-    addresses, checkpoints, checkpoints::dsl as checkpoints_dsl, epochs, epochs::dsl as epochs_dsl,
-    events, input_objects, input_objects::dsl as input_objects_dsl, move_calls,
-    move_calls::dsl as move_calls_dsl, objects, objects::dsl as objects_dsl, objects_history,
-    packages, recipients, recipients::dsl as recipients_dsl, system_states, transactions,
-    transactions::dsl as transactions_dsl, validators,
+    addresses, checkpoints, checkpoints::dsl as checkpoints_dsl, dynamic_fields,
+    dynamic_fields::dsl as dynamic_fields_dsl, epochs, epochs::dsl as epochs_dsl, events,
+    input_objects, move_calls, objects, objects::dsl as objects_dsl, objects_history, packages,
+    recipients, system_states, transactions, transactions::dsl as transactions_dsl, validators,
+    webhook_cursors,
 };
 use crate::store::diesel_marco::{
-    read_only, read_only_blocking, transactional, transactional_blocking,
+    filter_events, page_by_id, read_only, read_only_blocking, sequence_by_digest, transactional,
+    transactional_blocking,
+};
+use crate::store::indexer_store::{
+    compose_transaction_block_response, event_page_limit, get_transactions_in_digest_order,
+    to_event_page, TemporaryCheckpointStore,
 };
-use crate::store::indexer_store::TemporaryCheckpointStore;
 use crate::store::module_resolver::IndexerModuleResolver;
-use crate::store::query::DBFilter;
+use crate::store::query::{
+    to_input_object_transactions_sql, to_move_call_transactions_sql, to_recipient_transactions_sql,
+    to_transactions_sql, DBFilter, TempDigestTable,
+};
 use crate::store::TransactionObjectChanges;
 use crate::store::{IndexerStore, TemporaryEpochStore};
-use crate::utils::{get_balance_changes_from_effect, get_object_changes};
 use crate::{AsyncPgConnectionPool, PgConnectionPool};
 
-const MAX_EVENT_PAGE_SIZE: usize = 1000;
 const PG_COMMIT_CHUNK_SIZE: usize = 1000;
 
 const GET_PARTITION_SQL: &str = r#"
@@ -90,12 +89,6 @@ WHERE parent.relkind = 'p'
 GROUP BY table_name;
 "#;
 
-#[derive(QueryableByName, Debug, Clone)]
-struct TempDigestTable {
-    #[diesel(sql_type = VarChar)]
-    digest_name: String,
-}
-
 #[derive(Clone)]
 pub struct PgIndexerStore {
     cp: AsyncPgConnectionPool,
@@ -260,36 +253,12 @@ impl IndexerStore for PgIndexerStore {
                     )>(conn)
                     .await?;
 
-                let next_epoch_committee = next_epoch_committee
-                    .iter()
-                    .flatten()
-                    .zip(next_epoch_committee_stake.iter().flatten())
-                    .map(|(name, vote)| {
-                        AuthorityPublicKeyBytes::from_bytes(name.as_slice())
-                            .map(|b| (b, (*vote) as u64))
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let epoch_commitments = epoch_commitments
-                    .iter()
-                    .flatten()
-                    .flat_map(|v| {
-                        if let Ok(v) = v.clone().try_into() {
-                            return Some(CheckpointCommitment::ECMHLiveObjectSetDigest(
-                                ECMHLiveObjectSetDigest::from(Digest::new(v)),
-                            ));
-                        }
-                        None
-                    })
-                    .collect::<Vec<_>>();
-
-                next_version.map(|next_epoch_protocol_version| EndOfEpochData {
-                    next_epoch_committee,
-                    next_epoch_protocol_version: ProtocolVersion::from(
-                        next_epoch_protocol_version as u64,
-                    ),
-                    epoch_commitments,
-                })
+                to_end_of_epoch_data(
+                    next_version,
+                    &next_epoch_committee,
+                    &next_epoch_committee_stake,
+                    &epoch_commitments,
+                )?
             } else {
                 None
             };
@@ -333,51 +302,8 @@ impl IndexerStore for PgIndexerStore {
         limit: Option<usize>,
         descending_order: bool,
     ) -> Result<EventPage, IndexerError> {
-        let mut boxed_query = events::table.into_boxed();
-        match query {
-            EventFilter::All(..) => {}
-            EventFilter::Transaction(digest) => {
-                boxed_query =
-                    boxed_query.filter(events::dsl::transaction_digest.eq(digest.base58_encode()));
-            }
-            EventFilter::MoveModule { package, module } => {
-                boxed_query = boxed_query
-                    .filter(events::dsl::package.eq(package.to_string()))
-                    .filter(events::dsl::module.eq(module.to_string()));
-            }
-            EventFilter::MoveEventType(struct_name) => {
-                boxed_query =
-                    boxed_query.filter(events::dsl::event_type.eq(struct_name.to_string()));
-            }
-            EventFilter::Sender(sender) => {
-                boxed_query = boxed_query.filter(events::dsl::sender.eq(sender.to_string()));
-            }
-            EventFilter::TimeRange {
-                start_time,
-                end_time,
-            } => {
-                boxed_query = boxed_query
-                    .filter(events::dsl::event_time_ms.ge(start_time as i64))
-                    .filter(events::dsl::event_time_ms.lt(end_time as i64));
-            }
-            // TODO: Implement EventFilter to SQL
-            _ => {
-                return Err(IndexerError::NotSupportedError(format!(
-                    "Filter type [{query:?}] not supported by the Indexer."
-                )))
-            }
-        }
-
-        let mut page_limit = limit.unwrap_or(MAX_EVENT_PAGE_SIZE);
-        if page_limit > MAX_EVENT_PAGE_SIZE {
-            Err(IndexerError::InvalidArgumentError(format!(
-                "Limit {} exceeds the maximum page size {}",
-                page_limit, MAX_EVENT_PAGE_SIZE
-            )))?;
-        }
-        // fetch one more item to tell if there is next page
-        page_limit += 1;
-
+        let boxed_query = filter_events!(events, events::table.into_boxed(), query)?;
+        let page_limit = event_page_limit(limit)?;
         let pg_cursor =
             if let Some(cursor) = cursor {
                 Some(self.get_event(cursor).await?.id.ok_or_else(|| {
@@ -386,38 +312,20 @@ impl IndexerStore for PgIndexerStore {
             } else {
                 None
             };
+        let boxed_query = page_by_id!(boxed_query, events::id, pg_cursor, descending_order);
 
-        let events_vec: Vec<Event> = read_only!(&self.cp, |conn| {
-            if let Some(pg_cursor) = pg_cursor {
-                if descending_order {
-                    boxed_query = boxed_query.filter(events::dsl::id.lt(pg_cursor));
-                } else {
-                    boxed_query = boxed_query.filter(events::dsl::id.gt(pg_cursor));
-                }
-            }
-            if descending_order {
-                boxed_query = boxed_query.order(events::id.desc());
-            } else {
-                boxed_query = boxed_query.order(events::id.asc());
-            }
-            boxed_query.load(conn).scope_boxed()
-        })
+        // fetch one more item to tell if there is next page
+        let events_vec: Vec<Event> = read_only!(&self.cp, |conn| boxed_query
+            .limit(page_limit as i64 + 1)
+            .load(conn)
+            .scope_boxed())
         .context("Failed reading events from PostgresDB")?;
 
-        let mut sui_event_vec = events_vec
+        let sui_event_vec = events_vec
             .into_iter()
             .map(|event| event.try_into(&self.module_cache))
             .collect::<Result<Vec<SuiEvent>, _>>()?;
-        // reset to original limit for checking and truncating
-        page_limit -= 1;
-        let has_next_page = sui_event_vec.len() > page_limit;
-        sui_event_vec.truncate(page_limit);
-        let next_cursor = sui_event_vec.last().map(|e| e.id.clone());
-        Ok(EventPage {
-            data: sui_event_vec,
-            next_cursor,
-            has_next_page,
-        })
+        Ok(to_event_page(sui_event_vec, page_limit))
     }
 
     async fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError> {
@@ -451,86 +359,7 @@ impl IndexerStore for PgIndexerStore {
         tx: Transaction,
         options: Option<&SuiTransactionBlockResponseOptions>,
     ) -> Result<SuiTransactionBlockResponse, IndexerError> {
-        let transaction: SuiTransactionBlock =
-            serde_json::from_str(&tx.transaction_content).map_err(|err| {
-                IndexerError::InsertableParsingError(format!(
-                    "Failed converting transaction JSON {:?} to SuiTransactionBlock with error: {:?}",
-                    tx.transaction_content, err
-                ))
-            })?;
-        let effects: SuiTransactionBlockEffects = serde_json::from_str(&tx.transaction_effects_content).map_err(|err| {
-        IndexerError::InsertableParsingError(format!(
-            "Failed converting transaction effect JSON {:?} to SuiTransactionBlockEffects with error: {:?}",
-            tx.transaction_effects_content, err
-        ))
-        })?;
-
-        let tx_digest: TransactionDigest = tx.transaction_digest.parse().map_err(|e| {
-            IndexerError::InsertableParsingError(format!(
-                "Failed to parse transaction digest {} : {:?}",
-                tx.transaction_digest, e
-            ))
-        })?;
-        let sender = SuiAddress::from_str(tx.sender.as_str())?;
-
-        let (mut tx_opt, mut effects_opt, mut raw_tx) = (None, None, vec![]);
-        let (mut object_changes, mut balance_changes, mut events) = (None, None, None);
-        if let Some(options) = options {
-            if options.show_balance_changes {
-                let object_cache = ObjectProviderCache::new(self.clone());
-                balance_changes =
-                    Some(get_balance_changes_from_effect(&object_cache, &effects).await?);
-            }
-            if options.show_object_changes {
-                let object_cache = ObjectProviderCache::new(self.clone());
-                object_changes = Some(
-                    get_object_changes(
-                        &object_cache,
-                        sender,
-                        &effects.modified_at_versions(),
-                        effects.all_changed_objects(),
-                        effects.all_deleted_objects(),
-                    )
-                    .await?,
-                );
-            }
-            if options.show_events {
-                let event_page = self
-                    .get_events(
-                        EventFilter::Transaction(tx_digest),
-                        None,
-                        None,
-                        /* descending_order */ false,
-                    )
-                    .await?;
-                events = Some(SuiTransactionBlockEvents {
-                    data: event_page.data,
-                });
-            }
-            if options.show_input {
-                tx_opt = Some(transaction);
-            }
-            if options.show_raw_input {
-                raw_tx = tx.raw_transaction;
-            }
-            if options.show_effects {
-                effects_opt = Some(effects);
-            }
-        }
-
-        Ok(SuiTransactionBlockResponse {
-            digest: tx_digest,
-            transaction: tx_opt,
-            raw_transaction: raw_tx,
-            effects: effects_opt,
-            confirmed_local_execution: tx.confirmed_local_execution,
-            timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
-            checkpoint: tx.checkpoint_sequence_number.map(|c| c as u64),
-            events,
-            object_changes,
-            balance_changes,
-            errors: vec![],
-        })
+        compose_transaction_block_response(self, tx, options).await
     }
 
     async fn multi_get_transactions_by_digests(
@@ -553,27 +382,15 @@ impl IndexerStore for PgIndexerStore {
         tx_digest: Option<String>,
         is_descending: bool,
     ) -> Result<Option<i64>, IndexerError> {
-        read_only!(&self.cp, |conn| async {
-            if let Some(digest) = &tx_digest {
-                let mut boxed_query = transactions_dsl::transactions
-                    .filter(transactions_dsl::transaction_digest.eq(digest))
-                    .select(transactions_dsl::id)
-                    .into_boxed();
-                if is_descending {
-                    boxed_query = boxed_query.order(transactions_dsl::id.desc());
-                } else {
-                    boxed_query = boxed_query.order(transactions_dsl::id.asc());
-                }
-                Some(boxed_query.first::<i64>(conn).await)
-            } else {
-                None
-            }
-            .transpose()
-        }
-        .scope_boxed())
-        .context(&format!(
-            "Failed reading transaction sequence with digest {tx_digest:?}"
-        ))
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let query = sequence_by_digest!(transactions, &digest, is_descending);
+        read_only!(&self.cp, |conn| query.first::<i64>(conn).scope_boxed())
+            .context(&format!(
+                "Failed reading transaction sequence with digest {digest}"
+            ))
+            .map(Some)
     }
 
     async fn get_object(
@@ -623,6 +440,55 @@ impl IndexerStore for PgIndexerStore {
         }
     }
 
+    async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<ObjectRead, IndexerError> {
+        let object = read_only!(&self.cp, |conn| async {
+            objects_history::dsl::objects_history
+                .select((
+                    objects_history::epoch,
+                    objects_history::checkpoint,
+                    objects_history::object_id,
+                    objects_history::version,
+                    objects_history::object_digest,
+                    objects_history::owner_type,
+                    objects_history::owner_address,
+                    objects_history::initial_shared_version,
+                    objects_history::previous_transaction,
+                    objects_history::object_type,
+                    objects_history::object_status,
+                    objects_history::has_public_transfer,
+                    objects_history::storage_rebate,
+                    objects_history::bcs,
+                ))
+                .filter(objects_history::object_id.eq(object_id.to_string()))
+                // NOTE: rows from fast-path have checkpoint -1 and are not part of any checkpoint yet.
+                .filter(objects_history::checkpoint.between(0, checkpoint as i64))
+                .order((
+                    objects_history::version.desc(),
+                    objects_history::checkpoint.desc(),
+                ))
+                .first::<Object>(conn)
+                .await
+                .optional()
+        }
+        .scope_boxed())
+        .context(&format!(
+            "Failed reading object with id {object_id} at checkpoint {checkpoint}"
+        ))?;
+
+        match object {
+            None => Ok(ObjectRead::NotExists(object_id)),
+            // A wrapped object cannot be read at the checkpoint, as if it was deleted.
+            Some(o) if matches!(o.object_status, ObjectStatus::Wrapped) => {
+                Ok(ObjectRead::Deleted(o.get_object_ref()?))
+            }
+            Some(o) => o.try_into_object_read(&self.module_cache),
+        }
+    }
+
     async fn query_objects_history(
         &self,
         filter: SuiObjectDataFilter,
@@ -695,60 +561,83 @@ impl IndexerStore for PgIndexerStore {
             .collect()
     }
 
-    async fn get_move_call_sequence_by_digest(
+    async fn get_dynamic_fields(
         &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<DynamicField>, IndexerError> {
+        let parent_id = parent_object_id.to_string();
+        let cursor = cursor.map(|id| id.to_string());
         read_only!(&self.cp, |conn| async {
-            if let Some(digest) = &tx_digest {
-                let mut boxed_query = move_calls_dsl::move_calls
-                    .filter(move_calls_dsl::transaction_digest.eq(digest))
-                    .select(move_calls_dsl::id)
-                    .into_boxed();
-                if is_descending {
-                    boxed_query = boxed_query.order(move_calls_dsl::id.desc());
-                } else {
-                    boxed_query = boxed_query.order(move_calls_dsl::id.asc());
-                }
-                Some(boxed_query.first::<i64>(conn).await)
-            } else {
-                None
+            let mut boxed_query = dynamic_fields_dsl::dynamic_fields
+                .filter(dynamic_fields_dsl::parent_object_id.eq(&parent_id))
+                .order(dynamic_fields_dsl::object_id.asc())
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(cursor) = &cursor {
+                boxed_query = boxed_query.filter(dynamic_fields_dsl::object_id.gt(cursor));
             }
-            .transpose()
+            boxed_query.load::<DynamicField>(conn).await
         }
         .scope_boxed())
         .context(&format!(
-            "Failed reading move call sequence with digest {tx_digest:?}"
+            "Failed reading dynamic fields of object {parent_object_id}"
+        ))
+    }
+
+    async fn multi_get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        object_ids: &[ObjectID],
+    ) -> Result<Vec<DynamicField>, IndexerError> {
+        let parent_id = parent_object_id.to_string();
+        let object_ids = object_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        read_only!(&self.cp, |conn| {
+            dynamic_fields_dsl::dynamic_fields
+                .filter(dynamic_fields_dsl::parent_object_id.eq(&parent_id))
+                .filter(dynamic_fields_dsl::object_id.eq_any(&object_ids))
+                .load::<DynamicField>(conn)
+                .scope_boxed()
+        })
+        .context(&format!(
+            "Failed reading dynamic fields {object_ids:?} of object {parent_object_id}"
         ))
     }
 
+    async fn get_move_call_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let query = sequence_by_digest!(move_calls, &digest, is_descending);
+        read_only!(&self.cp, |conn| query.first::<i64>(conn).scope_boxed())
+            .context(&format!(
+                "Failed reading move call sequence with digest {digest}"
+            ))
+            .map(Some)
+    }
+
     async fn get_input_object_sequence_by_digest(
         &self,
         tx_digest: Option<String>,
         is_descending: bool,
     ) -> Result<Option<i64>, IndexerError> {
-        read_only!(&self.cp, |conn| async {
-            if let Some(digest) = &tx_digest {
-                let mut boxed_query = input_objects_dsl::input_objects
-                    .filter(input_objects_dsl::transaction_digest.eq(digest))
-                    .select(input_objects_dsl::id)
-                    .into_boxed();
-                if is_descending {
-                    boxed_query = boxed_query.order(input_objects_dsl::id.desc());
-                } else {
-                    boxed_query = boxed_query.order(input_objects_dsl::id.asc());
-                }
-                Some(boxed_query.first::<i64>(conn).await)
-            } else {
-                None
-            }
-            .transpose()
-        }
-        .scope_boxed())
-        .context(&format!(
-            "Failed reading input object sequence with digest {tx_digest:?}"
-        ))
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let query = sequence_by_digest!(input_objects, &digest, is_descending);
+        read_only!(&self.cp, |conn| query.first::<i64>(conn).scope_boxed())
+            .context(&format!(
+                "Failed reading input object sequence with digest {digest}"
+            ))
+            .map(Some)
     }
 
     async fn get_recipient_sequence_by_digest(
@@ -756,27 +645,15 @@ impl IndexerStore for PgIndexerStore {
         tx_digest: Option<String>,
         is_descending: bool,
     ) -> Result<Option<i64>, IndexerError> {
-        read_only!(&self.cp, |conn| async {
-            if let Some(digest) = &tx_digest {
-                let mut boxed_query = recipients_dsl::recipients
-                    .filter(recipients_dsl::transaction_digest.eq(digest))
-                    .select(recipients_dsl::id)
-                    .into_boxed();
-                if is_descending {
-                    boxed_query = boxed_query.order(recipients_dsl::id.desc());
-                } else {
-                    boxed_query = boxed_query.order(recipients_dsl::id.asc());
-                }
-                Some(boxed_query.first::<i64>(conn).await)
-            } else {
-                None
-            }
-            .transpose()
-        }
-        .scope_boxed())
-        .context(&format!(
-            "Failed reading recipients sequence with digest {tx_digest:?}"
-        ))
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let query = sequence_by_digest!(recipients, &digest, is_descending);
+        read_only!(&self.cp, |conn| query.first::<i64>(conn).scope_boxed())
+            .context(&format!(
+                "Failed reading recipients sequence with digest {digest}"
+            ))
+            .map(Some)
     }
 
     async fn get_all_transaction_page(
@@ -785,28 +662,17 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        read_only!(&self.cp, |conn| {
-            let mut boxed_query = transactions_dsl::transactions.into_boxed();
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.lt(start_sequence));
-                } else {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.gt(start_sequence));
-                }
-            }
-
-            if is_descending {
-                boxed_query
-                    .order(transactions_dsl::id.desc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            } else {
-                boxed_query
-                    .order(transactions_dsl::id.asc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            }
-        }).context(&format!("Failed reading all transaction digests with start_sequence {start_sequence:?} and limit {limit}"))
+        let query = page_by_id!(
+            transactions_dsl::transactions.into_boxed(),
+            transactions_dsl::id,
+            start_sequence,
+            is_descending
+        );
+        read_only!(&self.cp, |conn| query
+            .limit(limit as i64)
+            .load::<Transaction>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading all transaction digests with start_sequence {start_sequence:?} and limit {limit}"))
     }
 
     async fn get_transaction_page_by_checkpoint(
@@ -816,29 +682,19 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        read_only!(&self.cp, |conn| {
-            let mut boxed_query = transactions_dsl::transactions
+        let query = page_by_id!(
+            transactions_dsl::transactions
                 .filter(transactions_dsl::checkpoint_sequence_number.eq(checkpoint_sequence_number))
-                .into_boxed();
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.lt(start_sequence));
-                } else {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.gt(start_sequence));
-                }
-            }
-            if is_descending {
-                boxed_query
-                    .order(transactions_dsl::id.desc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            } else {
-                boxed_query
-                    .order(transactions_dsl::id.asc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            }
-        }).context(&format!("Failed reading transaction digests with checkpoint_sequence_number {checkpoint_sequence_number:?} and start_sequence {start_sequence:?} and limit {limit}"))
+                .into_boxed(),
+            transactions_dsl::id,
+            start_sequence,
+            is_descending
+        );
+        read_only!(&self.cp, |conn| query
+            .limit(limit as i64)
+            .load::<Transaction>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests with checkpoint_sequence_number {checkpoint_sequence_number:?} and start_sequence {start_sequence:?} and limit {limit}"))
     }
 
     async fn get_transaction_page_by_transaction_kind(
@@ -848,30 +704,19 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        read_only!(&self.cp, |conn| {
-            let mut boxed_query = transactions_dsl::transactions
+        let query = page_by_id!(
+            transactions_dsl::transactions
                 .filter(transactions_dsl::transaction_kind.eq(kind.clone()))
-                .into_boxed();
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.lt(start_sequence));
-                } else {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.gt(start_sequence));
-                }
-            }
-
-            if is_descending {
-                boxed_query
-                    .order(transactions_dsl::id.desc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            } else {
-               boxed_query
-                    .order(transactions_dsl::id.asc())
-                    .limit((limit) as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            }
-        }).context(&format!("Failed reading transaction digests with kind {kind} and start_sequence {start_sequence:?} and limit {limit}"))
+                .into_boxed(),
+            transactions_dsl::id,
+            start_sequence,
+            is_descending
+        );
+        read_only!(&self.cp, |conn| query
+            .limit(limit as i64)
+            .load::<Transaction>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests with kind {kind} and start_sequence {start_sequence:?} and limit {limit}"))
     }
 
     async fn get_transaction_page_by_mutated_object(
@@ -881,31 +726,21 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        read_only!(&self.cp, |conn| {
-            let mut boxed_query = transactions_dsl::transactions
+        let query = page_by_id!(
+            transactions_dsl::transactions
                 .filter(transactions_dsl::mutated.contains(vec![Some(object_id.clone())]))
                 .or_filter(transactions_dsl::created.contains(vec![Some(object_id.clone())]))
                 .or_filter(transactions_dsl::unwrapped.contains(vec![Some(object_id.clone())]))
-                .into_boxed();
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.lt(start_sequence));
-                } else {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.gt(start_sequence));
-                }
-            }
-            if is_descending {
-               boxed_query
-                    .order(transactions_dsl::id.desc())
-                    .limit(limit as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            } else {
-                boxed_query
-                    .order(transactions_dsl::id.asc())
-                    .limit(limit as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            }
-        }).context(&format!("Failed reading transaction digests by mutated object id {object_id} with start_sequence {start_sequence:?} and limit {limit}"))
+                .into_boxed(),
+            transactions_dsl::id,
+            start_sequence,
+            is_descending
+        );
+        read_only!(&self.cp, |conn| query
+            .limit(limit as i64)
+            .load::<Transaction>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests by mutated object id {object_id} with start_sequence {start_sequence:?} and limit {limit}"))
     }
 
     async fn get_transaction_page_by_sender_address(
@@ -915,30 +750,19 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        read_only!(&self.cp, |conn| {
-            let mut boxed_query = transactions_dsl::transactions
+        let query = page_by_id!(
+            transactions_dsl::transactions
                 .filter(transactions_dsl::sender.eq(sender_address.clone()))
-                .into_boxed();
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.lt(start_sequence));
-                } else {
-                    boxed_query = boxed_query.filter(transactions_dsl::id.gt(start_sequence));
-                }
-            }
-
-            if is_descending {
-                boxed_query
-                    .order(transactions_dsl::id.desc())
-                    .limit(limit as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            } else {
-               boxed_query
-                    .order(transactions_dsl::id.asc())
-                    .limit(limit as i64)
-                    .load::<Transaction>(conn).scope_boxed()
-            }
-        }).context(&format!("Failed reading transaction digests by sender address {sender_address} with start_sequence {start_sequence:?} and limit {limit}"))
+                .into_boxed(),
+            transactions_dsl::id,
+            start_sequence,
+            is_descending
+        );
+        read_only!(&self.cp, |conn| query
+            .limit(limit as i64)
+            .load::<Transaction>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests by sender address {sender_address} with start_sequence {start_sequence:?} and limit {limit}"))
     }
 
     async fn get_transaction_page_by_input_object(
@@ -949,38 +773,21 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name FROM (
-                SELECT transaction_digest, max(id) AS max_id
-                FROM input_objects
-                WHERE object_id = '{}' {} {}
-                GROUP BY transaction_digest
-                ORDER BY max_id {} LIMIT {}
-            ) AS t",
+        let sql_query = to_input_object_transactions_sql(
             object_id,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(version) = version {
-                format!("AND version = {}", version)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
+            version,
+            start_sequence,
+            limit,
+            is_descending,
         );
-        let tx_digests: Vec<String> = read_only!(&self.cp, |conn| diesel::sql_query(sql_query).load(conn).scope_boxed())
-                .context(&format!("Failed reading transaction digests by input object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"))?
-                .into_iter()
-                .map(|table: TempDigestTable| table.digest_name)
-                .collect();
-        self.multi_get_transactions_by_digests(&tx_digests).await
+        let tx_digests = read_only!(&self.cp, |conn| diesel::sql_query(sql_query)
+            .load::<TempDigestTable>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests by input object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"))?
+        .into_iter()
+        .map(|table| table.digest_name)
+        .collect();
+        get_transactions_in_digest_order(self, tx_digests).await
     }
 
     async fn get_transaction_page_by_move_call(
@@ -992,48 +799,22 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        // note: module_name and function_name are user-controlled, which is scary.
-        // however, but valid Move identifiers can only contain 0-9, a-z, A-Z, and _,
-        // so it is safe to use them as-is in the query below
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name FROM (
-                SELECT transaction_digest, max(id) AS max_id
-                FROM move_calls
-                WHERE move_package = '{}' {} {} {}
-                GROUP BY transaction_digest
-                ORDER BY max_id {} LIMIT {}
-            ) AS t",
+        let sql_query = to_move_call_transactions_sql(
             package_name,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(module_name) = module_name.clone() {
-                format!("AND move_module = '{}'", module_name)
-            } else {
-                "".to_string()
-            },
-            if let Some(function_name) = function_name.clone() {
-                format!("AND move_function = '{}'", function_name)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
+            module_name.as_ref(),
+            function_name.as_ref(),
+            start_sequence,
+            limit,
+            is_descending,
         );
-        let tx_digests: Vec<String> = read_only!(&self.cp, |conn| diesel::sql_query(sql_query).load(conn).scope_boxed())
-                .context(&format!(
-                        "Failed reading transaction digests with package_name {} module_name {:?} and function_name {:?} and start_sequence {:?} and limit {}",
-                        package_name, module_name, function_name, start_sequence, limit))?
-                .into_iter()
-                .map(|table: TempDigestTable| table.digest_name)
-                .collect();
-        self.multi_get_transactions_by_digests(&tx_digests).await
+        let tx_digests = read_only!(&self.cp, |conn| diesel::sql_query(sql_query)
+            .load::<TempDigestTable>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests with package_name {package_name} module_name {module_name:?} and function_name {function_name:?} and start_sequence {start_sequence:?} and limit {limit}"))?
+        .into_iter()
+        .map(|table| table.digest_name)
+        .collect();
+        get_transactions_in_digest_order(self, tx_digests).await
     }
 
     async fn get_transaction_page_by_sender_recipient_address(
@@ -1044,37 +825,34 @@ impl IndexerStore for PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name FROM (
-                SELECT transaction_digest, max(id) AS max_id
-                FROM recipients
-                WHERE recipient = '{}' {} {} GROUP BY transaction_digest
-                ORDER BY max_id {} LIMIT {}
-            ) AS t",
-            to,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(from) = from {
-                format!("AND sender = '{}'", from)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
-        );
-        let tx_digests: Vec<String> = read_only!(&self.cp, |conn| diesel::sql_query(sql_query).load(conn).scope_boxed())
-                .context(&format!("Failed reading transaction digests by recipient address {to} with start_sequence {start_sequence:?} and limit {limit}"))?
-                .into_iter()
-                .map(|table: TempDigestTable| table.digest_name)
-                .collect();
-        self.multi_get_transactions_by_digests(&tx_digests).await
+        let sql_query =
+            to_recipient_transactions_sql(from, to, start_sequence, limit, is_descending);
+        let tx_digests = read_only!(&self.cp, |conn| diesel::sql_query(sql_query)
+            .load::<TempDigestTable>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests by recipient address {to} with start_sequence {start_sequence:?} and limit {limit}"))?
+        .into_iter()
+        .map(|table| table.digest_name)
+        .collect();
+        get_transactions_in_digest_order(self, tx_digests).await
+    }
+
+    async fn get_transaction_page_by_filter(
+        &self,
+        filter: TransactionFilter,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = to_transactions_sql(&filter, start_sequence, limit, is_descending);
+        let tx_digests = read_only!(&self.cp, |conn| diesel::sql_query(sql_query)
+            .load::<TempDigestTable>(conn)
+            .scope_boxed())
+        .context(&format!("Failed reading transaction digests by filter {filter:?} with start_sequence {start_sequence:?} and limit {limit}"))?
+        .into_iter()
+        .map(|table| table.digest_name)
+        .collect();
+        get_transactions_in_digest_order(self, tx_digests).await
     }
 
     async fn get_network_metrics(&self) -> Result<NetworkMetrics, IndexerError> {
@@ -1087,33 +865,7 @@ impl IndexerStore for PgIndexerStore {
                 .get_results::<DBMoveCallMetrics>(conn)
                 .scope_boxed()
         })?;
-
-        let mut d3 = vec![];
-        let mut d7 = vec![];
-        let mut d30 = vec![];
-        for m in metrics {
-            let package = ObjectID::from_str(&m.move_package);
-            let module = Identifier::from_str(m.move_module.as_str());
-            let function = Identifier::from_str(m.move_function.as_str());
-            if let (Ok(package), Ok(module), Ok(function)) = (package, module, function) {
-                let fun = MoveFunctionName {
-                    package,
-                    module,
-                    function,
-                };
-                match m.count {
-                    3 => d3.push((fun, m.count as usize)),
-                    7 => d7.push((fun, m.count as usize)),
-                    30 => d30.push((fun, m.count as usize)),
-                    _ => {}
-                }
-            }
-        }
-        Ok(MoveCallMetrics {
-            rank_3_days: d3,
-            rank_7_days: d7,
-            rank_30_days: d30,
-        })
+        Ok(to_move_call_metrics(metrics))
     }
 
     async fn persist_fast_path(
@@ -1130,6 +882,10 @@ impl IndexerStore for PgIndexerStore {
                 .map_err(IndexerError::from)
                 .context("Failed writing transactions to PostgresDB")?;
 
+            let (changed_fields, deleted_field_ids) =
+                get_dynamic_field_changes(std::slice::from_ref(&tx_object_changes))?;
+            persist_dynamic_fields(conn, changed_fields, deleted_field_ids).await?;
+
             let deleted_objects: Vec<Object> = tx_object_changes
                 .deleted_objects
                 .iter()
@@ -1366,6 +1122,8 @@ WHERE e1.epoch = e2.epoch
             let (mutation_count, deletion_count) = (mutated_objects.len(),
             deleted_objects.len());
             persist_transaction_object_changes(conn, mutated_objects, deleted_objects, Some(object_mutation_latency), Some(object_deletion_latency)).await?;
+            let (changed_fields, deleted_field_ids) = get_dynamic_field_changes(tx_object_changes)?;
+            persist_dynamic_fields(conn, changed_fields, deleted_field_ids).await?;
             info!(
                 "Object checkpoint {} committed with {} transaction, {} mutated objects and {} deleted objects.",
                 checkpoint_seq,
@@ -1547,6 +1305,35 @@ WHERE e1.epoch = e2.epoch
         Ok(())
     }
 
+    async fn get_webhook_cursor(&self, name: &str) -> Result<Option<WebhookCursor>, IndexerError> {
+        read_only!(&self.cp, |conn| async {
+            webhook_cursors::dsl::webhook_cursors
+                .filter(webhook_cursors::name.eq(name))
+                .first::<WebhookCursor>(conn)
+                .await
+                .optional()
+        }
+        .scope_boxed())
+        .context(&format!("Failed reading cursor of webhook {name}"))
+    }
+
+    async fn persist_webhook_cursor(&self, cursor: &WebhookCursor) -> Result<(), IndexerError> {
+        transactional!(&self.cp, |conn| {
+            diesel::insert_into(webhook_cursors::table)
+                .values(cursor)
+                .on_conflict(webhook_cursors::name)
+                .do_update()
+                .set((
+                    webhook_cursors::cursor.eq(excluded(webhook_cursors::cursor)),
+                    webhook_cursors::updated_at.eq(excluded(webhook_cursors::updated_at)),
+                ))
+                .execute(conn)
+                .scope_boxed()
+        })
+        .context(&format!("Failed writing cursor of webhook {}", cursor.name))?;
+        Ok(())
+    }
+
     fn module_cache(&self) -> &Self::ModuleCache {
         &self.module_cache
     }
@@ -1557,59 +1344,26 @@ WHERE e1.epoch = e2.epoch
         limit: usize,
         descending_order: Option<bool>,
     ) -> Result<Vec<EpochInfo>, IndexerError> {
-        let is_descending = descending_order.unwrap_or_default();
-        let id = cursor
-            .map(|id| id as i64)
-            .unwrap_or(if is_descending { i64::MAX } else { -1 });
-        let mut query = epochs_dsl::epochs.into_boxed();
-        if is_descending {
-            query = query
-                .filter(epochs::epoch.lt(id))
-                .order_by(epochs::epoch.desc());
-        } else {
-            query = query
-                .filter(epochs::epoch.gt(id))
-                .order_by(epochs::epoch.asc());
-        }
-
+        let query = page_by_id!(
+            epochs_dsl::epochs.into_boxed(),
+            epochs::epoch,
+            cursor.map(|id| id as i64),
+            descending_order.unwrap_or_default()
+        );
         let epoch_info: Vec<DBEpochInfo> = read_only!(&self.cp, |conn| query
             .limit(limit as i64)
             .load(conn)
             .scope_boxed())
-        .map_err(|e| {
-            IndexerError::PostgresReadError(format!(
-                "Failed reading latest checkpoint sequence number in PostgresDB with error {:?}",
-                e
-            ))
-        })?;
+        .context("Failed reading epochs from PostgresDB")?;
 
+        let epoch_ids = epoch_info.iter().map(|info| info.epoch).collect::<Vec<_>>();
         let validators: Vec<DBValidatorSummary> =
             read_only!(&self.cp, |conn| validators::dsl::validators
-                .filter(validators::epoch.gt(id))
+                .filter(validators::epoch.eq_any(epoch_ids))
                 .load(conn)
                 .scope_boxed())
-            .map_err(|e| {
-                IndexerError::PostgresReadError(format!(
-                "Failed reading latest checkpoint sequence number in PostgresDB with error {:?}",
-                e
-            ))
-            })?;
-
-        let mut validators =
-            validators
-                .into_iter()
-                .fold(BTreeMap::<i64, Vec<_>>::new(), |mut acc, v| {
-                    acc.entry(v.epoch).or_default().push(v);
-                    acc
-                });
-
-        epoch_info
-            .into_iter()
-            .map(|info| {
-                let epoch = info.epoch;
-                info.to_epoch_info(validators.remove(&epoch).unwrap_or_default())
-            })
-            .collect()
+            .context("Failed reading validator summaries from PostgresDB")?;
+        to_epoch_infos(epoch_info, validators)
     }
 
     async fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
@@ -1748,6 +1502,40 @@ async fn persist_transaction_object_changes(
     Ok(0)
 }
 
+async fn persist_dynamic_fields(
+    conn: &mut AsyncPgConnection,
+    changed_fields: Vec<DynamicField>,
+    deleted_field_ids: Vec<String>,
+) -> Result<(), IndexerError> {
+    for changed_field_chunk in changed_fields.chunks(PG_COMMIT_CHUNK_SIZE) {
+        diesel::insert_into(dynamic_fields::table)
+            .values(changed_field_chunk)
+            .on_conflict(dynamic_fields::object_id)
+            .do_update()
+            .set((
+                dynamic_fields::parent_object_id.eq(excluded(dynamic_fields::parent_object_id)),
+                dynamic_fields::version.eq(excluded(dynamic_fields::version)),
+                dynamic_fields::object_digest.eq(excluded(dynamic_fields::object_digest)),
+                dynamic_fields::checkpoint.eq(excluded(dynamic_fields::checkpoint)),
+                dynamic_fields::bcs.eq(excluded(dynamic_fields::bcs)),
+            ))
+            .execute(conn)
+            .await
+            .map_err(IndexerError::from)
+            .context("Failed writing dynamic fields to PostgresDB")?;
+    }
+    for deleted_field_chunk in deleted_field_ids.chunks(PG_COMMIT_CHUNK_SIZE) {
+        diesel::delete(
+            dynamic_fields::table.filter(dynamic_fields::object_id.eq_any(deleted_field_chunk)),
+        )
+        .execute(conn)
+        .await
+        .map_err(IndexerError::from)
+        .context("Failed deleting dynamic fields from PostgresDB")?;
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 struct PartitionManager {
     cp: PgConnectionPool,
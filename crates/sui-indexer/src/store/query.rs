@@ -1,13 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use diesel::sql_types::Text;
+use diesel::QueryableByName;
+use move_core_types::identifier::Identifier;
+
 use sui_json_rpc_types::SuiObjectDataFilter;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::query::TransactionFilter;
 
 pub trait DBFilter<C> {
     fn to_objects_history_sql(&self, cursor: Option<C>, limit: usize, columns: Vec<&str>)
         -> String;
     fn to_latest_objects_sql(&self, cursor: Option<C>, limit: usize, columns: Vec<&str>) -> String;
+    fn to_sqlite_objects_history_sql(
+        &self,
+        cursor: Option<C>,
+        limit: usize,
+        columns: Vec<&str>,
+    ) -> String;
 }
 
 impl DBFilter<ObjectID> for SuiObjectDataFilter {
@@ -81,6 +92,48 @@ LIMIT {limit};"
         format!(
             "SELECT {columns}
 FROM objects o WHERE o.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted'){cursor}{inner_clauses}
+LIMIT {limit};"
+        )
+    }
+
+    fn to_sqlite_objects_history_sql(
+        &self,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        columns: Vec<&str>,
+    ) -> String {
+        let inner_clauses = to_clauses(self);
+        let inner_clauses = if let Some(inner_clauses) = inner_clauses {
+            format!("\n      AND {inner_clauses}")
+        } else {
+            "".to_string()
+        };
+        let outer_clauses = to_outer_clauses(self);
+        let outer_clauses = if let Some(outer_clauses) = outer_clauses {
+            format!("\nAND {outer_clauses}")
+        } else {
+            "".to_string()
+        };
+        let cursor = if let Some(cursor) = cursor {
+            format!("\n      AND o.object_id > '{cursor}'")
+        } else {
+            "".to_string()
+        };
+
+        let columns = columns
+            .iter()
+            .map(|c| format!("t1.{c}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // NOTE: SQLite has no DISTINCT ON, the latest row of each object is picked with a window
+        // function instead, with the same ordering as the Postgres query.
+        format!(
+            "SELECT {columns}
+FROM (SELECT o.*, ROW_NUMBER() OVER (PARTITION BY o.object_id ORDER BY o.version DESC, o.checkpoint DESC) AS row_num
+      FROM objects_history o
+      WHERE o.checkpoint <= ?{cursor}{inner_clauses}) AS t1
+WHERE t1.row_num = 1 AND t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted'){outer_clauses}
+ORDER BY t1.object_id
 LIMIT {limit};"
         )
     }
@@ -210,6 +263,240 @@ fn to_outer_clauses(filter: &SuiObjectDataFilter) -> Option<String> {
     }
 }
 
+/// Row of the queries selecting transaction digests.
+#[derive(QueryableByName, Debug, Clone)]
+pub struct TempDigestTable {
+    #[diesel(sql_type = Text)]
+    pub digest_name: String,
+}
+
+/// Builds a query selecting the digests of the transactions matching the filter, ordered by
+/// the transactions table sequence and starting after `start_sequence`.
+pub fn to_transactions_sql(
+    filter: &TransactionFilter,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    transactions_sql(
+        filter,
+        start_sequence,
+        limit,
+        is_descending,
+        pg_changed_object_clause,
+    )
+}
+
+/// Same as [to_transactions_sql], for the SQLite schema which keeps the changed objects of a
+/// transaction in a JSON array.
+pub fn to_sqlite_transactions_sql(
+    filter: &TransactionFilter,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    transactions_sql(
+        filter,
+        start_sequence,
+        limit,
+        is_descending,
+        sqlite_changed_object_clause,
+    )
+}
+
+fn transactions_sql(
+    filter: &TransactionFilter,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+    changed_object_clause: fn(&ObjectID) -> String,
+) -> String {
+    let clauses = to_transaction_clauses(filter, changed_object_clause);
+    let cursor = match start_sequence {
+        Some(start_sequence) if is_descending => format!("\nAND t.id < {start_sequence}"),
+        Some(start_sequence) => format!("\nAND t.id > {start_sequence}"),
+        None => "".to_string(),
+    };
+    let order = if is_descending { "DESC" } else { "ASC" };
+    format!(
+        "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE {clauses}{cursor}
+ORDER BY t.id {order}
+LIMIT {limit};"
+    )
+}
+
+/// Builds a query selecting the digests of the transactions sent to `to`, by `from` if set,
+/// ordered by the recipients table sequence and starting after `start_sequence`.
+pub fn to_recipient_transactions_sql(
+    from: Option<SuiAddress>,
+    to: SuiAddress,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    let mut clauses = vec![format!("recipient = '{to}'")];
+    if let Some(from) = from {
+        clauses.push(format!("sender = '{from}'"));
+    }
+    transaction_index_sql("recipients", clauses, start_sequence, limit, is_descending)
+}
+
+/// Builds a query selecting the digests of the transactions taking `object_id` as input, at
+/// `version` if set, ordered by the input objects table sequence.
+pub fn to_input_object_transactions_sql(
+    object_id: ObjectID,
+    version: Option<i64>,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    let mut clauses = vec![format!("object_id = '{object_id}'")];
+    if let Some(version) = version {
+        clauses.push(format!("object_version = {version}"));
+    }
+    transaction_index_sql(
+        "input_objects",
+        clauses,
+        start_sequence,
+        limit,
+        is_descending,
+    )
+}
+
+/// Builds a query selecting the digests of the transactions calling into `package`, and into
+/// `module` and `function` if set, ordered by the move calls table sequence.
+pub fn to_move_call_transactions_sql(
+    package: ObjectID,
+    module: Option<&Identifier>,
+    function: Option<&Identifier>,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    // valid Move identifiers can only contain 0-9, a-z, A-Z, and _,
+    // so it is safe to use them as-is in the query below
+    let mut clauses = vec![format!("move_package = '{package}'")];
+    if let Some(module) = module {
+        clauses.push(format!("move_module = '{module}'"));
+    }
+    if let Some(function) = function {
+        clauses.push(format!("move_function = '{function}'"));
+    }
+    transaction_index_sql("move_calls", clauses, start_sequence, limit, is_descending)
+}
+
+// A transaction can have several rows in an index table, the transactions are paged over the
+// last of their rows.
+fn transaction_index_sql(
+    table: &str,
+    mut clauses: Vec<String>,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    match start_sequence {
+        Some(start_sequence) if is_descending => clauses.push(format!("id < {start_sequence}")),
+        Some(start_sequence) => clauses.push(format!("id > {start_sequence}")),
+        None => {}
+    }
+    let clauses = clauses.join(" AND ");
+    let order = if is_descending { "DESC" } else { "ASC" };
+    format!(
+        "SELECT transaction_digest AS digest_name FROM (
+    SELECT transaction_digest, MAX(id) AS max_id
+    FROM {table}
+    WHERE {clauses}
+    GROUP BY transaction_digest
+    ORDER BY max_id {order}
+    LIMIT {limit}
+) AS t;"
+    )
+}
+
+fn to_transaction_clauses(
+    filter: &TransactionFilter,
+    changed_object_clause: fn(&ObjectID) -> String,
+) -> String {
+    match filter {
+        TransactionFilter::Checkpoint(checkpoint) => {
+            format!("t.checkpoint_sequence_number = {checkpoint}")
+        }
+        TransactionFilter::CheckpointRange {
+            start_checkpoint,
+            end_checkpoint,
+        } => format!(
+            "t.checkpoint_sequence_number BETWEEN {start_checkpoint} AND {end_checkpoint}"
+        ),
+        TransactionFilter::TimeRange {
+            start_time,
+            end_time,
+        } => format!("(t.timestamp_ms >= {start_time} AND t.timestamp_ms < {end_time})"),
+        TransactionFilter::MoveFunction {
+            package,
+            module,
+            function,
+        } => {
+            let module = module
+                .as_ref()
+                .map(|m| format!(" AND m.move_module = '{}'", escape(m)))
+                .unwrap_or_default();
+            let function = function
+                .as_ref()
+                .map(|f| format!(" AND m.move_function = '{}'", escape(f)))
+                .unwrap_or_default();
+            format!("EXISTS (SELECT 1 FROM move_calls m WHERE m.transaction_digest = t.transaction_digest AND m.move_package = '{package}'{module}{function})")
+        }
+        TransactionFilter::InputObject(object_id) => format!("EXISTS (SELECT 1 FROM input_objects i WHERE i.transaction_digest = t.transaction_digest AND i.object_id = '{object_id}')"),
+        TransactionFilter::ChangedObject(object_id) => changed_object_clause(object_id),
+        TransactionFilter::FromAddress(address) => format!("t.sender = '{address}'"),
+        TransactionFilter::ToAddress(address) => to_recipient_clause(address),
+        TransactionFilter::FromAndToAddress { from, to } => {
+            format!("(t.sender = '{from}' AND {})", to_recipient_clause(to))
+        }
+        TransactionFilter::TransactionKind(kind) => {
+            format!("t.transaction_kind = '{}'", escape(kind))
+        }
+        TransactionFilter::And(f1, f2) => format!(
+            "({} AND {})",
+            to_transaction_clauses(f1, changed_object_clause),
+            to_transaction_clauses(f2, changed_object_clause)
+        ),
+        TransactionFilter::Or(f1, f2) => format!(
+            "({} OR {})",
+            to_transaction_clauses(f1, changed_object_clause),
+            to_transaction_clauses(f2, changed_object_clause)
+        ),
+        TransactionFilter::Not(f) => format!(
+            "NOT {}",
+            to_transaction_clauses(f, changed_object_clause)
+        ),
+    }
+}
+
+fn pg_changed_object_clause(object_id: &ObjectID) -> String {
+    format!(
+        "(t.mutated @> ARRAY['{object_id}'] OR t.created @> ARRAY['{object_id}'] OR t.unwrapped @> ARRAY['{object_id}'])"
+    )
+}
+
+fn sqlite_changed_object_clause(object_id: &ObjectID) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM json_each(t.changed_objects) WHERE json_each.value = '{object_id}')"
+    )
+}
+
+fn to_recipient_clause(address: &SuiAddress) -> String {
+    format!("EXISTS (SELECT 1 FROM recipients r WHERE r.transaction_digest = t.transaction_digest AND r.recipient = '{address}')")
+}
+
+// Module, function and kind names are user-controlled, escape quotes so that they can only
+// ever be compared as string literals.
+fn escape(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
@@ -219,8 +506,12 @@ mod test {
     use sui_json_rpc_types::SuiObjectDataFilter;
     use sui_types::base_types::{ObjectID, SuiAddress};
     use sui_types::parse_sui_struct_tag;
+    use sui_types::query::TransactionFilter;
 
-    use crate::store::query::DBFilter;
+    use crate::store::query::{
+        to_input_object_transactions_sql, to_move_call_transactions_sql,
+        to_recipient_transactions_sql, to_sqlite_transactions_sql, to_transactions_sql, DBFilter,
+    };
 
     #[test]
     fn test_address_filter() {
@@ -326,4 +617,148 @@ LIMIT 100;";
             filter.to_objects_history_sql(None, 100, vec!["*"])
         );
     }
+
+    #[test]
+    fn test_composite_transaction_filter() {
+        let sender = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let package = ObjectID::from_str("0x2").unwrap();
+        let filter = TransactionFilter::FromAddress(sender)
+            .and(TransactionFilter::MoveFunction {
+                package,
+                module: Some("coin".to_string()),
+                function: None,
+            })
+            .and(TransactionFilter::CheckpointRange {
+                start_checkpoint: 10,
+                end_checkpoint: 20,
+            });
+
+        let expected_sql = "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE ((t.sender = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381' AND EXISTS (SELECT 1 FROM move_calls m WHERE m.transaction_digest = t.transaction_digest AND m.move_package = '0x0000000000000000000000000000000000000000000000000000000000000002' AND m.move_module = 'coin')) AND t.checkpoint_sequence_number BETWEEN 10 AND 20)
+AND t.id > 5
+ORDER BY t.id ASC
+LIMIT 100;";
+        assert_eq!(
+            expected_sql,
+            to_transactions_sql(&filter, Some(5), 100, false)
+        );
+    }
+
+    #[test]
+    fn test_negated_transaction_filter() {
+        let filter = TransactionFilter::TimeRange {
+            start_time: 1000,
+            end_time: 2000,
+        }
+        .and(
+            TransactionFilter::TransactionKind("Programmable'Transaction".to_string())
+                .or(TransactionFilter::Checkpoint(1))
+                .not(),
+        );
+
+        let expected_sql = "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE ((t.timestamp_ms >= 1000 AND t.timestamp_ms < 2000) AND NOT (t.transaction_kind = 'Programmable''Transaction' OR t.checkpoint_sequence_number = 1))
+ORDER BY t.id DESC
+LIMIT 50;";
+        assert_eq!(expected_sql, to_transactions_sql(&filter, None, 50, true));
+    }
+
+    #[test]
+    fn test_sqlite_address_filter() {
+        let address = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let filter = SuiObjectDataFilter::AddressOwner(address);
+
+        let expected_sql = "SELECT t1.data
+FROM (SELECT o.*, ROW_NUMBER() OVER (PARTITION BY o.object_id ORDER BY o.version DESC, o.checkpoint DESC) AS row_num
+      FROM objects_history o
+      WHERE o.checkpoint <= ?
+      AND ((o.owner_type = 'address_owner' AND o.owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381') OR (o.old_owner_type = 'address_owner' AND o.old_owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381'))) AS t1
+WHERE t1.row_num = 1 AND t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted')
+AND t1.owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381'
+ORDER BY t1.object_id
+LIMIT 100;";
+        assert_eq!(
+            expected_sql,
+            filter.to_sqlite_objects_history_sql(None, 100, vec!["data"])
+        );
+    }
+
+    #[test]
+    fn test_sqlite_changed_object_filter() {
+        let object_id = ObjectID::from_str("0x5").unwrap();
+        let filter = TransactionFilter::ChangedObject(object_id).not();
+
+        let expected_sql = "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE NOT EXISTS (SELECT 1 FROM json_each(t.changed_objects) WHERE json_each.value = '0x0000000000000000000000000000000000000000000000000000000000000005')
+AND t.id < 7
+ORDER BY t.id DESC
+LIMIT 10;";
+        assert_eq!(
+            expected_sql,
+            to_sqlite_transactions_sql(&filter, Some(7), 10, true)
+        );
+    }
+
+    #[test]
+    fn test_transaction_index_sql() {
+        let package = ObjectID::from_str("0x2").unwrap();
+        let expected_sql = "SELECT transaction_digest AS digest_name FROM (
+    SELECT transaction_digest, MAX(id) AS max_id
+    FROM move_calls
+    WHERE move_package = '0x0000000000000000000000000000000000000000000000000000000000000002' AND move_module = 'coin' AND id < 7
+    GROUP BY transaction_digest
+    ORDER BY max_id DESC
+    LIMIT 10
+) AS t;";
+        assert_eq!(
+            expected_sql,
+            to_move_call_transactions_sql(
+                package,
+                Some(&ident_str!("coin").to_owned()),
+                None,
+                Some(7),
+                10,
+                true
+            )
+        );
+
+        let expected_sql = "SELECT transaction_digest AS digest_name FROM (
+    SELECT transaction_digest, MAX(id) AS max_id
+    FROM input_objects
+    WHERE object_id = '0x0000000000000000000000000000000000000000000000000000000000000002' AND object_version = 3
+    GROUP BY transaction_digest
+    ORDER BY max_id ASC
+    LIMIT 10
+) AS t;";
+        assert_eq!(
+            expected_sql,
+            to_input_object_transactions_sql(package, Some(3), None, 10, false)
+        );
+
+        let recipient = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let expected_sql = "SELECT transaction_digest AS digest_name FROM (
+    SELECT transaction_digest, MAX(id) AS max_id
+    FROM recipients
+    WHERE recipient = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381' AND id > 5
+    GROUP BY transaction_digest
+    ORDER BY max_id ASC
+    LIMIT 10
+) AS t;";
+        assert_eq!(
+            expected_sql,
+            to_recipient_transactions_sql(None, recipient, Some(5), 10, false)
+        );
+    }
 }
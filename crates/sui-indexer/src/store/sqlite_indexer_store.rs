@@ -0,0 +1,1559 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use diesel::dsl::{max, sql};
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Binary, Bool, Text};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use diesel::upsert::excluded;
+use diesel::{
+    Connection, ExpressionMethods, Insertable, OptionalExtension, QueryDsl, QueryResult,
+    QueryableByName, RunQueryDsl,
+};
+use move_bytecode_utils::module_cache::SyncModuleCache;
+use move_core_types::identifier::Identifier;
+use prometheus::Histogram;
+use serde::Serialize;
+use tracing::info;
+
+use sui_json_rpc::ObjectProvider;
+use sui_json_rpc_types::{
+    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, NetworkMetrics, SuiEvent,
+    SuiObjectDataFilter, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::committee::EpochId;
+use sui_types::digests::CheckpointDigest;
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
+
+use crate::errors::{Context, IndexerError};
+use crate::models::addresses::Address;
+use crate::models::checkpoints::Checkpoint;
+use crate::models::dynamic_fields::{get_dynamic_field_changes, DynamicField};
+use crate::models::epoch::{to_end_of_epoch_data, to_epoch_infos, DBEpochInfo};
+use crate::models::events::Event;
+use crate::models::network_metrics::{to_move_call_metrics, DBMoveCallMetrics, DBNetworkMetrics};
+use crate::models::objects::{DeletedObject, Object, ObjectStatus};
+use crate::models::packages::Package;
+use crate::models::system_state::{DBSystemStateSummary, DBValidatorSummary};
+use crate::models::transaction_index::{InputObject, MoveCall, Recipient};
+use crate::models::transactions::Transaction;
+use crate::models::webhook_cursors::WebhookCursor;
+use crate::sqlite_schema::{
+    addresses, checkpoints, dynamic_fields, epochs, events, input_objects, move_calls, objects,
+    objects_history, packages, recipients, system_states, transactions, validators,
+    webhook_cursors,
+};
+use crate::store::diesel_marco::{filter_events, page_by_id, sequence_by_digest};
+use crate::store::indexer_store::{
+    compose_transaction_block_response, event_page_limit, get_transactions_in_digest_order,
+    to_event_page, TemporaryCheckpointStore,
+};
+use crate::store::module_resolver::SqliteModuleResolver;
+use crate::store::query::{
+    to_input_object_transactions_sql, to_move_call_transactions_sql, to_recipient_transactions_sql,
+    to_sqlite_transactions_sql, DBFilter, TempDigestTable,
+};
+use crate::store::{IndexerStore, TemporaryEpochStore, TransactionObjectChanges};
+use crate::{get_sqlite_pool_connection, SqliteConnectionPool};
+
+type TransactionQuery = transactions::BoxedQuery<'static, Sqlite, (BigInt, Binary)>;
+
+#[derive(QueryableByName, Debug, Clone)]
+struct DataRow {
+    #[diesel(sql_type = Binary)]
+    data: Vec<u8>,
+}
+
+// Each table keeps the columns its queries filter or sort on, and the model itself is stored
+// BCS-encoded in `data`.
+#[derive(Insertable)]
+#[diesel(table_name = checkpoints)]
+struct CheckpointRow {
+    sequence_number: i64,
+    checkpoint_digest: String,
+    epoch: i64,
+    total_transactions: i64,
+    network_total_transactions: i64,
+    timestamp_ms: i64,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = transactions)]
+struct TransactionRow {
+    transaction_digest: String,
+    sender: String,
+    checkpoint_sequence_number: Option<i64>,
+    timestamp_ms: Option<i64>,
+    transaction_kind: String,
+    transaction_count: i64,
+    changed_objects: String,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = events)]
+struct EventRow {
+    transaction_digest: String,
+    event_sequence: i64,
+    sender: String,
+    package: String,
+    module: String,
+    event_type: String,
+    event_time_ms: Option<i64>,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = objects)]
+struct ObjectRow {
+    object_id: String,
+    version: i64,
+    checkpoint: i64,
+    owner_type: String,
+    owner_address: Option<String>,
+    object_type: String,
+    object_status: String,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = dynamic_fields)]
+struct DynamicFieldRow {
+    object_id: String,
+    parent_object_id: String,
+    version: i64,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = packages)]
+struct PackageRow {
+    package_id: String,
+    version: i64,
+    author: String,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = epochs)]
+struct EpochRow {
+    epoch: i64,
+    epoch_start_timestamp: i64,
+    epoch_total_transactions: i64,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = system_states)]
+struct SystemStateRow {
+    epoch: i64,
+    data: Vec<u8>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = validators)]
+struct ValidatorRow {
+    epoch: i64,
+    sui_address: String,
+    data: Vec<u8>,
+}
+
+/// Rows of one checkpoint, encoded ahead of the write transaction.
+#[derive(Default)]
+struct CheckpointRows {
+    transactions: Vec<TransactionRow>,
+    events: Vec<EventRow>,
+    mutated_objects: Vec<ObjectRow>,
+    deleted_objects: Vec<DeletedObject>,
+    changed_dynamic_fields: Vec<DynamicFieldRow>,
+    deleted_dynamic_fields: Vec<String>,
+    addresses: Vec<Address>,
+    packages: Vec<PackageRow>,
+    move_calls: Vec<MoveCall>,
+    input_objects: Vec<InputObject>,
+    recipients: Vec<Recipient>,
+    checkpoint: Option<(CheckpointRow, usize)>,
+}
+
+/// An [IndexerStore] backed by a SQLite database file, which needs no database server and is
+/// meant for local development and tests.
+#[derive(Clone)]
+pub struct SqliteIndexerStore {
+    cp: SqliteConnectionPool,
+    module_cache: Arc<SyncModuleCache<SqliteModuleResolver>>,
+}
+
+impl SqliteIndexerStore {
+    pub fn new(cp: SqliteConnectionPool) -> Self {
+        let module_cache = Arc::new(SyncModuleCache::new(SqliteModuleResolver::new(cp.clone())));
+        Self { cp, module_cache }
+    }
+
+    /// Runs `query` in a read transaction, on the blocking thread pool as diesel's SQLite
+    /// connection is synchronous.
+    async fn read<T, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SqliteConnection) -> QueryResult<T> + Send + 'static,
+    {
+        let cp = self.cp.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut pool_conn = get_sqlite_pool_connection(&cp)?;
+            let conn: &mut SqliteConnection = &mut pool_conn;
+            conn.transaction(query)
+                .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+        })
+        .await
+        .map_err(|e| IndexerError::SqliteReadError(e.to_string()))?
+    }
+
+    /// Runs `query` in a write transaction, which takes the database write lock upfront so that
+    /// concurrent writers wait on the busy timeout instead of failing on lock upgrade.
+    async fn write<T, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SqliteConnection) -> QueryResult<T> + Send + 'static,
+    {
+        let cp = self.cp.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut pool_conn = get_sqlite_pool_connection(&cp)?;
+            let conn: &mut SqliteConnection = &mut pool_conn;
+            conn.immediate_transaction(query)
+                .map_err(|e| IndexerError::SqliteWriteError(e.to_string()))
+        })
+        .await
+        .map_err(|e| IndexerError::SqliteWriteError(e.to_string()))?
+    }
+
+    async fn get_sui_types_object(
+        &self,
+        object_id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<sui_types::object::Object, IndexerError> {
+        let (id, version) = (object_id.to_string(), version.value() as i64);
+        let data = self
+            .read(move |conn| {
+                objects_history::table
+                    .select(objects_history::data)
+                    .filter(objects_history::object_id.eq(id))
+                    .filter(objects_history::version.eq(version))
+                    // pick data from checkpoint if available
+                    .order(objects_history::checkpoint.desc())
+                    .first::<Vec<u8>>(conn)
+            })
+            .await
+            .context("Failed reading Object from SQLite DB")?;
+        let object: Object = bcs::from_bytes(&data)?;
+        sui_types::object::Object::try_from(object)
+    }
+
+    async fn find_sui_types_object_lt_or_eq_version(
+        &self,
+        object_id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<sui_types::object::Object>, IndexerError> {
+        let (id, version) = (object_id.to_string(), version.value() as i64);
+        let data = self
+            .read(move |conn| {
+                objects_history::table
+                    .select(objects_history::data)
+                    .filter(objects_history::object_id.eq(id))
+                    .filter(objects_history::version.le(version))
+                    .order((
+                        objects_history::version.desc(),
+                        objects_history::checkpoint.desc(),
+                    ))
+                    .first::<Vec<u8>>(conn)
+                    .optional()
+            })
+            .await
+            .context("Failed reading Object before version from SQLite DB")?;
+        data.map(|data| {
+            let object: Object = bcs::from_bytes(&data)?;
+            sui_types::object::Object::try_from(object)
+        })
+        .transpose()
+    }
+
+    async fn get_transaction_page(
+        &self,
+        query: TransactionQuery,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let query = page_by_id!(query, transactions::id, start_sequence, is_descending);
+        self.read(move |conn| query.limit(limit as i64).load::<(i64, Vec<u8>)>(conn))
+            .await?
+            .into_iter()
+            .map(decode_transaction)
+            .collect()
+    }
+
+    /// Loads the transactions of the digests returned by `sql_query`, in the query's order.
+    async fn get_transactions_by_digest_query(
+        &self,
+        sql_query: String,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let tx_digests = self
+            .read(move |conn| diesel::sql_query(sql_query).load::<TempDigestTable>(conn))
+            .await?
+            .into_iter()
+            .map(|table| table.digest_name)
+            .collect();
+        get_transactions_in_digest_order(self, tx_digests).await
+    }
+
+    async fn get_epoch_infos(
+        &self,
+        epochs: Vec<(i64, Vec<u8>)>,
+    ) -> Result<Vec<EpochInfo>, IndexerError> {
+        let epochs = epochs
+            .into_iter()
+            .map(|(epoch_total_transactions, data)| {
+                let mut info: DBEpochInfo = bcs::from_bytes(&data)?;
+                info.epoch_total_transactions = epoch_total_transactions;
+                Ok(info)
+            })
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+        let epoch_ids = epochs.iter().map(|info| info.epoch).collect::<Vec<_>>();
+        let validators = self
+            .read(move |conn| {
+                validators::table
+                    .select(validators::data)
+                    .filter(validators::epoch.eq_any(epoch_ids))
+                    .load::<Vec<u8>>(conn)
+            })
+            .await
+            .context("Failed reading validator summaries from SQLite DB")?
+            .iter()
+            .map(|data| Ok(bcs::from_bytes(data)?))
+            .collect::<Result<Vec<DBValidatorSummary>, IndexerError>>()?;
+        to_epoch_infos(epochs, validators)
+    }
+
+    async fn persist_checkpoint_rows(&self, rows: CheckpointRows) -> Result<usize, IndexerError> {
+        self.write(move |conn| {
+            let CheckpointRows {
+                transactions,
+                events,
+                mutated_objects,
+                deleted_objects,
+                changed_dynamic_fields,
+                deleted_dynamic_fields,
+                addresses,
+                packages,
+                move_calls,
+                input_objects,
+                recipients,
+                checkpoint,
+            } = rows;
+            for transaction in &transactions {
+                upsert_transaction(conn, transaction)?;
+            }
+            for event in &events {
+                diesel::insert_into(events::table)
+                    .values(event)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            persist_objects(conn, &mutated_objects, deleted_objects)?;
+            persist_dynamic_fields(conn, &changed_dynamic_fields, &deleted_dynamic_fields)?;
+            for address in &addresses {
+                diesel::insert_into(addresses::table)
+                    .values(address)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            for package in &packages {
+                diesel::insert_into(packages::table)
+                    .values(package)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            for move_call in &move_calls {
+                diesel::insert_into(move_calls::table)
+                    .values(move_call)
+                    .execute(conn)?;
+            }
+            for input_object in &input_objects {
+                diesel::insert_into(input_objects::table)
+                    .values(input_object)
+                    .execute(conn)?;
+            }
+            for recipient in &recipients {
+                diesel::insert_into(recipients::table)
+                    .values(recipient)
+                    .execute(conn)?;
+            }
+            // Commit the checkpoint last, so that if the checkpoint is committed,
+            // all related data have been committed as well.
+            match checkpoint {
+                Some((checkpoint, transaction_count)) => {
+                    diesel::update(epochs::table.filter(epochs::epoch.eq(checkpoint.epoch)))
+                        .set(
+                            epochs::epoch_total_transactions
+                                .eq(epochs::epoch_total_transactions + transaction_count as i64),
+                        )
+                        .execute(conn)?;
+                    diesel::insert_into(checkpoints::table)
+                        .values(&checkpoint)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                }
+                None => Ok(0),
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl IndexerStore for SqliteIndexerStore {
+    type ModuleCache = SyncModuleCache<SqliteModuleResolver>;
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
+        self.read(|conn| {
+            checkpoints::table
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)
+                // -1 to differentiate between no checkpoints and the first checkpoint
+                .map(|o| o.unwrap_or(-1))
+        })
+        .await
+        .context("Failed reading latest checkpoint sequence number from SQLite DB")
+    }
+
+    async fn get_checkpoint(
+        &self,
+        id: CheckpointId,
+    ) -> Result<sui_json_rpc_types::Checkpoint, IndexerError> {
+        let (checkpoint, epoch) = self
+            .read(move |conn| {
+                let query = checkpoints::table.select((checkpoints::epoch, checkpoints::data));
+                let (epoch, checkpoint) = match id {
+                    CheckpointId::SequenceNumber(seq) => query
+                        .filter(checkpoints::sequence_number.eq(seq as i64))
+                        .first::<(i64, Vec<u8>)>(conn),
+                    CheckpointId::Digest(digest) => query
+                        .filter(checkpoints::checkpoint_digest.eq(digest.base58_encode()))
+                        .first::<(i64, Vec<u8>)>(conn),
+                }?;
+                let epoch = epochs::table
+                    .select(epochs::data)
+                    .filter(epochs::epoch.eq(epoch))
+                    .first::<Vec<u8>>(conn)
+                    .optional()?;
+                Ok((checkpoint, epoch))
+            })
+            .await
+            .context(&format!("Failed reading checkpoint {id:?} from SQLite DB"))?;
+
+        let checkpoint: Checkpoint = bcs::from_bytes(&checkpoint)?;
+        let end_of_epoch_data = match epoch {
+            Some(epoch) if checkpoint.end_of_epoch => {
+                let epoch: DBEpochInfo = bcs::from_bytes(&epoch)?;
+                to_end_of_epoch_data(
+                    epoch.next_epoch_version,
+                    &epoch.next_epoch_committee,
+                    &epoch.next_epoch_committee_stake,
+                    &epoch.epoch_commitments,
+                )?
+            }
+            _ => None,
+        };
+        checkpoint.into_rpc(end_of_epoch_data)
+    }
+
+    async fn get_checkpoint_sequence_number(
+        &self,
+        digest: CheckpointDigest,
+    ) -> Result<CheckpointSequenceNumber, IndexerError> {
+        Ok(self
+            .read(move |conn| {
+                checkpoints::table
+                    .select(checkpoints::sequence_number)
+                    .filter(checkpoints::checkpoint_digest.eq(digest.base58_encode()))
+                    .first::<i64>(conn)
+            })
+            .await
+            .context("Failed reading checkpoint seq number from SQLite DB")? as u64)
+    }
+
+    async fn get_event(&self, id: EventID) -> Result<Event, IndexerError> {
+        let row = self
+            .read(move |conn| {
+                events::table
+                    .select((events::id, events::data))
+                    .filter(events::transaction_digest.eq(id.tx_digest.base58_encode()))
+                    .filter(events::event_sequence.eq(id.event_seq as i64))
+                    .first::<(i64, Vec<u8>)>(conn)
+            })
+            .await
+            .context("Failed reading event from SQLite DB")?;
+        decode_event(row)
+    }
+
+    async fn get_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> Result<EventPage, IndexerError> {
+        let boxed_query = filter_events!(
+            events,
+            events::table
+                .select((events::id, events::data))
+                .into_boxed::<Sqlite>(),
+            query
+        )?;
+        let page_limit = event_page_limit(limit)?;
+        let cursor = match cursor {
+            Some(cursor) => {
+                let event = self.get_event(cursor).await?;
+                let id = event
+                    .id
+                    .ok_or_else(|| IndexerError::SqliteReadError("Event ID is None".to_string()))?;
+                Some(id)
+            }
+            None => None,
+        };
+        let boxed_query = page_by_id!(boxed_query, events::id, cursor, descending_order);
+
+        // fetch one more item to tell if there is next page
+        let rows = self
+            .read(move |conn| {
+                boxed_query
+                    .limit(page_limit as i64 + 1)
+                    .load::<(i64, Vec<u8>)>(conn)
+            })
+            .await
+            .context("Failed reading events from SQLite DB")?;
+        let events = rows
+            .into_iter()
+            .map(|row| decode_event(row)?.try_into(&self.module_cache))
+            .collect::<Result<Vec<SuiEvent>, _>>()?;
+        Ok(to_event_page(events, page_limit))
+    }
+
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        let id = object_id.to_string();
+        let data = self
+            .read(move |conn| {
+                if let Some(version) = version {
+                    objects_history::table
+                        .select(objects_history::data)
+                        .filter(objects_history::object_id.eq(id))
+                        .filter(objects_history::version.eq(version.value() as i64))
+                        .order(objects_history::checkpoint.desc())
+                        .first::<Vec<u8>>(conn)
+                        .optional()
+                } else {
+                    objects::table
+                        .select(objects::data)
+                        .filter(objects::object_id.eq(id))
+                        .first::<Vec<u8>>(conn)
+                        .optional()
+                }
+            })
+            .await
+            .context(&format!("Failed reading object with id {object_id}"))?;
+
+        match data {
+            None => Ok(ObjectRead::NotExists(object_id)),
+            Some(data) => {
+                bcs::from_bytes::<Object>(&data)?.try_into_object_read(&self.module_cache)
+            }
+        }
+    }
+
+    async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<ObjectRead, IndexerError> {
+        let id = object_id.to_string();
+        let data = self
+            .read(move |conn| {
+                objects_history::table
+                    .select(objects_history::data)
+                    .filter(objects_history::object_id.eq(id))
+                    // NOTE: rows from fast-path have checkpoint -1 and are not part of any checkpoint yet.
+                    .filter(objects_history::checkpoint.between(0, checkpoint as i64))
+                    .order((
+                        objects_history::version.desc(),
+                        objects_history::checkpoint.desc(),
+                    ))
+                    .first::<Vec<u8>>(conn)
+                    .optional()
+            })
+            .await
+            .context(&format!(
+                "Failed reading object with id {object_id} at checkpoint {checkpoint}"
+            ))?;
+
+        let Some(data) = data else {
+            return Ok(ObjectRead::NotExists(object_id));
+        };
+        let object = bcs::from_bytes::<Object>(&data)?;
+        // A wrapped object cannot be read at the checkpoint, as if it was deleted.
+        if matches!(object.object_status, ObjectStatus::Wrapped) {
+            return Ok(ObjectRead::Deleted(object.get_object_ref()?));
+        }
+        object.try_into_object_read(&self.module_cache)
+    }
+
+    async fn query_objects_history(
+        &self,
+        filter: SuiObjectDataFilter,
+        at_checkpoint: CheckpointSequenceNumber,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        let sql_query = filter.to_sqlite_objects_history_sql(cursor, limit, vec!["data"]);
+        let rows = self
+            .read(move |conn| {
+                diesel::sql_query(sql_query)
+                    .bind::<BigInt, _>(at_checkpoint as i64)
+                    .load::<DataRow>(conn)
+            })
+            .await
+            .context("Failed reading objects history from SQLite DB")?;
+
+        rows.into_iter()
+            .map(|row| {
+                bcs::from_bytes::<Object>(&row.data)?.try_into_object_read(&self.module_cache)
+            })
+            .collect()
+    }
+
+    // NOTE: same as the Postgres store, only supports query by address owner
+    async fn query_latest_objects(
+        &self,
+        filter: SuiObjectDataFilter,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        let sql_query = filter.to_latest_objects_sql(cursor, limit, vec!["data"]);
+        let rows = self
+            .read(move |conn| diesel::sql_query(sql_query).load::<DataRow>(conn))
+            .await
+            .context("Failed reading latest objects from SQLite DB")?;
+
+        rows.into_iter()
+            .map(|row| {
+                bcs::from_bytes::<Object>(&row.data)?.try_into_object_read(&self.module_cache)
+            })
+            .collect()
+    }
+
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<DynamicField>, IndexerError> {
+        let parent_id = parent_object_id.to_string();
+        let cursor = cursor.map(|id| id.to_string());
+        let rows = self
+            .read(move |conn| {
+                let mut query = dynamic_fields::table
+                    .select(dynamic_fields::data)
+                    .filter(dynamic_fields::parent_object_id.eq(parent_id))
+                    .order(dynamic_fields::object_id.asc())
+                    .limit(limit as i64)
+                    .into_boxed();
+                if let Some(cursor) = cursor {
+                    query = query.filter(dynamic_fields::object_id.gt(cursor));
+                }
+                query.load::<Vec<u8>>(conn)
+            })
+            .await
+            .context(&format!(
+                "Failed reading dynamic fields of object {parent_object_id}"
+            ))?;
+        rows.iter().map(|data| Ok(bcs::from_bytes(data)?)).collect()
+    }
+
+    async fn multi_get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        object_ids: &[ObjectID],
+    ) -> Result<Vec<DynamicField>, IndexerError> {
+        let parent_id = parent_object_id.to_string();
+        let object_ids = object_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        let rows = self
+            .read(move |conn| {
+                dynamic_fields::table
+                    .select(dynamic_fields::data)
+                    .filter(dynamic_fields::parent_object_id.eq(parent_id))
+                    .filter(dynamic_fields::object_id.eq_any(object_ids))
+                    .load::<Vec<u8>>(conn)
+            })
+            .await
+            .context(&format!(
+                "Failed reading dynamic fields of object {parent_object_id}"
+            ))?;
+        rows.iter().map(|data| Ok(bcs::from_bytes(data)?)).collect()
+    }
+
+    async fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError> {
+        self.read(|conn| {
+            checkpoints::table
+                .select(checkpoints::network_total_transactions)
+                .order(checkpoints::network_total_transactions.desc())
+                .first::<i64>(conn)
+        })
+        .await
+        .context("Failed reading total transaction number")
+    }
+
+    async fn get_transaction_by_digest(
+        &self,
+        tx_digest: &str,
+    ) -> Result<Transaction, IndexerError> {
+        let digest = tx_digest.to_string();
+        let row = self
+            .read(move |conn| {
+                transactions::table
+                    .select((transactions::id, transactions::data))
+                    .filter(transactions::transaction_digest.eq(digest))
+                    .first::<(i64, Vec<u8>)>(conn)
+            })
+            .await
+            .context(&format!(
+                "Failed reading transaction with digest {tx_digest}"
+            ))?;
+        decode_transaction(row)
+    }
+
+    async fn multi_get_transactions_by_digests(
+        &self,
+        tx_digests: &[String],
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let digests = tx_digests.to_vec();
+        self.read(move |conn| {
+            transactions::table
+                .select((transactions::id, transactions::data))
+                .filter(transactions::transaction_digest.eq_any(digests))
+                .load::<(i64, Vec<u8>)>(conn)
+        })
+        .await
+        .context(&format!(
+            "Failed reading transactions with digests {tx_digests:?}"
+        ))?
+        .into_iter()
+        .map(decode_transaction)
+        .collect()
+    }
+
+    async fn compose_sui_transaction_block_response(
+        &self,
+        tx: Transaction,
+        options: Option<&SuiTransactionBlockResponseOptions>,
+    ) -> Result<SuiTransactionBlockResponse, IndexerError> {
+        compose_transaction_block_response(self, tx, options).await
+    }
+
+    async fn get_all_transaction_page(
+        &self,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        self.get_transaction_page(transaction_query(), start_sequence, limit, is_descending)
+            .await
+            .context(&format!("Failed reading all transaction digests with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_checkpoint(
+        &self,
+        checkpoint_sequence_number: i64,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let query = transaction_query()
+            .filter(transactions::checkpoint_sequence_number.eq(checkpoint_sequence_number));
+        self.get_transaction_page(query, start_sequence, limit, is_descending)
+            .await
+            .context(&format!("Failed reading transaction digests with checkpoint_sequence_number {checkpoint_sequence_number:?} and start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_transaction_kind(
+        &self,
+        kind: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let query = transaction_query().filter(transactions::transaction_kind.eq(kind.clone()));
+        self.get_transaction_page(query, start_sequence, limit, is_descending)
+            .await
+            .context(&format!("Failed reading transaction digests with kind {kind} and start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_sender_address(
+        &self,
+        sender_address: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let query = transaction_query().filter(transactions::sender.eq(sender_address.clone()));
+        self.get_transaction_page(query, start_sequence, limit, is_descending)
+            .await
+            .context(&format!("Failed reading transaction digests by sender address {sender_address} with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_mutated_object(
+        &self,
+        object_id: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let query = transaction_query().filter(
+            sql::<Bool>("EXISTS (SELECT 1 FROM json_each(transactions.changed_objects) WHERE json_each.value = ")
+                .bind::<Text, _>(object_id.clone())
+                .sql(")"),
+        );
+        self.get_transaction_page(query, start_sequence, limit, is_descending)
+            .await
+            .context(&format!("Failed reading transaction digests by mutated object id {object_id} with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_sender_recipient_address(
+        &self,
+        from: Option<SuiAddress>,
+        to: SuiAddress,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query =
+            to_recipient_transactions_sql(from, to, start_sequence, limit, is_descending);
+        self.get_transactions_by_digest_query(sql_query)
+            .await
+            .context(&format!("Failed reading transaction digests by recipient address {to} with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_input_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<i64>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = to_input_object_transactions_sql(
+            object_id,
+            version,
+            start_sequence,
+            limit,
+            is_descending,
+        );
+        self.get_transactions_by_digest_query(sql_query)
+            .await
+            .context(&format!("Failed reading transaction digests by input object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_page_by_move_call(
+        &self,
+        package_name: ObjectID,
+        module_name: Option<Identifier>,
+        function_name: Option<Identifier>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = to_move_call_transactions_sql(
+            package_name,
+            module_name.as_ref(),
+            function_name.as_ref(),
+            start_sequence,
+            limit,
+            is_descending,
+        );
+        self.get_transactions_by_digest_query(sql_query)
+            .await
+            .context(&format!(
+                "Failed reading transaction digests with package_name {} module_name {:?} and function_name {:?} and start_sequence {:?} and limit {}",
+                package_name, module_name, function_name, start_sequence, limit))
+    }
+
+    async fn get_transaction_page_by_filter(
+        &self,
+        filter: TransactionFilter,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = to_sqlite_transactions_sql(&filter, start_sequence, limit, is_descending);
+        self.get_transactions_by_digest_query(sql_query)
+            .await
+            .context(&format!("Failed reading transaction digests by filter {filter:?} with start_sequence {start_sequence:?} and limit {limit}"))
+    }
+
+    async fn get_transaction_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let context = format!("Failed reading transaction sequence with digest {digest}");
+        self.read(move |conn| {
+            sequence_by_digest!(transactions, digest, is_descending).first::<i64>(conn)
+        })
+        .await
+        .context(&context)
+        .map(Some)
+    }
+
+    async fn get_move_call_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let context = format!("Failed reading move call sequence with digest {digest}");
+        self.read(move |conn| {
+            sequence_by_digest!(move_calls, digest, is_descending).first::<i64>(conn)
+        })
+        .await
+        .context(&context)
+        .map(Some)
+    }
+
+    async fn get_input_object_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let context = format!("Failed reading input object sequence with digest {digest}");
+        self.read(move |conn| {
+            sequence_by_digest!(input_objects, digest, is_descending).first::<i64>(conn)
+        })
+        .await
+        .context(&context)
+        .map(Some)
+    }
+
+    async fn get_recipient_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        let Some(digest) = tx_digest else {
+            return Ok(None);
+        };
+        let context = format!("Failed reading recipients sequence with digest {digest}");
+        self.read(move |conn| {
+            sequence_by_digest!(recipients, digest, is_descending).first::<i64>(conn)
+        })
+        .await
+        .context(&context)
+        .map(Some)
+    }
+
+    async fn get_network_metrics(&self) -> Result<NetworkMetrics, IndexerError> {
+        let metrics = self
+            .read(|conn| {
+                diesel::sql_query("SELECT * FROM network_metrics;")
+                    .get_result::<DBNetworkMetrics>(conn)
+            })
+            .await
+            .context("Failed reading network metrics from SQLite DB")?;
+        Ok(metrics.into())
+    }
+
+    async fn get_move_call_metrics(&self) -> Result<MoveCallMetrics, IndexerError> {
+        let metrics = self
+            .read(|conn| {
+                diesel::sql_query("SELECT * FROM epoch_move_call_metrics;")
+                    .load::<DBMoveCallMetrics>(conn)
+            })
+            .await
+            .context("Failed reading move call metrics from SQLite DB")?;
+        Ok(to_move_call_metrics(metrics))
+    }
+
+    async fn persist_fast_path(
+        &self,
+        tx: Transaction,
+        tx_object_changes: TransactionObjectChanges,
+    ) -> Result<usize, IndexerError> {
+        let transaction = transaction_row(&tx)?;
+        let (changed_fields, deleted_field_ids) =
+            dynamic_field_rows(std::slice::from_ref(&tx_object_changes))?;
+        let mutated_objects = object_rows(tx_object_changes.changed_objects)?;
+        let deleted_objects = tx_object_changes.deleted_objects;
+        self.write(move |conn| {
+            let count = diesel::insert_into(transactions::table)
+                .values(&transaction)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            persist_dynamic_fields(conn, &changed_fields, &deleted_field_ids)?;
+            Ok(count + persist_objects(conn, &mutated_objects, deleted_objects)?)
+        })
+        .await
+        .context("Failed writing fast path transaction to SQLite DB")
+    }
+
+    async fn persist_all_checkpoint_data(
+        &self,
+        data: &TemporaryCheckpointStore,
+    ) -> Result<usize, IndexerError> {
+        let (changed_dynamic_fields, deleted_dynamic_fields) =
+            dynamic_field_rows(&data.object_changes)?;
+        let rows = CheckpointRows {
+            transactions: data
+                .transactions
+                .iter()
+                .map(transaction_row)
+                .collect::<Result<_, _>>()?,
+            events: data
+                .events
+                .iter()
+                .map(event_row)
+                .collect::<Result<_, _>>()?,
+            mutated_objects: object_rows(
+                data.object_changes
+                    .iter()
+                    .flat_map(|changes| changes.changed_objects.iter().cloned())
+                    .collect(),
+            )?,
+            deleted_objects: data
+                .object_changes
+                .iter()
+                .flat_map(|changes| changes.deleted_objects.iter().cloned())
+                .collect(),
+            changed_dynamic_fields,
+            deleted_dynamic_fields,
+            addresses: data.addresses.clone(),
+            packages: data
+                .packages
+                .iter()
+                .map(package_row)
+                .collect::<Result<_, _>>()?,
+            move_calls: data.move_calls.clone(),
+            input_objects: data.input_objects.clone(),
+            recipients: data.recipients.clone(),
+            checkpoint: Some((
+                checkpoint_row(&data.checkpoint)?,
+                data.checkpoint.transactions.len(),
+            )),
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing checkpoint data to SQLite DB")
+    }
+
+    async fn persist_checkpoint_transactions(
+        &self,
+        checkpoint: &Checkpoint,
+        transactions: &[Transaction],
+    ) -> Result<usize, IndexerError> {
+        let rows = CheckpointRows {
+            transactions: transactions
+                .iter()
+                .map(transaction_row)
+                .collect::<Result<_, _>>()?,
+            checkpoint: Some((checkpoint_row(checkpoint)?, checkpoint.transactions.len())),
+            ..Default::default()
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing checkpoint transactions to SQLite DB")
+    }
+
+    async fn persist_object_changes(
+        &self,
+        checkpoint_seq: i64,
+        tx_object_changes: &[TransactionObjectChanges],
+        object_mutation_latency: Histogram,
+        object_deletion_latency: Histogram,
+    ) -> Result<(), IndexerError> {
+        let mutated_objects = object_rows(
+            tx_object_changes
+                .iter()
+                .flat_map(|changes| changes.changed_objects.iter().cloned())
+                .collect(),
+        )?;
+        let deleted_objects = tx_object_changes
+            .iter()
+            .flat_map(|changes| changes.deleted_objects.iter().cloned())
+            .collect::<Vec<_>>();
+        let (changed_fields, deleted_field_ids) = dynamic_field_rows(tx_object_changes)?;
+        let (mutation_count, deletion_count) = (mutated_objects.len(), deleted_objects.len());
+        self.write(move |conn| {
+            let object_mutation_guard = object_mutation_latency.start_timer();
+            persist_objects(conn, &mutated_objects, vec![])?;
+            object_mutation_guard.stop_and_record();
+            let object_deletion_guard = object_deletion_latency.start_timer();
+            persist_objects(conn, &[], deleted_objects)?;
+            object_deletion_guard.stop_and_record();
+            persist_dynamic_fields(conn, &changed_fields, &deleted_field_ids)?;
+            Ok(())
+        })
+        .await
+        .context("Failed writing object changes to SQLite DB")?;
+        info!(
+            "Object checkpoint {} committed with {} transaction, {} mutated objects and {} deleted objects.",
+            checkpoint_seq,
+            tx_object_changes.len(),
+            mutation_count,
+            deletion_count
+        );
+        Ok(())
+    }
+
+    async fn persist_events(&self, events: &[Event]) -> Result<(), IndexerError> {
+        let rows = CheckpointRows {
+            events: events.iter().map(event_row).collect::<Result<_, _>>()?,
+            ..Default::default()
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing events to SQLite DB")?;
+        Ok(())
+    }
+
+    async fn persist_addresses(&self, addresses: &[Address]) -> Result<(), IndexerError> {
+        let rows = CheckpointRows {
+            addresses: addresses.to_vec(),
+            ..Default::default()
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing addresses to SQLite DB")?;
+        Ok(())
+    }
+
+    async fn persist_packages(&self, packages: &[Package]) -> Result<(), IndexerError> {
+        let rows = CheckpointRows {
+            packages: packages.iter().map(package_row).collect::<Result<_, _>>()?,
+            ..Default::default()
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing packages to SQLite DB")?;
+        Ok(())
+    }
+
+    async fn persist_transaction_index_tables(
+        &self,
+        input_objects: &[InputObject],
+        move_calls: &[MoveCall],
+        recipients: &[Recipient],
+    ) -> Result<(), IndexerError> {
+        let rows = CheckpointRows {
+            move_calls: move_calls.to_vec(),
+            input_objects: input_objects.to_vec(),
+            recipients: recipients.to_vec(),
+            ..Default::default()
+        };
+        self.persist_checkpoint_rows(rows)
+            .await
+            .context("Failed writing transaction index tables to SQLite DB")?;
+        Ok(())
+    }
+
+    async fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError> {
+        let epoch = data.new_epoch.epoch;
+        info!("Persisting epoch {}", epoch);
+        let last_epoch = data.last_epoch.as_ref().map(epoch_row).transpose()?;
+        let new_epoch = epoch_row(&data.new_epoch)?;
+        let system_state = system_state_row(&data.system_state)?;
+        let validators = data
+            .validators
+            .iter()
+            .map(validator_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.write(move |conn| {
+            if let Some(last_epoch) = &last_epoch {
+                // epoch_total_transactions is maintained as checkpoints are committed.
+                diesel::insert_into(epochs::table)
+                    .values(last_epoch)
+                    .on_conflict(epochs::epoch)
+                    .do_update()
+                    .set(epochs::data.eq(excluded(epochs::data)))
+                    .execute(conn)?;
+            }
+            diesel::insert_into(epochs::table)
+                .values(&new_epoch)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            diesel::insert_into(system_states::table)
+                .values(&system_state)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            for validator in &validators {
+                diesel::insert_into(validators::table)
+                    .values(validator)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .await
+        .context("Failed writing epoch to SQLite DB")?;
+        info!("Persisted epoch {}", epoch);
+        Ok(())
+    }
+
+    async fn get_epochs(
+        &self,
+        cursor: Option<EpochId>,
+        limit: usize,
+        descending_order: Option<bool>,
+    ) -> Result<Vec<EpochInfo>, IndexerError> {
+        let query = page_by_id!(
+            epochs::table
+                .select((epochs::epoch_total_transactions, epochs::data))
+                .into_boxed::<Sqlite>(),
+            epochs::epoch,
+            cursor.map(|id| id as i64),
+            descending_order.unwrap_or_default()
+        );
+        let epochs = self
+            .read(move |conn| query.limit(limit as i64).load::<(i64, Vec<u8>)>(conn))
+            .await
+            .context("Failed reading epochs from SQLite DB")?;
+        self.get_epoch_infos(epochs).await
+    }
+
+    async fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
+        let epoch = self
+            .read(|conn| {
+                epochs::table
+                    .select((epochs::epoch_total_transactions, epochs::data))
+                    .order_by(epochs::epoch.desc())
+                    .first::<(i64, Vec<u8>)>(conn)
+            })
+            .await
+            .context("Failed reading latest epoch")?;
+        let mut epochs = self.get_epoch_infos(vec![epoch]).await?;
+        epochs
+            .pop()
+            .ok_or_else(|| IndexerError::SqliteReadError("Latest epoch not found".to_string()))
+    }
+
+    async fn get_webhook_cursor(&self, name: &str) -> Result<Option<WebhookCursor>, IndexerError> {
+        let name = name.to_string();
+        self.read(move |conn| {
+            webhook_cursors::table
+                .filter(webhook_cursors::name.eq(name))
+                .first::<WebhookCursor>(conn)
+                .optional()
+        })
+        .await
+        .context("Failed reading webhook cursor from SQLite DB")
+    }
+
+    async fn persist_webhook_cursor(&self, cursor: &WebhookCursor) -> Result<(), IndexerError> {
+        let cursor = cursor.clone();
+        self.write(move |conn| {
+            diesel::insert_into(webhook_cursors::table)
+                .values(&cursor)
+                .on_conflict(webhook_cursors::name)
+                .do_update()
+                .set((
+                    webhook_cursors::cursor.eq(excluded(webhook_cursors::cursor)),
+                    webhook_cursors::updated_at.eq(excluded(webhook_cursors::updated_at)),
+                ))
+                .execute(conn)
+        })
+        .await
+        .context("Failed writing webhook cursor to SQLite DB")?;
+        Ok(())
+    }
+
+    fn module_cache(&self) -> &Self::ModuleCache {
+        &self.module_cache
+    }
+}
+
+#[async_trait]
+impl ObjectProvider for SqliteIndexerStore {
+    type Error = IndexerError;
+    async fn get_object(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<sui_types::object::Object, Self::Error> {
+        self.get_sui_types_object(id, version).await
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<sui_types::object::Object>, Self::Error> {
+        self.find_sui_types_object_lt_or_eq_version(id, version)
+            .await
+    }
+}
+
+fn transaction_query() -> TransactionQuery {
+    transactions::table
+        .select((transactions::id, transactions::data))
+        .into_boxed()
+}
+
+/// Upserts the mutated objects in version order, so that every version is archived in the
+/// objects history, and merges the deletions into the latest version of the objects.
+fn persist_objects(
+    conn: &mut SqliteConnection,
+    mutated_objects: &[ObjectRow],
+    deleted_objects: Vec<DeletedObject>,
+) -> QueryResult<usize> {
+    let mut count = 0;
+    for object in mutated_objects {
+        count += upsert_object(conn, object)?;
+    }
+    for deleted_object in deleted_objects {
+        let data = objects::table
+            .select(objects::data)
+            .filter(objects::object_id.eq(&deleted_object.object_id))
+            .first::<Vec<u8>>(conn)
+            .optional()?;
+        let object = match data {
+            Some(data) => {
+                let mut object: Object = bcs::from_bytes(&data)
+                    .map_err(|e| DieselError::DeserializationError(Box::new(e)))?;
+                object.epoch = deleted_object.epoch;
+                object.checkpoint = deleted_object.checkpoint.unwrap_or(-1);
+                object.version = deleted_object.version;
+                object.previous_transaction = deleted_object.previous_transaction;
+                object.object_status = deleted_object.object_status;
+                object
+            }
+            None => deleted_object.into(),
+        };
+        let row = object_row(&object).map_err(|e| DieselError::SerializationError(Box::new(e)))?;
+        count += upsert_object(conn, &row)?;
+    }
+    Ok(count)
+}
+
+fn upsert_object(conn: &mut SqliteConnection, object: &ObjectRow) -> QueryResult<usize> {
+    diesel::insert_into(objects::table)
+        .values(object)
+        .on_conflict(objects::object_id)
+        .do_update()
+        .set((
+            objects::version.eq(excluded(objects::version)),
+            objects::checkpoint.eq(excluded(objects::checkpoint)),
+            objects::owner_type.eq(excluded(objects::owner_type)),
+            objects::owner_address.eq(excluded(objects::owner_address)),
+            objects::object_type.eq(excluded(objects::object_type)),
+            objects::object_status.eq(excluded(objects::object_status)),
+            objects::data.eq(excluded(objects::data)),
+        ))
+        .execute(conn)
+}
+
+fn persist_dynamic_fields(
+    conn: &mut SqliteConnection,
+    changed_fields: &[DynamicFieldRow],
+    deleted_field_ids: &[String],
+) -> QueryResult<()> {
+    for field in changed_fields {
+        diesel::insert_into(dynamic_fields::table)
+            .values(field)
+            .on_conflict(dynamic_fields::object_id)
+            .do_update()
+            .set((
+                dynamic_fields::parent_object_id.eq(excluded(dynamic_fields::parent_object_id)),
+                dynamic_fields::version.eq(excluded(dynamic_fields::version)),
+                dynamic_fields::data.eq(excluded(dynamic_fields::data)),
+            ))
+            .execute(conn)?;
+    }
+    for object_id in deleted_field_ids {
+        diesel::delete(dynamic_fields::table.filter(dynamic_fields::object_id.eq(object_id)))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn upsert_transaction(
+    conn: &mut SqliteConnection,
+    transaction: &TransactionRow,
+) -> QueryResult<usize> {
+    diesel::insert_into(transactions::table)
+        .values(transaction)
+        .on_conflict(transactions::transaction_digest)
+        .do_update()
+        .set((
+            transactions::timestamp_ms.eq(excluded(transactions::timestamp_ms)),
+            transactions::checkpoint_sequence_number
+                .eq(excluded(transactions::checkpoint_sequence_number)),
+            transactions::data.eq(excluded(transactions::data)),
+        ))
+        .execute(conn)
+}
+
+fn decode_transaction((id, data): (i64, Vec<u8>)) -> Result<Transaction, IndexerError> {
+    let mut transaction: Transaction = bcs::from_bytes(&data)?;
+    transaction.id = Some(id);
+    Ok(transaction)
+}
+
+fn decode_event((id, data): (i64, Vec<u8>)) -> Result<Event, IndexerError> {
+    let mut event: Event = bcs::from_bytes(&data)?;
+    event.id = Some(id);
+    Ok(event)
+}
+
+// Enum columns hold the same snake_case names as the Postgres enum types, which the shared
+// queries in `query.rs` compare against.
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value)
+        .expect("unit enum variants always serialize")
+        .trim_matches('"')
+        .to_string()
+}
+
+fn object_rows(mut objects: Vec<Object>) -> Result<Vec<ObjectRow>, IndexerError> {
+    objects.sort_by(|a, b| (&a.object_id, a.version).cmp(&(&b.object_id, b.version)));
+    objects
+        .iter()
+        .map(|object| Ok(object_row(object)?))
+        .collect()
+}
+
+fn object_row(object: &Object) -> Result<ObjectRow, bcs::Error> {
+    Ok(ObjectRow {
+        object_id: object.object_id.clone(),
+        version: object.version,
+        checkpoint: object.checkpoint,
+        owner_type: enum_name(&object.owner_type),
+        owner_address: object.owner_address.clone(),
+        object_type: object.object_type.clone(),
+        object_status: enum_name(&object.object_status),
+        data: bcs::to_bytes(object)?,
+    })
+}
+
+fn dynamic_field_rows(
+    tx_object_changes: &[TransactionObjectChanges],
+) -> Result<(Vec<DynamicFieldRow>, Vec<String>), IndexerError> {
+    let (changed_fields, deleted_field_ids) = get_dynamic_field_changes(tx_object_changes)?;
+    let changed_fields = changed_fields
+        .iter()
+        .map(|field| {
+            Ok(DynamicFieldRow {
+                object_id: field.object_id.clone(),
+                parent_object_id: field.parent_object_id.clone(),
+                version: field.version,
+                data: bcs::to_bytes(field)?,
+            })
+        })
+        .collect::<Result<_, IndexerError>>()?;
+    Ok((changed_fields, deleted_field_ids))
+}
+
+fn checkpoint_row(checkpoint: &Checkpoint) -> Result<CheckpointRow, IndexerError> {
+    Ok(CheckpointRow {
+        sequence_number: checkpoint.sequence_number,
+        checkpoint_digest: checkpoint.checkpoint_digest.clone(),
+        epoch: checkpoint.epoch,
+        total_transactions: checkpoint.total_transactions,
+        network_total_transactions: checkpoint.network_total_transactions,
+        timestamp_ms: checkpoint.timestamp_ms,
+        data: bcs::to_bytes(checkpoint)?,
+    })
+}
+
+fn transaction_row(transaction: &Transaction) -> Result<TransactionRow, IndexerError> {
+    let changed_objects = transaction
+        .created
+        .iter()
+        .chain(&transaction.mutated)
+        .chain(&transaction.unwrapped)
+        .flatten()
+        .collect::<Vec<_>>();
+    Ok(TransactionRow {
+        transaction_digest: transaction.transaction_digest.clone(),
+        sender: transaction.sender.clone(),
+        checkpoint_sequence_number: transaction.checkpoint_sequence_number,
+        timestamp_ms: transaction.timestamp_ms,
+        transaction_kind: transaction.transaction_kind.clone(),
+        transaction_count: transaction.transaction_count,
+        changed_objects: serde_json::to_string(&changed_objects)
+            .map_err(|e| IndexerError::SerdeError(e.to_string()))?,
+        data: bcs::to_bytes(transaction)?,
+    })
+}
+
+fn event_row(event: &Event) -> Result<EventRow, IndexerError> {
+    Ok(EventRow {
+        transaction_digest: event.transaction_digest.clone(),
+        event_sequence: event.event_sequence,
+        sender: event.sender.clone(),
+        package: event.package.clone(),
+        module: event.module.clone(),
+        event_type: event.event_type.clone(),
+        event_time_ms: event.event_time_ms,
+        data: bcs::to_bytes(event)?,
+    })
+}
+
+fn package_row(package: &Package) -> Result<PackageRow, IndexerError> {
+    Ok(PackageRow {
+        package_id: package.package_id.clone(),
+        version: package.version,
+        author: package.author.clone(),
+        data: bcs::to_bytes(package)?,
+    })
+}
+
+fn epoch_row(epoch: &DBEpochInfo) -> Result<EpochRow, IndexerError> {
+    Ok(EpochRow {
+        epoch: epoch.epoch,
+        epoch_start_timestamp: epoch.epoch_start_timestamp,
+        epoch_total_transactions: epoch.epoch_total_transactions,
+        data: bcs::to_bytes(epoch)?,
+    })
+}
+
+fn system_state_row(system_state: &DBSystemStateSummary) -> Result<SystemStateRow, IndexerError> {
+    Ok(SystemStateRow {
+        epoch: system_state.epoch,
+        data: bcs::to_bytes(system_state)?,
+    })
+}
+
+fn validator_row(validator: &DBValidatorSummary) -> Result<ValidatorRow, IndexerError> {
+    Ok(ValidatorRow {
+        epoch: validator.epoch,
+        sui_address: validator.sui_address.clone(),
+        data: bcs::to_bytes(validator)?,
+    })
+}
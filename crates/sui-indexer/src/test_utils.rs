@@ -8,9 +8,12 @@ use tokio::task::JoinHandle;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 
 use crate::errors::IndexerError;
-use crate::store::PgIndexerStore;
-use crate::utils::reset_database;
-use crate::{new_pg_connection_pool, Indexer, IndexerConfig};
+use crate::store::{PgIndexerStore, SqliteIndexerStore};
+use crate::utils::{reset_database, run_sqlite_migrations};
+use crate::{
+    get_sqlite_pool_connection, new_pg_connection_pool, new_sqlite_connection_pool, Indexer,
+    IndexerConfig,
+};
 
 /// Spawns an indexer thread with provided Postgres DB url
 pub async fn start_test_indexer(
@@ -35,6 +38,20 @@ pub async fn start_test_indexer(
     Ok((store, handle))
 }
 
+/// Spawns an indexer thread writing to the SQLite database file at `config.db_url`
+pub async fn start_test_sqlite_indexer(
+    config: IndexerConfig,
+) -> Result<(SqliteIndexerStore, JoinHandle<Result<(), IndexerError>>), anyhow::Error> {
+    let pool = new_sqlite_connection_pool(&config.db_url)?;
+    run_sqlite_migrations(&mut get_sqlite_pool_connection(&pool)?, config.reset_db)?;
+    let store = SqliteIndexerStore::new(pool);
+
+    let registry = Registry::default();
+    let store_clone = store.clone();
+    let handle = tokio::spawn(async move { Indexer::start(&config, &registry, store_clone).await });
+    Ok((store, handle))
+}
+
 #[derive(Clone)]
 pub struct SuiTransactionBlockResponseBuilder<'a> {
     response: SuiTransactionBlockResponse,
@@ -27,9 +27,10 @@ use sui_types::storage::{DeleteKind, WriteKind};
 
 use crate::errors::IndexerError;
 use crate::types::CheckpointTransactionBlockResponse;
-use crate::PgPoolConnection;
+use crate::{PgPoolConnection, SqlitePoolConnection};
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
 
 /// Resets the database by reverting all migrations and reapplying them.
 ///
@@ -52,6 +53,22 @@ pub fn reset_database(conn: &mut PgPoolConnection, drop_all: bool) -> Result<(),
     Ok(())
 }
 
+/// Applies the pending migrations of the SQLite database, after reverting all of them first if
+/// `reset` is set.
+pub fn run_sqlite_migrations(
+    conn: &mut SqlitePoolConnection,
+    reset: bool,
+) -> Result<(), IndexerError> {
+    if reset {
+        info!("Resetting SQLite database ...");
+        conn.revert_all_migrations(SQLITE_MIGRATIONS)
+            .map_err(|e| IndexerError::SqliteMigrationError(e.to_string()))?;
+    }
+    conn.run_pending_migrations(SQLITE_MIGRATIONS)
+        .map_err(|e| IndexerError::SqliteMigrationError(e.to_string()))?;
+    Ok(())
+}
+
 pub fn drop_all_tables(conn: &mut PgConnection) -> Result<(), diesel::result::Error> {
     info!("Dropping all tables in the database");
     let table_names: Vec<String> = diesel::dsl::sql::<diesel::sql_types::Text>(
@@ -42,7 +42,7 @@ pub mod pg_integration_test {
     use sui_types::error::SuiObjectResponseError;
     use sui_types::gas_coin::GasCoin;
     use sui_types::messages::{ExecuteTransactionRequestType, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
-    use sui_types::object::ObjectFormatOptions;
+    use sui_types::object::{ObjectFormatOptions, Owner};
     use sui_types::query::TransactionFilter;
     use sui_types::utils::to_sender_signed_transaction;
     use test_utils::network::{TestCluster, TestClusterBuilder};
@@ -1265,6 +1265,72 @@ pub mod pg_integration_test {
         Ok(())
     }
 
+    #[tokio::test]
+    #[timeout(60000)]
+    async fn test_objects_at_checkpoint() -> Result<(), anyhow::Error> {
+        let (mut test_cluster, indexer_rpc_client, store, _handle) = start_test_cluster(None).await;
+        wait_until_next_checkpoint(&store).await;
+        let (tx_response, sender, recipient, gas_objects) =
+            execute_simple_transfer(&mut test_cluster, &indexer_rpc_client).await?;
+        let transferred_object = *gas_objects.first().unwrap();
+        wait_until_transaction_synced_in_checkpoint(
+            &store,
+            tx_response.digest.base58_encode().as_str(),
+        )
+        .await;
+        let checkpoint = indexer_rpc_client
+            .get_transaction_block(
+                tx_response.digest,
+                Some(SuiTransactionBlockResponseOptions::new()),
+            )
+            .await?
+            .checkpoint
+            .unwrap();
+        let options = Some(SuiObjectDataOptions::new().with_owner());
+
+        // The object is owned by the sender before the transfer, and by the recipient after.
+        let before = indexer_rpc_client
+            .get_object_at_checkpoint(transferred_object, (checkpoint - 1).into(), options.clone())
+            .await?
+            .into_object()?;
+        assert_eq!(before.owner, Some(Owner::AddressOwner(sender)));
+        let after = indexer_rpc_client
+            .get_object_at_checkpoint(transferred_object, checkpoint.into(), options.clone())
+            .await?
+            .into_object()?;
+        assert_eq!(after.owner, Some(Owner::AddressOwner(recipient)));
+        assert!(before.version < after.version);
+
+        let owned_by_recipient = |checkpoint: u64| {
+            let indexer_rpc_client = &indexer_rpc_client;
+            async move {
+                indexer_rpc_client
+                    .get_owned_objects_at_checkpoint(recipient, checkpoint.into(), None, None, None)
+                    .await
+                    .unwrap()
+                    .data
+                    .into_iter()
+                    .map(|object| object.into_object().unwrap().object_ref())
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert!(!owned_by_recipient(checkpoint - 1)
+            .await
+            .iter()
+            .any(|object_ref| object_ref.0 == transferred_object));
+        assert!(owned_by_recipient(checkpoint)
+            .await
+            .contains(&after.object_ref()));
+
+        // Checkpoints which are not indexed yet are rejected.
+        let latest = store.get_latest_checkpoint_sequence_number().await? as u64;
+        assert!(indexer_rpc_client
+            .get_object_at_checkpoint(transferred_object, (latest + 1000).into(), None)
+            .await
+            .is_err());
+        Ok(())
+    }
+
     async fn start_test_cluster(
         epoch_duration_ms: Option<u64>,
     ) -> (
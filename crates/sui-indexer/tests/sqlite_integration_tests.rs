@@ -0,0 +1,275 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// integration test with the embedded SQLite database, which needs no database server
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use ntest::timeout;
+use tempfile::TempDir;
+
+use sui_config::utils::get_available_port;
+use sui_config::SUI_KEYSTORE_FILENAME;
+use sui_indexer::store::{IndexerStore, SqliteIndexerStore};
+use sui_indexer::test_utils::start_test_sqlite_indexer;
+use sui_indexer::{DbBackend, IndexerConfig};
+use sui_json_rpc::api::{
+    CoinReadApiClient, IndexerApiClient, ReadApiClient, TransactionBuilderClient, WriteApiClient,
+};
+use sui_json_rpc_types::{
+    CheckpointId, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, TransactionBlockBytes,
+};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{ExecuteTransactionRequestType, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
+use sui_types::object::Owner;
+use sui_types::utils::to_sender_signed_transaction;
+use test_utils::network::{TestCluster, TestClusterBuilder};
+
+const WAIT_UNTIL_TIME_LIMIT: u64 = 60;
+
+#[tokio::test]
+#[timeout(60000)]
+async fn test_sqlite_checkpoints() -> Result<(), anyhow::Error> {
+    let (test_cluster, indexer_rpc_client, store, _db_dir) = start_test_cluster().await;
+    wait_for_checkpoint(&store, 1).await;
+
+    let latest = store.get_latest_checkpoint_sequence_number().await? as u64;
+    for seq in 0..=latest {
+        let checkpoint = store.get_checkpoint(seq.into()).await?;
+        let fullnode_checkpoint = test_cluster.rpc_client().get_checkpoint(seq.into()).await?;
+        assert_eq!(checkpoint.sequence_number, seq);
+        assert_eq!(checkpoint.digest, fullnode_checkpoint.digest);
+        assert_eq!(
+            checkpoint.previous_digest,
+            fullnode_checkpoint.previous_digest
+        );
+        assert_eq!(checkpoint.epoch, fullnode_checkpoint.epoch);
+        assert_eq!(checkpoint.timestamp_ms, fullnode_checkpoint.timestamp_ms);
+        assert_eq!(
+            checkpoint.network_total_transactions,
+            fullnode_checkpoint.network_total_transactions
+        );
+        assert_eq!(checkpoint.transactions, fullnode_checkpoint.transactions);
+        assert_eq!(
+            checkpoint.validator_signature,
+            fullnode_checkpoint.validator_signature
+        );
+
+        // Checkpoints can also be read by digest, through the store or the RPC server.
+        let by_digest = store
+            .get_checkpoint(CheckpointId::Digest(checkpoint.digest))
+            .await?;
+        assert_eq!(by_digest, checkpoint);
+        assert_eq!(
+            store
+                .get_checkpoint_sequence_number(checkpoint.digest)
+                .await?,
+            seq
+        );
+        let from_rpc = indexer_rpc_client.get_checkpoint(seq.into()).await?;
+        assert_eq!(from_rpc, checkpoint);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[timeout(60000)]
+async fn test_sqlite_transactions() -> Result<(), anyhow::Error> {
+    let (test_cluster, indexer_rpc_client, store, _db_dir) = start_test_cluster().await;
+    wait_for_checkpoint(&store, 1).await;
+    let (tx_response, sender, _, _) = execute_simple_transfer(&test_cluster).await?;
+    let digest = tx_response.digest.base58_encode();
+    wait_until_transaction_synced_in_checkpoint(&store, &digest).await;
+
+    let transaction = store.get_transaction_by_digest(&digest).await?;
+    assert_eq!(transaction.transaction_digest, digest);
+    assert_eq!(transaction.sender, sender.to_string());
+    let checkpoint = transaction.checkpoint_sequence_number.unwrap() as u64;
+    assert!(store
+        .get_checkpoint(checkpoint.into())
+        .await?
+        .transactions
+        .contains(&tx_response.digest));
+
+    // The transaction read back from the database is the one executed by the fullnode.
+    let options = SuiTransactionBlockResponseOptions::new()
+        .with_input()
+        .with_effects();
+    let fullnode_response = test_cluster
+        .rpc_client()
+        .get_transaction_block(tx_response.digest, Some(options.clone()))
+        .await?;
+    let indexer_response = indexer_rpc_client
+        .get_transaction_block(tx_response.digest, Some(options))
+        .await?;
+    assert_eq!(indexer_response.transaction, fullnode_response.transaction);
+    assert_eq!(indexer_response.effects, fullnode_response.effects);
+    assert_eq!(indexer_response.checkpoint, Some(checkpoint));
+
+    let multi_get = store
+        .multi_get_transactions_by_digests(&[digest.clone()])
+        .await?;
+    assert_eq!(multi_get.len(), 1);
+    assert_eq!(multi_get[0].transaction_digest, digest);
+    Ok(())
+}
+
+#[tokio::test]
+#[timeout(60000)]
+async fn test_sqlite_objects() -> Result<(), anyhow::Error> {
+    let (test_cluster, indexer_rpc_client, store, _db_dir) = start_test_cluster().await;
+    wait_for_checkpoint(&store, 1).await;
+    let (tx_response, sender, recipient, gas_objects) =
+        execute_simple_transfer(&test_cluster).await?;
+    let transferred_object = gas_objects[0];
+    wait_until_transaction_synced_in_checkpoint(&store, &tx_response.digest.base58_encode()).await;
+
+    // The latest version of the object is the one the fullnode has.
+    let object = store
+        .get_object(transferred_object, None)
+        .await?
+        .into_object()?;
+    let fullnode_object = test_cluster
+        .rpc_client()
+        .get_object(
+            transferred_object,
+            Some(SuiObjectDataOptions::new().with_owner()),
+        )
+        .await?
+        .into_object()?;
+    assert_eq!(
+        object.compute_object_reference(),
+        fullnode_object.object_ref()
+    );
+    assert_eq!(object.owner, Owner::AddressOwner(recipient));
+
+    // Past versions are kept in the objects history.
+    let checkpoint = store
+        .get_transaction_by_digest(&tx_response.digest.base58_encode())
+        .await?
+        .checkpoint_sequence_number
+        .unwrap() as u64;
+    let before = store
+        .get_object_at_checkpoint(transferred_object, checkpoint - 1)
+        .await?
+        .into_object()?;
+    assert_eq!(before.owner, Owner::AddressOwner(sender));
+    assert!(before.version() < object.version());
+    let by_version = store
+        .get_object(transferred_object, Some(before.version()))
+        .await?
+        .into_object()?;
+    assert_eq!(
+        by_version.compute_object_reference(),
+        before.compute_object_reference()
+    );
+
+    let owned = indexer_rpc_client
+        .get_owned_objects(recipient, None, None, None)
+        .await?
+        .data
+        .into_iter()
+        .map(|object| object.into_object().unwrap().object_id)
+        .collect::<Vec<_>>();
+    assert!(owned.contains(&transferred_object));
+    Ok(())
+}
+
+async fn start_test_cluster() -> (TestCluster, HttpClient, SqliteIndexerStore, TempDir) {
+    let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+    let db_dir = tempfile::tempdir().unwrap();
+    let rpc_server_port = get_available_port("127.0.0.1");
+    let config = IndexerConfig {
+        db_url: db_dir.path().join("indexer.db").display().to_string(),
+        db_backend: DbBackend::Sqlite,
+        rpc_client_url: test_cluster.rpc_url().to_string(),
+        rpc_server_url: "127.0.0.1".to_string(),
+        rpc_server_port,
+        migrated_methods: IndexerConfig::all_implemented_methods(),
+        reset_db: true,
+        ..Default::default()
+    };
+    let http_client = HttpClientBuilder::default()
+        .build(format!("http://127.0.0.1:{rpc_server_port}"))
+        .unwrap();
+    let (store, _handle) = start_test_sqlite_indexer(config).await.unwrap();
+    (test_cluster, http_client, store, db_dir)
+}
+
+/// Transfer a gas coin of the first account to the last one through the fullnode, returning the
+/// response along with the sender, the recipient and the gas coins of the sender.
+async fn execute_simple_transfer(
+    test_cluster: &TestCluster,
+) -> Result<
+    (
+        SuiTransactionBlockResponse,
+        SuiAddress,
+        SuiAddress,
+        Vec<ObjectID>,
+    ),
+    anyhow::Error,
+> {
+    let sender = *test_cluster.accounts.first().unwrap();
+    let recipient = *test_cluster.accounts.last().unwrap();
+    let fullnode_rpc_client = test_cluster.rpc_client();
+    let gas_objects: Vec<ObjectID> = fullnode_rpc_client
+        .get_coins(sender, None, None, None)
+        .await?
+        .data
+        .into_iter()
+        .map(|coin| coin.coin_object_id)
+        .collect();
+
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let transaction_bytes: TransactionBlockBytes = fullnode_rpc_client
+        .transfer_object(
+            sender,
+            gas_objects[0],
+            Some(*gas_objects.last().unwrap()),
+            (rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER).into(),
+            recipient,
+        )
+        .await?;
+    let keystore_path = test_cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(&sender)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let tx_response = fullnode_rpc_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(tx_response.effects.as_ref().unwrap().status().is_ok());
+    Ok((tx_response, sender, recipient, gas_objects))
+}
+
+async fn wait_for_checkpoint(store: &SqliteIndexerStore, target: i64) {
+    let since = std::time::Instant::now();
+    loop {
+        if matches!(store.get_latest_checkpoint_sequence_number().await, Ok(cp) if cp >= target) {
+            break;
+        }
+        if since.elapsed().as_secs() > WAIT_UNTIL_TIME_LIMIT {
+            panic!("wait_for_checkpoint timed out!");
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
+
+async fn wait_until_transaction_synced_in_checkpoint(store: &SqliteIndexerStore, tx_digest: &str) {
+    let since = std::time::Instant::now();
+    loop {
+        if let Ok(tx) = store.get_transaction_by_digest(tx_digest).await {
+            if tx.checkpoint_sequence_number.is_some() {
+                break;
+            }
+        }
+        if since.elapsed().as_secs() > WAIT_UNTIL_TIME_LIMIT {
+            panic!("wait_until_transaction_synced_in_checkpoint timed out!");
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
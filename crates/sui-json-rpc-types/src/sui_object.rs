@@ -439,6 +439,24 @@ impl TryFrom<(ObjectRead, SuiObjectDataOptions)> for SuiObjectResponse {
     }
 }
 
+impl TryFrom<(ObjectRead, SuiObjectDataOptions)> for SuiPastObjectResponse {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        (object_read, options): (ObjectRead, SuiObjectDataOptions),
+    ) -> Result<Self, Self::Error> {
+        match object_read {
+            ObjectRead::NotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
+            ObjectRead::Exists(object_ref, o, layout) => Ok(SuiPastObjectResponse::VersionFound(
+                (object_ref, o, layout, options).try_into()?,
+            )),
+            ObjectRead::Deleted(object_ref) => {
+                Ok(SuiPastObjectResponse::ObjectDeleted(object_ref.into()))
+            }
+        }
+    }
+}
+
 impl TryFrom<(ObjectInfo, SuiObjectDataOptions)> for SuiObjectResponse {
     type Error = anyhow::Error;
 
@@ -1041,6 +1059,7 @@ pub struct SuiMovePackage {
 
 pub type QueryObjectsPage = Page<SuiObjectResponse, CheckpointedObjectID>;
 pub type ObjectsPage = Page<SuiObjectResponse, ObjectID>;
+pub type PastObjectsPage = Page<SuiPastObjectResponse, ObjectID>;
 
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Eq, PartialEq)]
@@ -41,7 +41,7 @@ use sui_types::sui_serde::{
 
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
-use crate::{Page, SuiEvent, SuiMovePackage, SuiObjectRef};
+use crate::{Filter, Page, SuiEvent, SuiMovePackage, SuiObjectRef};
 
 // similar to EpochId of sui-types but BigInt
 pub type SuiEpochId = BigInt<u64>;
@@ -650,6 +650,67 @@ impl Display for SuiTransactionBlockEffects {
     }
 }
 
+/// Transaction effects paired with the transaction input they were produced from, used to
+/// match `TransactionFilter`s against finalized transactions in subscriptions.
+#[derive(Clone, Debug)]
+pub struct EffectsWithInput {
+    pub effects: SuiTransactionBlockEffects,
+    pub input: TransactionData,
+}
+
+impl From<EffectsWithInput> for SuiTransactionBlockEffects {
+    fn from(e: EffectsWithInput) -> Self {
+        e.effects
+    }
+}
+
+impl Filter<EffectsWithInput> for TransactionFilter {
+    fn matches(&self, item: &EffectsWithInput) -> bool {
+        match self {
+            TransactionFilter::InputObject(o) => {
+                let Ok(input_objects) = item.input.input_objects() else {
+                    return false;
+                };
+                input_objects.iter().any(|object| object.object_id() == *o)
+            }
+            TransactionFilter::ChangedObject(o) => item
+                .effects
+                .all_changed_objects()
+                .iter()
+                .any(|(oref, _)| &oref.reference.object_id == o),
+            TransactionFilter::FromAddress(a) => &item.input.sender() == a,
+            TransactionFilter::ToAddress(a) => {
+                item.effects.all_changed_objects().iter().any(
+                    |(oref, _)| matches!(oref.owner, Owner::AddressOwner(owner) if owner == *a),
+                )
+            }
+            TransactionFilter::FromAndToAddress { from, to } => {
+                Self::FromAddress(*from).matches(item) && Self::ToAddress(*to).matches(item)
+            }
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => item.input.move_calls().into_iter().any(|(p, m, f)| {
+                p == package
+                    && module.as_ref().map_or(true, |module| module == m.as_str())
+                    && function
+                        .as_ref()
+                        .map_or(true, |function| function == f.as_str())
+            }),
+            TransactionFilter::TransactionKind(kind) => item.input.kind().name() == kind,
+            TransactionFilter::And(f1, f2) => f1.matches(item) && f2.matches(item),
+            TransactionFilter::Or(f1, f2) => f1.matches(item) || f2.matches(item),
+            TransactionFilter::Not(f) => !f.matches(item),
+            // Checkpoint and timestamp are not known at the time the transaction is streamed,
+            // such subscriptions are rejected by the rpc layer.
+            TransactionFilter::Checkpoint(_)
+            | TransactionFilter::CheckpointRange { .. }
+            | TransactionFilter::TimeRange { .. } => false,
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DryRunTransactionBlockResponse {
@@ -5,14 +5,17 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, SuiEvent, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, PastObjectsPage, SuiEvent,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffects,
+    SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -35,6 +38,23 @@ pub trait IndexerApi {
         limit: Option<usize>,
     ) -> RpcResult<ObjectsPage>;
 
+    /// Return the list of objects owned by an address as of a checkpoint, each at the version
+    /// it had then. Fails for checkpoints the node did not index.
+    #[method(name = "getOwnedObjectsAtCheckpoint")]
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// the sequence number of the checkpoint
+        checkpoint: BigInt<u64>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<ObjectID>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT_OBJECTS] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<PastObjectsPage>;
+
     /// Return list of transactions for a specified query criteria.
     #[method(name = "queryTransactionBlocks", blocking)]
     fn query_transaction_blocks(
@@ -71,6 +91,14 @@ pub trait IndexerApi {
         filter: EventFilter,
     );
 
+    /// Subscribe to a stream of Sui transaction effects
+    #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockEffects)]
+    fn subscribe_transaction(
+        &self,
+        /// the filter criteria of the transaction stream. Filtering by checkpoint or time is not supported.
+        filter: TransactionFilter,
+    );
+
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields", blocking)]
     fn get_dynamic_fields(
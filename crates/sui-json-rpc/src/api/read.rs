@@ -86,6 +86,21 @@ pub trait ReadApi {
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<Vec<SuiPastObjectResponse>>;
 
+    /// Return the latest version of an object as of a checkpoint, i.e. the version written by the
+    /// last transaction up to and including that checkpoint which changed the object.
+    /// Like past objects, the version may have been pruned from the node. Fails for checkpoints
+    /// the node did not index, e.g. if it did not execute every checkpoint from genesis.
+    #[method(name = "getObjectAtCheckpoint")]
+    async fn get_object_at_checkpoint(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// the sequence number of the checkpoint
+        checkpoint: BigInt<u64>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse>;
+
     /// Return a checkpoint
     #[method(name = "getCheckpoint")]
     async fn get_checkpoint(
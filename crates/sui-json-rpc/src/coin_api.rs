@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -38,22 +37,6 @@ impl CoinReadApi {
         Self { state }
     }
 
-    fn multi_get_coin_objects(&self, coins: &[ObjectRef]) -> Result<Vec<Object>, Error> {
-        Ok(self
-            .state
-            .database
-            .multi_get_object_by_key(&coins.iter().map(ObjectKey::from).collect::<Vec<_>>())?
-            .into_iter()
-            .zip(coins)
-            .map(|(o, (id, version, _digest))| {
-                o.ok_or(UserInputError::ObjectNotFound {
-                    object_id: *id,
-                    version: Some(*version),
-                })
-            })
-            .collect::<Result<Vec<_>, UserInputError>>()?)
-    }
-
     /// Fetch all of the objects in `coins`. It's the caller's responsibility
     /// to ensure that every ObjRef in `coins` is in fact a coin by using `Authority::get_owner_coin_iterator`,
     /// and that every coin is of type `coin_type_tag`.
@@ -246,58 +229,29 @@ impl CoinReadApiServer for CoinReadApi {
             None => GAS::type_(),
         }));
 
-        // TODO: Add index to improve performance?
-        let coins = self.multi_get_coin_objects(
-            &self
-                .get_owner_coin_iterator(owner, Some(&coin_type))?
-                .collect::<Vec<_>>(),
-        )?;
-        let mut total_balance = 0u128;
-        let mut coin_object_count = 0;
-
-        for coin_obj in coins {
-            // unwraps safe because get_owner_coin_iterator can only return coin objects
-            let coin: Coin =
-                bcs::from_bytes(coin_obj.data.try_as_move().unwrap().contents()).unwrap();
-            total_balance += coin.balance.value() as u128;
-            coin_object_count += 1;
-        }
-
+        let balance = self.state.get_coin_balance(owner, coin_type.clone())?;
         Ok(Balance {
             coin_type: coin_type.to_string(),
-            coin_object_count,
-            total_balance,
+            coin_object_count: balance.coin_count as usize,
+            total_balance: balance.total_balance,
             // note: LockedCoin is deprecated
             locked_balance: Default::default(),
         })
     }
 
     fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
-        let mut balances: HashMap<TypeTag, Balance> = HashMap::new();
-        // TODO: Add index to improve performance?
-        let coin_objs = self.multi_get_coin_objects(
-            &self
-                .get_owner_coin_iterator(owner, None)?
-                .collect::<Vec<_>>(),
-        )?;
-        for coin_obj in coin_objs {
-            // unwrap safe because get_owner_coin_iterator can only return coin objects
-            let move_obj = coin_obj.data.try_as_move().unwrap();
-            // unwrap safe because each coin object has one type param
-            let coin_type = move_obj.type_().type_params().first().unwrap().clone();
-            let coin: Coin = bcs::from_bytes(move_obj.contents()).unwrap();
-
-            let balance = balances.entry(coin_type.clone()).or_insert(Balance {
+        Ok(self
+            .state
+            .get_coin_balances(owner)?
+            .into_iter()
+            .map(|(coin_type, balance)| Balance {
                 coin_type: coin_type.to_string(),
-                coin_object_count: 0,
-                total_balance: 0,
+                coin_object_count: balance.coin_count as usize,
+                total_balance: balance.total_balance,
                 // note: LockedCoin is deprecated
                 locked_balance: Default::default(),
-            });
-            balance.total_balance += coin.balance.value() as u128;
-            balance.coin_object_count += 1;
-        }
-        Ok(balances.into_values().collect())
+            })
+            .collect())
     }
 
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<SuiCoinMetadata> {
@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use futures::Stream;
 use jsonrpsee::core::error::SubscriptionClosed;
 use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 use serde::Serialize;
@@ -16,8 +17,8 @@ use tracing::{debug, warn};
 use mysten_metrics::spawn_monitored_task;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataOptions,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponse,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, PastObjectsPage,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponse,
     SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_open_rpc::Module;
@@ -25,10 +26,13 @@ use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 use crate::api::{
     cap_page_limit, validate_limit, IndexerApiServer, ReadApiServer, QUERY_MAX_RESULT_LIMIT_OBJECTS,
 };
+use crate::read_api::ensure_checkpoint_executed;
 use crate::SuiRpcModule;
 
 pub fn spawn_subscription<S, T>(mut sink: SubscriptionSink, rx: S)
@@ -104,6 +108,45 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         })
     }
 
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        checkpoint: BigInt<u64>,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<PastObjectsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
+        let checkpoint = *checkpoint;
+        ensure_checkpoint_executed(&self.state, checkpoint)?;
+        let mut objects = self
+            .state
+            .get_owner_objects_at_checkpoint(address, checkpoint, cursor, limit + 1)
+            .map_err(|e| anyhow!("{e}"))?;
+
+        // objects here are of size (limit + 1), where the last one is the cursor for the next page
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+        let next_cursor = objects
+            .last()
+            .map_or(cursor, |(object_id, _, _)| Some(*object_id));
+
+        let mut data = Vec::with_capacity(objects.len());
+        for (object_id, version, _) in objects {
+            data.push(
+                self.read_api
+                    .try_get_past_object(object_id, version, options.clone())
+                    .await?,
+            );
+        }
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
     fn query_transaction_blocks(
         &self,
         query: SuiTransactionBlockResponseQuery,
@@ -174,7 +217,30 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     }
 
     fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.state.event_handler.subscribe(filter));
+        spawn_subscription(
+            sink,
+            self.state.subscription_handler.subscribe_events(filter),
+        );
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        mut sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        if filter.requires_checkpoint() {
+            sink.reject(CallError::InvalidParams(anyhow!(
+                "Checkpoint and time range filters are not supported for transaction subscription"
+            )))?;
+            return Ok(());
+        }
+        spawn_subscription(
+            sink,
+            self.state
+                .subscription_handler
+                .subscribe_transactions(filter),
+        );
         Ok(())
     }
 
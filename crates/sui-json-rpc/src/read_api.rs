@@ -34,7 +34,7 @@ use sui_types::collection_types::VecMap;
 use sui_types::crypto::default_hash;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::display::DisplayVersionUpdatedEvent;
-use sui_types::error::{SuiObjectResponseError, UserInputError};
+use sui_types::error::{SuiError, SuiObjectResponseError, UserInputError};
 use sui_types::messages::TransactionDataAPI;
 use sui_types::messages::{
     TransactionData, TransactionEffects, TransactionEffectsAPI, TransactionEvents,
@@ -100,9 +100,7 @@ impl ReadApi {
             CheckpointId::SequenceNumber(seq) => {
                 let verified_summary =
                     self.state.get_verified_checkpoint_by_sequence_number(seq)?;
-                let content = self
-                    .state
-                    .get_checkpoint_contents(verified_summary.content_digest)?;
+                let content = self.state.get_checkpoint_contents_by_sequence_number(seq)?;
                 let signature = verified_summary.auth_sig().signature.clone();
                 (
                     verified_summary.into_inner().into_data(),
@@ -115,9 +113,9 @@ impl ReadApi {
                 let verified_summary = self
                     .state
                     .get_verified_checkpoint_summary_by_digest(digest)?;
-                let content = self
-                    .state
-                    .get_checkpoint_contents(verified_summary.content_digest)?;
+                let content = self.state.get_checkpoint_contents_by_sequence_number(
+                    *verified_summary.sequence_number(),
+                )?;
                 let signature = verified_summary.auth_sig().signature.clone();
                 (
                     verified_summary.into_inner().into_data(),
@@ -129,6 +127,43 @@ impl ReadApi {
         })
     }
 
+    fn past_object_response(
+        &self,
+        past_read: PastObjectRead,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let options = options.unwrap_or_default();
+        match past_read {
+            PastObjectRead::ObjectNotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
+            PastObjectRead::VersionFound(object_ref, o, layout) => {
+                let display_fields = if options.show_display {
+                    // TODO (jian): api breaking change to also modify past objects.
+                    Some(get_display_fields(self, &o, &layout)?)
+                } else {
+                    None
+                };
+                Ok(SuiPastObjectResponse::VersionFound(
+                    (object_ref, o, layout, options, display_fields).try_into()?,
+                ))
+            }
+            PastObjectRead::ObjectDeleted(oref) => {
+                Ok(SuiPastObjectResponse::ObjectDeleted(oref.into()))
+            }
+            PastObjectRead::VersionNotFound(id, seq_num) => {
+                Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num))
+            }
+            PastObjectRead::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            } => Ok(SuiPastObjectResponse::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            }),
+        }
+    }
+
     async fn multi_get_transaction_blocks_internal(
         &self,
         digests: Vec<TransactionDigest>,
@@ -195,6 +230,24 @@ impl ReadApi {
             cache_entry.checkpoint_seq = seq.map(|(_, seq)| seq);
         }
 
+        // Executed transactions missing from the store had their history pruned.
+        let lowest_available_checkpoint = self.state.get_lowest_available_checkpoint()?;
+        for (digest, cache_entry) in temp_response.iter_mut() {
+            let missing = (opts.require_input() && cache_entry.transaction.is_none())
+                || (opts.require_effects() && cache_entry.effects.is_none());
+            if missing
+                && matches!(cache_entry.checkpoint_seq, Some(seq) if seq < lowest_available_checkpoint)
+            {
+                cache_entry.errors.push(
+                    SuiError::TransactionPruned {
+                        digest: **digest,
+                        lowest_available_checkpoint,
+                    }
+                    .to_string(),
+                );
+            }
+        }
+
         let unique_checkpoint_numbers = temp_response
             .values()
             .filter_map(|cache_entry| cache_entry.checkpoint_seq.map(<u64>::from))
@@ -468,36 +521,26 @@ impl ReadApiServer for ReadApi {
                 error!("Failed to call try_get_past_object for object: {object_id:?} version: {version:?} with error: {e:?}");
                 anyhow!("{e}")
             })?;
-        let options = options.unwrap_or_default();
-        match past_read {
-            PastObjectRead::ObjectNotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
-            PastObjectRead::VersionFound(object_ref, o, layout) => {
-                let display_fields = if options.show_display {
-                    // TODO (jian): api breaking change to also modify past objects.
-                    Some(get_display_fields(self, &o, &layout)?)
-                } else {
-                    None
-                };
-                Ok(SuiPastObjectResponse::VersionFound(
-                    (object_ref, o, layout, options, display_fields).try_into()?,
-                ))
-            }
-            PastObjectRead::ObjectDeleted(oref) => {
-                Ok(SuiPastObjectResponse::ObjectDeleted(oref.into()))
-            }
-            PastObjectRead::VersionNotFound(id, seq_num) => {
-                Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num))
-            }
-            PastObjectRead::VersionTooHigh {
-                object_id,
-                asked_version,
-                latest_version,
-            } => Ok(SuiPastObjectResponse::VersionTooHigh {
-                object_id,
-                asked_version,
-                latest_version,
-            }),
-        }
+        self.past_object_response(past_read, options)
+    }
+
+    async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: BigInt<u64>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let checkpoint = *checkpoint;
+        ensure_checkpoint_executed(&self.state, checkpoint)?;
+        let past_read = self
+            .state
+            .get_object_read_at_checkpoint(&object_id, checkpoint)
+            .await
+            .map_err(|e| {
+                error!("Failed to call get_object_at_checkpoint for object: {object_id:?} checkpoint: {checkpoint} with error: {e:?}");
+                anyhow!("{e}")
+            })?;
+        self.past_object_response(past_read, options)
     }
 
     fn try_multi_get_past_objects(
@@ -758,6 +801,21 @@ impl SuiRpcModule for ReadApi {
     }
 }
 
+/// Reads at a checkpoint are only served once the checkpoint is executed, and so indexed.
+pub(crate) fn ensure_checkpoint_executed(
+    state: &AuthorityState,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<(), Error> {
+    let latest = state.get_latest_checkpoint_sequence_number()?;
+    if checkpoint > latest {
+        return Err(anyhow!(
+            "Checkpoint {checkpoint} is not executed yet, the latest executed checkpoint is {latest}"
+        )
+        .into());
+    }
+    Ok(())
+}
+
 fn to_sui_transaction_events(
     fullnode_api: &ReadApi,
     tx_digest: TransactionDigest,
@@ -415,6 +415,64 @@ async fn test_get_balance() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_balance_after_transfer() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let sender = cluster.accounts[0];
+    let recipient = cluster.accounts[1];
+
+    let coins: CoinPage = http_client.get_coins(sender, None, None, None).await?;
+    let coin = coins.data[0].coin_object_id;
+    let gas = coins.data[1].coin_object_id;
+
+    let transaction_bytes: TransactionBlockBytes = http_client
+        .transfer_object(sender, coin, Some(gas), 10_000.into(), recipient)
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(&sender)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let tx_response: SuiTransactionBlockResponse = http_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let gas_used = tx_response
+        .effects
+        .unwrap()
+        .gas_cost_summary()
+        .net_gas_usage();
+
+    let total_balance = DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT as i128 * DEFAULT_GAS_AMOUNT as i128;
+    let result: Balance = http_client.get_balance(sender, None).await?;
+    assert_eq!(
+        DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT - 1,
+        result.coin_object_count
+    );
+    assert_eq!(
+        total_balance - DEFAULT_GAS_AMOUNT as i128 - gas_used as i128,
+        result.total_balance as i128
+    );
+
+    let result: Vec<Balance> = http_client.get_all_balances(recipient).await?;
+    assert_eq!(1, result.len());
+    assert_eq!("0x2::sui::SUI", result[0].coin_type);
+    assert_eq!(
+        DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT + 1,
+        result[0].coin_object_count
+    );
+    assert_eq!(
+        total_balance + DEFAULT_GAS_AMOUNT as i128,
+        result[0].total_balance as i128
+    );
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_metadata() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
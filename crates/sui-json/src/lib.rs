@@ -771,20 +771,6 @@ fn resolve_call_arg(
     }
 }
 
-fn resolve_call_args(
-    view: &BinaryIndexedView,
-    type_args: &[TypeTag],
-    json_args: &[SuiJsonValue],
-    parameter_types: &[SignatureToken],
-) -> Result<Vec<ResolvedCallArg>, anyhow::Error> {
-    json_args
-        .iter()
-        .zip(parameter_types)
-        .enumerate()
-        .map(|(idx, (arg, param))| resolve_call_arg(view, type_args, idx, arg, param))
-        .collect()
-}
-
 /// Resolve a the JSON args of a function into the expected formats to make them usable by Move call
 /// This is because we have special types which we need to specify in other formats
 pub fn resolve_move_function_args(
@@ -795,6 +781,29 @@ pub fn resolve_move_function_args(
     combined_args_json: Vec<SuiJsonValue>,
     allow_arbitrary_function_call: bool,
 ) -> Result<Vec<(ResolvedCallArg, SignatureToken)>, anyhow::Error> {
+    Ok(resolve_partial_move_function_args(
+        package,
+        module_ident,
+        function,
+        type_args,
+        combined_args_json.into_iter().map(Some).collect(),
+        allow_arbitrary_function_call,
+    )?
+    .into_iter()
+    .flatten()
+    .collect())
+}
+
+/// Like [resolve_move_function_args], but args passed as `None` are left unresolved. This is used
+/// by programmable transactions, where some args are provided by the results of earlier commands.
+pub fn resolve_partial_move_function_args(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    combined_args_json: Vec<Option<SuiJsonValue>>,
+    allow_arbitrary_function_call: bool,
+) -> Result<Vec<Option<(ResolvedCallArg, SignatureToken)>>, anyhow::Error> {
     // Extract the expected function signature
     let module = package.deserialize_module(&module_ident, VERSION_MAX)?;
     let function_str = function.as_ident_str();
@@ -837,13 +846,21 @@ pub fn resolve_move_function_args(
         );
     }
     // Check that the args are valid and convert to the correct format
-    let call_args = resolve_call_args(&view, type_args, &combined_args_json, parameters)?;
-    let tupled_call_args = call_args
-        .into_iter()
-        .zip(parameters.iter())
-        .map(|(arg, expected_type)| (arg, expected_type.clone()))
-        .collect::<Vec<_>>();
-    Ok(tupled_call_args)
+    combined_args_json
+        .iter()
+        .zip(parameters)
+        .enumerate()
+        .map(|(idx, (arg, param))| {
+            arg.as_ref()
+                .map(|arg| {
+                    Ok((
+                        resolve_call_arg(&view, type_args, idx, arg, param)?,
+                        param.clone(),
+                    ))
+                })
+                .transpose()
+        })
+        .collect()
 }
 
 fn convert_string_to_u256(s: &str) -> Result<U256, anyhow::Error> {
@@ -25,7 +25,7 @@ use sui_types::object::Object;
 use sui_types::MOVE_STDLIB_ADDRESS;
 
 use super::{check_valid_homogeneous, HEX_PREFIX};
-use super::{resolve_move_function_args, SuiJsonValue};
+use super::{resolve_move_function_args, resolve_partial_move_function_args, SuiJsonValue};
 
 // Negative test cases
 #[test]
@@ -635,6 +635,37 @@ fn test_basic_args_linter_top_level() {
         ResolvedCallArg::Pure(bcs::to_bytes(&AccountAddress::from(address)).unwrap())
     );
 
+    // Test with the object arg provided by an earlier command of a programmable transaction
+    let args = resolve_partial_move_function_args(
+        framework_pkg,
+        Identifier::new("object_basics").unwrap(),
+        Identifier::new("transfer").unwrap(),
+        &[],
+        vec![
+            None,
+            Some(SuiJsonValue::new(json!(format!("{address}"))).unwrap()),
+        ],
+        /* allow_arbitrary_function_call */ false,
+    )
+    .unwrap();
+
+    assert!(args[0].is_none());
+    assert_eq!(
+        args[1].as_ref().unwrap().0,
+        ResolvedCallArg::Pure(bcs::to_bytes(&AccountAddress::from(address)).unwrap())
+    );
+
+    // Arity is still checked for unresolved args
+    assert!(resolve_partial_move_function_args(
+        framework_pkg,
+        Identifier::new("object_basics").unwrap(),
+        Identifier::new("transfer").unwrap(),
+        &[],
+        vec![None],
+        /* allow_arbitrary_function_call */ false,
+    )
+    .is_err());
+
     // Test with object vector  args
     let path = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("../sui-core/src/unit_tests/data/entry_point_vector");
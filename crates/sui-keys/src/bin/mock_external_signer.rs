@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A mock external signer holding its keys in a plaintext keystore file, for testing
+//! `ExternalKeystore` and as a starting point for real signers.
+//!
+//! Usage: `mock_external_signer <keystore path> [--socket <socket path>]`. Requests are served
+//! over stdin and stdout, or over a Unix socket when `--socket` is given.
+
+use std::io::{stdin, stdout};
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use sui_keys::external_signer::serve;
+use sui_keys::keystore::FileBasedKeystore;
+
+fn main() -> Result<(), anyhow::Error> {
+    let mut args = std::env::args().skip(1);
+    let keystore_path =
+        PathBuf::from(args.next().ok_or_else(|| {
+            anyhow!("Usage: mock_external_signer <keystore path> [--socket <path>]")
+        })?);
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+    match (args.next().as_deref(), args.next()) {
+        (None, _) => serve(&keystore, stdin().lock(), stdout().lock()),
+        (Some("--socket"), Some(socket_path)) => serve_socket(&keystore, socket_path.into()),
+        _ => Err(anyhow!("Unexpected arguments")),
+    }
+}
+
+#[cfg(unix)]
+fn serve_socket(keystore: &FileBasedKeystore, path: PathBuf) -> Result<(), anyhow::Error> {
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let reader = std::io::BufReader::new(stream.try_clone()?);
+        serve(keystore, reader, stream)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_keystore: &FileBasedKeystore, _path: PathBuf) -> Result<(), anyhow::Error> {
+    Err(anyhow!("Unix sockets are not supported on this platform"))
+}
@@ -0,0 +1,269 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protocol between [crate::keystore::ExternalKeystore] and a signer running in another process,
+//! e.g. a bridge to an HSM, a cloud KMS or a hardware wallet. Private keys never leave the
+//! signer.
+//!
+//! Every message is a single line of JSON. The keystore sends a request and waits for exactly
+//! one response, either over the stdin and stdout of a signer it spawns, or over a Unix socket
+//! the signer listens on, with one request per connection.
+//!
+//! Requests:
+//! - `{"method":"list_keys"}` answered with `{"keys":["<flag || pk>", ...]}`.
+//! - `{"method":"sign","params":{"address":"0x...","intent_message":"<bytes>"}}` answered with
+//!   `{"signature":"<flag || sig || pk>"}`, where the signature is over the Blake2b256 digest of
+//!   `intent_message`, the BCS bytes of an `IntentMessage`. The signer can decode the intent from
+//!   the first three bytes to decide what it agrees to sign.
+//!
+//! Keys, messages and signatures are Base64 encoded. Any request can be answered with
+//! `{"error":"<reason>"}`. See [serve] for a reference signer.
+
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::ToFromBytes;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, PublicKey, Signature};
+
+use crate::keystore::AccountKeystore;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    ListKeys,
+    Sign {
+        address: SuiAddress,
+        intent_message: String,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Keys(Vec<String>),
+    Signature(String),
+    Error(String),
+}
+
+/// How to reach an external signer, as configured in the `keystore` section of the client config.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerTransport {
+    /// Spawn `program` on first use and talk to it over its stdin and stdout. Its stderr is
+    /// inherited, so the signer can prompt the user there.
+    Command {
+        program: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Connect to a signer listening on a Unix socket.
+    Socket { path: PathBuf },
+}
+
+impl Display for SignerTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerTransport::Command { program, args } => {
+                write!(f, "{}", program.display())?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                Ok(())
+            }
+            SignerTransport::Socket { path } => write!(f, "socket {}", path.display()),
+        }
+    }
+}
+
+struct SignerProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Drop for SignerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Client side of the protocol. A spawned signer is kept running for the lifetime of the client
+/// and restarted on the next request if it fails.
+pub struct ExternalSigner {
+    transport: SignerTransport,
+    process: Mutex<Option<SignerProcess>>,
+}
+
+impl ExternalSigner {
+    pub fn new(transport: SignerTransport) -> Self {
+        Self {
+            transport,
+            process: Mutex::new(None),
+        }
+    }
+
+    pub fn transport(&self) -> &SignerTransport {
+        &self.transport
+    }
+
+    pub fn list_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        match self.request(&SignerRequest::ListKeys)? {
+            SignerResponse::Keys(keys) => keys
+                .iter()
+                .map(|key| {
+                    PublicKey::decode_base64(key)
+                        .map_err(|e| anyhow!("Invalid public key {key} from signer: {e}"))
+                })
+                .collect(),
+            response => Err(anyhow!("Unexpected response from signer: {response:?}")),
+        }
+    }
+
+    /// Sign the BCS bytes of an intent message with the key of `address`.
+    pub fn sign(
+        &self,
+        address: SuiAddress,
+        intent_message: &[u8],
+    ) -> Result<Signature, anyhow::Error> {
+        let request = SignerRequest::Sign {
+            address,
+            intent_message: Base64::encode(intent_message),
+        };
+        match self.request(&request)? {
+            SignerResponse::Signature(signature) => {
+                let bytes = Base64::decode(&signature)
+                    .map_err(|e| anyhow!("Invalid signature encoding from signer: {e}"))?;
+                Signature::from_bytes(&bytes)
+                    .map_err(|e| anyhow!("Invalid signature from signer: {e}"))
+            }
+            response => Err(anyhow!("Unexpected response from signer: {response:?}")),
+        }
+    }
+
+    fn request(&self, request: &SignerRequest) -> Result<SignerResponse, anyhow::Error> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        let response = match &self.transport {
+            SignerTransport::Command { program, args } => {
+                let mut process = self.process.lock().unwrap();
+                if process.is_none() {
+                    *process = Some(Self::spawn(program, args)?);
+                }
+                let signer = process.as_mut().unwrap();
+                let response = Self::exchange(&mut signer.stdin, &mut signer.stdout, &line);
+                if response.is_err() {
+                    // Restart the signer on the next request rather than reading a stale answer.
+                    *process = None;
+                }
+                response?
+            }
+            SignerTransport::Socket { path } => Self::exchange_over_socket(path, &line)?,
+        };
+        match serde_json::from_str(&response)
+            .map_err(|e| anyhow!("Invalid response from signer: {e}"))?
+        {
+            SignerResponse::Error(e) => Err(anyhow!("External signer error: {e}")),
+            response => Ok(response),
+        }
+    }
+
+    fn spawn(program: &Path, args: &[String]) -> Result<SignerProcess, anyhow::Error> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| anyhow!("Cannot start external signer {:?}: {e}", program))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(SignerProcess {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn exchange(
+        writer: &mut impl Write,
+        reader: &mut impl BufRead,
+        line: &str,
+    ) -> Result<String, anyhow::Error> {
+        writer.write_all(line.as_bytes())?;
+        writer.flush()?;
+        let mut response = String::new();
+        if reader.read_line(&mut response)? == 0 {
+            return Err(anyhow!("External signer closed the connection"));
+        }
+        Ok(response)
+    }
+
+    #[cfg(unix)]
+    fn exchange_over_socket(path: &Path, line: &str) -> Result<String, anyhow::Error> {
+        let mut stream = std::os::unix::net::UnixStream::connect(path)
+            .map_err(|e| anyhow!("Cannot connect to external signer at {:?}: {e}", path))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        Self::exchange(&mut stream, &mut reader, line)
+    }
+
+    #[cfg(not(unix))]
+    fn exchange_over_socket(_path: &Path, _line: &str) -> Result<String, anyhow::Error> {
+        Err(anyhow!(
+            "Unix socket signers are not supported on this platform"
+        ))
+    }
+}
+
+/// A reference signer answering the requests read from `reader` with the keys of `keystore`,
+/// until `reader` is closed.
+pub fn serve(
+    keystore: &impl AccountKeystore,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<(), anyhow::Error> {
+    for line in reader.lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(request) => handle_request(keystore, request),
+            Err(e) => SignerResponse::Error(format!("Invalid request: {e}")),
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn handle_request(keystore: &impl AccountKeystore, request: SignerRequest) -> SignerResponse {
+    match request {
+        SignerRequest::ListKeys => SignerResponse::Keys(
+            keystore
+                .keys()
+                .iter()
+                .map(EncodeDecodeBase64::encode_base64)
+                .collect(),
+        ),
+        SignerRequest::Sign {
+            address,
+            intent_message,
+        } => {
+            let intent_message = match Base64::decode(&intent_message) {
+                Ok(bytes) => bytes,
+                Err(e) => return SignerResponse::Error(format!("Invalid intent message: {e}")),
+            };
+            let mut hasher = DefaultHash::default();
+            hasher.update(&intent_message);
+            match keystore.sign_hashed(&address, &hasher.finalize().digest) {
+                Ok(signature) => SignerResponse::Signature(Base64::encode(signature.as_ref())),
+                Err(e) => SignerResponse::Error(e.to_string()),
+            }
+        }
+    }
+}
@@ -1,11 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::external_signer::{ExternalSigner, SignerTransport};
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use anyhow::anyhow;
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
-use rand::{rngs::StdRng, SeedableRng};
+use fastcrypto::encoding::{Base64, Encoding};
+use hmac::Hmac;
+use once_cell::sync::OnceCell;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::collections::BTreeMap;
@@ -15,22 +21,32 @@ use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::get_key_pair_from_rng;
 use sui_types::crypto::{
     enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
+    SuiSignature,
 };
+use tracing::warn;
+use zeroize::Zeroizing;
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileBasedKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
     fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error>;
     fn keys(&self) -> Vec<PublicKey>;
+    /// The keys of the keystore, failing if they cannot be listed rather than returning none.
+    fn try_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        Ok(self.keys())
+    }
     fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error>;
 
     fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error>;
@@ -91,6 +107,17 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                writeln!(writer, "Keystore Path : {:?}", file.path)?;
+                write!(writer, "Keystore Locked : {}", file.is_locked())?;
+                write!(f, "{}", writer)
+            }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External")?;
+                write!(writer, "Keystore Signer : {}", external.transport())?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -203,6 +230,10 @@ impl FileBasedKeystore {
         self.path = Some(path.to_path_buf());
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn save(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
             let store = serde_json::to_string_pretty(
@@ -223,6 +254,662 @@ impl FileBasedKeystore {
     }
 }
 
+/// Version of the on-disk format written by [EncryptedFileBasedKeystore].
+pub const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+/// Default number of PBKDF2-HMAC-SHA256 rounds used to derive the encryption key from the password.
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+const KDF_SALT_LENGTH: usize = 32;
+const AES_GCM_NONCE_LENGTH: usize = 12;
+
+/// Parameters of the password based key derivation, persisted alongside the encrypted keys.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Base64 encoded random salt.
+    pub salt: String,
+    pub iterations: u32,
+}
+
+impl KdfParams {
+    pub fn new(iterations: u32) -> Self {
+        let mut salt = [0u8; KDF_SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            salt: Base64::encode(salt),
+            iterations,
+        }
+    }
+
+    fn derive_key(&self, password: &str) -> Result<Zeroizing<[u8; 32]>, anyhow::Error> {
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid KDF salt: {e}"))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        pbkdf2::pbkdf2::<Hmac<sha2::Sha256>>(
+            password.as_bytes(),
+            &salt,
+            self.iterations,
+            &mut *key,
+        );
+        Ok(key)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKey {
+    /// Base64 encoded `flag || pk`, also used as associated data of the ciphertext.
+    public_key: String,
+    nonce: String,
+    /// Base64 encoded AES-256-GCM ciphertext of the Base64 encoded `flag || privkey`.
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    kdf: KdfParams,
+    keys: Vec<EncryptedKey>,
+}
+
+/// Returns the password of an encrypted keystore, e.g. by prompting the user.
+pub type PasswordPrompt = Box<dyn Fn() -> Result<String, anyhow::Error> + Send + Sync>;
+
+/// Decrypted state of an unlocked [EncryptedFileBasedKeystore].
+struct UnlockedKeys {
+    encryption_key: Zeroizing<[u8; 32]>,
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+}
+
+/// The derived encryption key cached by [EncryptedFileBasedKeystore::start_session], bound to
+/// the salt of the keystore so it is invalidated by a password change.
+#[derive(Serialize, Deserialize)]
+struct SessionFile {
+    salt: String,
+    encryption_key: String,
+    expires_at_ms: u64,
+}
+
+/// A file based keystore where private keys are encrypted at rest with a password derived key.
+/// Public keys are stored in clear so addresses can be listed without the password. Keys are
+/// decrypted on first use, with the password from [Self::set_password_prompt] or from a session
+/// started by [Self::start_session], or explicitly with [Self::unlock].
+pub struct EncryptedFileBasedKeystore {
+    unlocked: OnceCell<UnlockedKeys>,
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    kdf: KdfParams,
+    password_prompt: Option<PasswordPrompt>,
+    path: Option<PathBuf>,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(
+            self.path
+                .as_ref()
+                .unwrap_or(&PathBuf::default())
+                .to_str()
+                .unwrap_or(""),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(
+            msg,
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+
+    fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.unlocked_keys()?;
+        let unlocked = self.unlocked.get_mut().expect("keystore is unlocked");
+        let address: SuiAddress = (&keypair.public()).into();
+        self.public_keys.insert(address, keypair.public());
+        unlocked.keys.insert(address, keypair);
+        self.save()?;
+        Ok(())
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.public_keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        if !self.public_keys.contains_key(address) {
+            return Err(anyhow!("Cannot find key for address: [{address}]"));
+        }
+        match self.unlocked_keys()?.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Open the keystore at `path`, unlocked only if a session started by [Self::start_session]
+    /// is still valid. An empty keystore is returned if the file does not exist yet, it will be
+    /// created on first [Self::unlock].
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let (kdf, public_keys) = if path.exists() {
+            let file = Self::read_file(path)?;
+            let public_keys = file
+                .keys
+                .iter()
+                .map(|k| {
+                    PublicKey::decode_base64(&k.public_key)
+                        .map(|pk| (Into::<SuiAddress>::into(&pk), pk))
+                })
+                .collect::<Result<BTreeMap<_, _>, _>>()
+                .map_err(|e| anyhow!("Invalid public key in keystore {:?}: {e}", path))?;
+            (file.kdf, public_keys)
+        } else {
+            (KdfParams::new(DEFAULT_KDF_ITERATIONS), BTreeMap::new())
+        };
+
+        let keystore = Self {
+            unlocked: OnceCell::new(),
+            public_keys,
+            kdf,
+            password_prompt: None,
+            path: Some(path.to_path_buf()),
+        };
+        if let Some(encryption_key) = keystore.read_session() {
+            // A session which does not decrypt the keystore anymore is ignored.
+            if let Ok(unlocked) = keystore.decrypt_keys(encryption_key) {
+                let _ = keystore.unlocked.set(unlocked);
+            }
+        }
+        Ok(keystore)
+    }
+
+    /// Create an unlocked keystore at `path` holding `keys` encrypted under `password`, and
+    /// write it to disk.
+    pub fn create(
+        path: &Path,
+        keys: Vec<SuiKeyPair>,
+        password: &str,
+        kdf_iterations: u32,
+    ) -> Result<Self, anyhow::Error> {
+        let kdf = KdfParams::new(kdf_iterations);
+        let encryption_key = kdf.derive_key(password)?;
+        let keys = keys
+            .into_iter()
+            .map(|k| (Into::<SuiAddress>::into(&k.public()), k))
+            .collect::<BTreeMap<_, _>>();
+        let keystore = Self {
+            public_keys: keys.iter().map(|(a, k)| (*a, k.public())).collect(),
+            unlocked: OnceCell::from(UnlockedKeys {
+                encryption_key,
+                keys,
+            }),
+            kdf,
+            password_prompt: None,
+            path: Some(path.to_path_buf()),
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Returns true if the file at `path` is in the encrypted keystore format, false if it does
+    /// not exist or is a plaintext keystore.
+    pub fn is_encrypted_file(path: &Path) -> Result<bool, anyhow::Error> {
+        if !path.exists() {
+            return Ok(false);
+        }
+        let content: serde_json::Value = serde_json::from_reader(BufReader::new(
+            File::open(path).map_err(|e| anyhow!("Can't open keystore {:?}: {e}", path))?,
+        ))
+        .map_err(|e| anyhow!("Can't deserialize keystore {:?}: {e}", path))?;
+        if content.is_array() {
+            return Ok(false);
+        }
+        Self::read_file(path)?;
+        Ok(true)
+    }
+
+    fn read_file(path: &Path) -> Result<EncryptedKeystoreFile, anyhow::Error> {
+        let reader =
+            BufReader::new(File::open(path).map_err(|e| {
+                anyhow!("Can't open EncryptedFileBasedKeystore from {:?}: {e}", path)
+            })?);
+        let file: EncryptedKeystoreFile = serde_json::from_reader(reader).map_err(|e| {
+            anyhow!(
+                "Can't deserialize EncryptedFileBasedKeystore from {:?}: {e}",
+                path
+            )
+        })?;
+        if file.version != ENCRYPTED_KEYSTORE_VERSION {
+            return Err(anyhow!(
+                "Unsupported encrypted keystore version {} in {:?}, expected {}",
+                file.version,
+                path,
+                ENCRYPTED_KEYSTORE_VERSION
+            ));
+        }
+        Ok(file)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.get().is_none()
+    }
+
+    /// Ask for the password with `prompt` the first time a private key is needed.
+    pub fn set_password_prompt(&mut self, prompt: PasswordPrompt) {
+        self.password_prompt = Some(prompt);
+    }
+
+    /// Decrypt all keys with the password, keeping them in memory until [Self::lock] is called
+    /// or the keystore is dropped.
+    pub fn unlock(&mut self, password: &str) -> Result<(), anyhow::Error> {
+        let unlocked = self.decrypt_keys(self.kdf.derive_key(password)?)?;
+        self.public_keys = unlocked
+            .keys
+            .iter()
+            .map(|(a, k)| (*a, k.public()))
+            .collect();
+        self.unlocked = OnceCell::from(unlocked);
+        Ok(())
+    }
+
+    /// Drop the decrypted keys and the derived encryption key from memory, and end any session.
+    pub fn lock(&mut self) -> Result<(), anyhow::Error> {
+        self.unlocked.take();
+        self.end_session()
+    }
+
+    fn unlocked_keys(&self) -> Result<&UnlockedKeys, anyhow::Error> {
+        if let Some(unlocked) = self.unlocked.get() {
+            return Ok(unlocked);
+        }
+        let prompt = self
+            .password_prompt
+            .as_ref()
+            .ok_or_else(|| anyhow!("Keystore is locked, unlock it before signing"))?;
+        let unlocked = self.decrypt_keys(self.kdf.derive_key(&prompt()?)?)?;
+        Ok(self.unlocked.get_or_init(|| unlocked))
+    }
+
+    fn decrypt_keys(
+        &self,
+        encryption_key: Zeroizing<[u8; 32]>,
+    ) -> Result<UnlockedKeys, anyhow::Error> {
+        let mut keys = BTreeMap::new();
+        if let Some(path) = self.path.as_ref().filter(|p| p.exists()) {
+            let cipher = Aes256Gcm::new_from_slice(&encryption_key[..])
+                .map_err(|e| anyhow!("Invalid encryption key: {e}"))?;
+            for key in Self::read_file(path)?.keys {
+                let kp = Self::decrypt_key(&cipher, &key)?;
+                keys.insert((&kp.public()).into(), kp);
+            }
+        }
+        Ok(UnlockedKeys {
+            encryption_key,
+            keys,
+        })
+    }
+
+    /// Re-encrypt all keys under `new_password` with a fresh salt. This ends any session.
+    pub fn change_password(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.unlock(old_password)?;
+        let kdf = KdfParams::new(self.kdf.iterations);
+        let unlocked = self.unlocked.get_mut().expect("keystore is unlocked");
+        unlocked.encryption_key = kdf.derive_key(new_password)?;
+        self.kdf = kdf;
+        self.end_session()?;
+        self.save()
+    }
+
+    fn session_path(&self) -> Option<PathBuf> {
+        self.path
+            .as_ref()
+            .map(|path| path.with_extension("session"))
+    }
+
+    /// Cache the derived encryption key next to the keystore, readable only by the current user,
+    /// so that the keystore opens unlocked until `duration` elapses or the keystore is locked.
+    /// The password itself is never stored. An expired session is deleted the next time the
+    /// keystore is opened.
+    pub fn start_session(&self, duration: Duration) -> Result<(), anyhow::Error> {
+        let session_path = self
+            .session_path()
+            .ok_or_else(|| anyhow!("Keystore has no path"))?;
+        let unlocked = self
+            .unlocked
+            .get()
+            .ok_or_else(|| anyhow!("Keystore is locked, unlock it before starting a session"))?;
+        let expires_at = SystemTime::now() + duration;
+        let session = serde_json::to_string(&SessionFile {
+            salt: self.kdf.salt.clone(),
+            encryption_key: Base64::encode(&unlocked.encryption_key[..]),
+            expires_at_ms: expires_at.duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        })?;
+        write_private_file(&session_path, session.as_bytes())
+    }
+
+    /// Forget the cached encryption key of the current session, if any.
+    pub fn end_session(&self) -> Result<(), anyhow::Error> {
+        match self.session_path() {
+            Some(path) if path.exists() => Ok(fs::remove_file(path)?),
+            _ => Ok(()),
+        }
+    }
+
+    /// The encryption key of the current session, if any. Sessions which expired, were started
+    /// with another password, or could be read by other users are deleted.
+    fn read_session(&self) -> Option<Zeroizing<[u8; 32]>> {
+        let path = self.session_path()?;
+        if !path.exists() {
+            return None;
+        }
+        let encryption_key = self.read_session_file(&path);
+        if encryption_key.is_none() {
+            let _ = fs::remove_file(&path);
+        }
+        encryption_key
+    }
+
+    fn read_session_file(&self, path: &Path) -> Option<Zeroizing<[u8; 32]>> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if fs::metadata(path).ok()?.permissions().mode() & 0o077 != 0 {
+                return None;
+            }
+        }
+        let content = Zeroizing::new(fs::read(path).ok()?);
+        let session: SessionFile = serde_json::from_slice(&content).ok()?;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_millis() as u64;
+        if session.salt != self.kdf.salt || now_ms >= session.expires_at_ms {
+            return None;
+        }
+        let bytes = Zeroizing::new(Base64::decode(&session.encryption_key).ok()?);
+        let mut encryption_key = Zeroizing::new([0u8; 32]);
+        if bytes.len() != encryption_key.len() {
+            return None;
+        }
+        encryption_key.copy_from_slice(&bytes);
+        Some(encryption_key)
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.to_path_buf());
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let unlocked = self
+            .unlocked
+            .get()
+            .ok_or_else(|| anyhow!("Keystore is locked, cannot save"))?;
+        let cipher = Aes256Gcm::new_from_slice(&unlocked.encryption_key[..])
+            .map_err(|e| anyhow!("Invalid encryption key: {e}"))?;
+        let keys = unlocked
+            .keys
+            .values()
+            .map(|kp| Self::encrypt_key(&cipher, kp))
+            .collect::<Result<Vec<_>, _>>()?;
+        let store = serde_json::to_string_pretty(&EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: self.kdf.clone(),
+            keys,
+        })?;
+        write_private_file(path, store.as_bytes())
+    }
+
+    fn encrypt_key(cipher: &Aes256Gcm, kp: &SuiKeyPair) -> Result<EncryptedKey, anyhow::Error> {
+        let public_key = kp.public().encode_base64();
+        let mut nonce = [0u8; AES_GCM_NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let plaintext = Zeroizing::new(kp.encode_base64());
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: public_key.as_bytes(),
+                },
+            )
+            .map_err(|e| anyhow!("Failed to encrypt key: {e}"))?;
+        Ok(EncryptedKey {
+            public_key,
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    fn decrypt_key(cipher: &Aes256Gcm, key: &EncryptedKey) -> Result<SuiKeyPair, anyhow::Error> {
+        let nonce = Base64::decode(&key.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        if nonce.len() != AES_GCM_NONCE_LENGTH {
+            return Err(anyhow!("Invalid nonce length: {}", nonce.len()));
+        }
+        let ciphertext =
+            Base64::decode(&key.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: key.public_key.as_bytes(),
+                    },
+                )
+                .map_err(|_| anyhow!("Incorrect keystore password"))?,
+        );
+        let kp = SuiKeyPair::decode_base64(
+            std::str::from_utf8(&plaintext).map_err(|e| anyhow!("Invalid key encoding: {e}"))?,
+        )
+        .map_err(|e| anyhow!("Invalid keypair: {e}"))?;
+        if kp.public().encode_base64() != key.public_key {
+            return Err(anyhow!(
+                "Decrypted key does not match public key {}",
+                key.public_key
+            ));
+        }
+        Ok(kp)
+    }
+
+    /// The decrypted keys, empty while the keystore is locked.
+    pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
+        self.unlocked
+            .get()
+            .map(|unlocked| unlocked.keys.values().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A keystore delegating signatures to a signer process, see [crate::external_signer] for the
+/// protocol. The signer is only reached on first use, when its keys are listed once. Keys are
+/// managed by the signer, so they cannot be added or read from here.
+pub struct ExternalKeystore {
+    signer: ExternalSigner,
+    public_keys: OnceCell<BTreeMap<SuiAddress, PublicKey>>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.transport().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(ExternalKeystore::new(SignerTransport::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn sign_hashed(
+        &self,
+        address: &SuiAddress,
+        _msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        Err(signature::Error::from_source(format!(
+            "External signers cannot sign a hashed message for address: [{address}]"
+        )))
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let public_keys = self
+            .public_keys()
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        if !public_keys.contains_key(address) {
+            return Err(signature::Error::from_source(format!(
+                "Cannot find key for address: [{address}]"
+            )));
+        }
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?;
+        let signature = self
+            .signer
+            .sign(*address, &bytes)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        // Do not hand out a signature the network would reject.
+        signature
+            .verify_secure(&intent_msg, *address)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        Ok(signature)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Keys of an external keystore must be added to the signer ({})",
+            self.transport()
+        ))
+    }
+
+    /// The keys of the signer, empty while it cannot be reached, see [Self::try_keys].
+    fn keys(&self) -> Vec<PublicKey> {
+        self.try_keys().unwrap_or_else(|e| {
+            warn!(
+                "Cannot list the keys of external signer ({}): {e}",
+                self.transport()
+            );
+            vec![]
+        })
+    }
+
+    fn try_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        Ok(self.public_keys()?.values().cloned().collect())
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "The private key of address [{address}] is held by an external signer"
+        ))
+    }
+}
+
+impl ExternalKeystore {
+    /// A keystore of the signer reached through `transport`, which is neither started nor
+    /// connected to until its keys are first needed.
+    pub fn new(transport: SignerTransport) -> Self {
+        Self {
+            signer: ExternalSigner::new(transport),
+            public_keys: OnceCell::new(),
+        }
+    }
+
+    pub fn transport(&self) -> &SignerTransport {
+        self.signer.transport()
+    }
+
+    /// List the keys of the signer, starting it if needed. A failure is not kept, so that the
+    /// signer is reached again on the next call.
+    fn public_keys(&self) -> Result<&BTreeMap<SuiAddress, PublicKey>, anyhow::Error> {
+        self.public_keys.get_or_try_init(|| {
+            Ok(self
+                .signer
+                .list_keys()?
+                .into_iter()
+                .map(|pk| ((&pk).into(), pk))
+                .collect())
+        })
+    }
+}
+
+/// Write `contents` to `path`, readable only by the current user. The file is written next to
+/// `path` and renamed into place, so that an interrupted write leaves the previous content.
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path {:?}", path))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp_path)?;
+    // The mode only applies to new files, a leftover of an interrupted write keeps its own.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    std::io::Write::write_all(&mut file, contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
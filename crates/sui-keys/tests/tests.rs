@@ -1,12 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::fs;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use fastcrypto::hash::HashFunction;
+use shared_crypto::intent::Intent;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use shared_crypto::intent::IntentMessage;
+use sui_keys::external_signer::SignerTransport;
+use sui_keys::keystore::{
+    AccountKeystore, EncryptedFileBasedKeystore, ExternalKeystore, FileBasedKeystore,
+    InMemKeystore, Keystore,
+};
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, SignatureScheme, SuiSignature, SuiSignatureInner,
+};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +81,248 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn encrypted_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let (address2, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::Secp256k1, None, None)?;
+    assert!(!EncryptedFileBasedKeystore::is_encrypted_file(
+        &keystore_path
+    )?);
+
+    let keys = keystore
+        .key_pairs()
+        .into_iter()
+        .map(|kp| kp.copy())
+        .collect();
+    EncryptedFileBasedKeystore::create(&keystore_path, keys, "password", 10)?;
+    assert!(EncryptedFileBasedKeystore::is_encrypted_file(
+        &keystore_path
+    )?);
+    assert!(
+        !fs::read_to_string(&keystore_path)?.contains(&keystore.get_key(&address)?.encode_base64())
+    );
+    // The plaintext format can no longer be read from the encrypted file.
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+
+    // Addresses are readable while locked, but signing is not possible.
+    let mut encrypted = EncryptedFileBasedKeystore::new(&keystore_path)?;
+    assert!(encrypted.is_locked());
+    assert_eq!(encrypted.addresses(), keystore.addresses());
+    assert!(encrypted
+        .sign_secure(&address, b"hello", Intent::sui_transaction())
+        .is_err());
+
+    assert!(encrypted.unlock("wrong password").is_err());
+    assert!(encrypted.is_locked());
+    encrypted.unlock("password")?;
+    assert_eq!(encrypted.get_key(&address)?, keystore.get_key(&address)?);
+    assert_eq!(encrypted.get_key(&address2)?, keystore.get_key(&address2)?);
+
+    // New keys are persisted encrypted.
+    let (address3, _, _) =
+        encrypted.generate_and_add_new_key(SignatureScheme::Secp256r1, None, None)?;
+    encrypted.change_password("password", "new password")?;
+
+    let mut encrypted = Keystore::from(EncryptedFileBasedKeystore::new(&keystore_path)?);
+    assert_eq!(encrypted.addresses().len(), 3);
+    if let Keystore::Encrypted(encrypted) = &mut encrypted {
+        assert!(encrypted.unlock("password").is_err());
+        encrypted.unlock("new password")?;
+    }
+    encrypted.sign_secure(&address3, b"hello", Intent::sui_transaction())?;
+    assert!(encrypted.to_string().contains("Encrypted"));
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_session_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = InMemKeystore::default();
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let keys = vec![keystore.get_key(&address)?.copy()];
+    EncryptedFileBasedKeystore::create(&keystore_path, keys, "password", 10)?;
+
+    // The password is only asked for when a key is needed.
+    let prompts = Arc::new(AtomicUsize::new(0));
+    let mut encrypted = EncryptedFileBasedKeystore::new(&keystore_path)?;
+    let counter = prompts.clone();
+    encrypted.set_password_prompt(Box::new(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok("password".to_string())
+    }));
+    assert_eq!(encrypted.addresses(), vec![address]);
+    assert_eq!(prompts.load(Ordering::SeqCst), 0);
+    encrypted.sign_secure(&address, b"hello", Intent::sui_transaction())?;
+    encrypted.sign_secure(&address, b"hello", Intent::sui_transaction())?;
+    assert_eq!(prompts.load(Ordering::SeqCst), 1);
+
+    // A session keeps new instances unlocked until it ends or expires.
+    encrypted.start_session(Duration::from_secs(60))?;
+    let session = EncryptedFileBasedKeystore::new(&keystore_path)?;
+    assert!(!session.is_locked());
+    session.sign_secure(&address, b"hello", Intent::sui_transaction())?;
+    session.end_session()?;
+    assert!(EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+
+    // Expired sessions are deleted when the keystore is opened.
+    let session_path = keystore_path.with_extension("session");
+    encrypted.start_session(Duration::ZERO)?;
+    assert!(session_path.exists());
+    assert!(EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+    assert!(!session_path.exists());
+
+    // Locking the keystore ends the session.
+    let mut session = EncryptedFileBasedKeystore::new(&keystore_path)?;
+    session.unlock("password")?;
+    session.start_session(Duration::from_secs(60))?;
+    session.lock()?;
+    assert!(session.is_locked());
+    assert!(!session_path.exists());
+    assert!(EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Only the current user can read the session and the keystore, even if they replace
+        // files with looser permissions.
+        fs::write(&session_path, "")?;
+        fs::set_permissions(&session_path, fs::Permissions::from_mode(0o644))?;
+        encrypted.start_session(Duration::from_secs(60))?;
+        for path in [&session_path, &keystore_path] {
+            assert_eq!(fs::metadata(path)?.permissions().mode() & 0o777, 0o600);
+        }
+        assert!(!EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+
+        // A session readable by other users is not trusted.
+        fs::set_permissions(&session_path, fs::Permissions::from_mode(0o644))?;
+        assert!(EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+        assert!(!session_path.exists());
+    }
+
+    // Keystores are written to a temporary file renamed into place.
+    assert!(!temp_dir.path().join("sui.keystore.tmp").exists());
+    assert!(!temp_dir.path().join("sui.session.tmp").exists());
+
+    // Changing the password invalidates the session.
+    encrypted.start_session(Duration::from_secs(60))?;
+    encrypted.change_password("password", "new password")?;
+    assert!(EncryptedFileBasedKeystore::new(&keystore_path)?.is_locked());
+
+    // Unreadable keystores are reported rather than treated as plaintext.
+    let content = fs::read_to_string(&keystore_path)?;
+    fs::write(
+        &keystore_path,
+        content.replacen("\"version\": 1", "\"version\": 2", 1),
+    )?;
+    assert!(EncryptedFileBasedKeystore::is_encrypted_file(&keystore_path).is_err());
+    fs::write(&keystore_path, "not json")?;
+    assert!(EncryptedFileBasedKeystore::is_encrypted_file(&keystore_path).is_err());
+    Ok(())
+}
+
+#[test]
+fn external_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let (address2, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::Secp256k1, None, None)?;
+
+    let transport = SignerTransport::Command {
+        program: env!("CARGO_BIN_EXE_mock_external_signer").into(),
+        args: vec![keystore_path.to_string_lossy().to_string()],
+    };
+    let mut external = Keystore::from(ExternalKeystore::new(transport.clone()));
+    assert_eq!(external.addresses(), keystore.addresses());
+
+    for address in [address, address2] {
+        let signature = external.sign_secure(&address, b"hello", Intent::sui_transaction())?;
+        signature.verify_secure(
+            &IntentMessage::new(Intent::sui_transaction(), b"hello"),
+            address,
+        )?;
+    }
+    assert!(external
+        .sign_secure(&SuiAddress::ZERO, b"hello", Intent::sui_transaction())
+        .is_err());
+    assert!(external.get_key(&address).is_err());
+    assert!(external
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None)
+        .is_err());
+
+    // The client config only records how to reach the signer.
+    let config = serde_json::to_string(&external)?;
+    assert!(!config.contains(&keystore.get_key(&address)?.encode_base64()));
+    let external: Keystore = serde_json::from_str(&config)?;
+    assert_eq!(external.addresses(), keystore.addresses());
+    assert!(external.to_string().contains("External"));
+
+    // Opening the keystore does not start the signer, which is only needed to sign.
+    let missing = Keystore::from(ExternalKeystore::new(SignerTransport::Command {
+        program: temp_dir.path().join("missing_signer"),
+        args: vec![],
+    }));
+    let missing: Keystore = serde_json::from_str(&serde_json::to_string(&missing)?)?;
+    assert!(missing.addresses().is_empty());
+    assert!(missing.try_keys().is_err());
+    assert!(missing
+        .sign_secure(&address, b"hello", Intent::sui_transaction())
+        .is_err());
+
+    // A signer exiting before answering is reported as such rather than as holding no keys.
+    let failing = Keystore::from(ExternalKeystore::new(SignerTransport::Command {
+        program: env!("CARGO_BIN_EXE_mock_external_signer").into(),
+        args: vec![
+            keystore_path.to_string_lossy().to_string(),
+            "--unexpected".to_string(),
+        ],
+    }));
+    assert!(failing.try_keys().is_err());
+    assert!(failing.addresses().is_empty());
+    assert!(failing
+        .sign_secure(&address, b"hello", Intent::sui_transaction())
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_socket_test() -> Result<(), anyhow::Error> {
+    use std::io::BufReader;
+    use std::os::unix::net::UnixListener;
+    use sui_keys::external_signer::serve;
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = UnixListener::bind(&socket_path)?;
+    let keystore = InMemKeystore::new_insecure_for_tests(2);
+    let addresses = keystore.addresses();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            serve(&keystore, reader, stream).unwrap();
+        }
+    });
+
+    let external = ExternalKeystore::new(SignerTransport::Socket { path: socket_path });
+    assert_eq!(external.addresses(), addresses);
+    let signature = external.sign_secure(&addresses[1], b"hello", Intent::sui_transaction())?;
+    signature.verify_secure(
+        &IntentMessage::new(Intent::sui_transaction(), b"hello"),
+        addresses[1],
+    )?;
+    Ok(())
+}
@@ -137,6 +137,15 @@ fn build_anemo_services(out_dir: &Path) {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("get_checkpoint_availability")
+                .route_name("GetCheckpointAvailability")
+                .request_type("()")
+                .response_type("crate::state_sync::GetCheckpointAvailabilityResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             anemo_build::manual::Method::builder()
                 .name("get_checkpoint_contents")
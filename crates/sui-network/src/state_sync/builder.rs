@@ -8,12 +8,14 @@ use std::{
     sync::{Arc, RwLock},
 };
 use sui_config::p2p::StateSyncConfig;
+use sui_storage::archive::reader::ArchiveReader;
 use sui_types::{messages_checkpoint::VerifiedCheckpoint, storage::ReadStore};
-use tap::Pipe;
+use tap::{Pipe, TapFallible};
 use tokio::{
     sync::{broadcast, mpsc},
     task::JoinSet,
 };
+use tracing::warn;
 
 use super::{
     metrics::Metrics, server::Server, Handle, PeerHeights, StateSync, StateSyncEventLoop,
@@ -106,6 +108,15 @@ where
         let store = store.unwrap();
         let config = config.unwrap_or_default();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
+        let archive_reader = config
+            .archive_object_store_config
+            .as_ref()
+            .and_then(|archive_config| {
+                ArchiveReader::new(archive_config)
+                    .tap_err(|e| warn!("unable to open checkpoint archive, ignoring it: {e}"))
+                    .ok()
+            })
+            .map(Arc::new);
 
         let (sender, mailbox) = mpsc::channel(config.mailbox_capacity());
         let (checkpoint_event_sender, _receiver) =
@@ -138,6 +149,7 @@ where
                 peer_heights,
                 checkpoint_event_sender,
                 metrics,
+                archive_reader,
             },
             server,
         )
@@ -152,6 +164,7 @@ pub struct UnstartedStateSync<S> {
     pub(super) peer_heights: Arc<RwLock<PeerHeights>>,
     pub(super) checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     pub(super) metrics: Metrics,
+    pub(super) archive_reader: Option<Arc<ArchiveReader>>,
 }
 
 impl<S> UnstartedStateSync<S>
@@ -168,6 +181,7 @@ where
             peer_heights,
             checkpoint_event_sender,
             metrics,
+            archive_reader,
         } = self;
 
         (
@@ -178,6 +192,10 @@ where
                 tasks: JoinSet::new(),
                 sync_checkpoint_summaries_task: None,
                 sync_checkpoint_contents_task: None,
+                sync_checkpoints_from_archive_task: None,
+                archive_reader,
+                highest_synced_checkpoint_at_last_tick: None,
+                archive_sync_pending: false,
                 store,
                 peer_heights,
                 checkpoint_event_sender,
@@ -27,7 +27,9 @@
 //!   our latest checkpoint, and is intended to be used as a guarantee of data availability.
 //!
 //! The `PeerHeights` struct is used to track the highest_synced_checkpoint watermark for all of
-//! our peers.
+//! our peers, along with the lowest checkpoint whose contents they are still able to serve, as
+//! nodes pruning their history no longer have the contents of older checkpoints. Both are shared
+//! by a node when queried for the availability of its checkpoints.
 //!
 //! When a new checkpoint is discovered, and we've determined that it is higher than our
 //! highest_verified_checkpoint, then StateSync will kick off a task to synchronize and verify all
@@ -46,6 +48,15 @@
 //! indicating that a new checkpoint has been fully downloaded. Notifications on this broadcast
 //! channel will always be made in order. StateSync will also send out a notification to its peers
 //! of the newly synchronized checkpoint so that it can help other peers synchronize.
+//!
+//! # Syncing from an Archive
+//!
+//! Peers are not required to keep the full history of checkpoints, so a node which is far behind
+//! may be unable to find a peer willing to serve the checkpoints it is missing. If an archive of
+//! checkpoints is configured (see [sui_storage::archive]), StateSync will fall back to it whenever
+//! our highest_synced_checkpoint watermark does not move for a whole tick. Syncing from peers is
+//! paused while the archive is being used, and checkpoints read from it are verified exactly like
+//! the ones received from peers before both watermarks are ratcheted up.
 
 use anemo::{types::PeerEvent, PeerId, Request, Response, Result};
 use anyhow::anyhow;
@@ -56,14 +67,14 @@ use std::{
     time::{Duration, SystemTime},
 };
 use sui_config::p2p::StateSyncConfig;
+use sui_storage::archive::reader::ArchiveReader;
 use sui_types::{
     digests::{CheckpointContentsDigest, CheckpointDigest},
     messages_checkpoint::{
         CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber, FullCheckpointContents,
         VerifiedCheckpoint, VerifiedCheckpointContents,
     },
-    storage::ReadStore,
-    storage::WriteStore,
+    storage::{verify_checkpoint, ReadStore, WriteStore},
 };
 use tap::{Pipe, TapFallible, TapOptional};
 use tokio::{
@@ -87,7 +98,7 @@ pub use generated::{
     state_sync_client::StateSyncClient,
     state_sync_server::{StateSync, StateSyncServer},
 };
-pub use server::GetCheckpointSummaryRequest;
+pub use server::{GetCheckpointAvailabilityResponse, GetCheckpointSummaryRequest};
 
 use self::metrics::Metrics;
 
@@ -138,6 +149,8 @@ struct PeerStateSyncInfo {
     on_same_chain_as_us: bool,
     /// Highest checkpoint sequence number we know of for this Peer.
     height: CheckpointSequenceNumber,
+    /// Lowest checkpoint sequence number whose contents this Peer is able to serve.
+    lowest: CheckpointSequenceNumber,
 }
 
 impl PeerHeights {
@@ -164,13 +177,21 @@ impl PeerHeights {
     //
     // This will return false if the given peer doesn't have an entry or is not on the same chain
     // as us
-    pub fn update_peer_info(&mut self, peer_id: PeerId, checkpoint: Checkpoint) -> bool {
+    pub fn update_peer_info(
+        &mut self,
+        peer_id: PeerId,
+        checkpoint: Checkpoint,
+        low_watermark: Option<CheckpointSequenceNumber>,
+    ) -> bool {
         let info = match self.peers.get_mut(&peer_id) {
             Some(info) if info.on_same_chain_as_us => info,
             _ => return false,
         };
 
         info.height = std::cmp::max(*checkpoint.sequence_number(), info.height);
+        if let Some(low_watermark) = low_watermark {
+            info.lowest = low_watermark;
+        }
         self.insert_checkpoint(checkpoint);
 
         true
@@ -186,6 +207,7 @@ impl PeerHeights {
                 let entry = entry.get_mut();
                 if entry.genesis_checkpoint_digest == info.genesis_checkpoint_digest {
                     entry.height = std::cmp::max(entry.height, info.height);
+                    entry.lowest = std::cmp::max(entry.lowest, info.lowest);
                 } else {
                     *entry = info;
                 }
@@ -260,6 +282,15 @@ struct StateSyncEventLoop<S> {
     tasks: JoinSet<()>,
     sync_checkpoint_summaries_task: Option<AbortHandle>,
     sync_checkpoint_contents_task: Option<AbortHandle>,
+    sync_checkpoints_from_archive_task: Option<AbortHandle>,
+
+    /// Reader for the archive of checkpoints, if one is configured.
+    archive_reader: Option<Arc<ArchiveReader>>,
+    /// Our highest_synced_checkpoint watermark as of the last tick, used to detect when syncing
+    /// from peers has stalled.
+    highest_synced_checkpoint_at_last_tick: Option<CheckpointSequenceNumber>,
+    /// Set once syncing from peers has stalled, until we start syncing from the archive.
+    archive_sync_pending: bool,
 
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
@@ -344,9 +375,14 @@ where
                     if matches!(&self.sync_checkpoint_summaries_task, Some(t) if t.is_finished()) {
                         self.sync_checkpoint_summaries_task = None;
                     }
+
+                    if matches!(&self.sync_checkpoints_from_archive_task, Some(t) if t.is_finished()) {
+                        self.sync_checkpoints_from_archive_task = None;
+                    }
                 },
             }
 
+            self.maybe_start_archive_sync_task();
             self.maybe_start_checkpoint_summary_sync_task();
             self.maybe_start_checkpoint_contents_sync_task();
         }
@@ -501,11 +537,67 @@ where
             self.config.timeout(),
         );
         self.tasks.spawn(task);
+
+        self.check_for_stalled_sync();
+    }
+
+    // Fall back to the archive, if there is one, when our highest synced checkpoint hasn't moved
+    // since the last tick.
+    fn check_for_stalled_sync(&mut self) {
+        if self.archive_reader.is_none() || self.sync_checkpoints_from_archive_task.is_some() {
+            return;
+        }
+
+        let highest_synced_checkpoint = *self
+            .store
+            .get_highest_synced_checkpoint()
+            .expect("store operation should not fail")
+            .sequence_number();
+
+        if self
+            .highest_synced_checkpoint_at_last_tick
+            .replace(highest_synced_checkpoint)
+            == Some(highest_synced_checkpoint)
+        {
+            self.archive_sync_pending = true;
+        }
+    }
+
+    fn maybe_start_archive_sync_task(&mut self) {
+        // Wait for in-flight sync tasks to finish so that watermarks are only ratcheted up by a
+        // single task at a time
+        if !self.archive_sync_pending
+            || self.sync_checkpoints_from_archive_task.is_some()
+            || self.sync_checkpoint_summaries_task.is_some()
+            || self.sync_checkpoint_contents_task.is_some()
+        {
+            return;
+        }
+        let Some(archive_reader) = self.archive_reader.clone() else {
+            return;
+        };
+        self.archive_sync_pending = false;
+
+        let task = sync_checkpoints_from_archive(
+            archive_reader,
+            self.store.clone(),
+            self.weak_sender.clone(),
+            self.checkpoint_event_sender.clone(),
+            self.metrics.clone(),
+        )
+        .map(|result| match result {
+            Ok(()) => {}
+            Err(e) => {
+                debug!("error syncing checkpoints from archive {e}");
+            }
+        });
+        let task_handle = self.tasks.spawn(task);
+        self.sync_checkpoints_from_archive_task = Some(task_handle);
     }
 
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
-        // Only run one sync task at a time
-        if self.sync_checkpoint_summaries_task.is_some() {
+        // Only run one sync task at a time, and none while syncing from the archive
+        if self.sync_checkpoint_summaries_task.is_some() || self.syncing_from_archive() {
             return;
         }
 
@@ -549,8 +641,8 @@ where
     }
 
     fn maybe_start_checkpoint_contents_sync_task(&mut self) {
-        // Only run one sync task at a time
-        if self.sync_checkpoint_contents_task.is_some() {
+        // Only run one sync task at a time, and none while syncing from the archive
+        if self.sync_checkpoint_contents_task.is_some() || self.syncing_from_archive() {
             return;
         }
 
@@ -590,6 +682,10 @@ where
         }
     }
 
+    fn syncing_from_archive(&self) -> bool {
+        self.archive_sync_pending || self.sync_checkpoints_from_archive_task.is_some()
+    }
+
     fn spawn_notify_peers_of_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
         let task = notify_peers_of_checkpoint(
             self.network.clone(),
@@ -659,12 +755,14 @@ async fn get_latest_from_peer(
                         genesis_checkpoint_digest: digest,
                         on_same_chain_as_us: our_genesis_checkpoint_digest == digest,
                         height: *checkpoint.sequence_number(),
+                        lowest: CheckpointSequenceNumber::default(),
                     }
                 }
                 Ok(None) => PeerStateSyncInfo {
                     genesis_checkpoint_digest: CheckpointDigest::default(),
                     on_same_chain_as_us: false,
                     height: CheckpointSequenceNumber::default(),
+                    lowest: CheckpointSequenceNumber::default(),
                 },
                 Err(status) => {
                     trace!("get_latest_checkpoint_summary request failed: {status:?}");
@@ -684,17 +782,19 @@ async fn get_latest_from_peer(
         return;
     }
 
-    let checkpoint = {
-        let request = Request::new(GetCheckpointSummaryRequest::Latest).with_timeout(timeout);
+    let (checkpoint, low_watermark) = {
+        let request = Request::new(()).with_timeout(timeout);
         let response = client
-            .get_checkpoint_summary(request)
+            .get_checkpoint_availability(request)
             .await
             .map(Response::into_inner);
         match response {
-            Ok(Some(checkpoint)) => checkpoint,
-            Ok(None) => return,
+            Ok(GetCheckpointAvailabilityResponse {
+                highest_synced_checkpoint,
+                lowest_available_checkpoint,
+            }) => (highest_synced_checkpoint, lowest_available_checkpoint),
             Err(status) => {
-                trace!("get_latest_checkpoint_summary request failed: {status:?}");
+                trace!("get_checkpoint_availability request failed: {status:?}");
                 return;
             }
         }
@@ -703,7 +803,7 @@ async fn get_latest_from_peer(
     peer_heights
         .write()
         .unwrap()
-        .update_peer_info(peer_id, checkpoint);
+        .update_peer_info(peer_id, checkpoint, Some(low_watermark));
 }
 
 async fn query_peers_for_their_latest_checkpoint(
@@ -724,21 +824,26 @@ async fn query_peers_for_their_latest_checkpoint(
             let mut client = StateSyncClient::new(peer);
 
             async move {
-                let request =
-                    Request::new(GetCheckpointSummaryRequest::Latest).with_timeout(timeout);
+                let request = Request::new(()).with_timeout(timeout);
                 let response = client
-                    .get_checkpoint_summary(request)
+                    .get_checkpoint_availability(request)
                     .await
                     .map(Response::into_inner);
                 match response {
-                    Ok(Some(checkpoint)) => peer_heights
+                    Ok(GetCheckpointAvailabilityResponse {
+                        highest_synced_checkpoint,
+                        lowest_available_checkpoint,
+                    }) => peer_heights
                         .write()
                         .unwrap()
-                        .update_peer_info(peer_id, checkpoint.clone())
-                        .then_some(checkpoint),
-                    Ok(None) => None,
+                        .update_peer_info(
+                            peer_id,
+                            highest_synced_checkpoint.clone(),
+                            Some(lowest_available_checkpoint),
+                        )
+                        .then_some(highest_synced_checkpoint),
                     Err(status) => {
-                        trace!("get_latest_checkpoint_summary request failed: {status:?}");
+                        trace!("get_checkpoint_availability request failed: {status:?}");
                         None
                     }
                 }
@@ -911,63 +1016,93 @@ where
     Ok(())
 }
 
-fn verify_checkpoint<S>(
-    current: &VerifiedCheckpoint,
+async fn sync_checkpoints_from_archive<S>(
+    archive_reader: Arc<ArchiveReader>,
     store: S,
-    checkpoint: Checkpoint,
-) -> Result<VerifiedCheckpoint, Checkpoint>
+    sender: mpsc::WeakSender<StateSyncMessage>,
+    checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    metrics: Metrics,
+) -> Result<()>
 where
     S: WriteStore,
     <S as ReadStore>::Error: std::error::Error,
 {
-    assert_eq!(
-        *checkpoint.sequence_number(),
-        current.sequence_number().saturating_add(1)
-    );
+    // Upper bound on the number of checkpoints read from the archive at once
+    const ARCHIVE_READ_BATCH_SIZE: u64 = 1_000;
 
-    if Some(*current.digest()) != checkpoint.previous_digest {
-        debug!(
-            current_sequence_number = current.sequence_number(),
-            current_digest =% current.digest(),
-            checkpoint_sequence_number = checkpoint.sequence_number(),
-            checkpoint_digest =% checkpoint.digest(),
-            checkpoint_previous_digest =? checkpoint.previous_digest,
-            "checkpoint not on same chain"
-        );
-        return Err(checkpoint);
+    let Some(latest_archived_checkpoint) = archive_reader.latest_available_checkpoint().await?
+    else {
+        return Ok(());
+    };
+    let mut highest_synced = store
+        .get_highest_synced_checkpoint()
+        .expect("store operation should not fail");
+    if *highest_synced.sequence_number() >= latest_archived_checkpoint {
+        return Ok(());
     }
+    info!(
+        highest_synced_checkpoint = highest_synced.sequence_number(),
+        latest_archived_checkpoint, "syncing checkpoints from archive"
+    );
 
-    let current_epoch = current.epoch();
-    if checkpoint.epoch() != current_epoch && checkpoint.epoch() != current_epoch.saturating_add(1)
-    {
-        debug!(
-            current_epoch = current_epoch,
-            checkpoint_epoch = checkpoint.epoch(),
-            "cannot verify checkpoint with too high of an epoch",
+    while *highest_synced.sequence_number() < latest_archived_checkpoint {
+        let start = highest_synced.sequence_number().saturating_add(1);
+        let end = std::cmp::min(
+            start.saturating_add(ARCHIVE_READ_BATCH_SIZE),
+            latest_archived_checkpoint.saturating_add(1),
         );
-        return Err(checkpoint);
-    }
+        let checkpoints = archive_reader.read(start..end).await?;
+        if checkpoints.is_empty() {
+            return Err(anyhow!("archive is missing checkpoint {start}"));
+        }
 
-    if checkpoint.epoch() == current_epoch.saturating_add(1)
-        && current.next_epoch_committee().is_none()
-    {
-        debug!(
-            "next checkpoint claims to be from the next epoch but the latest verified \
-            checkpoint does not indicate that it is the last checkpoint of an epoch"
-        );
-        return Err(checkpoint);
+        for (checkpoint, contents) in checkpoints {
+            if *checkpoint.sequence_number() != highest_synced.sequence_number().saturating_add(1) {
+                return Err(anyhow!(
+                    "archive is missing checkpoint {}",
+                    highest_synced.sequence_number().saturating_add(1)
+                ));
+            }
+            let checkpoint =
+                verify_checkpoint(&highest_synced, &store, checkpoint).map_err(|checkpoint| {
+                    anyhow!("unable to verify archived checkpoint {checkpoint:?}")
+                })?;
+            contents
+                .contents
+                .verify_digests(checkpoint.content_digest)?;
+            // if this fails, there is a bug in checkpoint construction (or the chain is
+            // corrupted)
+            assert_eq!(
+                highest_synced.network_total_transactions + contents.contents.size() as u64,
+                checkpoint.network_total_transactions
+            );
+
+            store
+                .insert_checkpoint(checkpoint.clone())
+                .expect("store operation should not fail");
+            store
+                .insert_checkpoint_contents(VerifiedCheckpointContents::new_unchecked(
+                    contents.contents,
+                ))
+                .expect("store operation should not fail");
+            store
+                .update_highest_synced_checkpoint(&checkpoint)
+                .expect("store operation should not fail");
+            metrics.set_highest_verified_checkpoint(*checkpoint.sequence_number());
+            metrics.set_highest_synced_checkpoint(*checkpoint.sequence_number());
+            // We don't care if no one is listening as this is a broadcast channel
+            let _ = checkpoint_event_sender.send(checkpoint.clone());
+            highest_synced = checkpoint;
+        }
     }
 
-    let committee = store
-        .get_committee(checkpoint.epoch())
-        .expect("store operation should not fail")
-        .expect("BUG: should have a committee for an epoch before we try to verify checkpoints from an epoch");
+    // Notify event loop to notify our peers that we've synced to a new checkpoint height
+    if let Some(sender) = sender.upgrade() {
+        let message = StateSyncMessage::SyncedCheckpoint(Box::new(highest_synced));
+        let _ = sender.send(message).await;
+    }
 
-    checkpoint.verify_signature(&committee).map_err(|e| {
-        debug!("error verifying checkpoint: {e}");
-        checkpoint.clone()
-    })?;
-    Ok(VerifiedCheckpoint::new_unchecked(checkpoint))
+    Ok(())
 }
 
 async fn sync_checkpoint_contents<S>(
@@ -1060,8 +1195,12 @@ where
         .read()
         .unwrap()
         .peers_on_same_chain()
-        // Filter out any peers who can't help with this particular checkpoint
-        .filter(|(_peer_id, info)| info.height >= *checkpoint.sequence_number())
+        // Filter out any peers who can't help with this particular checkpoint, either because
+        // they haven't synced it yet or because they pruned its contents
+        .filter(|(_peer_id, info)| {
+            info.height >= *checkpoint.sequence_number()
+                && info.lowest <= *checkpoint.sequence_number()
+        })
         // Filter out any peers who we aren't connected with
         .flat_map(|(peer_id, _height)| network.peer(*peer_id))
         .map(StateSyncClient::new)
@@ -23,6 +23,13 @@ pub enum GetCheckpointSummaryRequest {
     BySequenceNumber(CheckpointSequenceNumber),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetCheckpointAvailabilityResponse {
+    pub(crate) highest_synced_checkpoint: Checkpoint,
+    /// Checkpoints below this one had their contents pruned and can't be served.
+    pub(crate) lowest_available_checkpoint: CheckpointSequenceNumber,
+}
+
 pub(super) struct Server<S> {
     pub(super) store: S,
     pub(super) peer_heights: Arc<RwLock<PeerHeights>>,
@@ -50,7 +57,7 @@ where
             .peer_heights
             .write()
             .unwrap()
-            .update_peer_info(peer_id, checkpoint.clone())
+            .update_peer_info(peer_id, checkpoint.clone(), None)
         {
             return Ok(Response::new(()));
         }
@@ -93,10 +100,32 @@ where
         Ok(Response::new(checkpoint))
     }
 
+    async fn get_checkpoint_availability(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<GetCheckpointAvailabilityResponse>, Status> {
+        let highest_synced_checkpoint = self
+            .store
+            .get_highest_synced_checkpoint()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_inner();
+        let lowest_available_checkpoint = self
+            .store
+            .get_lowest_available_checkpoint()
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(GetCheckpointAvailabilityResponse {
+            highest_synced_checkpoint,
+            lowest_available_checkpoint,
+        }))
+    }
+
     async fn get_checkpoint_contents(
         &self,
         request: Request<CheckpointContentsDigest>,
     ) -> Result<Response<Option<FullCheckpointContents>>, Status> {
+        // Contents of pruned checkpoints are missing from the store, along with their
+        // transactions, so they are never served even while pruning is in progress.
         let contents = self
             .store
             .get_full_checkpoint_contents(request.inner())
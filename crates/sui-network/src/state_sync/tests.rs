@@ -11,6 +11,8 @@ use crate::{
 };
 use anemo::{PeerId, Request};
 use std::{collections::HashMap, time::Duration};
+use sui_config::p2p::StateSyncConfig;
+use sui_storage::{archive::writer::ArchiveWriter, object_store::ObjectStoreConfig};
 use sui_types::{
     messages_checkpoint::CheckpointDigest,
     storage::{ReadStore, SharedInMemoryStore, WriteStore},
@@ -46,6 +48,7 @@ async fn server_push_checkpoint() {
             genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
             on_same_chain_as_us: true,
             height: 0,
+            lowest: 0,
         },
     );
 
@@ -59,6 +62,7 @@ async fn server_push_checkpoint() {
             genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
             on_same_chain_as_us: true,
             height: 1,
+            lowest: 0,
         })
     );
     assert_eq!(
@@ -169,6 +173,68 @@ async fn server_get_checkpoint() {
     }
 }
 
+#[tokio::test]
+async fn server_get_checkpoint_availability() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _sequence_number_to_digest, _checkpoints) =
+        committee.make_checkpoints(3, None);
+
+    let (builder, server) = Builder::new()
+        .store(SharedInMemoryStore::default())
+        .build_internal();
+
+    builder.store.inner_mut().insert_genesis_state(
+        ordered_checkpoints.first().cloned().unwrap(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    for checkpoint in ordered_checkpoints.clone() {
+        builder.store.inner_mut().insert_checkpoint(checkpoint)
+    }
+    let latest = ordered_checkpoints.last().unwrap().clone();
+    builder
+        .store
+        .inner_mut()
+        .update_highest_synced_checkpoint(&latest);
+    let content_digest = latest.content_digest;
+
+    let response = server
+        .get_checkpoint_availability(Request::new(()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.highest_synced_checkpoint.data(), latest.data());
+    assert_eq!(response.lowest_available_checkpoint, 0);
+    let response = server
+        .get_checkpoint_contents(Request::new(content_digest))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.is_some());
+
+    // Once pruned, contents are no longer advertised nor served
+    builder
+        .store
+        .inner_mut()
+        .prune_checkpoint_contents(*latest.sequence_number());
+    let response = server
+        .get_checkpoint_availability(Request::new(()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.highest_synced_checkpoint.data(), latest.data());
+    assert_eq!(
+        response.lowest_available_checkpoint,
+        *latest.sequence_number() + 1
+    );
+    let response = server
+        .get_checkpoint_contents(Request::new(content_digest))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.is_none());
+}
+
 #[tokio::test]
 async fn isolated_sync_job() {
     let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
@@ -212,6 +278,7 @@ async fn isolated_sync_job() {
             genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
             on_same_chain_as_us: true,
             height: *ordered_checkpoints.last().unwrap().sequence_number(),
+            lowest: 0,
         },
     );
     event_loop_1
@@ -292,6 +359,7 @@ async fn sync_with_checkpoints_being_inserted() {
             genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
             on_same_chain_as_us: true,
             height: 0,
+            lowest: 0,
         },
     );
     // Start both event loops
@@ -380,3 +448,78 @@ async fn sync_with_checkpoints_being_inserted() {
         &sequence_number_to_digest
     );
 }
+
+#[tokio::test]
+async fn sync_from_archive() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _sequence_number_to_digest, _checkpoints) =
+        committee.make_checkpoints(10, None);
+
+    // Archive all the checkpoints
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive_config = ObjectStoreConfig::local(archive_dir.path());
+    let archived_store = SharedInMemoryStore::default();
+    archived_store.inner_mut().insert_genesis_state(
+        ordered_checkpoints.first().cloned().unwrap(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    for checkpoint in &ordered_checkpoints[1..] {
+        archived_store
+            .insert_checkpoint(checkpoint.clone())
+            .unwrap();
+        archived_store
+            .update_highest_synced_checkpoint(checkpoint)
+            .unwrap();
+    }
+    let mut writer = ArchiveWriter::new(
+        archived_store,
+        &archive_config,
+        usize::MAX,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )
+    .unwrap();
+    writer.sync().await.unwrap();
+    writer.flush().await.unwrap();
+
+    // Build a node without any peers
+    let config = StateSyncConfig {
+        interval_period_ms: Some(100),
+        archive_object_store_config: Some(archive_config),
+        ..Default::default()
+    };
+    let (builder, server) = Builder::new()
+        .store(SharedInMemoryStore::default())
+        .config(config)
+        .build();
+    let network = build_network(|router| router.add_rpc_service(server));
+    let (event_loop, handle) = builder.build(network);
+    event_loop.store.inner_mut().insert_genesis_state(
+        ordered_checkpoints.first().cloned().unwrap(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    let store = event_loop.store.clone();
+
+    let mut subscriber = handle.subscribe_to_synced_checkpoints();
+    tokio::spawn(event_loop.start());
+
+    // Checkpoints are synced from the archive once syncing from peers stalls
+    timeout(Duration::from_secs(5), async {
+        for checkpoint in &ordered_checkpoints[1..] {
+            assert_eq!(subscriber.recv().await.unwrap().data(), checkpoint.data());
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        store.get_highest_synced_checkpoint().unwrap().data(),
+        ordered_checkpoints.last().unwrap().data()
+    );
+    assert_eq!(
+        store.get_highest_verified_checkpoint().unwrap().data(),
+        ordered_checkpoints.last().unwrap().data()
+    );
+}
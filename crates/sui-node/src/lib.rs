@@ -81,6 +81,7 @@ use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
+use sui_storage::archive::writer::ArchiveWriter;
 use sui_storage::IndexStore;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::committee::Committee;
@@ -134,6 +135,8 @@ pub struct SuiNode {
 
     _db_checkpoint_handle: Option<Sender<()>>,
 
+    _checkpoint_archive_handle: Option<Sender<()>>,
+
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
 
@@ -165,6 +168,11 @@ impl SuiNode {
 
         let is_validator = config.consensus_config().is_some();
         let is_full_node = !is_validator;
+        if is_validator && config.authority_store_pruning_config.prunes_history() {
+            return Err(anyhow!(
+                "Transaction history can only be pruned on fullnodes, validators must retain it"
+            ));
+        }
         let prometheus_registry = registry_service.default_registry();
 
         info!(node =? config.protocol_public_key(),
@@ -251,6 +259,20 @@ impl SuiNode {
             Some(Arc::new(IndexStore::new(config.db_path().join("indexes"))))
         };
 
+        let checkpoint_archive_handle = match &config.checkpoint_archive_config {
+            Some(archive_config) => {
+                let writer = ArchiveWriter::new(
+                    state_sync_store.clone(),
+                    &archive_config.object_store_config,
+                    archive_config.commit_file_size,
+                    Duration::from_secs(archive_config.commit_duration_seconds),
+                    Duration::from_secs(5),
+                )?;
+                Some(writer.start().await?)
+            }
+            None => None,
+        };
+
         // Create network
         // TODO only configure validators as seed/preferred peers for validators and not for
         // fullnodes once we've had a chance to re-work fullnode configuration generation.
@@ -418,6 +440,7 @@ impl SuiNode {
             trusted_peer_change_tx,
 
             _db_checkpoint_handle: db_checkpoint_handle,
+            _checkpoint_archive_handle: checkpoint_archive_handle,
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
             #[cfg(msim)]
@@ -877,6 +900,7 @@ impl SuiNode {
             self.state.database.clone(),
             self.state.transaction_manager().clone(),
             self.accumulator.clone(),
+            self.state.get_indexes().ok(),
             self.config.checkpoint_executor_config.clone(),
             &self.registry_service.default_registry(),
         );
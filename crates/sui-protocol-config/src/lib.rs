@@ -1016,6 +1016,9 @@ impl ProtocolConfig {
     pub fn set_package_upgrades_for_testing(&mut self, val: bool) {
         self.feature_flags.package_upgrades = val
     }
+    pub fn set_max_tx_size_bytes_for_testing(&mut self, m: u64) {
+        self.max_tx_size_bytes = Some(m)
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
@@ -16,7 +16,7 @@ use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
     Amount, Coin, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
 /// at which the balance lookup was performed.
@@ -45,13 +45,34 @@ pub async fn balance(
             ctx.blocks().current_block_identifier().await?
         };
 
-        ctx.blocks()
-            .get_balance_at_block(address, block_identifier.index)
-            .await
-            .map(|balance| AccountBalanceResponse {
-                block_identifier,
-                balances: vec![Amount::new(balance)],
-            })
+        // Only the SUI balance is returned when no currency is requested.
+        let currencies = if request.currencies.is_empty() {
+            vec![SUI.clone()]
+        } else {
+            request.currencies
+        };
+        let mut balances = vec![];
+        for requested in currencies {
+            let currency = ctx
+                .coin_metadata_cache
+                .get_currency(&requested.coin_type()?)
+                .await?;
+            if (&requested.symbol, requested.decimals) != (&currency.symbol, currency.decimals) {
+                return Err(Error::InvalidInput(format!(
+                    "Currency [{}] does not match its coin type, expected symbol [{}] and {} decimals",
+                    requested.symbol, currency.symbol, currency.decimals
+                )));
+            }
+            let balance = ctx
+                .blocks()
+                .get_balance_at_block(address, &currency, block_identifier.index)
+                .await?;
+            balances.push(Amount::new_with_currency(balance, currency));
+        }
+        Ok(AccountBalanceResponse {
+            block_identifier,
+            balances,
+        })
     }
 }
 
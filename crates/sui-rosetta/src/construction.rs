@@ -222,6 +222,30 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![], 5_000_000)
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let amount = amounts.iter().sum::<u64>();
+            let coin_refs = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    *sender,
+                    Some(currency.coin_type()?.to_string()),
+                    amount.into(),
+                    None,
+                    vec![],
+                )
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            // The coins paid are selected above, only gas is paid in SUI.
+            (Some(0), coin_refs, 5_000_000)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![], 100_000_000),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
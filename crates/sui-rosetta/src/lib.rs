@@ -12,11 +12,11 @@ use tokio::task::JoinHandle;
 use tracing::info;
 
 use mysten_metrics::spawn_monitored_task;
-use sui_sdk::SuiClient;
+use sui_sdk::{SuiClient, SUI_COIN_TYPE};
 
 use crate::errors::Error;
 use crate::state::{CheckpointBlockProvider, OnlineServerContext};
-use crate::types::{Currency, SuiEnv};
+use crate::types::{Currency, CurrencyMetadata, SuiEnv};
 
 /// This lib implements the Rosetta online and offline server defined by the [Rosetta API Spec](https://www.rosetta-api.org/docs/Reference.html)
 mod account;
@@ -28,9 +28,14 @@ pub mod operations;
 mod state;
 pub mod types;
 
+pub use crate::state::CoinMetadataCache;
+
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: Some(CurrencyMetadata {
+        coin_type: SUI_COIN_TYPE.to_string(),
+    }),
 });
 
 pub struct RosettaOnlineServer {
@@ -40,10 +45,15 @@ pub struct RosettaOnlineServer {
 
 impl RosettaOnlineServer {
     pub fn new(env: SuiEnv, client: SuiClient, data_path: &Path) -> Self {
-        let blocks = Arc::new(CheckpointBlockProvider::spawn(client.clone(), data_path));
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        let blocks = Arc::new(CheckpointBlockProvider::spawn(
+            client.clone(),
+            coin_metadata_cache.clone(),
+            data_path,
+        ));
         Self {
             env,
-            context: OnlineServerContext::new(client, blocks),
+            context: OnlineServerContext::new(client, blocks, coin_metadata_cache),
         }
     }
 
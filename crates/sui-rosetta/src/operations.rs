@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::ops::Not;
 use std::str::FromStr;
 use std::vec;
@@ -30,10 +30,10 @@ use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{CoinMetadataCache, Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -101,6 +101,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -108,11 +109,49 @@ impl Operations {
     }
 
     fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops()?;
+        if !currency.is_sui() {
+            return Err(Error::InvalidInput(format!(
+                "PaySui cannot pay {}, use PayCoin instead",
+                currency.symbol
+            )));
+        }
+        Ok(InternalOperation::PaySui {
+            sender,
+            recipients,
+            amounts,
+        })
+    }
+
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops()?;
+        if currency.is_sui() {
+            return Err(Error::InvalidInput(
+                "PayCoin cannot pay SUI, use PaySui instead".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
+    /// Extract the sender, recipients, amounts and currency of pay operations, all amounts must be
+    /// in the same currency.
+    fn pay_ops(self) -> Result<(SuiAddress, Vec<SuiAddress>, Vec<u64>, Currency), Error> {
         let mut recipients = vec![];
         let mut amounts = vec![];
         let mut sender = None;
+        let mut currency = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                if currency.get_or_insert_with(|| amount.currency.clone()) != &amount.currency {
+                    return Err(Error::InvalidInput(
+                        "Pay operations should use the same currency".to_string(),
+                    ));
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -128,11 +167,10 @@ impl Operations {
             }
         }
         let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
-        Ok(InternalOperation::PaySui {
-            sender,
-            recipients,
-            amounts,
-        })
+        let currency = currency.ok_or_else(|| Error::MissingInput("Amount".to_string()))?;
+        // Reject currencies which do not identify their coin type.
+        currency.coin_type()?;
+        Ok((sender, recipients, amounts, currency))
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
@@ -213,19 +251,24 @@ impl Operations {
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, currency)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
     }
 
+    /// Parse the operations of a programmable transaction. Input coins cannot be told apart from
+    /// the transaction alone, so paying from an input coin is only parsed as PayCoin when the
+    /// `currency` of the coin is given.
     fn parse_programmable_transaction(
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
@@ -245,6 +288,7 @@ impl Operations {
             known_results: &[Vec<KnownValue>],
             coin: SuiArgument,
             amounts: &[SuiArgument],
+            paid_coin: Option<u16>,
         ) -> Option<Vec<KnownValue>> {
             match coin {
                 SuiArgument::Result(i) => {
@@ -253,9 +297,12 @@ impl Operations {
                 SuiArgument::NestedResult(i, j) => {
                     let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
                 }
-                SuiArgument::GasCoin => (),
+                SuiArgument::GasCoin if paid_coin.is_none() => (),
+                // Values split from the paid coin are tracked like gas coin values, the paid coin
+                // being the only coin the transaction splits.
+                SuiArgument::Input(i) if paid_coin == Some(i) => (),
                 // Might not be a SUI coin
-                SuiArgument::Input(_) => return None,
+                SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
             };
             let amounts = amounts
                 .iter()
@@ -357,6 +404,13 @@ impl Operations {
             Ok(id.cloned())
         }
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
+        // The input coin paying the recipients of a PayCoin transaction, which merges all the
+        // input coins into the first one before splitting it.
+        let paid_coin = currency.as_ref().and_then(|_| match commands.first() {
+            Some(SuiCommand::MergeCoins(SuiArgument::Input(i), _))
+            | Some(SuiCommand::SplitCoins(SuiArgument::Input(i), _)) => Some(*i),
+            _ => None,
+        });
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
         let mut needs_generic = false;
@@ -365,7 +419,13 @@ impl Operations {
         for command in commands {
             let result = match command {
                 SuiCommand::SplitCoins(coin, amounts) => {
-                    split_coins(inputs, &known_results, *coin, amounts)
+                    split_coins(inputs, &known_results, *coin, amounts, paid_coin)
+                }
+                SuiCommand::MergeCoins(SuiArgument::Input(i), coins)
+                    if paid_coin == Some(*i)
+                        && coins.iter().all(|c| matches!(c, SuiArgument::Input(_))) =>
+                {
+                    Some(vec![])
                 }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
@@ -406,14 +466,18 @@ impl Operations {
 
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
+            let pay = |address: SuiAddress, amount: i128| match (paid_coin, &currency) {
+                (Some(_), Some(currency)) => {
+                    Operation::pay_coin(status, address, amount, currency.clone())
+                }
+                _ => Operation::pay_sui(status, address, amount),
+            };
             operations.extend(
                 aggregated_recipients
                     .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
+                    .map(|(recipient, amount)| pay(recipient, amount.into())),
             );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            operations.push(pay(sender, -(total_paid as i128)));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -451,31 +515,32 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
-    fn process_balance_change(
+    async fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
-    ) -> impl Iterator<Item = Operation> {
-        let mut balances = balance_changes
-            .iter()
-            .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
-                }
-                balances
-            });
+        mut balances: HashMap<(SuiAddress, Currency), i128>,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<impl Iterator<Item = Operation>, Error> {
+        for balance_change in balance_changes {
+            // Rosetta only care about address owner
+            if let Owner::AddressOwner(owner) = balance_change.owner {
+                let currency = coin_metadata_cache
+                    .get_currency(&balance_change.coin_type)
+                    .await?;
+                *balances.entry((owner, currency)).or_default() += balance_change.amount;
+            }
+        }
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                let amount = Amount::new_with_currency(amount, currency);
+                Operation::balance_change(status, addr, amount)
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -483,25 +548,28 @@ impl Operations {
             // Gas can be 0 for system tx
             vec![]
         };
-        balance_change.chain(gas)
+        Ok(balance_change.chain(gas))
     }
-}
 
-impl TryFrom<SuiTransactionBlockData> for Operations {
-    type Error = Error;
-    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+    fn try_from_data(
+        data: SuiTransactionBlockData,
+        currency: Option<Currency>,
+    ) -> Result<Self, Error> {
         let sender = *data.sender();
         Ok(Self::new(Self::from_transaction(
             data.transaction().clone(),
             sender,
             None,
+            currency,
         )?))
     }
-}
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+    /// Create the operations of an executed transaction, including the balance changes of all
+    /// coin types, resolving their currencies with `coin_metadata_cache`.
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -516,7 +584,28 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.computation_cost as i128;
 
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+        let balance_changes = response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+
+        // A transaction paying from input coins pays the only coin type, other than SUI, that the
+        // sender spent.
+        let mut spent_coin_types = balance_changes
+            .iter()
+            .filter(|change| {
+                change.owner == Owner::AddressOwner(sender)
+                    && change.amount < 0
+                    && change.coin_type != GAS::type_tag()
+            })
+            .map(|change| &change.coin_type)
+            .collect::<BTreeSet<_>>()
+            .into_iter();
+        let currency = match (spent_coin_types.next(), spent_coin_types.next()) {
+            (Some(coin_type), None) => Some(coin_metadata_cache.get_currency(coin_type).await?),
+            _ => None,
+        };
+
+        let ops = Self::try_from_data(tx.data, currency)?;
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
@@ -528,7 +617,9 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -560,8 +651,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -574,12 +665,12 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            &balance_changes,
             status,
             accounted_balances,
-        );
+            coin_metadata_cache,
+        )
+        .await?;
 
         Ok(ops
             .into_iter()
@@ -589,22 +680,30 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
     }
 }
 
+impl TryFrom<SuiTransactionBlockData> for Operations {
+    type Error = Error;
+    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+        Self::try_from_data(data, None)
+    }
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
         && tag.name.as_ident_str() == ident_str!("UnstakingRequestEvent")
 }
 
+struct NoOpsModuleResolver;
+impl ModuleResolver for NoOpsModuleResolver {
+    type Error = Error;
+    fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
 impl TryFrom<TransactionData> for Operations {
     type Error = Error;
     fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
-        struct NoOpsModuleResolver;
-        impl ModuleResolver for NoOpsModuleResolver {
-            type Error = Error;
-            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
-                Ok(None)
-            }
-        }
         // Rosetta don't need the call args to be parsed into readable format
         SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?.try_into()
     }
@@ -694,13 +793,30 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: Amount) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(amount),
             coin_change: None,
             metadata: None,
         }
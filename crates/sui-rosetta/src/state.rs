@@ -3,23 +3,25 @@
 
 use crate::operations::Operations;
 use crate::types::{
-    Block, BlockHash, BlockIdentifier, BlockResponse, OperationStatus, OperationType, Transaction,
-    TransactionIdentifier,
+    Block, BlockHash, BlockIdentifier, BlockResponse, Currency, CurrencyMetadata, OperationStatus,
+    OperationType, Transaction, TransactionIdentifier,
 };
-use crate::Error;
+use crate::{Error, SUI};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use rocksdb::Options;
 use std::collections::HashMap;
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, UNIX_EPOCH};
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::rpc_types::Checkpoint;
 use sui_sdk::SuiClient;
 use sui_types::base_types::{EpochId, SuiAddress};
+use sui_types::gas_coin::GAS;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use tracing::{debug, error, info, warn};
 use typed_store::rocks::{point_lookup_db_options, DBMap, DBOptions, MetricConf};
@@ -35,13 +37,19 @@ mod balance_changing_tx_tests;
 #[derive(Clone)]
 pub struct OnlineServerContext {
     pub client: SuiClient,
+    pub coin_metadata_cache: CoinMetadataCache,
     block_provider: Arc<dyn BlockProvider + Send + Sync>,
 }
 
 impl OnlineServerContext {
-    pub fn new(client: SuiClient, block_provider: Arc<dyn BlockProvider + Send + Sync>) -> Self {
+    pub fn new(
+        client: SuiClient,
+        block_provider: Arc<dyn BlockProvider + Send + Sync>,
+        coin_metadata_cache: CoinMetadataCache,
+    ) -> Self {
         Self {
             client,
+            coin_metadata_cache,
             block_provider,
         }
     }
@@ -62,14 +70,77 @@ pub trait BlockProvider {
     async fn get_balance_at_block(
         &self,
         addr: SuiAddress,
+        currency: &Currency,
         block_height: u64,
     ) -> Result<i128, Error>;
 }
 
+/// Resolves the Rosetta [Currency] of coin types from their `CoinMetadata`, fetched once per coin
+/// type.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<HashMap<TypeTag, Currency>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Default::default(),
+        }
+    }
+
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Currency, Error> {
+        if coin_type == &GAS::type_tag() {
+            return Ok(SUI.clone());
+        }
+        if let Some(currency) = self.currencies.lock().unwrap().get(coin_type) {
+            return Ok(currency.clone());
+        }
+        let metadata = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type.to_string())
+            .await;
+        let currency = match metadata {
+            Ok(metadata) => {
+                let currency = Currency {
+                    symbol: metadata.symbol,
+                    decimals: metadata.decimals.into(),
+                    metadata: Some(CurrencyMetadata {
+                        coin_type: coin_type.to_string(),
+                    }),
+                };
+                self.currencies
+                    .lock()
+                    .unwrap()
+                    .insert(coin_type.clone(), currency.clone());
+                currency
+            }
+            // Coins are not required to have a `CoinMetadata`, their amounts are then reported in
+            // their smallest unit. This is not cached, as the lookup may have failed for other
+            // reasons.
+            Err(e) => {
+                debug!("No coin metadata for [{coin_type}], cause: {e:?}");
+                Currency {
+                    symbol: coin_type.to_string(),
+                    decimals: 0,
+                    metadata: Some(CurrencyMetadata {
+                        coin_type: coin_type.to_string(),
+                    }),
+                }
+            }
+        };
+        Ok(currency)
+    }
+}
+
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     index_store: Arc<CheckpointIndexStore>,
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -105,32 +176,64 @@ impl BlockProvider for CheckpointBlockProvider {
     async fn get_balance_at_block(
         &self,
         addr: SuiAddress,
+        currency: &Currency,
+        block_height: u64,
+    ) -> Result<i128, Error> {
+        self.balance_at_block(addr, &currency.coin_type()?, block_height)
+    }
+}
+
+impl CheckpointBlockProvider {
+    fn balance_at_block(
+        &self,
+        addr: SuiAddress,
+        coin_type: &TypeTag,
         block_height: u64,
     ) -> Result<i128, Error> {
+        if coin_type == &GAS::type_tag() {
+            return Ok(self
+                .index_store
+                .balances
+                .iter()
+                .skip_prior_to(&(addr, block_height))?
+                .next()
+                .and_then(
+                    |((address, _), balance)| {
+                        if address == addr {
+                            Some(balance)
+                        } else {
+                            None
+                        }
+                    },
+                )
+                .unwrap_or_default());
+        }
+        let coin_type = coin_type.to_string();
         Ok(self
             .index_store
-            .balances
+            .coin_balances
             .iter()
-            .skip_prior_to(&(addr, block_height))?
+            .skip_prior_to(&(addr, coin_type.clone(), block_height))?
             .next()
-            .and_then(
-                |((address, _), balance)| {
-                    if address == addr {
-                        Some(balance)
-                    } else {
-                        None
-                    }
-                },
-            )
+            .and_then(|((address, type_, _), balance)| {
+                if address == addr && type_ == coin_type {
+                    Some(balance)
+                } else {
+                    None
+                }
+            })
             .unwrap_or_default())
     }
-}
 
-impl CheckpointBlockProvider {
-    pub fn spawn(client: SuiClient, db_path: &Path) -> Self {
+    pub fn spawn(
+        client: SuiClient,
+        coin_metadata_cache: CoinMetadataCache,
+        db_path: &Path,
+    ) -> Self {
         let blocks = Self {
             index_store: Arc::new(CheckpointIndexStore::open(db_path, None)),
             client,
+            coin_metadata_cache,
         };
 
         let update_interval = option_env!("CHECKPOINT_UPDATE_INTERVAL")
@@ -152,10 +255,18 @@ impl CheckpointBlockProvider {
                     }
                 }
                 let resp = f.create_block_response(checkpoint.unwrap()).await.unwrap();
-                f.update_balance(resp.block).await.unwrap();
+                f.update_balance(resp.block, true).await.unwrap();
+                f.index_store
+                    .coin_balances_checkpoint
+                    .insert(&true, &0)
+                    .unwrap();
             } else {
                 let current_block = f.current_block_identifier().await.unwrap();
                 info!("Resuming from block {}", current_block.index);
+                while let Err(e) = f.backfill_coin_balances().await {
+                    error!("Error indexing coin balances, cause: {e:?}");
+                    tokio::time::sleep(update_interval).await;
+                }
             };
             loop {
                 if let Err(e) = f.index_checkpoints().await {
@@ -185,8 +296,11 @@ impl CheckpointBlockProvider {
                     DateTime::<Utc>::from(timestamp).format("%Y-%m-%d %H:%M:%S")
                 );
                 let resp = self.create_block_response(checkpoint).await?;
-                self.update_balance(resp.block).await?;
-                self.index_store.last_checkpoint.insert(&true, &seq)?;
+                self.update_balance(resp.block, true).await?;
+                let mut batch = self.index_store.last_checkpoint.batch();
+                batch.insert_batch(&self.index_store.last_checkpoint, [(true, seq)])?;
+                batch.insert_batch(&self.index_store.coin_balances_checkpoint, [(true, seq)])?;
+                batch.write()?;
             }
         } else {
             debug!("No new checkpoints.")
@@ -194,35 +308,62 @@ impl CheckpointBlockProvider {
         Ok(())
     }
 
-    async fn update_balance(&self, block: Block) -> Result<(), anyhow::Error> {
+    /// Index the balances of coin types other than SUI of the checkpoints which were indexed
+    /// before they were supported, or since the last checkpoint whose coin balances were indexed.
+    async fn backfill_coin_balances(&self) -> Result<(), Error> {
+        let start = match self.index_store.coin_balances_checkpoint.get(&true)? {
+            Some(checkpoint) => checkpoint + 1,
+            None => 0,
+        };
+        let last_checkpoint = self.last_indexed_checkpoint()?;
+        for seq in start..=last_checkpoint {
+            let checkpoint = self.client.read_api().get_checkpoint(seq.into()).await?;
+            info!("indexing coin balances of checkpoint {seq}");
+            let resp = self.create_block_response(checkpoint).await?;
+            self.update_balance(resp.block, false).await?;
+            self.index_store
+                .coin_balances_checkpoint
+                .insert(&true, &seq)?;
+        }
+        Ok(())
+    }
+
+    /// Index the balance changes of `block`, skipping SUI balances unless `include_sui` is set.
+    async fn update_balance(&self, block: Block, include_sui: bool) -> Result<(), anyhow::Error> {
         let block_height = block.block_identifier.index;
         let last_block_height = if block_height == 0 {
             0
         } else {
             block_height - 1
         };
-        let balances: HashMap<SuiAddress, i128> =
-            block
-                .transactions
-                .into_iter()
-                .fold(HashMap::new(), |mut changes, tx| {
-                    for (address, balance) in extract_balance_changes_from_ops(tx.operations) {
-                        *changes.entry(address).or_default() += balance;
-                    }
-                    changes
-                });
+        let mut balances: HashMap<(SuiAddress, TypeTag), i128> = HashMap::new();
+        for tx in block.transactions {
+            for (key, balance) in extract_balance_changes_from_ops(tx.operations)? {
+                *balances.entry(key).or_default() += balance;
+            }
+        }
 
-        for (addr, value) in balances {
-            let current_balance = self.get_balance_at_block(addr, last_block_height).await?;
+        for ((addr, coin_type), value) in balances {
+            let is_sui = coin_type == GAS::type_tag();
+            if is_sui && !include_sui {
+                continue;
+            }
+            let current_balance = self.balance_at_block(addr, &coin_type, last_block_height)?;
             let new_balance = current_balance + value;
             if new_balance < 0 {
                 // This can happen due to missing transactions data due to unstable validators, causing balance to
                 // fall below zero temporarily. The problem should go away when we start using checkpoints for event and indexing
-                warn!("Account gas value fall below 0 at block {block_height}, address: [{addr}], current balance = {current_balance}, balance change = {value}.");
+                warn!("Account [{coin_type}] value fall below 0 at block {block_height}, address: [{addr}], current balance = {current_balance}, balance change = {value}.");
+            }
+            if is_sui {
+                self.index_store
+                    .balances
+                    .insert(&(addr, block_height), &new_balance)?;
+            } else {
+                self.index_store
+                    .coin_balances
+                    .insert(&(addr, coin_type.to_string(), block_height), &new_balance)?;
             }
-            self.index_store
-                .balances
-                .insert(&(addr, block_height), &new_balance)?;
         }
         Ok(())
     }
@@ -246,7 +387,7 @@ impl CheckpointBlockProvider {
                 .await?;
             transactions.push(Transaction {
                 transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                operations: Operations::try_from(tx)?,
+                operations: Operations::try_from_response(tx, &self.coin_metadata_cache).await?,
                 related_transactions: vec![],
                 metadata: None,
             })
@@ -303,32 +444,44 @@ impl CheckpointBlockProvider {
     }
 }
 
-fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128> {
-    ops.into_iter()
-        .fold(HashMap::<SuiAddress, i128>::new(), |mut changes, op| {
-            if let Some(OperationStatus::Success) = op.status {
-                match op.type_ {
-                    OperationType::SuiBalanceChange
-                    | OperationType::Gas
-                    | OperationType::PaySui
-                    | OperationType::StakeReward
-                    | OperationType::StakePrinciple
-                    | OperationType::Stake => {
-                        if let (Some(addr), Some(amount)) = (op.account, op.amount) {
-                            *changes.entry(addr.address).or_default() += amount.value
-                        }
+fn extract_balance_changes_from_ops(
+    ops: Operations,
+) -> Result<HashMap<(SuiAddress, TypeTag), i128>, Error> {
+    let mut changes = HashMap::<(SuiAddress, TypeTag), i128>::new();
+    for op in ops {
+        if let Some(OperationStatus::Success) = op.status {
+            match op.type_ {
+                OperationType::SuiBalanceChange
+                | OperationType::Gas
+                | OperationType::PaySui
+                | OperationType::PayCoin
+                | OperationType::StakeReward
+                | OperationType::StakePrinciple
+                | OperationType::Stake => {
+                    if let (Some(addr), Some(amount)) = (op.account, op.amount) {
+                        *changes
+                            .entry((addr.address, amount.currency.coin_type()?))
+                            .or_default() += amount.value
                     }
-                    _ => {}
-                };
-            }
-            changes
-        })
+                }
+                _ => {}
+            };
+        }
+    }
+    Ok(changes)
 }
 
 #[derive(DBMapUtils)]
 pub struct CheckpointIndexStore {
     #[default_options_override_fn = "default_config"]
     balances: DBMap<(SuiAddress, EpochId), i128>,
+    /// Balances of coin types other than SUI, keyed by address, coin type and checkpoint.
+    #[default_options_override_fn = "default_config"]
+    coin_balances: DBMap<(SuiAddress, String, CheckpointSequenceNumber), i128>,
+    /// The last checkpoint whose balances of coin types other than SUI are indexed, which can be
+    /// behind `last_checkpoint` for stores created before these were indexed.
+    #[default_options_override_fn = "default_config"]
+    coin_balances_checkpoint: DBMap<bool, CheckpointSequenceNumber>,
     #[default_options_override_fn = "default_config"]
     last_checkpoint: DBMap<bool, CheckpointSequenceNumber>,
 }
@@ -8,6 +8,7 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use fastcrypto::encoding::Hex;
 use fastcrypto::traits::ToFromBytes;
+use move_core_types::language_storage::TypeTag;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
@@ -19,13 +20,15 @@ use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{Argument, CallArg, Command, ObjectArg, TransactionData};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::{
-    SUI_SYSTEM_PACKAGE_ID, SUI_SYSTEM_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+    parse_sui_type_tag, SUI_SYSTEM_PACKAGE_ID, SUI_SYSTEM_STATE_OBJECT_ID,
+    SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
 };
 
 use crate::errors::{Error, ErrorType};
@@ -98,11 +101,42 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
+}
+
+impl Currency {
+    /// The Move type `T` of the `Coin<T>` objects holding this currency. Currencies without
+    /// metadata are only accepted for SUI, for compatibility with clients predating other coin
+    /// types, as their symbol alone does not identify a coin type.
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        match &self.metadata {
+            Some(CurrencyMetadata { coin_type }) => parse_sui_type_tag(coin_type)
+                .map_err(|e| Error::InvalidInput(format!("Invalid coin type [{coin_type}]: {e}"))),
+            None if self.symbol == SUI.symbol && self.decimals == SUI.decimals => {
+                Ok(GAS::type_tag())
+            }
+            None => Err(Error::InvalidInput(format!(
+                "Currency [{}] is ambiguous, its coin type must be set in its metadata",
+                self.symbol
+            ))),
+        }
+    }
+
+    pub fn is_sui(&self) -> bool {
+        matches!(self.coin_type(), Ok(coin_type) if coin_type == GAS::type_tag())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
 }
+
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
     pub network_identifier: NetworkIdentifier,
@@ -156,9 +190,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -402,6 +439,7 @@ pub enum OperationType {
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -543,6 +581,7 @@ pub struct ConstructionPreprocessRequest {
 #[derive(Serialize, Deserialize)]
 pub enum PreprocessMetadata {
     PaySui,
+    PayCoin,
     Delegation,
 }
 
@@ -844,6 +883,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -860,6 +905,7 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
@@ -876,6 +922,17 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            // The coins paying the recipients are selected into the metadata objects, the metadata
+            // coins only pay for gas.
+            Self::PayCoin {
+                recipients,
+                amounts,
+                ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.pay(metadata.objects, recipients, amounts)?;
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
@@ -27,7 +27,7 @@ use sui_sdk::rpc_types::{
 };
 use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::gas_coin::GasCoin;
+use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::messages::{
     CallArg, ExecuteTransactionRequestType, InputObjectKind, ObjectArg, ProgrammableTransaction,
     Transaction, TransactionData, TransactionDataAPI, TransactionKind,
@@ -38,6 +38,7 @@ use test_utils::network::TestClusterBuilder;
 
 use crate::state::extract_balance_changes_from_ops;
 use crate::types::ConstructionMetadata;
+use crate::CoinMetadataCache;
 
 #[tokio::test]
 async fn test_transfer_sui() {
@@ -730,15 +731,18 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
-    let balances_from_ops = extract_balance_changes_from_ops(ops);
+    let coin_cache = CoinMetadataCache::new(client.clone());
+    let ops = Operations::try_from_response(response.clone(), &coin_cache)
+        .await
+        .unwrap();
+    let balances_from_ops = extract_balance_changes_from_ops(ops).unwrap();
 
     // get actual balance changed after transaction
     let mut actual_balance_change = HashMap::new();
     for (addr, balance) in balances {
         let new_balance = get_balance(client, addr).await as i128;
         let balance_changed = new_balance - balance as i128;
-        actual_balance_change.insert(addr, balance_changed);
+        actual_balance_change.insert((addr, GAS::type_tag()), balance_changed);
     }
     assert_eq!(
         actual_balance_change, balances_from_ops,
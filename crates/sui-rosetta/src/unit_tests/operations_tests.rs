@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_core_types::value::MoveTypeLayout;
-use sui_json_rpc_types::SuiCallArg;
+use serde_json::json;
+use sui_json_rpc_types::{SuiCallArg, SuiTransactionBlockData};
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::messages::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 
-use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::operations::{NoOpsModuleResolver, Operations};
+use crate::types::{ConstructionMetadata, Currency, CurrencyMetadata, OperationType};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -50,6 +51,100 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pay_coin_data_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let coins = (0..2)
+        .map(|_| {
+            (
+                ObjectID::random(),
+                SequenceNumber::new(),
+                ObjectDigest::random(),
+            )
+        })
+        .collect();
+
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "USDC".to_string(),
+        decimals: 6,
+        metadata: Some(CurrencyMetadata {
+            coin_type: format!("{}::usdc::USDC", ObjectID::random()),
+        }),
+    };
+
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PayCoin",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "10000" , "currency": currency }
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PayCoin",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-10000" , "currency": currency }
+        }]
+    ))?;
+    let gas_price = 10;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+
+    // The coin type of the input coins is unknown without the currency.
+    let generic_ops: Operations = data.clone().try_into()?;
+    assert_eq!(
+        Some(OperationType::ProgrammableTransaction),
+        generic_ops.type_()
+    );
+
+    let data = SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?;
+    assert_eq!(ops, Operations::try_from_data(data, Some(currency))?);
+
+    Ok(())
+}
+
+#[test]
+fn test_currency_without_metadata() {
+    let sui: Currency = serde_json::from_value(json!({ "symbol": "SUI", "decimals": 9 })).unwrap();
+    assert!(sui.is_sui());
+
+    // Only SUI can be identified without its coin type.
+    let usdc: Currency =
+        serde_json::from_value(json!({ "symbol": "USDC", "decimals": 6 })).unwrap();
+    assert!(usdc.coin_type().is_err());
+
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PayCoin",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "10000" , "currency": usdc }
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PayCoin",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-10000" , "currency": usdc }
+        }]
+    ))
+    .unwrap();
+    assert!(ops.into_internal().is_err());
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
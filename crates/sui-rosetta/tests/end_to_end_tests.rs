@@ -14,6 +14,7 @@ use sui_rosetta::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
     SubAccount, SubAccountType, SuiEnv,
 };
+use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_types::messages::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
@@ -130,6 +131,7 @@ async fn test_stake() {
     let test_cluster = TestClusterBuilder::new().build().await.unwrap();
     let sender = test_cluster.accounts[0];
     let client = test_cluster.wallet.get_client().await.unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
     let keystore = &test_cluster.wallet.config.keystore;
 
     let (rosetta_client, _handle) =
@@ -176,7 +178,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -194,6 +198,7 @@ async fn test_stake_all() {
     let test_cluster = TestClusterBuilder::new().build().await.unwrap();
     let sender = test_cluster.accounts[0];
     let client = test_cluster.wallet.get_client().await.unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
     let keystore = &test_cluster.wallet.config.keystore;
 
     let (rosetta_client, _handle) =
@@ -239,7 +244,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -261,6 +268,7 @@ async fn test_withdraw_stake() {
         .unwrap();
     let sender = test_cluster.accounts[0];
     let client = test_cluster.wallet.get_client().await.unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
     let keystore = &test_cluster.wallet.config.keystore;
 
     let (rosetta_client, _handle) =
@@ -357,7 +365,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -386,6 +396,7 @@ async fn test_pay_sui() {
     let sender = test_cluster.accounts[0];
     let recipient = test_cluster.accounts[1];
     let client = test_cluster.wallet.get_client().await.unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
     let keystore = &test_cluster.wallet.config.keystore;
 
     let (rosetta_client, _handle) =
@@ -427,7 +438,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -446,6 +459,7 @@ async fn test_pay_sui_multiple_times() {
     let sender = test_cluster.accounts[0];
     let recipient = test_cluster.accounts[1];
     let client = test_cluster.wallet.get_client().await.unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
     let keystore = &test_cluster.wallet.config.keystore;
 
     let (rosetta_client, _handle) =
@@ -487,7 +501,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &coin_cache)
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
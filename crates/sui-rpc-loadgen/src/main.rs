@@ -3,6 +3,7 @@
 
 mod load_test;
 mod payload;
+mod scenario;
 
 use anyhow::Result;
 use clap::Parser;
@@ -20,6 +21,7 @@ use crate::payload::{
     load_addresses_from_file, load_digests_from_file, load_objects_from_file, Command,
     RpcCommandProcessor, SignerInfo,
 };
+use crate::scenario::{ScenarioConfig, ScenarioRunner};
 
 #[derive(Parser)]
 #[clap(
@@ -28,7 +30,6 @@ use crate::payload::{
     about = "A load test application for Sui RPC"
 )]
 struct Opts {
-    // TODO(chris): support running multiple commands at once
     #[clap(subcommand)]
     pub command: ClapCommand,
     #[clap(long, default_value_t = 1)]
@@ -133,6 +134,17 @@ pub enum ClapCommand {
         #[clap(flatten)]
         common: CommonOptions,
     },
+    /// Run a mix of commands at a target rate, as described by a YAML or TOML scenario file
+    #[clap(name = "scenario")]
+    Scenario {
+        /// path to the scenario file, parsed as TOML if it has a `.toml` extension
+        #[clap(long)]
+        config: PathBuf,
+
+        /// where to write the JSON report, in addition to printing it
+        #[clap(long)]
+        report: Option<PathBuf>,
+    },
 }
 
 fn get_keypair() -> Result<SignerInfo> {
@@ -188,6 +200,29 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("Logging to {}", &log_filename);
     info!("Running Load Gen with following urls {:?}", opts.urls);
 
+    if let ClapCommand::Scenario { config, report } = &opts.command {
+        let config = ScenarioConfig::load(config)?;
+        let signer_info = if config.needs_keystore() {
+            Some(get_keypair()?)
+        } else {
+            None
+        };
+        let data_dir = expand_path(&opts.data_directory);
+        let processor = RpcCommandProcessor::new(&opts.urls, data_dir.clone()).await;
+        let runner = ScenarioRunner {
+            processor,
+            config,
+            data_dir,
+            signer_info,
+        };
+        let report_json = serde_json::to_string_pretty(&runner.run().await?)?;
+        println!("{report_json}");
+        if let Some(report) = report {
+            std::fs::write(report, report_json)?;
+        }
+        return Ok(());
+    }
+
     let (command, common, need_keystore) = match opts.command {
         ClapCommand::DryRun { common } => (Command::new_dry_run(), common, false),
         ClapCommand::PaySui { common } => (Command::new_pay_sui(), common, true),
@@ -252,6 +287,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             let objects = load_objects_from_file(expand_path(&opts.data_directory));
             (Command::new_get_object(objects, chunk_size), common, false)
         }
+        ClapCommand::Scenario { .. } => unreachable!("scenarios are run above"),
     };
 
     let signer_info = need_keystore.then_some(get_keypair()?);
@@ -12,7 +12,8 @@ mod pay_sui;
 mod query_transactions;
 mod rpc_command_processor;
 mod validation;
-use strum_macros::EnumString;
+use serde::Deserialize;
+use strum_macros::{EnumString, IntoStaticStr};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -24,6 +25,7 @@ use sui_types::{
 };
 
 use crate::load_test::LoadTestConfig;
+pub(crate) use rpc_command_processor::prepare_new_signer_and_coins;
 pub use rpc_command_processor::{
     load_addresses_from_file, load_digests_from_file, load_objects_from_file, RpcCommandProcessor,
 };
@@ -170,7 +172,7 @@ impl Command {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, IntoStaticStr)]
 #[allow(dead_code)]
 pub enum CommandData {
     DryRun(DryRun),
@@ -218,8 +220,9 @@ pub struct MultiGetTransactionBlocks {
     pub digests: Vec<TransactionDigest>,
 }
 
-#[derive(Clone, EnumString)]
+#[derive(Clone, Debug, EnumString, Deserialize)]
 #[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum AddressQueryType {
     From,
     To,
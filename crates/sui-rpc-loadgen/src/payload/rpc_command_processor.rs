@@ -72,7 +72,7 @@ impl RpcCommandProcessor {
         }
     }
 
-    async fn process_command_data(
+    pub(crate) async fn process_command_data(
         &self,
         command: &CommandData,
         signer_info: &Option<SignerInfo>,
@@ -90,6 +90,35 @@ impl RpcCommandProcessor {
         }
     }
 
+    /// Split the work of `command` into about `num_chunks` commands, which together cover the same
+    /// inputs. Commands whose work cannot be split are repeated `num_chunks` times.
+    pub(crate) async fn divide_command(
+        &self,
+        clients: &[SuiClient],
+        command: &Command,
+        num_chunks: usize,
+    ) -> Vec<Command> {
+        match &command.data {
+            CommandData::GetCheckpoints(data) => {
+                divide_checkpoint_tasks(clients, data, num_chunks).await
+            }
+            CommandData::QueryTransactionBlocks(data) => {
+                divide_query_transaction_blocks_tasks(data, num_chunks).await
+            }
+            CommandData::MultiGetTransactionBlocks(data) => {
+                divide_multi_get_transaction_blocks_tasks(data, num_chunks).await
+            }
+            CommandData::GetAllBalances(data) => {
+                divide_get_all_balances_tasks(data, num_chunks).await
+            }
+            CommandData::MultiGetObjects(data) => {
+                divide_multi_get_objects_tasks(data, num_chunks).await
+            }
+            CommandData::GetObject(data) => divide_get_object_tasks(data, num_chunks).await,
+            _ => vec![command.clone(); num_chunks],
+        }
+    }
+
     pub(crate) async fn get_clients(&self) -> Result<Vec<SuiClient>> {
         let read = self.clients.read().await;
         Ok(read.clone())
@@ -253,50 +282,11 @@ impl Processor for RpcCommandProcessor {
             repeat_interval,
             ..
         } = &config.command;
-        let command_payloads = match &config.command.data {
-            CommandData::GetCheckpoints(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_checkpoint_tasks(&clients, data, config.num_threads).await
-                }
-            }
-            CommandData::QueryTransactionBlocks(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_query_transaction_blocks_tasks(data, config.num_threads).await
-                }
-            }
-            CommandData::MultiGetTransactionBlocks(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_multi_get_transaction_blocks_tasks(data, config.num_threads).await
-                }
-            }
-            CommandData::GetAllBalances(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_get_all_balances_tasks(data, config.num_threads).await
-                }
-            }
-            CommandData::MultiGetObjects(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_multi_get_objects_tasks(data, config.num_threads).await
-                }
-            }
-            CommandData::GetObject(data) => {
-                if !config.divide_tasks {
-                    vec![config.command.clone(); config.num_threads]
-                } else {
-                    divide_get_object_tasks(data, config.num_threads).await
-                }
-            }
-            _ => vec![config.command.clone(); config.num_threads],
+        let command_payloads = if config.divide_tasks {
+            self.divide_command(&clients, &config.command, config.num_threads)
+                .await
+        } else {
+            vec![config.command.clone(); config.num_threads]
         };
 
         let command_payloads = command_payloads.into_iter().map(|command| {
@@ -536,7 +526,7 @@ async fn divide_get_object_tasks(data: &GetObject, num_threads: usize) -> Vec<Co
         .collect()
 }
 
-async fn prepare_new_signer_and_coins(
+pub(crate) async fn prepare_new_signer_and_coins(
     client: &SuiClient,
     signer_info: &SignerInfo,
     num_coins: usize,
@@ -0,0 +1,688 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use hdrhistogram::Histogram;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sui_types::base_types::ObjectID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::Semaphore;
+use tracing::{error, info};
+
+use crate::payload::{
+    load_addresses_from_file, load_digests_from_file, load_objects_from_file,
+    prepare_new_signer_and_coins, AddressQueryType, Command, CommandData, RpcCommandProcessor,
+    SignerInfo,
+};
+
+/// How often the scenario checks whether more requests are due.
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+/// Latencies above this many milliseconds are recorded as this value.
+const MAX_RECORDED_LATENCY_MS: u64 = 120_000;
+
+/// A load test mixing several commands, read from a YAML or TOML file, e.g.
+///
+/// ```yaml
+/// max_concurrency: 100
+/// stages:
+///   # ramp up from 0 to 50 requests per second over a minute, then hold for 5 minutes
+///   - duration_secs: 60
+///     target_qps: 50
+///   - duration_secs: 300
+///     target_qps: 50
+/// commands:
+///   - weight: 4
+///     chunks: 100
+///     payload:
+///       type: MultiGetObjects
+///   - weight: 1
+///     payload:
+///       type: GetAllBalances
+///       chunk_size: 10
+///   - weight: 1
+///     name: GetAllBalancesBatched
+///     payload:
+///       type: GetAllBalances
+///       chunk_size: 100
+/// ```
+#[derive(Deserialize, Debug)]
+pub struct ScenarioConfig {
+    /// The request rate goes through the stages in order, and the scenario ends after the last one.
+    pub stages: Vec<Stage>,
+    pub commands: Vec<ScenarioCommand>,
+    /// Maximum number of requests in flight. Requests are delayed rather than dropped once it is
+    /// reached, so the achieved rate may fall short of the target.
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct Stage {
+    pub duration_secs: u64,
+    /// The request rate changes linearly from the target of the previous stage (0 for the first
+    /// stage) to this target over the stage.
+    pub target_qps: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ScenarioCommand {
+    /// Relative share of the requests sent for this command.
+    pub weight: u32,
+    /// Name of the command in the report. Defaults to the type of its payload, numbered in order
+    /// if several commands have the same type.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The inputs of the command are split into this many chunks, and each request processes the
+    /// next chunk. Defaults to one chunk, i.e. each request processes all the inputs.
+    #[serde(default = "default_chunks")]
+    pub chunks: usize,
+    pub payload: ScenarioPayload,
+}
+
+/// The commands of the CLI which can be part of a scenario. Commands reading addresses, digests or
+/// objects load them from the data directory.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum ScenarioPayload {
+    DryRun,
+    GetCheckpoints {
+        #[serde(default)]
+        start: CheckpointSequenceNumber,
+        end: Option<CheckpointSequenceNumber>,
+        #[serde(default)]
+        verify_transactions: bool,
+        #[serde(default)]
+        verify_objects: bool,
+    },
+    PaySui,
+    QueryTransactionBlocks {
+        address_type: AddressQueryType,
+    },
+    MultiGetTransactionBlocks,
+    MultiGetObjects,
+    GetObject {
+        chunk_size: usize,
+    },
+    GetAllBalances {
+        chunk_size: usize,
+    },
+    GetReferenceGasPrice,
+}
+
+fn default_max_concurrency() -> usize {
+    100
+}
+
+fn default_chunks() -> usize {
+    1
+}
+
+impl ScenarioConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Unable to read scenario file {path:?}: {e}"))?;
+        let config: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents)?,
+            _ => serde_yaml::from_str(&contents)?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.stages.is_empty() {
+            return Err(anyhow!("Scenario must have at least one stage"));
+        }
+        if self.commands.iter().all(|command| command.weight == 0) {
+            return Err(anyhow!(
+                "Scenario must have at least one command with a weight"
+            ));
+        }
+        if self.commands.iter().any(|command| command.chunks == 0) {
+            return Err(anyhow!("Commands must be split into at least one chunk"));
+        }
+        if self.max_concurrency == 0 {
+            return Err(anyhow!("Max concurrency must be at least 1"));
+        }
+        Ok(())
+    }
+
+    pub fn needs_keystore(&self) -> bool {
+        self.commands
+            .iter()
+            .any(|command| matches!(command.payload, ScenarioPayload::PaySui))
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.stages.iter().map(|stage| stage.duration_secs).sum())
+    }
+
+    /// Time into the scenario at which the `request`-th request (counting from 1) is due, i.e. the
+    /// inverse of `expected_requests`. Requests beyond the last stage are due at its end.
+    pub fn intended_send_time(&self, request: u64) -> Duration {
+        let mut remaining = request as f64;
+        let mut previous_qps = 0.0;
+        let mut elapsed = 0.0;
+        for stage in &self.stages {
+            let duration = stage.duration_secs as f64;
+            let target_qps = stage.target_qps as f64;
+            let stage_requests = (previous_qps + target_qps) * duration / 2.0;
+            if remaining <= stage_requests {
+                // Solves previous_qps * t + (target_qps - previous_qps) * t^2 / (2 * duration)
+                // = remaining for t, in a form which also holds for a constant rate.
+                let a = (target_qps - previous_qps) / (2.0 * duration);
+                let t = 2.0 * remaining
+                    / (previous_qps + (previous_qps * previous_qps + 4.0 * a * remaining).sqrt());
+                return Duration::from_secs_f64(elapsed + t);
+            }
+            remaining -= stage_requests;
+            elapsed += duration;
+            previous_qps = target_qps;
+        }
+        Duration::from_secs_f64(elapsed)
+    }
+
+    /// Number of requests due `elapsed` into the scenario, following the request rate of the stages.
+    pub fn expected_requests(&self, elapsed: Duration) -> f64 {
+        let mut remaining = elapsed.as_secs_f64();
+        let mut previous_qps = 0.0;
+        let mut requests = 0.0;
+        for stage in &self.stages {
+            let duration = stage.duration_secs as f64;
+            let target_qps = stage.target_qps as f64;
+            let t = remaining.min(duration);
+            if duration > 0.0 {
+                requests +=
+                    previous_qps * t + (target_qps - previous_qps) * t * t / (2.0 * duration);
+            }
+            remaining -= t;
+            previous_qps = target_qps;
+            if remaining <= 0.0 {
+                break;
+            }
+        }
+        requests
+    }
+
+    fn weight_share(&self, command: &ScenarioCommand) -> f64 {
+        let total: u32 = self.commands.iter().map(|command| command.weight).sum();
+        command.weight as f64 / total as f64
+    }
+
+    /// Names of the commands in the report, given the type of each of their payloads.
+    fn command_names(&self, types: &[&str]) -> Result<Vec<String>> {
+        let names: Vec<_> = self
+            .commands
+            .iter()
+            .zip(types)
+            .enumerate()
+            .map(|(i, (command, type_))| match &command.name {
+                Some(name) => name.clone(),
+                None if types.iter().filter(|t| *t == type_).count() == 1 => type_.to_string(),
+                None => format!(
+                    "{type_}#{}",
+                    types[..i].iter().filter(|t| *t == type_).count() + 1
+                ),
+            })
+            .collect();
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(anyhow!("Several commands are named {name}"));
+            }
+        }
+        Ok(names)
+    }
+}
+
+impl ScenarioPayload {
+    fn to_command(&self, data_dir: &str) -> Command {
+        match self {
+            ScenarioPayload::DryRun => Command::new_dry_run(),
+            ScenarioPayload::GetCheckpoints {
+                start,
+                end,
+                verify_transactions,
+                verify_objects,
+            } => Command::new_get_checkpoints(
+                *start,
+                *end,
+                *verify_transactions,
+                *verify_objects,
+                false,
+            ),
+            ScenarioPayload::PaySui => Command::new_pay_sui(),
+            ScenarioPayload::QueryTransactionBlocks { address_type } => {
+                Command::new_query_transaction_blocks(
+                    address_type.clone(),
+                    load_addresses_from_file(data_dir.to_string()),
+                )
+            }
+            ScenarioPayload::MultiGetTransactionBlocks => {
+                Command::new_multi_get_transaction_blocks(load_digests_from_file(
+                    data_dir.to_string(),
+                ))
+            }
+            ScenarioPayload::MultiGetObjects => {
+                Command::new_multi_get_objects(load_objects_from_file(data_dir.to_string()))
+            }
+            ScenarioPayload::GetObject { chunk_size } => {
+                Command::new_get_object(load_objects_from_file(data_dir.to_string()), *chunk_size)
+            }
+            ScenarioPayload::GetAllBalances { chunk_size } => Command::new_get_all_balances(
+                load_addresses_from_file(data_dir.to_string()),
+                *chunk_size,
+            ),
+            ScenarioPayload::GetReferenceGasPrice => Command::new_get_reference_gas_price(1),
+        }
+    }
+}
+
+/// Latencies and errors of the requests of one command.
+struct MethodStats {
+    latency_ms: Histogram<u64>,
+    errors: u64,
+}
+
+impl Default for MethodStats {
+    fn default() -> Self {
+        Self {
+            latency_ms: Histogram::<u64>::new_with_max(MAX_RECORDED_LATENCY_MS, 3).unwrap(),
+            errors: 0,
+        }
+    }
+}
+
+/// The chunks of a command, which are sent in turn.
+struct PreparedCommand {
+    name: String,
+    commands: Vec<Command>,
+    next: AtomicUsize,
+    stats: Mutex<MethodStats>,
+}
+
+impl PreparedCommand {
+    fn next_command(&self) -> &Command {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.commands[i % self.commands.len()]
+    }
+}
+
+/// Outcome of a scenario, per command and overall.
+#[derive(Serialize, Debug)]
+pub struct ScenarioReport {
+    pub duration_secs: f64,
+    pub requests: u64,
+    pub errors: u64,
+    pub qps: f64,
+    pub methods: BTreeMap<String, MethodReport>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MethodReport {
+    pub requests: u64,
+    pub errors: u64,
+    pub qps: f64,
+    /// Latencies of the successful requests.
+    pub latency_ms: LatencyReport,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LatencyReport {
+    pub min: u64,
+    pub mean: f64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl MethodReport {
+    fn new(stats: &MethodStats, elapsed: Duration) -> Self {
+        let histogram = &stats.latency_ms;
+        let requests = histogram.len() + stats.errors;
+        Self {
+            requests,
+            errors: stats.errors,
+            qps: requests as f64 / elapsed.as_secs_f64(),
+            latency_ms: LatencyReport {
+                min: histogram.min(),
+                mean: histogram.mean(),
+                p50: histogram.value_at_quantile(0.5),
+                p90: histogram.value_at_quantile(0.9),
+                p99: histogram.value_at_quantile(0.99),
+                max: histogram.max(),
+            },
+        }
+    }
+}
+
+pub(crate) struct ScenarioRunner {
+    pub processor: RpcCommandProcessor,
+    pub config: ScenarioConfig,
+    pub data_dir: String,
+    pub signer_info: Option<SignerInfo>,
+}
+
+impl ScenarioRunner {
+    pub(crate) async fn run(self) -> Result<ScenarioReport> {
+        let clients = self.processor.get_clients().await?;
+        let mut chunked_commands = vec![];
+        for command in &self.config.commands {
+            let base = command.payload.to_command(&self.data_dir);
+            let commands = if command.chunks > 1 {
+                self.processor
+                    .divide_command(&clients, &base, command.chunks)
+                    .await
+            } else {
+                vec![base]
+            };
+            if commands.is_empty() {
+                return Err(anyhow!("{:?} has no inputs to send", command.payload));
+            }
+            chunked_commands.push(commands);
+        }
+        let types: Vec<&str> = chunked_commands
+            .iter()
+            .map(|commands| (&commands[0].data).into())
+            .collect();
+        let prepared: Vec<_> = self
+            .config
+            .command_names(&types)?
+            .into_iter()
+            .zip(chunked_commands)
+            .map(|(name, commands)| {
+                Arc::new(PreparedCommand {
+                    name,
+                    commands,
+                    next: AtomicUsize::new(0),
+                    stats: Default::default(),
+                })
+            })
+            .collect();
+
+        // Each PaySui request in flight uses its own gas coin to avoid equivocation.
+        let gas_coins = match &self.signer_info {
+            Some(signer_info) => {
+                let expected_requests = self
+                    .config
+                    .commands
+                    .iter()
+                    .filter(|command| matches!(command.payload, ScenarioPayload::PaySui))
+                    .map(|command| {
+                        self.config.weight_share(command)
+                            * self.config.expected_requests(self.config.duration())
+                    })
+                    .sum::<f64>()
+                    .ceil() as u64;
+                let num_coins = self.config.max_concurrency;
+                let (coins, encoded_keypair) = prepare_new_signer_and_coins(
+                    clients.first().unwrap(),
+                    signer_info,
+                    num_coins,
+                    expected_requests / num_coins as u64 + 1,
+                )
+                .await;
+                Some((Arc::new(Mutex::new(coins)), encoded_keypair))
+            }
+            None => None,
+        };
+
+        let weights =
+            WeightedIndex::new(self.config.commands.iter().map(|command| command.weight))?;
+        let mut rng = StdRng::from_entropy();
+        let semaphore = Arc::new(Semaphore::new(self.config.max_concurrency));
+        let duration = self.config.duration();
+        let mut sent = 0u64;
+
+        info!(
+            "Running scenario for {:?} with {} commands",
+            duration,
+            prepared.len()
+        );
+        let start_time = Instant::now();
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let elapsed = start_time.elapsed();
+            if elapsed >= duration {
+                break;
+            }
+            let due = self.config.expected_requests(elapsed) as u64;
+            while sent < due {
+                sent += 1;
+                // Latencies are measured from the time requests are due rather than sent, so that
+                // requests delayed by a slow server or by the concurrency limit are accounted for.
+                let intended_send_time = start_time + self.config.intended_send_time(sent);
+                let permit = semaphore.clone().acquire_owned().await.unwrap();
+                let prepared = prepared[weights.sample(&mut rng)].clone();
+                let command = prepared.next_command().clone();
+                let processor = self.processor.clone();
+                let gas_coins = gas_coins.clone();
+                tokio::spawn(async move {
+                    let gas_coin = match (&command.data, &gas_coins) {
+                        (CommandData::PaySui(_), Some((coins, _))) => coins.lock().unwrap().pop(),
+                        _ => None,
+                    };
+                    let signer_info = gas_coins.as_ref().map(|(_, encoded_keypair)| SignerInfo {
+                        encoded_keypair: encoded_keypair.clone(),
+                        gas_payment: gas_coin.map(|coin| vec![coin]),
+                        gas_budget: None,
+                    });
+                    // Processors panic on some failures, which are counted as errors as well.
+                    let result = tokio::spawn(async move {
+                        processor
+                            .process_command_data(&command.data, &signer_info)
+                            .await
+                    })
+                    .await;
+                    let latency = intended_send_time.elapsed();
+                    release_gas_coin(&gas_coins, gas_coin);
+
+                    let mut method_stats = prepared.stats.lock().unwrap();
+                    match result {
+                        Ok(Ok(())) => method_stats
+                            .latency_ms
+                            .saturating_record(latency.as_millis() as u64),
+                        Ok(Err(e)) => {
+                            error!("{} request failed: {e}", prepared.name);
+                            method_stats.errors += 1;
+                        }
+                        Err(e) => {
+                            error!("{} request panicked: {e}", prepared.name);
+                            method_stats.errors += 1;
+                        }
+                    }
+                    drop(method_stats);
+                    drop(permit);
+                });
+            }
+        }
+        // Wait for the requests in flight, which hold the other permits.
+        let _permits = semaphore
+            .acquire_many(self.config.max_concurrency as u32)
+            .await
+            .unwrap();
+        let elapsed = start_time.elapsed();
+
+        let methods: BTreeMap<_, _> = prepared
+            .iter()
+            .map(|command| {
+                let stats = command.stats.lock().unwrap();
+                (command.name.clone(), MethodReport::new(&stats, elapsed))
+            })
+            .filter(|(_, report)| report.requests > 0)
+            .collect();
+        let requests = methods.values().map(|method| method.requests).sum::<u64>();
+        Ok(ScenarioReport {
+            duration_secs: elapsed.as_secs_f64(),
+            requests,
+            errors: methods.values().map(|method| method.errors).sum(),
+            qps: requests as f64 / elapsed.as_secs_f64(),
+            methods,
+        })
+    }
+}
+
+fn release_gas_coin(
+    gas_coins: &Option<(Arc<Mutex<Vec<ObjectID>>>, String)>,
+    gas_coin: Option<ObjectID>,
+) {
+    if let (Some((coins, _)), Some(coin)) = (gas_coins, gas_coin) {
+        coins.lock().unwrap().push(coin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let yaml = r#"
+stages:
+  - duration_secs: 10
+    target_qps: 100
+commands:
+  - weight: 3
+    chunks: 10
+    payload:
+      type: MultiGetObjects
+  - weight: 1
+    payload:
+      type: QueryTransactionBlocks
+      address_type: both
+"#;
+        let config: ScenarioConfig = serde_yaml::from_str(yaml).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.max_concurrency, 100);
+        assert_eq!(config.commands[0].chunks, 10);
+        assert_eq!(config.commands[1].chunks, 1);
+        assert!(!config.needs_keystore());
+
+        let toml = r#"
+max_concurrency = 10
+
+[[stages]]
+duration_secs = 10
+target_qps = 100
+
+[[commands]]
+weight = 1
+
+[commands.payload]
+type = "PaySui"
+"#;
+        let config: ScenarioConfig = toml::from_str(toml).unwrap();
+        config.validate().unwrap();
+        assert!(config.needs_keystore());
+    }
+
+    #[test]
+    fn test_expected_requests() {
+        let config = ScenarioConfig {
+            stages: vec![
+                Stage {
+                    duration_secs: 10,
+                    target_qps: 100,
+                },
+                Stage {
+                    duration_secs: 10,
+                    target_qps: 100,
+                },
+            ],
+            commands: vec![],
+            max_concurrency: 1,
+        };
+        // Ramping up from 0 to 100 qps over 10 seconds sends 500 requests.
+        assert_eq!(config.expected_requests(Duration::from_secs(5)), 125.0);
+        assert_eq!(config.expected_requests(Duration::from_secs(10)), 500.0);
+        assert_eq!(config.expected_requests(Duration::from_secs(15)), 1000.0);
+        assert_eq!(config.expected_requests(Duration::from_secs(20)), 1500.0);
+        // Requests stop after the last stage.
+        assert_eq!(config.expected_requests(Duration::from_secs(30)), 1500.0);
+    }
+
+    #[test]
+    fn test_intended_send_time() {
+        let config = ScenarioConfig {
+            stages: vec![
+                Stage {
+                    duration_secs: 10,
+                    target_qps: 100,
+                },
+                Stage {
+                    duration_secs: 10,
+                    target_qps: 100,
+                },
+                Stage {
+                    duration_secs: 10,
+                    target_qps: 0,
+                },
+            ],
+            commands: vec![],
+            max_concurrency: 1,
+        };
+        for request in [1, 125, 500, 1000, 1500, 1875, 2000] {
+            let time = config.intended_send_time(request);
+            assert!((config.expected_requests(time) - request as f64).abs() < 1e-6);
+        }
+        assert_eq!(config.intended_send_time(500), Duration::from_secs(10));
+        assert_eq!(config.intended_send_time(1000), Duration::from_secs(15));
+        // Requests beyond the last stage are due at its end.
+        assert_eq!(config.intended_send_time(3000), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_command_names() {
+        let yaml = r#"
+stages:
+  - duration_secs: 10
+    target_qps: 100
+commands:
+  - weight: 1
+    payload:
+      type: GetAllBalances
+      chunk_size: 1
+  - weight: 1
+    payload:
+      type: GetAllBalances
+      chunk_size: 10
+  - weight: 1
+    payload:
+      type: MultiGetObjects
+  - weight: 1
+    name: Batched
+    payload:
+      type: GetAllBalances
+      chunk_size: 100
+"#;
+        let mut config: ScenarioConfig = serde_yaml::from_str(yaml).unwrap();
+        let types = [
+            "GetAllBalances",
+            "GetAllBalances",
+            "MultiGetObjects",
+            "GetAllBalances",
+        ];
+        assert_eq!(
+            config.command_names(&types).unwrap(),
+            vec![
+                "GetAllBalances#1",
+                "GetAllBalances#2",
+                "MultiGetObjects",
+                "Batched"
+            ]
+        );
+
+        config.commands[3].name = Some("MultiGetObjects".to_string());
+        assert!(config.command_names(&types).is_err());
+    }
+}
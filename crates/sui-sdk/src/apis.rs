@@ -17,11 +17,12 @@ use sui_json_rpc::api::{
     CoinReadApiClient, IndexerApiClient, MoveUtilsClient, ReadApiClient, WriteApiClient,
 };
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake,
+    Balance, Checkpoint, CheckpointId, CheckpointPage, Coin, CoinPage, DelegatedStake,
     DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage,
-    SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest, SuiMoveNormalizedModule,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
-    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    PastObjectsPage, SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest,
+    SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    SuiPastObjectResponse, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
     SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_types::balance::Supply;
@@ -31,6 +32,7 @@ use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
 use sui_types::event::EventID;
 use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::query::TransactionFilter;
 use sui_types::sui_serde::BigInt;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
@@ -61,6 +63,27 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        checkpoint: CheckpointSequenceNumber,
+        options: SuiObjectDataOptions,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> SuiRpcResult<PastObjectsPage> {
+        Ok(self
+            .api
+            .http
+            .get_owned_objects_at_checkpoint(
+                address,
+                checkpoint.into(),
+                Some(options),
+                cursor,
+                limit,
+            )
+            .await?)
+    }
+
     pub async fn get_dynamic_fields(
         &self,
         object_id: ObjectID,
@@ -87,6 +110,19 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn get_object_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+        options: SuiObjectDataOptions,
+    ) -> SuiRpcResult<SuiPastObjectResponse> {
+        Ok(self
+            .api
+            .http
+            .get_object_at_checkpoint(object_id, checkpoint.into(), Some(options))
+            .await?)
+    }
+
     pub async fn try_multi_get_parsed_past_object(
         &self,
         past_objects: Vec<SuiGetPastObjectRequest>,
@@ -173,6 +209,24 @@ impl ReadApi {
         Ok(self.api.http.get_checkpoint(id).await?)
     }
 
+    /// Return a page of checkpoints, starting after `cursor` when provided
+    pub async fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> SuiRpcResult<CheckpointPage> {
+        Ok(self
+            .api
+            .http
+            .get_checkpoints(
+                cursor.map(BigInt::from),
+                limit.map(|limit| BigInt::from(limit as u64)),
+                descending_order,
+            )
+            .await?)
+    }
+
     /// Return the sequence number of the latest checkpoint that has been executed
     pub async fn get_latest_checkpoint_sequence_number(
         &self,
@@ -380,6 +434,23 @@ impl EventApi {
         }
     }
 
+    /// Subscribe to a stream of effects of finalized transactions matching the filter.
+    pub async fn subscribe_transaction(
+        &self,
+        filter: TransactionFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiTransactionBlockEffects>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiTransactionBlockEffects> =
+                    c.subscribe_transaction(filter).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
     pub async fn get_events(&self, digest: TransactionDigest) -> SuiRpcResult<Vec<SuiEvent>> {
         Ok(self.api.http.get_events(digest).await?)
     }
@@ -0,0 +1,377 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An ordered stream of full checkpoints, for consumers which must not miss any transaction across
+//! restarts. The last processed checkpoint is kept in a [CheckpointCursorStore], RPC failures are
+//! retried with exponential backoff across all the configured fullnodes, and a checkpoint which
+//! none of them can serve, e.g. because they pruned it, is reported as a [Error::CheckpointGap]
+//! instead of being skipped.
+//!
+//! Delivery is at-least-once: a checkpoint is only saved as processed once the next one is
+//! requested, so the checkpoint being handled when the consumer stops is streamed again after a
+//! restart. Consumers must handle a checkpoint idempotently.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream;
+use futures_core::Stream;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::{CallError, CALL_EXECUTION_FAILED_CODE};
+
+use sui_json_rpc::api::{QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS};
+use sui_json_rpc_types::{
+    Checkpoint, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
+
+use crate::error::{Error, SuiRpcResult};
+use crate::{SuiClient, SuiClientBuilder};
+
+/// A checkpoint along with its transactions, in execution order. Every transaction is fetched
+/// with its input, effects, events, object changes and balance changes.
+#[derive(Debug, Clone)]
+pub struct CheckpointData {
+    pub checkpoint: Checkpoint,
+    pub transactions: Vec<SuiTransactionBlockResponse>,
+}
+
+/// Where a [CheckpointStream] keeps the sequence number of the last checkpoint processed by its
+/// consumer.
+#[async_trait]
+pub trait CheckpointCursorStore: Send + Sync {
+    async fn load(&self) -> SuiRpcResult<Option<CheckpointSequenceNumber>>;
+    async fn save(&self, sequence_number: CheckpointSequenceNumber) -> SuiRpcResult<()>;
+}
+
+/// A cursor which does not survive restarts, e.g. for tests or consumers with their own storage.
+#[derive(Debug, Default)]
+pub struct InMemoryCursorStore(Mutex<Option<CheckpointSequenceNumber>>);
+
+#[async_trait]
+impl CheckpointCursorStore for InMemoryCursorStore {
+    async fn load(&self) -> SuiRpcResult<Option<CheckpointSequenceNumber>> {
+        Ok(*self.0.lock().unwrap())
+    }
+
+    async fn save(&self, sequence_number: CheckpointSequenceNumber) -> SuiRpcResult<()> {
+        *self.0.lock().unwrap() = Some(sequence_number);
+        Ok(())
+    }
+}
+
+/// A cursor kept in a text file, replaced atomically on every save.
+#[derive(Debug, Clone)]
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CheckpointCursorStore for FileCursorStore {
+    async fn load(&self) -> SuiRpcResult<Option<CheckpointSequenceNumber>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::CursorStore(e.to_string())),
+        };
+        let sequence_number = content.trim().parse().map_err(|e| {
+            Error::CursorStore(format!("Invalid cursor in {}: {e}", self.path.display()))
+        })?;
+        Ok(Some(sequence_number))
+    }
+
+    async fn save(&self, sequence_number: CheckpointSequenceNumber) -> SuiRpcResult<()> {
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, sequence_number.to_string())
+            .await
+            .map_err(|e| Error::CursorStore(e.to_string()))?;
+        tokio::fs::rename(&tmp, &self.path)
+            .await
+            .map_err(|e| Error::CursorStore(e.to_string()))
+    }
+}
+
+pub struct CheckpointStreamBuilder {
+    rpc_urls: Vec<String>,
+    start_checkpoint: CheckpointSequenceNumber,
+    poll_interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    request_timeout: Duration,
+}
+
+impl CheckpointStreamBuilder {
+    /// Fullnodes are used in the given order, moving to the next one whenever a request fails.
+    pub fn new(rpc_urls: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            rpc_urls: rpc_urls
+                .into_iter()
+                .map(|url| url.as_ref().to_string())
+                .collect(),
+            start_checkpoint: 0,
+            poll_interval: Duration::from_secs(1),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// The first checkpoint to stream when the cursor store is empty.
+    pub fn start_checkpoint(mut self, start_checkpoint: CheckpointSequenceNumber) -> Self {
+        self.start_checkpoint = start_checkpoint;
+        self
+    }
+
+    /// How long to wait for new checkpoints once the stream caught up with the fullnode.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Resumes after the checkpoint saved in `cursor_store`, if any. No connection is made until
+    /// the first checkpoint is requested.
+    pub async fn build(
+        self,
+        cursor_store: impl CheckpointCursorStore + 'static,
+    ) -> SuiRpcResult<CheckpointStream> {
+        if self.rpc_urls.is_empty() {
+            return Err(Error::DataError(
+                "A checkpoint stream requires at least one RPC url".into(),
+            ));
+        }
+        let next_sequence_number = match cursor_store.load().await? {
+            Some(sequence_number) => sequence_number + 1,
+            None => self.start_checkpoint,
+        };
+        Ok(CheckpointStream {
+            backoff: self.initial_backoff,
+            config: self,
+            url_index: 0,
+            client: None,
+            cursor_store: Box::new(cursor_store),
+            processed: None,
+            next_sequence_number,
+            previous_digest: None,
+            buffer: VecDeque::new(),
+        })
+    }
+}
+
+enum FetchError {
+    /// The fullnode could not be reached or failed to answer, worth retrying later.
+    Rpc(Error),
+    /// The fullnode answered but does not have the next checkpoint or its transactions.
+    Gap(String),
+}
+
+impl From<Error> for FetchError {
+    fn from(e: Error) -> Self {
+        FetchError::Rpc(e)
+    }
+}
+
+impl FetchError {
+    /// Classifies the error `url` answered a request with: the fullnode reporting that it does
+    /// not have the data, e.g. as it was pruned, is a gap rather than a failure to answer.
+    fn from_response(url: &str, e: Error) -> Self {
+        let Error::RpcError(RpcError::Call(CallError::Custom(error))) = &e else {
+            return FetchError::Rpc(e);
+        };
+        let message = error.message();
+        if error.code() == CALL_EXECUTION_FAILED_CODE
+            && (message.contains("pruned")
+                || message.contains("not found")
+                || message.contains("Could not find"))
+        {
+            FetchError::Gap(format!("{url}: {message}"))
+        } else {
+            FetchError::Rpc(e)
+        }
+    }
+}
+
+/// Use [CheckpointStreamBuilder] to build a CheckpointStream.
+pub struct CheckpointStream {
+    config: CheckpointStreamBuilder,
+    url_index: usize,
+    client: Option<SuiClient>,
+    backoff: Duration,
+    cursor_store: Box<dyn CheckpointCursorStore>,
+    /// The checkpoint last returned to the consumer, saved in the cursor store on the next call.
+    processed: Option<CheckpointSequenceNumber>,
+    next_sequence_number: CheckpointSequenceNumber,
+    /// Unknown for the first checkpoint after a (re)start.
+    previous_digest: Option<CheckpointDigest>,
+    /// Summaries of the checkpoints following `next_sequence_number`.
+    buffer: VecDeque<Checkpoint>,
+}
+
+impl CheckpointStream {
+    /// The sequence number of the checkpoint the next call to [Self::next] returns.
+    pub fn next_sequence_number(&self) -> CheckpointSequenceNumber {
+        self.next_sequence_number
+    }
+
+    /// Returns the next checkpoint, waiting for it to be executed if needed. Calling `next` marks
+    /// the checkpoint returned by the previous call as processed and saves it in the cursor store,
+    /// so after a restart the stream resumes with the first checkpoint that was not fully handled.
+    ///
+    /// RPC failures are retried forever, while a missing checkpoint, e.g. pruned by all the
+    /// fullnodes, or one which does not chain with the previous checkpoint, is returned as a
+    /// [Error::CheckpointGap]. The stream can be polled again after an error.
+    pub async fn next(&mut self) -> SuiRpcResult<CheckpointData> {
+        if let Some(sequence_number) = self.processed {
+            self.cursor_store.save(sequence_number).await?;
+            self.processed = None;
+        }
+
+        let mut gaps = 0;
+        loop {
+            match self.try_next().await {
+                Ok(Some(data)) => {
+                    self.backoff = self.config.initial_backoff;
+                    self.processed = Some(data.checkpoint.sequence_number);
+                    self.previous_digest = Some(data.checkpoint.digest);
+                    self.next_sequence_number += 1;
+                    return Ok(data);
+                }
+                Ok(None) => {
+                    self.backoff = self.config.initial_backoff;
+                    tokio::time::sleep(self.config.poll_interval).await;
+                }
+                Err(FetchError::Gap(reason)) => {
+                    self.buffer.clear();
+                    gaps += 1;
+                    if gaps >= self.config.rpc_urls.len() {
+                        return Err(Error::CheckpointGap {
+                            sequence_number: self.next_sequence_number,
+                            reason,
+                        });
+                    }
+                    self.rotate();
+                }
+                Err(FetchError::Rpc(_)) => {
+                    tokio::time::sleep(self.backoff).await;
+                    self.backoff = (self.backoff * 2).min(self.config.max_backoff);
+                    self.rotate();
+                }
+            }
+        }
+    }
+
+    /// The checkpoints as a never ending stream, see [Self::next].
+    pub fn into_stream(self) -> impl Stream<Item = SuiRpcResult<CheckpointData>> {
+        stream::unfold(self, |mut checkpoint_stream| async move {
+            let item = checkpoint_stream.next().await;
+            Some((item, checkpoint_stream))
+        })
+    }
+
+    fn rotate(&mut self) {
+        self.client = None;
+        self.url_index = (self.url_index + 1) % self.config.rpc_urls.len();
+    }
+
+    async fn client(&mut self) -> SuiRpcResult<SuiClient> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let client = SuiClientBuilder::default()
+            .request_timeout(self.config.request_timeout)
+            .build(&self.config.rpc_urls[self.url_index])
+            .await?;
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Fetches the next checkpoint from the current fullnode, `None` if it is not executed yet.
+    async fn try_next(&mut self) -> Result<Option<CheckpointData>, FetchError> {
+        let client = self.client().await?;
+        let url = self.config.rpc_urls[self.url_index].clone();
+
+        if self.buffer.is_empty() {
+            let page = client
+                .read_api()
+                .get_checkpoints(
+                    self.next_sequence_number.checked_sub(1),
+                    Some(QUERY_MAX_RESULT_LIMIT_CHECKPOINTS),
+                    false,
+                )
+                .await
+                .map_err(|e| FetchError::from_response(&url, e))?;
+            // Checkpoints which are not available are left out of the page.
+            for (expected, checkpoint) in (self.next_sequence_number..).zip(page.data) {
+                if checkpoint.sequence_number != expected {
+                    return Err(FetchError::Gap(format!(
+                        "{url} returned checkpoint {} instead of {expected}",
+                        checkpoint.sequence_number
+                    )));
+                }
+                self.buffer.push_back(checkpoint);
+            }
+        }
+        let Some(checkpoint) = self.buffer.front() else {
+            return Ok(None);
+        };
+
+        if let Some(previous_digest) = self.previous_digest {
+            if checkpoint.previous_digest != Some(previous_digest) {
+                return Err(FetchError::Gap(format!(
+                    "checkpoint {} from {url} does not follow checkpoint {previous_digest}",
+                    checkpoint.sequence_number
+                )));
+            }
+        }
+
+        let mut transactions = Vec::with_capacity(checkpoint.transactions.len());
+        for digests in checkpoint.transactions.chunks(QUERY_MAX_RESULT_LIMIT) {
+            transactions.extend(
+                client
+                    .read_api()
+                    .multi_get_transactions_with_options(
+                        digests.to_vec(),
+                        SuiTransactionBlockResponseOptions::full_content(),
+                    )
+                    .await
+                    .map_err(|e| FetchError::from_response(&url, e))?,
+            );
+        }
+        let complete = transactions.len() == checkpoint.transactions.len()
+            && transactions
+                .iter()
+                .zip(&checkpoint.transactions)
+                .all(|(tx, digest)| &tx.digest == digest && tx.errors.is_empty());
+        if !complete {
+            return Err(FetchError::Gap(format!(
+                "{url} is missing transactions of checkpoint {}",
+                checkpoint.sequence_number
+            )));
+        }
+
+        let checkpoint = self.buffer.pop_front().unwrap();
+        Ok(Some(CheckpointData {
+            checkpoint,
+            transactions,
+        }))
+    }
+}
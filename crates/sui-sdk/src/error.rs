@@ -3,6 +3,7 @@
 
 use sui_types::base_types::{SuiAddress, TransactionDigest};
 use sui_types::error::UserInputError;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use thiserror::Error;
 
 pub type SuiRpcResult<T = ()> = Result<T, Error>;
@@ -28,6 +29,13 @@ pub enum Error {
         client_version: String,
         server_version: String,
     },
+    #[error("Checkpoint {sequence_number} is not available from any RPC url: {reason}")]
+    CheckpointGap {
+        sequence_number: CheckpointSequenceNumber,
+        reason: String,
+    },
+    #[error("Failed to persist checkpoint cursor: {0}")]
+    CursorStore(String),
     #[error("Insufficient fund for address [{address}], requested amount: {amount}")]
     InsufficientFund { address: SuiAddress, amount: u128 },
 }
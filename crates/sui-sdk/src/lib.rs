@@ -32,6 +32,7 @@ use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriver, ReadApi};
 use crate::error::{Error, SuiRpcResult};
 
 pub mod apis;
+pub mod checkpoint_stream;
 pub mod error;
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
 const WAIT_FOR_TX_TIMEOUT_SEC: u64 = 60;
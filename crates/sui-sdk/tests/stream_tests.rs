@@ -1,10 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
 use futures::StreamExt;
+use serde_json::json;
 use std::future;
+use std::sync::{Arc, Mutex};
 use sui_config::genesis_config::DEFAULT_GAS_AMOUNT;
 use sui_config::genesis_config::DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT;
+use sui_sdk::checkpoint_stream::{
+    CheckpointCursorStore, CheckpointStreamBuilder, FileCursorStore, InMemoryCursorStore,
+};
+use sui_sdk::error::Error;
 use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
 use test_utils::network::TestClusterBuilder;
 
@@ -135,3 +144,183 @@ async fn test_coins_stream() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_checkpoint_stream_resumes_from_cursor() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let rpc_url = test_cluster.rpc_url();
+    let dir = tempfile::tempdir()?;
+    let cursor = dir.path().join("cursor");
+
+    let mut stream = CheckpointStreamBuilder::new([rpc_url])
+        .build(FileCursorStore::new(&cursor))
+        .await?;
+    let genesis = stream.next().await?;
+    assert_eq!(0, genesis.checkpoint.sequence_number);
+    assert_eq!(
+        genesis.checkpoint.transactions,
+        genesis
+            .transactions
+            .iter()
+            .map(|tx| tx.digest)
+            .collect::<Vec<_>>()
+    );
+    assert!(genesis.transactions.iter().all(|tx| tx.effects.is_some()));
+    let first = stream.next().await?;
+    assert_eq!(1, first.checkpoint.sequence_number);
+    assert_eq!(
+        Some(genesis.checkpoint.digest),
+        first.checkpoint.previous_digest
+    );
+    drop(stream);
+
+    // Checkpoint 1 was never marked as processed, so it is streamed again, after failing over
+    // from an unreachable fullnode.
+    let mut stream = CheckpointStreamBuilder::new(["http://127.0.0.1:1", rpc_url])
+        .build(FileCursorStore::new(&cursor))
+        .await?;
+    assert_eq!(1, stream.next_sequence_number());
+    let resumed = stream.next().await?;
+    assert_eq!(first.checkpoint.digest, resumed.checkpoint.digest);
+    let second = stream.next().await?;
+    assert_eq!(
+        Some(resumed.checkpoint.digest),
+        second.checkpoint.previous_digest
+    );
+    assert_eq!("1", std::fs::read_to_string(&cursor)?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_cursor_store() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cursor");
+    let store = FileCursorStore::new(&path);
+    assert_eq!(None, store.load().await?);
+
+    store.save(3).await?;
+    store.save(5).await?;
+    assert_eq!(Some(5), store.load().await?);
+    assert_eq!(Some(5), FileCursorStore::new(&path).load().await?);
+    // The temporary file a save goes through is renamed over the cursor.
+    assert_eq!(
+        vec![path.clone()],
+        std::fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+    );
+
+    std::fs::write(&path, "not a checkpoint")?;
+    assert!(matches!(store.load().await, Err(Error::CursorStore(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_checkpoint_stream_gap() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let (first, first_url) = FullnodeProxy::start(test_cluster.rpc_url(), ProxyMode::Pruned);
+    let (second, second_url) = FullnodeProxy::start(test_cluster.rpc_url(), ProxyMode::Pruned);
+    let mut stream = CheckpointStreamBuilder::new([&first_url, &second_url])
+        .build(InMemoryCursorStore::default())
+        .await?;
+
+    // A checkpoint pruned by every fullnode is reported instead of being retried or skipped.
+    match stream.next().await {
+        Err(Error::CheckpointGap {
+            sequence_number,
+            reason,
+        }) => {
+            assert_eq!(0, sequence_number);
+            assert!(reason.contains("pruned"), "{reason}");
+        }
+        other => panic!("Expected a checkpoint gap, got {other:?}"),
+    }
+
+    // The stream can be polled again once a fullnode has the checkpoint.
+    second.set_mode(ProxyMode::Forward);
+    let genesis = stream.next().await?;
+    assert_eq!(0, genesis.checkpoint.sequence_number);
+    let first_checkpoint = stream.next().await?;
+    assert_eq!(1, first_checkpoint.checkpoint.sequence_number);
+
+    // It fails over to another fullnode when the current one pruned the next checkpoint.
+    second.set_mode(ProxyMode::Pruned);
+    first.set_mode(ProxyMode::Forward);
+    let second_checkpoint = stream.next().await?;
+    assert_eq!(2, second_checkpoint.checkpoint.sequence_number);
+    assert_eq!(
+        Some(first_checkpoint.checkpoint.digest),
+        second_checkpoint.checkpoint.previous_digest
+    );
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProxyMode {
+    Forward,
+    /// Fail checkpoint and transaction reads like a fullnode which pruned them.
+    Pruned,
+}
+
+/// A JSON-RPC proxy in front of a fullnode.
+#[derive(Clone)]
+struct FullnodeProxy {
+    upstream: String,
+    mode: Arc<Mutex<ProxyMode>>,
+}
+
+impl FullnodeProxy {
+    /// Serves the proxy on a local port, returning it along with its URL.
+    fn start(upstream: &str, mode: ProxyMode) -> (Self, String) {
+        let proxy = Self {
+            upstream: upstream.to_string(),
+            mode: Arc::new(Mutex::new(mode)),
+        };
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/", post(proxy_request))
+            .with_state(proxy.clone());
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        (proxy, url)
+    }
+
+    fn set_mode(&self, mode: ProxyMode) {
+        *self.mode.lock().unwrap() = mode;
+    }
+}
+
+async fn proxy_request(
+    State(proxy): State<FullnodeProxy>,
+    Json(request): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let mode = *proxy.mode.lock().unwrap();
+    let method = request["method"].as_str().unwrap_or_default();
+    if mode == ProxyMode::Pruned
+        && (method == "sui_getCheckpoints" || method == "sui_multiGetTransactionBlocks")
+    {
+        return Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": {
+                "code": -32000,
+                "message": "Contents of checkpoint 0 were pruned from this node, history is only \
+                    available from checkpoint 1000.",
+            },
+        }));
+    }
+    let response = reqwest::Client::new()
+        .post(&proxy.upstream)
+        .json(&request)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    Json(response)
+}
@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SponsorError {
+    #[error("Sponsor cannot read objects from fullnode: {0}")]
+    FullnodeReadingError(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Transaction is not allowed by the sponsor policy: {0}")]
+    PolicyViolation(String),
+
+    #[error("Sender `{0}` sent too many requests, try again later")]
+    RateLimited(String),
+
+    #[error("Sender `{0}` exceeded its gas budget, try again later")]
+    BudgetExceeded(String),
+
+    #[error(
+        "Gas coin `{0}` does not have sufficient balance and has been removed from gas coin pool"
+    )]
+    GasCoinWithInsufficientBalance(String),
+
+    #[error("Gas coin `{0}` is not valid and has been removed from gas coin pool")]
+    InvalidGasCoin(String),
+
+    #[error("Timed out waiting for a coin from the gas coin pool")]
+    NoGasCoinAvailable,
+
+    #[error("Wallet Error: `{0}`")]
+    Wallet(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl SponsorError {
+    pub(crate) fn internal(e: impl ToString) -> Self {
+        SponsorError::Internal(e.to_string())
+    }
+}
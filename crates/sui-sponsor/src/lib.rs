@@ -0,0 +1,13 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+mod errors;
+mod metrics;
+mod requests;
+mod responses;
+mod sponsor;
+
+pub use errors::SponsorError;
+pub use requests::*;
+pub use responses::*;
+pub use sponsor::*;
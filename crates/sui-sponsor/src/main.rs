@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    error_handling::HandleErrorLayer,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    BoxError, Extension, Json, Router,
+};
+use clap::Parser;
+use fastcrypto::encoding::{Base64, Encoding};
+use http::Method;
+use mysten_metrics::spawn_monitored_task;
+use std::env;
+use std::{
+    borrow::Cow,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use sui::client_commands::WalletContext;
+use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
+use sui_sponsor::{
+    SimpleSponsor, Sponsor, SponsorConfig, SponsorError, SponsorRequest, SponsorResponse,
+};
+use sui_types::messages::TransactionKind;
+use tower::{limit::RateLimitLayer, ServiceBuilder};
+use tower_http::cors::{Any, CorsLayer};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const CONCURRENCY_LIMIT: usize = 30;
+
+struct AppState<S = SimpleSponsor> {
+    sponsor: S,
+}
+
+const PROM_PORT_ADDR: &str = "0.0.0.0:9185";
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // initialize tracing
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let max_concurrency = match env::var("MAX_CONCURRENCY") {
+        Ok(val) => val.parse::<usize>().unwrap(),
+        _ => CONCURRENCY_LIMIT,
+    };
+    info!("Max concurrency: {max_concurrency}.");
+
+    let config: SponsorConfig = SponsorConfig::parse();
+    let SponsorConfig {
+        port,
+        host_ip,
+        request_buffer_size,
+        max_request_per_second,
+        wallet_client_timeout_secs,
+        ref write_ahead_log,
+        wal_retry_interval,
+        ..
+    } = config;
+
+    let context = create_wallet_context(wallet_client_timeout_secs).await?;
+
+    let prom_binding = PROM_PORT_ADDR.parse().unwrap();
+    info!("Starting Prometheus HTTP endpoint at {}", prom_binding);
+    let registry_service = sui_node::metrics::start_prometheus_server(prom_binding);
+    let prometheus_registry = registry_service.default_registry();
+
+    let app_state = Arc::new(AppState {
+        sponsor: SimpleSponsor::new(context, &prometheus_registry, write_ahead_log, &config)
+            .await
+            .unwrap(),
+    });
+
+    // TODO: restrict access if needed
+    let cors = CorsLayer::new()
+        .allow_methods(vec![Method::GET, Method::POST])
+        .allow_headers(Any)
+        .allow_origin(Any);
+
+    let app = Router::new()
+        .route("/", get(health))
+        .route("/sponsor", post(sponsor_transaction))
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_error))
+                .layer(cors)
+                .load_shed()
+                .buffer(request_buffer_size)
+                .layer(RateLimitLayer::new(
+                    max_request_per_second,
+                    Duration::from_secs(1),
+                ))
+                .concurrency_limit(max_concurrency)
+                .layer(Extension(app_state.clone()))
+                .into_inner(),
+        );
+
+    spawn_monitored_task!(async move {
+        info!("Starting task to release reserved coins.");
+        loop {
+            tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
+            if let Err(e) = app_state.sponsor.release_reserved_coins().await {
+                error!("Failed to release reserved coins: {e:?}");
+            }
+        }
+    });
+
+    let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
+    info!("listening on {}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// basic handler that responds with a static string
+async fn health() -> &'static str {
+    "OK"
+}
+
+/// handler for all the sponsor requests
+async fn sponsor_transaction(
+    Extension(state): Extension<Arc<AppState>>,
+    Json(payload): Json<SponsorRequest>,
+) -> impl IntoResponse {
+    // ID for traceability
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, sender = ?payload.sender, "Got new sponsor request.");
+
+    let kind = match Base64::decode(&payload.tx_kind_bytes)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| bcs::from_bytes::<TransactionKind>(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(kind) => kind,
+        Err(e) => {
+            let err = SponsorError::InvalidRequest(format!("invalid transaction kind: {e}"));
+            return (StatusCode::BAD_REQUEST, Json(SponsorResponse::from(err)));
+        }
+    };
+
+    // We spawn a tokio task for this such that connection drop will not interrupt it after the
+    // coin was reserved.
+    let result = spawn_monitored_task!(async move {
+        state
+            .sponsor
+            .sponsor(id, payload.sender, kind, payload.gas_budget)
+            .await
+    })
+    .await
+    .unwrap()
+    .and_then(SponsorResponse::try_from);
+
+    match result {
+        Ok(v) => {
+            info!(uuid =?id, "Request is successfully served");
+            (StatusCode::OK, Json(v))
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to sponsor transaction: {:?}", v);
+            let status = match v {
+                SponsorError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+                SponsorError::PolicyViolation(_) => StatusCode::FORBIDDEN,
+                SponsorError::RateLimited(_) | SponsorError::BudgetExceeded(_) => {
+                    StatusCode::TOO_MANY_REQUESTS
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(SponsorResponse::from(v)))
+        }
+    }
+}
+
+async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
+    let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+    info!("Initialize wallet from config path: {:?}", wallet_conf);
+    WalletContext::new(&wallet_conf, Some(Duration::from_secs(timeout_secs))).await
+}
+
+async fn handle_error(error: BoxError) -> impl IntoResponse {
+    if error.is::<tower::load_shed::error::Overloaded>() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Cow::from("service is overloaded, please try again later"),
+        );
+    }
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Cow::from(format!("Unhandled internal error: {}", error)),
+    )
+}
@@ -0,0 +1,54 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
+    Registry,
+};
+
+/// Metrics relevant to the running of the service
+#[derive(Clone, Debug)]
+pub struct SponsorMetrics {
+    pub(crate) total_sponsored_transactions: IntCounter,
+    pub(crate) total_rejected_requests: IntCounter,
+    pub(crate) total_available_coins: IntGauge,
+    pub(crate) total_reserved_coins: IntGauge,
+    pub(crate) total_discarded_coins: IntGauge,
+}
+
+impl SponsorMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_sponsored_transactions: register_int_counter_with_registry!(
+                "total_sponsored_transactions",
+                "Total number of transactions signed by the sponsor",
+                registry,
+            )
+            .unwrap(),
+            total_rejected_requests: register_int_counter_with_registry!(
+                "total_rejected_requests",
+                "Total number of requests rejected by the sponsor policy",
+                registry,
+            )
+            .unwrap(),
+            total_available_coins: register_int_gauge_with_registry!(
+                "total_available_coins",
+                "Total number of available coins in queue",
+                registry,
+            )
+            .unwrap(),
+            total_reserved_coins: register_int_gauge_with_registry!(
+                "total_reserved_coins",
+                "Total number of coins reserved by sponsored transactions which did not land yet",
+                registry,
+            )
+            .unwrap(),
+            total_discarded_coins: register_int_gauge_with_registry!(
+                "total_discarded_coins",
+                "Total number of discarded coins",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
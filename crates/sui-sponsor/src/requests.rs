@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorRequest {
+    /// The sender of the transaction, which signs it along with the sponsor.
+    pub sender: SuiAddress,
+    /// BCS serialized `TransactionKind`, as base-64 encoded string.
+    pub tx_kind_bytes: String,
+    /// Gas budget requested for the transaction, defaults to the sponsor's default gas budget.
+    pub gas_budget: Option<u64>,
+}
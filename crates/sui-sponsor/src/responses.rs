@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorResponse {
+    /// BCS serialized `TransactionData` paid by the sponsor, as base-64 encoded string. The sender
+    /// signs it and executes it with both its signature and the sponsor's.
+    pub tx_bytes: Option<String>,
+    /// Base64 encoded signature `flag || signature || pubkey` of the sponsor.
+    pub sponsor_signature: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<SponsorError> for SponsorResponse {
+    fn from(e: SponsorError) -> Self {
+        Self {
+            tx_bytes: None,
+            sponsor_signature: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl TryFrom<SponsoredTransaction> for SponsorResponse {
+    type Error = SponsorError;
+
+    fn try_from(v: SponsoredTransaction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_bytes: Some(Base64::encode(
+                bcs::to_bytes(&v.tx_data).map_err(SponsorError::internal)?,
+            )),
+            sponsor_signature: Some(Base64::encode(v.sponsor_signature)),
+            error: None,
+        })
+    }
+}
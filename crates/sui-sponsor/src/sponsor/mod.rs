@@ -0,0 +1,122 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::SponsorError;
+use async_trait::async_trait;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::Signature;
+use sui_types::messages::{TransactionData, TransactionKind};
+use uuid::Uuid;
+
+mod policy;
+mod simple_sponsor;
+mod write_ahead_log;
+pub use self::policy::SponsorPolicy;
+pub use self::simple_sponsor::SimpleSponsor;
+use clap::Parser;
+use std::{net::Ipv4Addr, path::PathBuf};
+
+/// A transaction paid by the sponsor, which the sender needs to sign before executing it.
+#[derive(Debug, Clone)]
+pub struct SponsoredTransaction {
+    pub tx_data: TransactionData,
+    pub sponsor_signature: Signature,
+}
+
+#[async_trait]
+pub trait Sponsor {
+    /// Pay for the gas of transaction `kind` sent by `sender`, if the sponsor policy allows it.
+    async fn sponsor(
+        &self,
+        id: Uuid,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_budget: Option<u64>,
+    ) -> Result<SponsoredTransaction, SponsorError>;
+}
+
+#[derive(Parser, Clone)]
+#[clap(
+    name = "Sui Sponsor",
+    about = "Service paying for the gas of transactions sent by other addresses",
+    rename_all = "kebab-case"
+)]
+pub struct SponsorConfig {
+    #[clap(long, default_value_t = 5004)]
+    pub port: u16,
+
+    #[clap(long, default_value = "127.0.0.1")]
+    pub host_ip: Ipv4Addr,
+
+    #[clap(long, default_value_t = 10)]
+    pub request_buffer_size: usize,
+
+    #[clap(long, default_value_t = 10)]
+    pub max_request_per_second: u64,
+
+    #[clap(long, default_value_t = 60)]
+    pub wallet_client_timeout_secs: u64,
+
+    #[clap(long)]
+    pub write_ahead_log: PathBuf,
+
+    /// Interval in seconds at which coins reserved by sponsored transactions are checked, and
+    /// released once their transaction landed or their lease expired.
+    #[clap(long, default_value_t = 60)]
+    pub wal_retry_interval: u64,
+
+    /// How long in seconds a coin stays reserved for a sponsored transaction. Once the lease
+    /// expired, the sponsor cancels the transaction if the sender did not execute it, by spending
+    /// its gas coin, and the coin returns to the pool.
+    #[clap(long, default_value_t = 60)]
+    pub reservation_lease_secs: u64,
+
+    /// Gas budget of transactions for which the client doesn't request one.
+    #[clap(long, default_value_t = 10_000_000)]
+    pub default_gas_budget: u64,
+
+    /// Max gas budget of a single sponsored transaction.
+    #[clap(long, default_value_t = 100_000_000)]
+    pub max_gas_budget: u64,
+
+    /// Packages whose functions may be called by sponsored transactions. At least one package or
+    /// function must be allowed, calls to any other function are not sponsored.
+    #[clap(long = "allowed-package")]
+    pub allowed_packages: Vec<ObjectID>,
+
+    /// Functions which may be called by sponsored transactions, as `<package>::<module>::<function>`.
+    #[clap(long = "allowed-function")]
+    pub allowed_functions: Vec<String>,
+
+    /// Total gas budget sponsored for a single sender within a budget window.
+    #[clap(long, default_value_t = 1_000_000_000)]
+    pub sender_gas_budget: u64,
+
+    /// Number of transactions sponsored for a single sender within a budget window.
+    #[clap(long, default_value_t = 100)]
+    pub sender_max_requests: u64,
+
+    #[clap(long, default_value_t = 86_400)]
+    pub budget_window_secs: u64,
+}
+
+impl Default for SponsorConfig {
+    fn default() -> Self {
+        Self {
+            port: 5004,
+            host_ip: Ipv4Addr::new(127, 0, 0, 1),
+            request_buffer_size: 10,
+            max_request_per_second: 10,
+            wallet_client_timeout_secs: 60,
+            write_ahead_log: Default::default(),
+            wal_retry_interval: 60,
+            reservation_lease_secs: 60,
+            default_gas_budget: 10_000_000,
+            max_gas_budget: 100_000_000,
+            allowed_packages: vec![],
+            allowed_functions: vec![],
+            sender_gas_budget: 1_000_000_000,
+            sender_max_requests: 100,
+            budget_window_secs: 86_400,
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{Argument, Command, TransactionKind};
+use typed_store::Map;
+
+use super::write_ahead_log::{SenderUsage, WriteAheadLog};
+use crate::{SponsorConfig, SponsorError};
+
+/// Decides which transactions the sponsor pays for, and keeps track of how much each sender
+/// spent within the current budget window, in the WAL of the sponsor.
+pub struct SponsorPolicy {
+    allowed_packages: BTreeSet<ObjectID>,
+    allowed_functions: BTreeSet<(ObjectID, String, String)>,
+    default_gas_budget: u64,
+    max_gas_budget: u64,
+    sender_gas_budget: u64,
+    sender_max_requests: u64,
+    budget_window: Duration,
+}
+
+impl SponsorPolicy {
+    pub fn new(config: &SponsorConfig) -> Result<Self, SponsorError> {
+        if config.allowed_packages.is_empty() && config.allowed_functions.is_empty() {
+            return Err(SponsorError::InvalidRequest(
+                "at least one package or function must be allowed".into(),
+            ));
+        }
+        let allowed_functions = config
+            .allowed_functions
+            .iter()
+            .map(|function| parse_function(function))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            allowed_packages: config.allowed_packages.iter().copied().collect(),
+            allowed_functions,
+            default_gas_budget: config.default_gas_budget,
+            max_gas_budget: config.max_gas_budget,
+            sender_gas_budget: config.sender_gas_budget,
+            sender_max_requests: config.sender_max_requests,
+            budget_window: Duration::from_secs(config.budget_window_secs),
+        })
+    }
+
+    /// The gas budget granted for a request, failing if it asks for more than the max budget.
+    pub fn gas_budget(&self, requested: Option<u64>) -> Result<u64, SponsorError> {
+        let gas_budget = requested.unwrap_or(self.default_gas_budget);
+        if gas_budget > self.max_gas_budget {
+            return Err(SponsorError::PolicyViolation(format!(
+                "gas budget {gas_budget} is above the max gas budget {}",
+                self.max_gas_budget
+            )));
+        }
+        Ok(gas_budget)
+    }
+
+    /// Check that the sponsor pays for transactions of this kind. Only programmable transactions
+    /// calling allowed functions are sponsored, and they cannot touch the sponsor's gas coin.
+    pub fn check_transaction(&self, kind: &TransactionKind) -> Result<(), SponsorError> {
+        let TransactionKind::ProgrammableTransaction(pt) = kind else {
+            return Err(SponsorError::PolicyViolation(
+                "only programmable transactions are sponsored".into(),
+            ));
+        };
+        for command in &pt.commands {
+            let arguments: Vec<&Argument> = match command {
+                Command::MoveCall(call) => {
+                    self.check_function(
+                        &call.package,
+                        call.module.as_str(),
+                        call.function.as_str(),
+                    )?;
+                    call.arguments.iter().collect()
+                }
+                Command::TransferObjects(objects, address) => {
+                    objects.iter().chain(std::iter::once(address)).collect()
+                }
+                Command::SplitCoins(coin, amounts) => {
+                    std::iter::once(coin).chain(amounts).collect()
+                }
+                Command::MergeCoins(coin, coins) => std::iter::once(coin).chain(coins).collect(),
+                Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+                Command::Publish(..) | Command::Upgrade(..) => {
+                    return Err(SponsorError::PolicyViolation(
+                        "publishing or upgrading packages is not sponsored".into(),
+                    ))
+                }
+            };
+            if arguments.contains(&&Argument::GasCoin) {
+                return Err(SponsorError::PolicyViolation(
+                    "sponsored transactions cannot use the gas coin".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_function(
+        &self,
+        package: &ObjectID,
+        module: &str,
+        function: &str,
+    ) -> Result<(), SponsorError> {
+        if self.allowed_packages.contains(package)
+            || self.allowed_functions.contains(&(
+                *package,
+                module.to_string(),
+                function.to_string(),
+            ))
+        {
+            return Ok(());
+        }
+        Err(SponsorError::PolicyViolation(format!(
+            "calls to {package}::{module}::{function} are not sponsored"
+        )))
+    }
+
+    /// Account for a sponsored transaction of `sender` with `gas_budget`, requested at `now_ms`,
+    /// failing if the sender exceeded its number of requests or its gas budget in the current
+    /// window.
+    pub(crate) fn charge_sender(
+        &self,
+        wal: &WriteAheadLog,
+        sender: SuiAddress,
+        gas_budget: u64,
+        now_ms: u64,
+    ) -> Result<(), SponsorError> {
+        let mut usage = wal
+            .sender_usage
+            .get(&sender)
+            .map_err(SponsorError::internal)?
+            .unwrap_or_default();
+        if now_ms.saturating_sub(usage.window_start_ms) >= self.budget_window.as_millis() as u64 {
+            usage = SenderUsage {
+                window_start_ms: now_ms,
+                requests: 0,
+                gas_budget: 0,
+            };
+        }
+        if usage.requests >= self.sender_max_requests {
+            return Err(SponsorError::RateLimited(sender.to_string()));
+        }
+        if usage.gas_budget + gas_budget > self.sender_gas_budget {
+            return Err(SponsorError::BudgetExceeded(sender.to_string()));
+        }
+        usage.requests += 1;
+        usage.gas_budget += gas_budget;
+        wal.sender_usage
+            .insert(&sender, &usage)
+            .map_err(SponsorError::internal)
+    }
+
+    /// Give back a request of `sender` with `gas_budget` which was charged, but not sponsored.
+    pub(crate) fn refund_sender(
+        &self,
+        wal: &WriteAheadLog,
+        sender: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<(), SponsorError> {
+        let Some(mut usage) = wal.sender_usage.get(&sender).map_err(SponsorError::internal)? else {
+            return Ok(());
+        };
+        usage.requests = usage.requests.saturating_sub(1);
+        usage.gas_budget = usage.gas_budget.saturating_sub(gas_budget);
+        wal.sender_usage
+            .insert(&sender, &usage)
+            .map_err(SponsorError::internal)
+    }
+}
+
+/// Parse a function given as `<package>::<module>::<function>`.
+fn parse_function(function: &str) -> Result<(ObjectID, String, String), SponsorError> {
+    let invalid = || {
+        SponsorError::InvalidRequest(format!(
+            "invalid function {function}, expected <package>::<module>::<function>"
+        ))
+    };
+    let mut parts = function.split("::");
+    let (Some(package), Some(module), Some(name), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let package = ObjectID::from_str(package).map_err(|_| invalid())?;
+    Ok((package, module.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::random_object_ref;
+    use sui_types::messages::{CallArg, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction};
+    use sui_types::SUI_FRAMEWORK_OBJECT_ID;
+
+    use super::*;
+
+    fn move_call(
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        arguments: Vec<Argument>,
+    ) -> Command {
+        Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package,
+            module: module.parse().unwrap(),
+            function: function.parse().unwrap(),
+            type_arguments: vec![],
+            arguments,
+        }))
+    }
+
+    fn programmable(commands: Vec<Command>) -> TransactionKind {
+        TransactionKind::ProgrammableTransaction(ProgrammableTransaction {
+            inputs: vec![CallArg::Object(ObjectArg::ImmOrOwnedObject(
+                random_object_ref(),
+            ))],
+            commands,
+        })
+    }
+
+    #[test]
+    fn test_allowed_functions() {
+        let config = SponsorConfig {
+            allowed_functions: vec![format!("{SUI_FRAMEWORK_OBJECT_ID}::coin::join")],
+            ..Default::default()
+        };
+        let policy = SponsorPolicy::new(&config).unwrap();
+
+        let join = move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            "coin",
+            "join",
+            vec![Argument::Input(0)],
+        );
+        assert!(policy.check_transaction(&programmable(vec![join])).is_ok());
+
+        let split = move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            "coin",
+            "split",
+            vec![Argument::Input(0)],
+        );
+        assert!(matches!(
+            policy.check_transaction(&programmable(vec![split])),
+            Err(SponsorError::PolicyViolation(_))
+        ));
+
+        // Calls are only sponsored if they are explicitly allowed.
+        assert!(SponsorPolicy::new(&SponsorConfig::default()).is_err());
+
+        let config = SponsorConfig {
+            allowed_packages: vec![SUI_FRAMEWORK_OBJECT_ID],
+            ..Default::default()
+        };
+        let policy = SponsorPolicy::new(&config).unwrap();
+        let split = move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            "coin",
+            "split",
+            vec![Argument::Input(0)],
+        );
+        assert!(policy.check_transaction(&programmable(vec![split])).is_ok());
+        let call = move_call(
+            ObjectID::random(),
+            "coin",
+            "split",
+            vec![Argument::Input(0)],
+        );
+        assert!(matches!(
+            policy.check_transaction(&programmable(vec![call])),
+            Err(SponsorError::PolicyViolation(_))
+        ));
+
+        let config = SponsorConfig {
+            allowed_functions: vec!["coin::join".into()],
+            ..Default::default()
+        };
+        assert!(SponsorPolicy::new(&config).is_err());
+    }
+
+    #[test]
+    fn test_gas_coin_is_not_usable() {
+        let config = SponsorConfig {
+            allowed_packages: vec![SUI_FRAMEWORK_OBJECT_ID],
+            ..Default::default()
+        };
+        let policy = SponsorPolicy::new(&config).unwrap();
+        let transfer = Command::TransferObjects(vec![Argument::GasCoin], Argument::Input(0));
+        assert!(matches!(
+            policy.check_transaction(&programmable(vec![transfer])),
+            Err(SponsorError::PolicyViolation(_))
+        ));
+        let split = Command::SplitCoins(Argument::GasCoin, vec![Argument::Input(0)]);
+        assert!(matches!(
+            policy.check_transaction(&programmable(vec![split])),
+            Err(SponsorError::PolicyViolation(_))
+        ));
+        let publish = Command::Publish(vec![], vec![]);
+        assert!(matches!(
+            policy.check_transaction(&programmable(vec![publish])),
+            Err(SponsorError::PolicyViolation(_))
+        ));
+    }
+
+    #[test]
+    fn test_sender_budget() {
+        let config = SponsorConfig {
+            max_gas_budget: 100,
+            sender_gas_budget: 250,
+            sender_max_requests: 5,
+            budget_window_secs: 10,
+            allowed_packages: vec![SUI_FRAMEWORK_OBJECT_ID],
+            ..Default::default()
+        };
+        let policy = SponsorPolicy::new(&config).unwrap();
+        assert!(policy.gas_budget(Some(101)).is_err());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        let sender = SuiAddress::random_for_testing_only();
+        let now = 1_000_000;
+        policy.charge_sender(&wal, sender, 100, now).unwrap();
+        policy.charge_sender(&wal, sender, 100, now).unwrap();
+        assert_eq!(
+            policy.charge_sender(&wal, sender, 100, now),
+            Err(SponsorError::BudgetExceeded(sender.to_string()))
+        );
+        // Requests which were not sponsored in the end are refunded.
+        policy.refund_sender(&wal, sender, 100).unwrap();
+        policy.charge_sender(&wal, sender, 100, now).unwrap();
+        assert_eq!(
+            policy.charge_sender(&wal, sender, 100, now),
+            Err(SponsorError::BudgetExceeded(sender.to_string()))
+        );
+        // Other senders have their own budget.
+        policy
+            .charge_sender(&wal, SuiAddress::random_for_testing_only(), 100, now)
+            .unwrap();
+
+        for _ in 0..3 {
+            policy.charge_sender(&wal, sender, 0, now).unwrap();
+        }
+        assert_eq!(
+            policy.charge_sender(&wal, sender, 0, now),
+            Err(SponsorError::RateLimited(sender.to_string()))
+        );
+
+        // Budgets survive restarts.
+        drop(wal);
+        let wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        assert_eq!(
+            policy.charge_sender(&wal, sender, 0, now),
+            Err(SponsorError::RateLimited(sender.to_string()))
+        );
+
+        // The budget is reset at the end of the window.
+        policy
+            .charge_sender(&wal, sender, 100, now + 10_000)
+            .unwrap();
+    }
+}
@@ -0,0 +1,723 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::SponsorMetrics;
+use async_trait::async_trait;
+use prometheus::Registry;
+use tap::tap::TapFallible;
+
+use shared_crypto::intent::Intent;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use typed_store::Map;
+
+use sui::client_commands::WalletContext;
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use sui_keys::keystore::AccountKeystore;
+use sui_types::object::Owner;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    gas_coin::GasCoin,
+    messages::{
+        ExecuteTransactionRequestType, GasData, Transaction, TransactionData, TransactionDataAPI,
+        TransactionDataV1, TransactionExpiration, TransactionKind,
+    },
+};
+use tokio::sync::{
+    mpsc::{self, Receiver, Sender},
+    Mutex,
+};
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::write_ahead_log::WriteAheadLog;
+use crate::{Sponsor, SponsorConfig, SponsorError, SponsorPolicy, SponsoredTransaction};
+
+/// Sponsors transactions with the gas coins of the active address of its wallet. A coin pays for
+/// a single transaction at a time: it is leased in the WAL when the transaction is handed back to
+/// the sender, and only returns to the pool once the transaction landed, or once the lease expired
+/// and the sponsor cancelled the transaction.
+pub struct SimpleSponsor {
+    wallet: WalletContext,
+    active_address: SuiAddress,
+    policy: SponsorPolicy,
+    /// Coins with a lower balance than the max gas budget are discarded.
+    min_coin_balance: u64,
+    reservation_lease: Duration,
+    producer: Mutex<Sender<ObjectID>>,
+    consumer: Mutex<Receiver<ObjectID>>,
+    metrics: SponsorMetrics,
+    wal: Mutex<WriteAheadLog>,
+}
+
+enum GasCoinResponse {
+    GasCoinWithInsufficientBalance(ObjectID),
+    InvalidGasCoin(ObjectID),
+    NoGasCoinAvailable,
+    UnknownGasCoin(ObjectID),
+    ValidGasCoin(ObjectRef),
+}
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl SimpleSponsor {
+    pub async fn new(
+        mut wallet: WalletContext,
+        prometheus_registry: &Registry,
+        wal_path: &Path,
+        config: &SponsorConfig,
+    ) -> Result<Self, SponsorError> {
+        let policy = SponsorPolicy::new(config)?;
+        let active_address = wallet
+            .active_address()
+            .map_err(|err| SponsorError::Wallet(err.to_string()))?;
+        info!("SimpleSponsor::new with active address: {active_address}");
+
+        let coins = wallet
+            .gas_objects(active_address)
+            .await
+            .map_err(|e| SponsorError::Wallet(e.to_string()))?
+            .iter()
+            // Ok to unwrap() since `get_gas_objects` guarantees gas
+            .map(|q| GasCoin::try_from(&q.1).unwrap())
+            .filter(|coin| coin.0.balance.value() >= config.max_gas_budget)
+            .collect::<Vec<GasCoin>>();
+        let metrics = SponsorMetrics::new(prometheus_registry);
+
+        let wal = WriteAheadLog::open(wal_path);
+        let (producer, consumer) = mpsc::channel(coins.len().max(1));
+        for coin in &coins {
+            let coin_id = *coin.id();
+            if wal
+                .reclaim(coin_id)
+                .map_err(SponsorError::internal)?
+                .is_some()
+            {
+                info!(
+                    ?coin_id,
+                    "Coin is reserved by a sponsored transaction in the WAL"
+                );
+                metrics.total_reserved_coins.inc();
+            } else {
+                producer
+                    .send(coin_id)
+                    .await
+                    .tap_ok(|_| {
+                        info!(?coin_id, "Adding coin to gas pool");
+                        metrics.total_available_coins.inc();
+                    })
+                    .tap_err(|e| error!(?coin_id, "Failed to add coin to gas pools: {e:?}"))
+                    .unwrap();
+            }
+        }
+
+        let sponsor = Self {
+            wallet,
+            active_address,
+            policy,
+            min_coin_balance: config.max_gas_budget,
+            reservation_lease: Duration::from_secs(config.reservation_lease_secs),
+            producer: Mutex::new(producer),
+            consumer: Mutex::new(consumer),
+            metrics,
+            wal: Mutex::new(wal),
+        };
+
+        // Release the coins of transactions which landed or whose lease expired while the sponsor
+        // was down.
+        sponsor.release_reserved_coins().await?;
+        Ok(sponsor)
+    }
+
+    /// Take the consumer lock and pull a Coin ID from the queue, without checking whether it is
+    /// valid or not.
+    async fn pop_gas_coin(&self, uuid: Uuid) -> Option<ObjectID> {
+        let Ok(mut consumer) = tokio::time::timeout(LOCK_TIMEOUT, self.consumer.lock()).await else {
+            error!(?uuid, "Timeout when getting consumer lock");
+            return None;
+        };
+
+        let Ok(coin) = tokio::time::timeout(RECV_TIMEOUT, consumer.recv()).await else {
+            error!(?uuid, "Timeout when getting gas coin from the queue");
+            return None;
+        };
+
+        let Some(coin) = coin else {
+            unreachable!("channel is closed");
+        };
+
+        self.metrics.total_available_coins.dec();
+        Some(coin)
+    }
+
+    /// Pulls a coin from the queue and makes sure it is fit for use (belongs to the sponsor, has
+    /// sufficient balance).
+    async fn prepare_gas_coin(&self, uuid: Uuid) -> GasCoinResponse {
+        let Some(coin_id) = self.pop_gas_coin(uuid).await else {
+            warn!("Failed getting gas coin, try later!");
+            return GasCoinResponse::NoGasCoinAvailable;
+        };
+
+        match self.get_gas_coin_and_check_sponsor_owner(coin_id).await {
+            Ok(Some((coin_ref, gas_coin))) if gas_coin.value() >= self.min_coin_balance => {
+                info!(?uuid, ?coin_id, "balance: {}", gas_coin.value());
+                GasCoinResponse::ValidGasCoin(coin_ref)
+            }
+
+            Ok(Some(_)) => GasCoinResponse::GasCoinWithInsufficientBalance(coin_id),
+
+            Ok(None) => GasCoinResponse::InvalidGasCoin(coin_id),
+
+            Err(e) => {
+                error!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                GasCoinResponse::UnknownGasCoin(coin_id)
+            }
+        }
+    }
+
+    /// Read the gas coin from the fullnode.
+    /// If the coin exists and is a gas coin, return Ok(Some((owner, ref, GasCoin)))
+    /// If the coin invalid, return Ok(None)
+    /// If the fullnode returns an unexpected error, returns Err(e)
+    async fn get_coin(
+        &self,
+        coin_id: ObjectID,
+    ) -> anyhow::Result<Option<(Option<Owner>, ObjectRef, GasCoin)>> {
+        let client = self.wallet.get_client().await?;
+        let gas_obj = client
+            .read_api()
+            .get_object_with_options(
+                coin_id,
+                SuiObjectDataOptions::new()
+                    .with_type()
+                    .with_owner()
+                    .with_content(),
+            )
+            .await?;
+        let o = gas_obj.data;
+        if let Some(o) = o {
+            Ok(GasCoin::try_from(&o)
+                .ok()
+                .map(|coin| (o.owner, o.object_ref(), coin)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Similar to get_coin but checks that the owner is the sponsor address. If the coin exists,
+    /// but does not have the correct owner, returns None
+    async fn get_gas_coin_and_check_sponsor_owner(
+        &self,
+        coin_id: ObjectID,
+    ) -> anyhow::Result<Option<(ObjectRef, GasCoin)>> {
+        let gas_obj = self.get_coin(coin_id).await?;
+        Ok(
+            gas_obj.and_then(|(owner_opt, coin_ref, coin)| match owner_opt {
+                Some(Owner::AddressOwner(owner_addr)) if owner_addr == self.active_address => {
+                    Some((coin_ref, coin))
+                }
+                _ => None,
+            }),
+        )
+    }
+
+    async fn get_system_state(&self) -> Result<SuiSystemStateSummary, SponsorError> {
+        let client = self
+            .wallet
+            .get_client()
+            .await
+            .map_err(|e| SponsorError::Wallet(format!("Unable to get client: {e:?}")))?;
+        client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .map_err(|e| {
+                SponsorError::FullnodeReadingError(format!("Error fetching system state {e:?}"))
+            })
+    }
+
+    /// Release the coins reserved by sponsored transactions which landed, and cancel the
+    /// transactions whose lease expired. Coins are put back in the pool if they are still fit for
+    /// use.
+    pub async fn release_reserved_coins(&self) -> Result<(), SponsorError> {
+        let entries = self
+            .wal
+            .lock()
+            .await
+            .log
+            .safe_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SponsorError::internal)?;
+
+        for (coin_id, entry) in entries {
+            let uuid = Uuid::from_bytes(entry.uuid);
+            let mut coin = match self.get_coin(coin_id).await {
+                Ok(coin) => coin,
+                Err(e) => {
+                    warn!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                    continue;
+                }
+            };
+            // Any change to the version of the coin means that the transaction landed, as the
+            // sponsor does not use the coin for anything else while it is reserved.
+            let reserved_version = entry.tx.gas().first().map(|coin_ref| coin_ref.1);
+            let reserved_coin = coin
+                .as_ref()
+                .map(|(_, coin_ref, _)| *coin_ref)
+                .filter(|coin_ref| Some(coin_ref.1) == reserved_version);
+            let landed = reserved_coin.is_none();
+            if let Some(coin_ref) = reserved_coin {
+                if now_ms() < entry.lease_expiration_ms {
+                    continue;
+                }
+                if let Err(e) = self
+                    .cancel_reservation(uuid, coin_ref, entry.tx.gas_budget())
+                    .await
+                {
+                    warn!(
+                        ?uuid,
+                        ?coin_id,
+                        "Failed to cancel expired reservation: {e:?}"
+                    );
+                    continue;
+                }
+                coin = match self.get_coin(coin_id).await {
+                    Ok(coin) => coin,
+                    Err(e) => {
+                        warn!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                        continue;
+                    }
+                };
+            }
+
+            // It's important to remove the coin from the WAL before recycling it, to avoid a race
+            // with the next request served with this coin.
+            if let Err(e) = self.wal.lock().await.commit(coin_id) {
+                error!(?uuid, ?coin_id, "Failed to remove coin from WAL: {e:?}");
+                continue;
+            }
+            self.metrics.total_reserved_coins.dec();
+            info!(?uuid, ?coin_id, landed, "Released reserved coin");
+            match coin {
+                Some((Some(Owner::AddressOwner(owner)), _, gas_coin))
+                    if owner == self.active_address
+                        && gas_coin.value() >= self.min_coin_balance =>
+                {
+                    self.recycle_gas_coin(coin_id, uuid).await
+                }
+                _ => {
+                    warn!(
+                        ?uuid,
+                        ?coin_id,
+                        "Released coin is not usable, removing from pool"
+                    );
+                    self.metrics.total_discarded_coins.inc();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel the sponsored transaction reserving `coin_ref`, by spending the coin in a transaction
+    /// of the sponsor to itself. This changes the version of the coin, after which the sponsored
+    /// transaction cannot be executed anymore.
+    async fn cancel_reservation(
+        &self,
+        uuid: Uuid,
+        coin_ref: ObjectRef,
+        gas_budget: u64,
+    ) -> Result<(), SponsorError> {
+        let gas_price = self.get_system_state().await?.reference_gas_price;
+        let tx_data = TransactionData::new_transfer_sui(
+            self.active_address,
+            self.active_address,
+            None,
+            coin_ref,
+            gas_budget,
+            gas_price,
+        );
+        let signature = self
+            .wallet
+            .config
+            .keystore
+            .sign_secure(&self.active_address, &tx_data, Intent::sui_transaction())
+            .map_err(SponsorError::internal)?;
+        let tx = Transaction::from_data(tx_data, Intent::sui_transaction(), vec![signature])
+            .verify()
+            .map_err(SponsorError::internal)?;
+        let client = self
+            .wallet
+            .get_client()
+            .await
+            .map_err(|e| SponsorError::Wallet(format!("Unable to get client: {e:?}")))?;
+        // The version of the coin changes even if the transaction fails.
+        let response = client
+            .quorum_driver()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await
+            .map_err(SponsorError::internal)?;
+        info!(
+            ?uuid,
+            coin_id = ?coin_ref.0,
+            tx_digest = ?response.digest,
+            "Cancelled sponsored transaction with an expired lease"
+        );
+        Ok(())
+    }
+
+    async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
+        // The producer should never wait indefinitely, in that the channel is initialized with
+        // big enough capacity.
+        let producer = self.producer.lock().await;
+        info!(?uuid, ?coin_id, "Got producer lock and recycling coin");
+        producer
+            .try_send(coin_id)
+            .expect("unexpected - queue is large enough to hold all coins");
+        self.metrics.total_available_coins.inc();
+        info!(?uuid, ?coin_id, "Recycled coin");
+    }
+
+    /// Reserve `coin_ref` for the transaction and sign it as the sponsor.
+    async fn reserve_and_sign(
+        &self,
+        uuid: Uuid,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        coin_ref: ObjectRef,
+        gas_budget: u64,
+        system_state: &SuiSystemStateSummary,
+    ) -> Result<SponsoredTransaction, SponsorError> {
+        let tx_data = TransactionData::V1(TransactionDataV1 {
+            kind,
+            sender,
+            gas_data: GasData {
+                payment: vec![coin_ref],
+                owner: self.active_address,
+                price: system_state.reference_gas_price,
+                budget: gas_budget,
+            },
+            // The transaction is cancelled by the sponsor once the lease on its coin expired.
+            expiration: TransactionExpiration::None,
+        });
+
+        {
+            // Register the reservation before handing the transaction out, so that the coin is not
+            // reused for another transaction even if the sponsor restarts.
+            let lease_expiration_ms = now_ms() + self.reservation_lease.as_millis() as u64;
+            let mut wal = self.wal.lock().await;
+            wal.reserve(
+                uuid,
+                coin_ref.0,
+                sender,
+                tx_data.clone(),
+                lease_expiration_ms,
+            )
+            .map_err(SponsorError::internal)?;
+        }
+        self.metrics.total_reserved_coins.inc();
+
+        let sponsor_signature = self
+            .wallet
+            .config
+            .keystore
+            .sign_secure(&self.active_address, &tx_data, Intent::sui_transaction())
+            .map_err(SponsorError::internal)?;
+        Ok(SponsoredTransaction {
+            tx_data,
+            sponsor_signature,
+        })
+    }
+
+    /// Pay for transaction `kind` with `gas_budget`, which the policy allowed, out of a coin of the
+    /// pool.
+    async fn sponsor_with_gas_budget(
+        &self,
+        id: Uuid,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_budget: u64,
+    ) -> Result<SponsoredTransaction, SponsorError> {
+        let system_state = self.get_system_state().await?;
+
+        match self.prepare_gas_coin(id).await {
+            GasCoinResponse::ValidGasCoin(coin_ref) => {
+                let coin_id = coin_ref.0;
+                match self
+                    .reserve_and_sign(id, sender, kind, coin_ref, gas_budget, &system_state)
+                    .await
+                {
+                    Ok(sponsored) => {
+                        info!(uuid = ?id, ?sender, ?coin_id, "Sponsored transaction");
+                        self.metrics.total_sponsored_transactions.inc();
+                        Ok(sponsored)
+                    }
+                    Err(e) => {
+                        // The coin is only recycled if it is not reserved, otherwise it is
+                        // released once its lease expires.
+                        if matches!(self.wal.lock().await.reclaim(coin_id), Ok(None)) {
+                            self.recycle_gas_coin(coin_id, id).await;
+                        }
+                        Err(e)
+                    }
+                }
+            }
+
+            GasCoinResponse::UnknownGasCoin(coin_id) => {
+                self.recycle_gas_coin(coin_id, id).await;
+                Err(SponsorError::FullnodeReadingError(format!(
+                    "unknown gas coin {coin_id:?}"
+                )))
+            }
+
+            GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
+                warn!(uuid = ?id, ?coin_id, "Insufficient balance, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                Err(SponsorError::GasCoinWithInsufficientBalance(
+                    coin_id.to_hex_uncompressed(),
+                ))
+            }
+
+            GasCoinResponse::InvalidGasCoin(coin_id) => {
+                // The coin does not exist, or does not belong to the sponsor address.
+                warn!(uuid = ?id, ?coin_id, "Invalid, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                Err(SponsorError::InvalidGasCoin(coin_id.to_hex_uncompressed()))
+            }
+
+            GasCoinResponse::NoGasCoinAvailable => Err(SponsorError::NoGasCoinAvailable),
+        }
+    }
+}
+
+#[async_trait]
+impl Sponsor for SimpleSponsor {
+    async fn sponsor(
+        &self,
+        id: Uuid,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_budget: Option<u64>,
+    ) -> Result<SponsoredTransaction, SponsorError> {
+        info!(?sender, uuid = ?id, "Getting sponsor request");
+
+        let gas_budget = match self
+            .policy
+            .check_transaction(&kind)
+            .and_then(|_| self.policy.gas_budget(gas_budget))
+        {
+            Ok(gas_budget) => self
+                .policy
+                .charge_sender(&*self.wal.lock().await, sender, gas_budget, now_ms())
+                .map(|_| gas_budget),
+            Err(e) => Err(e),
+        }
+        .tap_err(|e| {
+            warn!(?sender, uuid = ?id, "Rejected sponsor request: {e}");
+            self.metrics.total_rejected_requests.inc();
+        })?;
+
+        // The sender is only charged for transactions which are sponsored in the end.
+        let result = self
+            .sponsor_with_gas_budget(id, sender, kind, gas_budget)
+            .await;
+        if result.is_err() {
+            if let Err(e) = self
+                .policy
+                .refund_sender(&*self.wal.lock().await, sender, gas_budget)
+            {
+                error!(?sender, uuid = ?id, "Failed to refund sender: {e}");
+            }
+        }
+        result
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_json_rpc_types::{
+        SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    };
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::SUI_FRAMEWORK_OBJECT_ID;
+    use test_utils::network::TestClusterBuilder;
+
+    use super::*;
+
+    fn sponsor_config() -> SponsorConfig {
+        SponsorConfig {
+            allowed_packages: vec![SUI_FRAMEWORK_OBJECT_ID],
+            ..Default::default()
+        }
+    }
+
+    /// A transaction of `sender` transferring one of its coins to a random address.
+    async fn transfer_kind(sponsor: &SimpleSponsor, sender: SuiAddress) -> TransactionKind {
+        let coin = sponsor.wallet.gas_objects(sender).await.unwrap()[0]
+            .1
+            .object_ref();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .transfer_object(SuiAddress::random_for_testing_only(), coin)
+            .unwrap();
+        TransactionKind::programmable(builder.finish())
+    }
+
+    /// Sign `sponsored` as `sender` and execute it with the signature of the sponsor.
+    async fn execute(
+        sponsor: &SimpleSponsor,
+        sender: SuiAddress,
+        sponsored: SponsoredTransaction,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let sender_signature = sponsor.wallet.config.keystore.sign_secure(
+            &sender,
+            &sponsored.tx_data,
+            Intent::sui_transaction(),
+        )?;
+        let tx = Transaction::from_data(
+            sponsored.tx_data,
+            Intent::sui_transaction(),
+            vec![sender_signature, sponsored.sponsor_signature],
+        )
+        .verify()?;
+        Ok(sponsor
+            .wallet
+            .get_client()
+            .await?
+            .quorum_driver()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn test_sponsored_transaction_releases_coin() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let sponsor = SimpleSponsor::new(
+            test_cluster.wallet,
+            &Registry::new(),
+            &tmp.path().join("sponsor.wal"),
+            &sponsor_config(),
+        )
+        .await
+        .unwrap();
+        assert_ne!(sender, sponsor.active_address);
+        let available = sponsor.metrics.total_available_coins.get();
+
+        let kind = transfer_kind(&sponsor, sender).await;
+        let sponsored = sponsor
+            .sponsor(Uuid::new_v4(), sender, kind, None)
+            .await
+            .unwrap();
+        let coin_id = sponsored.tx_data.gas()[0].0;
+        assert_eq!(sponsored.tx_data.gas_owner(), sponsor.active_address);
+        assert_eq!(available - 1, sponsor.metrics.total_available_coins.get());
+        assert_eq!(1, sponsor.metrics.total_reserved_coins.get());
+
+        // The coin stays reserved until the transaction lands.
+        sponsor.release_reserved_coins().await.unwrap();
+        assert_eq!(1, sponsor.metrics.total_reserved_coins.get());
+        assert!(sponsor.wal.lock().await.reclaim(coin_id).unwrap().is_some());
+
+        let response = execute(&sponsor, sender, sponsored).await.unwrap();
+        assert_eq!(
+            response.effects.unwrap().status(),
+            &SuiExecutionStatus::Success
+        );
+
+        sponsor.release_reserved_coins().await.unwrap();
+        assert_eq!(0, sponsor.metrics.total_reserved_coins.get());
+        assert_eq!(available, sponsor.metrics.total_available_coins.get());
+        assert!(sponsor.wal.lock().await.reclaim(coin_id).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_cancels_transaction() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config = SponsorConfig {
+            reservation_lease_secs: 0,
+            ..sponsor_config()
+        };
+        let sponsor = SimpleSponsor::new(
+            test_cluster.wallet,
+            &Registry::new(),
+            &tmp.path().join("sponsor.wal"),
+            &config,
+        )
+        .await
+        .unwrap();
+        let available = sponsor.metrics.total_available_coins.get();
+
+        let kind = transfer_kind(&sponsor, sender).await;
+        let sponsored = sponsor
+            .sponsor(Uuid::new_v4(), sender, kind, None)
+            .await
+            .unwrap();
+
+        // The lease expired before the sender executed the transaction, so the sponsor spends the
+        // coin and puts it back in the pool.
+        sponsor.release_reserved_coins().await.unwrap();
+        assert_eq!(0, sponsor.metrics.total_reserved_coins.get());
+        assert_eq!(available, sponsor.metrics.total_available_coins.get());
+
+        // The sponsored transaction refers to a version of the coin which no longer exists.
+        assert!(execute(&sponsor, sender, sponsored).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_refunds_sender() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+
+        let tmp = tempfile::tempdir().unwrap();
+        // No coin has enough balance to pay for the max gas budget, so the pool is empty.
+        let config = SponsorConfig {
+            max_gas_budget: u64::MAX,
+            sender_max_requests: 1,
+            ..sponsor_config()
+        };
+        let sponsor = SimpleSponsor::new(
+            test_cluster.wallet,
+            &Registry::new(),
+            &tmp.path().join("sponsor.wal"),
+            &config,
+        )
+        .await
+        .unwrap();
+        assert_eq!(0, sponsor.metrics.total_available_coins.get());
+
+        // The second request would be rate limited if the sender had been charged for the first.
+        for _ in 0..2 {
+            let kind = transfer_kind(&sponsor, sender).await;
+            assert!(matches!(
+                sponsor.sponsor(Uuid::new_v4(), sender, kind, None).await,
+                Err(SponsorError::NoGasCoinAvailable)
+            ));
+        }
+    }
+}
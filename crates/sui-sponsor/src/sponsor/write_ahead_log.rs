@@ -0,0 +1,203 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use sui_types::{base_types::ObjectID, messages::TransactionData};
+use typed_store::rocks::{DBMap, TypedStoreError};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+
+use typed_store_derive::DBMapUtils;
+use uuid::Uuid;
+
+/// Persistent log of the transactions sponsored by the service, keyed by the gas coin paying for
+/// them. Transactions are written to the log before they are handed back to the sender for
+/// execution, and removed once they landed or were cancelled, before the coin becomes available
+/// for subsequent transactions.
+///
+/// This allows the sponsor to go down and back up without reusing a coin still reserved by a
+/// transaction that the sender may execute, which would equivocate the coin.
+///
+/// The log also keeps what each sender spent within its budget window, so that budgets are not
+/// reset across restarts.
+#[derive(DBMapUtils, Clone)]
+pub struct WriteAheadLog {
+    pub log: DBMap<ObjectID, Entry>,
+    pub sender_usage: DBMap<SuiAddress, SenderUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Entry {
+    pub uuid: uuid::Bytes,
+    pub sender: SuiAddress,
+    pub tx: TransactionData,
+    /// When the reservation expires, in ms since the unix epoch. Past that point, the sponsor
+    /// cancels the transaction if it did not land.
+    pub lease_expiration_ms: u64,
+}
+
+/// What a sender spent within its current budget window.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct SenderUsage {
+    /// When the window started, in ms since the unix epoch.
+    pub window_start_ms: u64,
+    pub requests: u64,
+    pub gas_budget: u64,
+}
+
+impl WriteAheadLog {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::default(),
+            None,
+            None,
+        )
+    }
+
+    /// Mark `coin` as reserved for transaction `tx` sponsored for `sender` until
+    /// `lease_expiration_ms`. Fails if `coin` is already in the WAL pointing to an existing
+    /// transaction.
+    pub(crate) fn reserve(
+        &mut self,
+        uuid: Uuid,
+        coin: ObjectID,
+        sender: SuiAddress,
+        tx: TransactionData,
+        lease_expiration_ms: u64,
+    ) -> Result<(), TypedStoreError> {
+        if self.log.contains_key(&coin)? {
+            // Don't permit multiple transactions paid by the same coin
+            return Err(TypedStoreError::SerializationError(format!(
+                "Duplicate WAL entry for coin {coin:?}",
+            )));
+        }
+
+        let uuid = *uuid.as_bytes();
+        self.log.insert(
+            &coin,
+            &Entry {
+                uuid,
+                sender,
+                tx,
+                lease_expiration_ms,
+            },
+        )
+    }
+
+    /// Check whether `coin` is reserved by a transaction in the WAL.  Returns `Ok(Some(entry))` if
+    /// it is, `Ok(None)` if not, and `Err(_)` if there was an internal error accessing the WAL.
+    pub(crate) fn reclaim(&self, coin: ObjectID) -> Result<Option<Entry>, TypedStoreError> {
+        self.log.get(&coin)
+    }
+
+    /// Indicate that the transaction reserving `coin` has landed or was cancelled, and the entry in
+    /// the WAL can be removed.
+    pub(crate) fn commit(&mut self, coin: ObjectID) -> Result<(), TypedStoreError> {
+        self.log.remove(&coin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::{
+        base_types::{random_object_ref, ObjectRef},
+        messages::TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reserve_reclaim_reclaim() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let uuid = Uuid::new_v4();
+        let coin = random_object_ref();
+        let (sender, tx) = random_request(coin);
+
+        assert!(wal.reserve(uuid, coin.0, sender, tx.clone(), 1000).is_ok());
+
+        // Reclaim once
+        let Some(entry) = wal.reclaim(coin.0).unwrap() else {
+            panic!("Entry not found for {}", coin.0);
+        };
+
+        assert_eq!(uuid, Uuid::from_bytes(entry.uuid));
+        assert_eq!(sender, entry.sender);
+        assert_eq!(tx, entry.tx);
+        assert_eq!(1000, entry.lease_expiration_ms);
+
+        // Reclaim again, should still be there.
+        let Some(entry) = wal.reclaim(coin.0).unwrap() else {
+            panic!("Entry not found for {}", coin.0);
+        };
+
+        assert_eq!(uuid, Uuid::from_bytes(entry.uuid));
+        assert_eq!(sender, entry.sender);
+        assert_eq!(tx, entry.tx);
+    }
+
+    #[tokio::test]
+    async fn reserve_reserve() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let uuid = Uuid::new_v4();
+        let coin = random_object_ref();
+        let (sender0, tx0) = random_request(coin);
+        let (sender1, tx1) = random_request(coin);
+
+        // First write goes through
+        wal.reserve(uuid, coin.0, sender0, tx0, 1000).unwrap();
+
+        // Second write fails because it tries to write to the same coin
+        assert!(matches!(
+            wal.reserve(uuid, coin.0, sender1, tx1, 1000),
+            Err(TypedStoreError::SerializationError(_)),
+        ));
+    }
+
+    #[tokio::test]
+    async fn reserve_commit_reserve() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let uuid = Uuid::new_v4();
+        let coin = random_object_ref();
+        let (sender0, tx0) = random_request(coin);
+        let (sender1, tx1) = random_request(coin);
+
+        // Write the transaction
+        wal.reserve(uuid, coin.0, sender0, tx0, 1000).unwrap();
+
+        // Commit the transaction, which removes it from the log.
+        wal.commit(coin.0).unwrap();
+        assert_eq!(Ok(None), wal.reclaim(coin.0));
+
+        // Write a fresh transaction, which should now pass
+        wal.reserve(uuid, coin.0, sender1, tx1, 1000).unwrap();
+    }
+
+    fn random_request(coin: ObjectRef) -> (SuiAddress, TransactionData) {
+        let gas_price = 1;
+        let sender = SuiAddress::random_for_testing_only();
+        let recv = SuiAddress::random_for_testing_only();
+        (
+            sender,
+            TransactionData::new_pay_sui(
+                sender,
+                vec![random_object_ref()],
+                vec![recv],
+                vec![1000],
+                coin,
+                gas_price * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+                gas_price,
+            )
+            .unwrap(),
+        )
+    }
+}
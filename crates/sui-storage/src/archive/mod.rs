@@ -0,0 +1,387 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archival of checkpoints into an object store.
+//!
+//! The [writer::ArchiveWriter] continuously uploads certified checkpoint summaries, along with the
+//! transactions, effects and events they contain, into any of the backends supported by
+//! [crate::object_store::ObjectStoreConfig]. The [reader::ArchiveReader] reads them back,
+//! verifying them along the way, so that a node can be synced from the archive alone.
+//!
+//! Checkpoints are grouped into files, each file covering a contiguous range of checkpoints from
+//! a single epoch. The archive is laid out as follows:
+//! ```text
+//! <root>
+//!  ├── MANIFEST
+//!  ├── epoch_0
+//!  │    ├── 0.chk
+//!  │    ├── 0.sum
+//!  │    ├── 1000.chk
+//!  │    ├── 1000.sum
+//!  │    └── ...
+//!  └── epoch_1
+//!       ├── 4500.chk
+//!       └── ...
+//! ```
+//! where files are named after the first checkpoint they contain, `*.sum` files hold the
+//! [CertifiedCheckpointSummary] of each checkpoint and `*.chk` files the matching
+//! [CheckpointArchiveContents].
+//!
+//! Both kinds of files are encoded as:
+//! ```text
+//! ┌──────────────┬────────────────┬────────────────────┬───────────────────────────────┐
+//! │ magic <4 B>  │ version <1 B>  │ compression <1 B>  │ (compressed) blobs            │
+//! └──────────────┴────────────────┴────────────────────┴───────────────────────────────┘
+//! ```
+//! where the blobs are the BCS encoded entries of the file, one per checkpoint in order, each
+//! prefixed by its length as a little endian u32. Compression applies to the whole sequence of
+//! blobs.
+//!
+//! The MANIFEST lists every file of the archive along with the checkpoints it covers and the
+//! checksum of its content, and is encoded as:
+//! ```text
+//! ┌──────────────┬─────────────────────┬───────────────────┐
+//! │ magic <4 B>  │ BCS(Manifest) <var> │ checksum <32 B>   │
+//! └──────────────┴─────────────────────┴───────────────────┘
+//! ```
+//! Files are always uploaded before the MANIFEST referencing them, so the MANIFEST only ever
+//! refers to complete files. Files which are not referenced by the MANIFEST are ignored by
+//! readers and overwritten by writers.
+
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bytes::Bytes;
+use fastcrypto::hash::HashFunction;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::EpochId;
+use sui_types::crypto::DefaultHash;
+use sui_types::messages::TransactionEvents;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, FullCheckpointContents};
+
+use crate::object_store::util::put;
+
+pub mod reader;
+pub mod writer;
+
+#[cfg(test)]
+mod tests;
+
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const EPOCH_DIR_PREFIX: &str = "epoch_";
+pub const CHECKPOINT_FILE_SUFFIX: &str = "chk";
+pub const SUMMARY_FILE_SUFFIX: &str = "sum";
+
+pub const MANIFEST_FILE_MAGIC: u32 = 0x00C0_FFEE;
+pub const CHECKPOINT_FILE_MAGIC: u32 = 0x0000_DEAD;
+pub const SUMMARY_FILE_MAGIC: u32 = 0x0000_CAFE;
+
+const FILE_FORMAT_VERSION: u8 = 1;
+pub const CHECKSUM_LENGTH: usize = 32;
+
+/// Transactions, effects and events of a single checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointArchiveContents {
+    pub contents: FullCheckpointContents,
+    /// Events of the transactions emitting any, in the order of the transactions.
+    pub events: Vec<TransactionEvents>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum FileCompression {
+    None = 0,
+    Snappy = 1,
+}
+
+impl TryFrom<u8> for FileCompression {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(FileCompression::None),
+            1 => Ok(FileCompression::Snappy),
+            _ => Err(anyhow!("Unknown file compression: {value}")),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileType {
+    CheckpointContents,
+    CheckpointSummary,
+}
+
+impl FileType {
+    fn magic(&self) -> u32 {
+        match self {
+            FileType::CheckpointContents => CHECKPOINT_FILE_MAGIC,
+            FileType::CheckpointSummary => SUMMARY_FILE_MAGIC,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            FileType::CheckpointContents => CHECKPOINT_FILE_SUFFIX,
+            FileType::CheckpointSummary => SUMMARY_FILE_SUFFIX,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub file_type: FileType,
+    pub epoch_num: EpochId,
+    pub checkpoint_seq_range: Range<CheckpointSequenceNumber>,
+    pub checksum: [u8; CHECKSUM_LENGTH],
+}
+
+impl FileMetadata {
+    pub fn new(
+        file_type: FileType,
+        epoch_num: EpochId,
+        checkpoint_seq_range: Range<CheckpointSequenceNumber>,
+        bytes: &[u8],
+    ) -> Self {
+        Self {
+            file_type,
+            epoch_num,
+            checkpoint_seq_range,
+            checksum: compute_checksum(bytes),
+        }
+    }
+
+    pub fn file_path(&self) -> Path {
+        Path::from(format!("{EPOCH_DIR_PREFIX}{}", self.epoch_num)).child(format!(
+            "{}.{}",
+            self.checkpoint_seq_range.start,
+            self.file_type.suffix()
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Manifest {
+    V1(ManifestV1),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestV1 {
+    /// Epoch of the last archived checkpoint.
+    pub epoch: EpochId,
+    /// All checkpoints before this one are archived.
+    pub next_checkpoint_seq_num: CheckpointSequenceNumber,
+    pub file_metadata: Vec<FileMetadata>,
+}
+
+impl Manifest {
+    pub fn new(epoch: EpochId, next_checkpoint_seq_num: CheckpointSequenceNumber) -> Self {
+        Manifest::V1(ManifestV1 {
+            epoch,
+            next_checkpoint_seq_num,
+            file_metadata: vec![],
+        })
+    }
+
+    pub fn epoch_num(&self) -> EpochId {
+        match self {
+            Manifest::V1(manifest) => manifest.epoch,
+        }
+    }
+
+    pub fn next_checkpoint_seq_num(&self) -> CheckpointSequenceNumber {
+        match self {
+            Manifest::V1(manifest) => manifest.next_checkpoint_seq_num,
+        }
+    }
+
+    pub fn files(&self) -> &[FileMetadata] {
+        match self {
+            Manifest::V1(manifest) => &manifest.file_metadata,
+        }
+    }
+
+    /// Record files covering the checkpoints right after the ones already archived.
+    pub fn update(
+        &mut self,
+        epoch: EpochId,
+        next_checkpoint_seq_num: CheckpointSequenceNumber,
+        files: impl IntoIterator<Item = FileMetadata>,
+    ) {
+        match self {
+            Manifest::V1(manifest) => {
+                manifest.epoch = epoch;
+                manifest.next_checkpoint_seq_num = next_checkpoint_seq_num;
+                manifest.file_metadata.extend(files);
+            }
+        }
+    }
+
+    /// Pairs of summary and contents files covering checkpoints in `range`, in order.
+    pub fn file_pairs(
+        &self,
+        range: Range<CheckpointSequenceNumber>,
+    ) -> Vec<(&FileMetadata, &FileMetadata)> {
+        let files_of_type = |file_type| {
+            self.files()
+                .iter()
+                .filter(move |f| f.file_type == file_type)
+                .filter(|f| {
+                    f.checkpoint_seq_range.start < range.end
+                        && range.start < f.checkpoint_seq_range.end
+                })
+        };
+        files_of_type(FileType::CheckpointSummary)
+            .zip(files_of_type(FileType::CheckpointContents))
+            .collect()
+    }
+}
+
+pub fn compute_checksum(bytes: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut hasher = DefaultHash::default();
+    hasher.update(bytes);
+    hasher.finalize().digest
+}
+
+/// Encode BCS serialized blobs into an archive file.
+pub fn encode_file(
+    file_type: FileType,
+    compression: FileCompression,
+    blobs: &[Vec<u8>],
+) -> Result<Bytes> {
+    encode_blobs(file_type.magic(), compression, blobs)
+}
+
+/// Decode the entries of an archive file.
+pub fn decode_file<T: DeserializeOwned>(file_type: FileType, bytes: &[u8]) -> Result<Vec<T>> {
+    decode_blobs(file_type.magic(), bytes)
+        .with_context(|| format!("Failed to decode {file_type:?} file"))
+}
+
+/// Encode BCS serialized blobs into a file starting with `magic`, using the same format as
+/// archive files.
+pub fn encode_blobs(magic: u32, compression: FileCompression, blobs: &[Vec<u8>]) -> Result<Bytes> {
+    let mut body = Vec::with_capacity(blobs.iter().map(|b| b.len() + 4).sum());
+    for blob in blobs {
+        body.extend_from_slice(&u32::try_from(blob.len())?.to_le_bytes());
+        body.extend_from_slice(blob);
+    }
+    let mut bytes = Vec::with_capacity(body.len() + 6);
+    bytes.extend_from_slice(&magic.to_be_bytes());
+    bytes.push(FILE_FORMAT_VERSION);
+    bytes.push(compression as u8);
+    match compression {
+        FileCompression::None => bytes.extend_from_slice(&body),
+        FileCompression::Snappy => {
+            let mut encoder = snap::write::FrameEncoder::new(bytes);
+            encoder.write_all(&body)?;
+            bytes = encoder
+                .into_inner()
+                .map_err(|e| anyhow!("Failed to compress file: {e}"))?;
+        }
+    }
+    Ok(Bytes::from(bytes))
+}
+
+/// Decode the entries of a file written by [encode_blobs], checking that it starts with `magic`.
+pub fn decode_blobs<T: DeserializeOwned>(magic: u32, bytes: &[u8]) -> Result<Vec<T>> {
+    ensure!(bytes.len() >= 6, "File is too short");
+    let file_magic = u32::from_be_bytes(bytes[0..4].try_into()?);
+    ensure!(
+        file_magic == magic,
+        "Unexpected magic {file_magic:#x}, expected {magic:#x}"
+    );
+    ensure!(
+        bytes[4] == FILE_FORMAT_VERSION,
+        "Unsupported file format version {}",
+        bytes[4]
+    );
+    let body = match FileCompression::try_from(bytes[5])? {
+        FileCompression::None => bytes[6..].to_vec(),
+        FileCompression::Snappy => {
+            let mut body = vec![];
+            snap::read::FrameDecoder::new(&bytes[6..]).read_to_end(&mut body)?;
+            body
+        }
+    };
+
+    let mut entries = vec![];
+    let mut remaining = body.as_slice();
+    while !remaining.is_empty() {
+        ensure!(remaining.len() >= 4, "Truncated file entry");
+        let (len, rest) = remaining.split_at(4);
+        let len = u32::from_le_bytes(len.try_into()?) as usize;
+        ensure!(rest.len() >= len, "Truncated file entry");
+        let (blob, rest) = rest.split_at(len);
+        entries.push(bcs::from_bytes(blob)?);
+        remaining = rest;
+    }
+    Ok(entries)
+}
+
+/// Read the MANIFEST of the archive, if there is one.
+pub async fn read_manifest(store: Arc<DynObjectStore>) -> Result<Option<Manifest>> {
+    read_manifest_file(store, &Path::from(MANIFEST_FILENAME), MANIFEST_FILE_MAGIC).await
+}
+
+pub async fn write_manifest(manifest: &Manifest, store: Arc<DynObjectStore>) -> Result<()> {
+    write_manifest_file(
+        manifest,
+        &Path::from(MANIFEST_FILENAME),
+        MANIFEST_FILE_MAGIC,
+        store,
+    )
+    .await
+}
+
+/// Read a MANIFEST written by [write_manifest_file] with the same `magic`, if there is one at
+/// `path`.
+pub async fn read_manifest_file<T: DeserializeOwned>(
+    store: Arc<DynObjectStore>,
+    path: &Path,
+    magic: u32,
+) -> Result<Option<T>> {
+    let bytes = match store.get(path).await {
+        Ok(result) => result.bytes().await?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    ensure!(
+        bytes.len() >= 4 + CHECKSUM_LENGTH,
+        "MANIFEST {path} is too short"
+    );
+    let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LENGTH);
+    ensure!(
+        compute_checksum(content) == checksum,
+        "MANIFEST {path} checksum mismatch"
+    );
+    let file_magic = u32::from_be_bytes(content[0..4].try_into()?);
+    ensure!(
+        file_magic == magic,
+        "Unexpected magic {file_magic:#x} for MANIFEST {path}, expected {magic:#x}"
+    );
+    let manifest = bcs::from_bytes(&content[4..])
+        .with_context(|| format!("Failed to decode MANIFEST {path}"))?;
+    Ok(Some(manifest))
+}
+
+/// Write a MANIFEST at `path`, encoded as the magic, the BCS bytes of the manifest and their
+/// checksum.
+pub async fn write_manifest_file<T: Serialize>(
+    manifest: &T,
+    path: &Path,
+    magic: u32,
+    store: Arc<DynObjectStore>,
+) -> Result<()> {
+    let mut bytes = magic.to_be_bytes().to_vec();
+    bytes.extend(bcs::to_bytes(manifest)?);
+    let checksum = compute_checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    put(path, Bytes::from(bytes), store).await?;
+    Ok(())
+}
@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use object_store::DynObjectStore;
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, VerifiedCheckpoint,
+    VerifiedCheckpointContents,
+};
+use sui_types::storage::{verify_checkpoint, ReadStore, WriteStore};
+use tracing::info;
+
+use super::{
+    compute_checksum, decode_file, read_manifest, CheckpointArchiveContents, FileMetadata, Manifest,
+};
+use crate::object_store::ObjectStoreConfig;
+
+/// Reads checkpoints back from an archive written by [super::writer::ArchiveWriter].
+pub struct ArchiveReader {
+    remote_store: Arc<DynObjectStore>,
+}
+
+impl ArchiveReader {
+    pub fn new(remote_store_config: &ObjectStoreConfig) -> Result<Self> {
+        Ok(Self {
+            remote_store: remote_store_config.make()?,
+        })
+    }
+
+    /// The MANIFEST of the archive, failing if there is none yet.
+    pub async fn manifest(&self) -> Result<Manifest> {
+        read_manifest(self.remote_store.clone())
+            .await?
+            .ok_or_else(|| anyhow!("Archive has no MANIFEST"))
+    }
+
+    /// The highest checkpoint available in the archive, if any.
+    pub async fn latest_available_checkpoint(&self) -> Result<Option<CheckpointSequenceNumber>> {
+        let next = self.manifest().await?.next_checkpoint_seq_num();
+        Ok(next.checked_sub(1))
+    }
+
+    /// Read the checkpoints in `range` from the archive, only checking that files match their
+    /// checksum in the MANIFEST. Checkpoints past the end of the archive are silently omitted.
+    pub async fn read(
+        &self,
+        range: Range<CheckpointSequenceNumber>,
+    ) -> Result<Vec<(CertifiedCheckpointSummary, CheckpointArchiveContents)>> {
+        let manifest = self.manifest().await?;
+        let mut checkpoints = vec![];
+        for (summary_file, contents_file) in manifest.file_pairs(range.clone()) {
+            checkpoints.extend(
+                self.read_file_pair(summary_file, contents_file, &range)
+                    .await?,
+            );
+        }
+        Ok(checkpoints)
+    }
+
+    /// Sync `store` from the archive, up to the `up_to` checkpoint (inclusive) or to the end of
+    /// the archive. Checkpoints are verified against the committees known to the store, exactly
+    /// as state sync does, before being inserted. Only one pair of archive files is held in
+    /// memory at a time.
+    pub async fn sync_to<S>(&self, store: S, up_to: Option<CheckpointSequenceNumber>) -> Result<()>
+    where
+        S: WriteStore,
+        <S as ReadStore>::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let manifest = self.manifest().await?;
+        let Some(latest) = manifest.next_checkpoint_seq_num().checked_sub(1) else {
+            return Ok(());
+        };
+        let end = up_to.map_or(latest, |up_to| up_to.min(latest));
+        let mut current = store.get_highest_synced_checkpoint()?;
+        let start = current.sequence_number().saturating_add(1);
+        if start > end {
+            return Ok(());
+        }
+        info!(start, end, "Syncing checkpoints from archive");
+
+        let range = start..end + 1;
+        for (summary_file, contents_file) in manifest.file_pairs(range.clone()) {
+            for (summary, contents) in self
+                .read_file_pair(summary_file, contents_file, &range)
+                .await?
+            {
+                ensure!(
+                    *summary.sequence_number() == current.sequence_number().saturating_add(1),
+                    "Expected checkpoint {} in archive, found {}",
+                    current.sequence_number().saturating_add(1),
+                    summary.sequence_number()
+                );
+                let checkpoint =
+                    verify_checkpoint(&current, &store, summary).map_err(|checkpoint| {
+                        anyhow!(
+                            "Archived checkpoint {} failed verification",
+                            checkpoint.sequence_number()
+                        )
+                    })?;
+                verify_contents(&checkpoint, &contents)?;
+                store.insert_checkpoint(checkpoint.clone())?;
+                store.insert_checkpoint_contents(VerifiedCheckpointContents::new_unchecked(
+                    contents.contents,
+                ))?;
+                store.update_highest_synced_checkpoint(&checkpoint)?;
+                current = checkpoint;
+            }
+        }
+        ensure!(
+            *current.sequence_number() == end,
+            "Archive is missing checkpoints after {}",
+            current.sequence_number()
+        );
+        Ok(())
+    }
+
+    /// Read the checkpoints of a pair of summary and contents files that fall in `range`.
+    async fn read_file_pair(
+        &self,
+        summary_file: &FileMetadata,
+        contents_file: &FileMetadata,
+        range: &Range<CheckpointSequenceNumber>,
+    ) -> Result<Vec<(CertifiedCheckpointSummary, CheckpointArchiveContents)>> {
+        ensure!(
+            summary_file.checkpoint_seq_range == contents_file.checkpoint_seq_range,
+            "Mismatched archive files {} and {}",
+            summary_file.file_path(),
+            contents_file.file_path()
+        );
+        let summaries: Vec<CertifiedCheckpointSummary> = self.read_file(summary_file).await?;
+        let contents: Vec<CheckpointArchiveContents> = self.read_file(contents_file).await?;
+        ensure!(
+            summaries.len() == contents.len()
+                && summaries.len() as u64
+                    == summary_file.checkpoint_seq_range.end
+                        - summary_file.checkpoint_seq_range.start,
+            "Unexpected number of checkpoints in archive files {} and {}",
+            summary_file.file_path(),
+            contents_file.file_path()
+        );
+        Ok(summaries
+            .into_iter()
+            .zip(contents)
+            .filter(|(summary, _)| range.contains(summary.sequence_number()))
+            .collect())
+    }
+
+    async fn read_file<T: serde::de::DeserializeOwned>(
+        &self,
+        metadata: &FileMetadata,
+    ) -> Result<Vec<T>> {
+        let path = metadata.file_path();
+        let bytes = self.remote_store.get(&path).await?.bytes().await?;
+        ensure!(
+            compute_checksum(&bytes) == metadata.checksum,
+            "Checksum mismatch for archive file {path}"
+        );
+        decode_file(metadata.file_type, &bytes)
+            .with_context(|| format!("Failed to decode archive file {path}"))
+    }
+}
+
+fn verify_contents(
+    checkpoint: &VerifiedCheckpoint,
+    contents: &CheckpointArchiveContents,
+) -> Result<()> {
+    contents
+        .contents
+        .verify_digests(checkpoint.content_digest)?;
+    let mut events = contents.events.iter();
+    for tx in contents.contents.iter() {
+        if let Some(digest) = tx.effects.events_digest() {
+            let tx_events = events.next().ok_or_else(|| {
+                anyhow!(
+                    "Missing events in archived checkpoint {}",
+                    checkpoint.sequence_number()
+                )
+            })?;
+            ensure!(
+                tx_events.digest() == *digest,
+                "Events digest mismatch in archived checkpoint {}",
+                checkpoint.sequence_number()
+            );
+        }
+    }
+    ensure!(
+        events.next().is_none(),
+        "Unexpected events in archived checkpoint {}",
+        checkpoint.sequence_number()
+    );
+    Ok(())
+}
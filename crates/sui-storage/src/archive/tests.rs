@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_network::state_sync::test_utils::{empty_contents, CommitteeFixture};
+use sui_types::messages_checkpoint::VerifiedCheckpoint;
+use sui_types::storage::{ReadStore, SharedInMemoryStore, WriteStore};
+use tempfile::TempDir;
+
+use super::reader::ArchiveReader;
+use super::writer::ArchiveWriter;
+use super::*;
+use crate::object_store::ObjectStoreConfig;
+
+fn insert_checkpoints(store: &SharedInMemoryStore, checkpoints: &[VerifiedCheckpoint]) {
+    for checkpoint in checkpoints {
+        store.insert_checkpoint(checkpoint.clone()).unwrap();
+        store.insert_checkpoint_contents(empty_contents()).unwrap();
+        store.update_highest_synced_checkpoint(checkpoint).unwrap();
+    }
+}
+
+#[test]
+fn test_file_roundtrip() -> Result<()> {
+    let blobs = vec![vec![1u8, 2, 3], vec![], vec![4u8; 1000]];
+    for compression in [FileCompression::None, FileCompression::Snappy] {
+        let bytes = encode_file(
+            FileType::CheckpointContents,
+            compression,
+            &blobs
+                .iter()
+                .map(bcs::to_bytes)
+                .collect::<Result<Vec<_>, _>>()?,
+        )?;
+        let decoded: Vec<Vec<u8>> = decode_file(FileType::CheckpointContents, &bytes)?;
+        assert_eq!(decoded, blobs);
+        assert!(decode_file::<Vec<u8>>(FileType::CheckpointSummary, &bytes).is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_write_and_sync() -> Result<()> {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _) = committee.make_checkpoints(10, None);
+    let source = SharedInMemoryStore::default();
+    source.inner_mut().insert_genesis_state(
+        ordered_checkpoints[0].clone(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    insert_checkpoints(&source, &ordered_checkpoints[1..6]);
+
+    let archive_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(archive_dir.path());
+    // Every checkpoint is large enough to be committed into its own pair of files.
+    let mut writer = ArchiveWriter::new(
+        source.clone(),
+        &config,
+        1,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )?;
+    writer.sync().await?;
+
+    let reader = ArchiveReader::new(&config)?;
+    assert_eq!(reader.latest_available_checkpoint().await?, Some(5));
+    assert_eq!(reader.manifest().await?.files().len(), 12);
+
+    // Resume archival from the MANIFEST, leaving the last checkpoints pending.
+    insert_checkpoints(&source, &ordered_checkpoints[6..]);
+    let mut writer = ArchiveWriter::new(
+        source.clone(),
+        &config,
+        usize::MAX,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )?;
+    writer.sync().await?;
+    assert_eq!(reader.latest_available_checkpoint().await?, Some(5));
+    writer.flush().await?;
+    assert_eq!(reader.latest_available_checkpoint().await?, Some(9));
+    assert_eq!(reader.manifest().await?.files().len(), 14);
+
+    let target = SharedInMemoryStore::default();
+    target.inner_mut().insert_genesis_state(
+        ordered_checkpoints[0].clone(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    reader.sync_to(&target, Some(7)).await?;
+    assert_eq!(
+        target.get_highest_synced_checkpoint()?,
+        ordered_checkpoints[7]
+    );
+    reader.sync_to(&target, None).await?;
+    assert_eq!(
+        target.get_highest_synced_checkpoint()?,
+        ordered_checkpoints[9]
+    );
+    for checkpoint in &ordered_checkpoints {
+        assert_eq!(
+            target.get_checkpoint_by_sequence_number(checkpoint.sequence_number)?,
+            Some(checkpoint.clone())
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_rejects_unsigned_checkpoints() -> Result<()> {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _) = committee.make_checkpoints(3, None);
+    let source = SharedInMemoryStore::default();
+    source.inner_mut().insert_genesis_state(
+        ordered_checkpoints[0].clone(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    insert_checkpoints(&source, &ordered_checkpoints[1..]);
+
+    let archive_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(archive_dir.path());
+    let mut writer = ArchiveWriter::new(
+        source,
+        &config,
+        usize::MAX,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )?;
+    writer.sync().await?;
+    writer.flush().await?;
+
+    // A node which trusts another committee must not accept the archived checkpoints.
+    let other_committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let target = SharedInMemoryStore::default();
+    target.inner_mut().insert_genesis_state(
+        ordered_checkpoints[0].clone(),
+        empty_contents(),
+        other_committee.committee().to_owned(),
+    );
+    let reader = ArchiveReader::new(&config)?;
+    assert!(reader.sync_to(&target, None).await.is_err());
+    assert_eq!(
+        target.get_highest_synced_checkpoint()?,
+        ordered_checkpoints[0]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_writer_fails_on_pruned_store() -> Result<()> {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _) = committee.make_checkpoints(5, None);
+    let source = SharedInMemoryStore::default();
+    source.inner_mut().insert_genesis_state(
+        ordered_checkpoints[0].clone(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    insert_checkpoints(&source, &ordered_checkpoints[1..]);
+    source.inner_mut().prune_checkpoint_contents(2);
+
+    // A new archive has to start at genesis, whose contents are gone.
+    let archive_dir = TempDir::new()?;
+    let config = ObjectStoreConfig::local(archive_dir.path());
+    let writer = ArchiveWriter::new(
+        source.clone(),
+        &config,
+        usize::MAX,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )?;
+    assert!(writer.start().await.is_err());
+    let mut writer = ArchiveWriter::new(
+        source,
+        &config,
+        usize::MAX,
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    )?;
+    assert!(writer.sync().await.is_err());
+    let reader = ArchiveReader::new(&config)?;
+    assert!(reader.manifest().await.is_err());
+    Ok(())
+}
@@ -0,0 +1,251 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{ensure, Result};
+use mysten_metrics::spawn_monitored_task;
+use object_store::DynObjectStore;
+use sui_types::base_types::EpochId;
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use sui_types::storage::ReadStore;
+use tokio::sync::oneshot::{self, Sender};
+use tracing::{debug, error, info};
+
+use super::{
+    encode_file, read_manifest, write_manifest, CheckpointArchiveContents, FileCompression,
+    FileMetadata, FileType, Manifest,
+};
+use crate::object_store::util::put;
+use crate::object_store::ObjectStoreConfig;
+
+/// Checkpoints waiting to be written into the next pair of archive files.
+struct PendingFiles {
+    epoch: EpochId,
+    start: CheckpointSequenceNumber,
+    summaries: Vec<Vec<u8>>,
+    contents: Vec<Vec<u8>>,
+    size: usize,
+    created_at: Instant,
+}
+
+/// Continuously archives the checkpoints of a [ReadStore] into an object store, see
+/// [crate::archive] for the layout of the archive.
+pub struct ArchiveWriter<S> {
+    store: S,
+    remote_store: Arc<DynObjectStore>,
+    /// Files are committed once their checkpoints reach this uncompressed size...
+    commit_file_size: usize,
+    /// ...or once their first checkpoint has been waiting for this long.
+    commit_duration: Duration,
+    /// Time interval to check for new checkpoints to archive
+    interval: Duration,
+    manifest: Option<Manifest>,
+    pending: Option<PendingFiles>,
+}
+
+impl<S> ArchiveWriter<S>
+where
+    S: ReadStore + Send + Sync + 'static,
+    <S as ReadStore>::Error: std::error::Error + Send + Sync + 'static,
+{
+    pub fn new(
+        store: S,
+        remote_store_config: &ObjectStoreConfig,
+        commit_file_size: usize,
+        commit_duration: Duration,
+        interval: Duration,
+    ) -> Result<Self> {
+        Ok(Self {
+            store,
+            remote_store: remote_store_config.make()?,
+            commit_file_size,
+            commit_duration,
+            interval,
+            manifest: None,
+            pending: None,
+        })
+    }
+
+    /// Start archiving in the background, failing if the checkpoints the archive needs next
+    /// were already pruned from the store.
+    pub async fn start(mut self) -> Result<Sender<()>> {
+        self.check_next_checkpoint_available().await?;
+        let (sender, mut recv) = oneshot::channel::<()>();
+        let mut interval = tokio::time::interval(self.interval);
+        spawn_monitored_task!(async move {
+            info!("Checkpoint archive writer loop started");
+            loop {
+                tokio::select! {
+                    _now = interval.tick() => {
+                        if let Err(err) = self.sync().await {
+                            error!("Failed to archive checkpoints with err: {:?}", err);
+                        }
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+            if let Err(err) = self.flush().await {
+                error!(
+                    "Failed to archive checkpoints on shutdown with err: {:?}",
+                    err
+                );
+            }
+        });
+        Ok(sender)
+    }
+
+    /// Sequence number of the next checkpoint to archive.
+    pub async fn next_checkpoint_seq_num(&mut self) -> Result<CheckpointSequenceNumber> {
+        if let Some(pending) = &self.pending {
+            return Ok(pending.start + pending.summaries.len() as u64);
+        }
+        Ok(self.manifest().await?.next_checkpoint_seq_num())
+    }
+
+    /// Archive all checkpoints whose contents and events are available in the store. Files are
+    /// only committed once large or old enough, or at the end of an epoch.
+    pub async fn sync(&mut self) -> Result<()> {
+        let mut next = self.check_next_checkpoint_available().await?;
+        while let Some((summary, contents)) = self.read_checkpoint(next)? {
+            if matches!(&self.pending, Some(pending) if pending.epoch != summary.epoch()) {
+                self.flush().await?;
+            }
+            let end_of_epoch = summary.end_of_epoch_data.is_some();
+            let summary_bytes = bcs::to_bytes(&summary)?;
+            let contents_bytes = bcs::to_bytes(&contents)?;
+            let pending = self.pending.get_or_insert_with(|| PendingFiles {
+                epoch: summary.epoch(),
+                start: next,
+                summaries: vec![],
+                contents: vec![],
+                size: 0,
+                created_at: Instant::now(),
+            });
+            pending.size += summary_bytes.len() + contents_bytes.len();
+            pending.summaries.push(summary_bytes);
+            pending.contents.push(contents_bytes);
+            if end_of_epoch || pending.size >= self.commit_file_size {
+                self.flush().await?;
+            }
+            next += 1;
+        }
+        if matches!(&self.pending, Some(pending) if pending.created_at.elapsed() >= self.commit_duration)
+        {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// The archive can only be continued from a store which still has the contents of its next
+    /// checkpoint, otherwise archival would stall forever.
+    async fn check_next_checkpoint_available(&mut self) -> Result<CheckpointSequenceNumber> {
+        let next = self.next_checkpoint_seq_num().await?;
+        let lowest_available = self.store.get_lowest_available_checkpoint()?;
+        ensure!(
+            next >= lowest_available,
+            "Cannot archive checkpoint {next}, the store pruned the contents of all checkpoints \
+            before {lowest_available}. Archive from a node which does not prune checkpoints."
+        );
+        Ok(next)
+    }
+
+    /// Commit the pending checkpoints to the archive, regardless of their size.
+    pub async fn flush(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        let range = pending.start..pending.start + pending.summaries.len() as u64;
+        let summary_file = encode_file(
+            FileType::CheckpointSummary,
+            FileCompression::Snappy,
+            &pending.summaries,
+        )?;
+        let contents_file = encode_file(
+            FileType::CheckpointContents,
+            FileCompression::Snappy,
+            &pending.contents,
+        )?;
+        let summary_metadata = FileMetadata::new(
+            FileType::CheckpointSummary,
+            pending.epoch,
+            range.clone(),
+            &summary_file,
+        );
+        let contents_metadata = FileMetadata::new(
+            FileType::CheckpointContents,
+            pending.epoch,
+            range.clone(),
+            &contents_file,
+        );
+        put(
+            &summary_metadata.file_path(),
+            summary_file,
+            self.remote_store.clone(),
+        )
+        .await?;
+        put(
+            &contents_metadata.file_path(),
+            contents_file,
+            self.remote_store.clone(),
+        )
+        .await?;
+
+        let remote_store = self.remote_store.clone();
+        let manifest = self.manifest().await?;
+        manifest.update(
+            pending.epoch,
+            range.end,
+            [summary_metadata, contents_metadata],
+        );
+        write_manifest(manifest, remote_store).await?;
+        debug!(epoch = pending.epoch, ?range, "Archived checkpoints");
+        Ok(())
+    }
+
+    async fn manifest(&mut self) -> Result<&mut Manifest> {
+        if self.manifest.is_none() {
+            let manifest = read_manifest(self.remote_store.clone())
+                .await?
+                .unwrap_or_else(|| Manifest::new(0, 0));
+            info!(
+                next_checkpoint_seq_num = manifest.next_checkpoint_seq_num(),
+                "Resuming checkpoint archival"
+            );
+            self.manifest = Some(manifest);
+        }
+        Ok(self.manifest.as_mut().unwrap())
+    }
+
+    /// Read a checkpoint along with its contents, if all of them are available in the store.
+    fn read_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<(CertifiedCheckpointSummary, CheckpointArchiveContents)>> {
+        let Some(checkpoint) = self.store.get_checkpoint_by_sequence_number(sequence_number)? else {
+            return Ok(None);
+        };
+        let Some(contents) = self
+            .store
+            .get_full_checkpoint_contents(&checkpoint.content_digest)?
+        else {
+            return Ok(None);
+        };
+        let mut events = vec![];
+        for tx in contents.iter() {
+            if let Some(digest) = tx.effects.events_digest() {
+                // Events are only available once the transaction is executed locally.
+                let Some(tx_events) = self.store.get_transaction_events(digest)? else {
+                    return Ok(None);
+                };
+                events.push(tx_events);
+            }
+        }
+        Ok(Some((
+            checkpoint.into_inner(),
+            CheckpointArchiveContents { contents, events },
+        )))
+    }
+}
@@ -5,22 +5,26 @@
 //! The main user of this data is the explorer.
 
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
+use std::iter::Peekable;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::{ModuleId, StructTag};
-use serde::{de::DeserializeOwned, Serialize};
-use tracing::debug;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{debug, warn};
 
 use sui_json_rpc_types::SuiObjectDataFilter;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest, TxSequenceNumber};
-use sui_types::base_types::{ObjectInfo, ObjectRef};
+use sui_types::base_types::{ObjectInfo, ObjectRef, ObjectType};
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
 use sui_types::error::{SuiError, SuiResult};
-use sui_types::messages::TransactionEvents;
+use sui_types::messages::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 use sui_types::query::TransactionFilter;
 use typed_store::rocks::DBOptions;
@@ -30,19 +34,51 @@ use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store_derive::DBMapUtils;
 
 type OwnerIndexKey = (SuiAddress, ObjectID);
+type OwnerHistoryKey = (SuiAddress, ObjectID, CheckpointSequenceNumber);
+/// Reference to an object at the end of a checkpoint, along with its owner unless the object was
+/// deleted or wrapped.
+pub type ObjectVersionAtCheckpoint = (ObjectRef, Option<Owner>);
 type DynamicFieldKey = (ObjectID, ObjectID);
+/// An address along with the type of the coins it owns, e.g. `0x2::sui::SUI` for gas coins.
+pub type CoinBalanceKey = (SuiAddress, TypeTag);
 type EventId = (TxSequenceNumber, usize);
 type EventIndex = (TransactionEventsDigest, TransactionDigest, u64);
+type TxIter<'a> = Box<dyn Iterator<Item = (TxSequenceNumber, TransactionDigest)> + 'a>;
 
 pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
 pub const MAX_GET_OWNED_OBJECT_SIZE: usize = 256;
 
+/// Number of entries of the owner index whose coins are added to the coin balance index at a time
+/// by the backfill.
+const COIN_BALANCE_BACKFILL_BATCH_SIZE: usize = 10_000;
+
 pub struct ObjectIndexChanges {
     pub deleted_owners: Vec<OwnerIndexKey>,
     pub deleted_dynamic_fields: Vec<DynamicFieldKey>,
     pub new_owners: Vec<(OwnerIndexKey, ObjectInfo)>,
     pub new_dynamic_fields: Vec<(DynamicFieldKey, DynamicFieldInfo)>,
+    /// Changes to the total balance and to the number of coins owned by an address, one entry
+    /// per coin which was added or removed.
+    pub coin_balance_changes: Vec<(CoinBalanceKey, i128, i64)>,
+}
+
+/// A transaction to remove from the indexes when pruning history, along with the data it was
+/// indexed with.
+pub struct PrunedTransaction {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    pub input_objects: Vec<ObjectID>,
+    pub mutated_objects: Vec<(ObjectRef, Owner)>,
+    pub move_functions: Vec<(ObjectID, Identifier, Identifier)>,
+    pub events: TransactionEvents,
+}
+
+/// Total balance and number of the coins of a type owned by an address.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoinBalance {
+    pub total_balance: u128,
+    pub coin_count: u64,
 }
 
 #[derive(DBMapUtils)]
@@ -97,6 +133,40 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "dynamic_field_index_table_default_config"]
     dynamic_field_index: DBMap<DynamicFieldKey, DynamicFieldInfo>,
 
+    /// Index from object id and checkpoint to the version of the object at the end of that
+    /// checkpoint, for every checkpoint which changed the object.
+    #[default_options_override_fn = "index_table_default_config"]
+    object_versions_by_checkpoint:
+        DBMap<(ObjectID, CheckpointSequenceNumber), ObjectVersionAtCheckpoint>,
+
+    /// History of the owner index by checkpoint: an entry is the reference to the object if it is
+    /// owned by the address at the end of the checkpoint, and None if it stopped being owned by
+    /// the address during the checkpoint.
+    #[default_options_override_fn = "index_table_default_config"]
+    owner_history: DBMap<OwnerHistoryKey, Option<ObjectRef>>,
+
+    /// First and last checkpoints indexed in `object_versions_by_checkpoint` and `owner_history`.
+    /// Checkpoints are only indexed by nodes executing every checkpoint from genesis, as the
+    /// history of objects not changed since indexing started would be missing otherwise.
+    #[default_options_override_fn = "index_table_default_config"]
+    indexed_object_checkpoints: DBMap<(), (CheckpointSequenceNumber, CheckpointSequenceNumber)>,
+
+    /// Index from an address and a coin type to the total balance and number of the coins of
+    /// that type owned by the address. Entries are removed once the address owns no such coin.
+    #[default_options_override_fn = "index_table_default_config"]
+    coin_balances: DBMap<CoinBalanceKey, CoinBalance>,
+
+    /// Set once `coin_balances` has been built from the owner index, which is needed for databases
+    /// created before the balance index existed. Balances are only served from the index after
+    /// that.
+    #[default_options_override_fn = "index_table_default_config"]
+    coin_balances_backfilled: DBMap<(), ()>,
+
+    /// The last owner whose balances were built from the owner index while the backfill is in
+    /// progress. Balances of the owners up to it are kept up to date by indexed transactions.
+    #[default_options_override_fn = "index_table_default_config"]
+    coin_balances_backfill_cursor: DBMap<(), SuiAddress>,
+
     #[default_options_override_fn = "index_table_default_config"]
     event_order: DBMap<EventId, EventIndex>,
     #[default_options_override_fn = "index_table_default_config"]
@@ -109,9 +179,54 @@ pub struct IndexStoreTables {
     event_by_time: DBMap<(u64, EventId), EventIndex>,
 }
 
+/// Merges two iterators ordered by transaction sequence number, dropping duplicates.
+struct MergedTxIter<'a> {
+    left: Peekable<TxIter<'a>>,
+    right: Peekable<TxIter<'a>>,
+    reverse: bool,
+}
+
+impl Iterator for MergedTxIter<'_> {
+    type Item = (TxSequenceNumber, TransactionDigest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let left = self.left.peek().map(|(seq, _)| *seq);
+        let right = self.right.peek().map(|(seq, _)| *seq);
+        let take_left = match (left, right) {
+            (Some(left), Some(right)) if left == right => {
+                self.right.next();
+                true
+            }
+            (Some(left), Some(right)) => (left < right) != self.reverse,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if take_left {
+            self.left.next()
+        } else {
+            self.right.next()
+        }
+    }
+}
+
+/// Returns true if the filter or any of its sub-filters satisfies the predicate.
+fn any_filter(filter: &TransactionFilter, predicate: &dyn Fn(&TransactionFilter) -> bool) -> bool {
+    predicate(filter)
+        || match filter {
+            TransactionFilter::And(f1, f2) | TransactionFilter::Or(f1, f2) => {
+                any_filter(f1, predicate) || any_filter(f2, predicate)
+            }
+            TransactionFilter::Not(f) => any_filter(f, predicate),
+            _ => false,
+        }
+}
+
 pub struct IndexStore {
     next_sequence_number: AtomicU64,
     tables: IndexStoreTables,
+    /// Serializes the read-modify-write updates of `coin_balances` across transactions indexed
+    /// concurrently and the backfill.
+    coin_balances_lock: Mutex<()>,
 }
 
 // These functions are used to initialize the DB tables
@@ -165,6 +280,7 @@ impl IndexStore {
         Self {
             tables,
             next_sequence_number,
+            coin_balances_lock: Mutex::new(()),
         }
     }
 
@@ -253,6 +369,22 @@ impl IndexStore {
             object_index_changes.new_dynamic_fields.into_iter(),
         )?;
 
+        // Coin balances, the lock is held until the batch is written so that the balances read
+        // here are not concurrently updated. The changes of a transaction which was already
+        // indexed are accounted for, so they are only applied once per transaction.
+        let coin_balance_changes = object_index_changes.coin_balance_changes;
+        let _coin_balances_guard = if coin_balance_changes.is_empty() {
+            None
+        } else {
+            Some(self.coin_balances_lock.lock().unwrap())
+        };
+        if !coin_balance_changes.is_empty() && !self.tables.transactions_seq.contains_key(digest)? {
+            let (updated_balances, removed_balances) =
+                self.apply_coin_balance_changes(coin_balance_changes)?;
+            batch.insert_batch(&self.tables.coin_balances, updated_balances)?;
+            batch.delete_batch(&self.tables.coin_balances, removed_balances)?;
+        }
+
         // events
         let event_digest = events.digest();
         batch.insert_batch(
@@ -311,16 +443,293 @@ impl IndexStore {
         Ok(sequence)
     }
 
+    /// Remove pruned transactions and their events from the transaction and event indexes. The
+    /// indexes of the live object set are not affected. Transactions which are not indexed are
+    /// ignored.
+    pub fn prune_transactions(&self, transactions: &[PrunedTransaction]) -> SuiResult {
+        let mut batch = self.tables.transactions_from_addr.batch();
+        for transaction in transactions {
+            let digest = transaction.digest;
+            let Some(sequence) = self.tables.transactions_seq.get(&digest)? else {
+                continue;
+            };
+            let timestamp_ms = self.tables.timestamps.get(&digest)?.unwrap_or_default();
+
+            batch.delete_batch(&self.tables.transaction_order, [sequence])?;
+            batch.delete_batch(&self.tables.transactions_seq, [digest])?;
+            batch.delete_batch(&self.tables.timestamps, [digest])?;
+            batch.delete_batch(
+                &self.tables.transactions_from_addr,
+                [(transaction.sender, sequence)],
+            )?;
+            batch.delete_batch(
+                &self.tables.transactions_by_input_object_id,
+                transaction.input_objects.iter().map(|id| (*id, sequence)),
+            )?;
+            batch.delete_batch(
+                &self.tables.transactions_by_mutated_object_id,
+                transaction
+                    .mutated_objects
+                    .iter()
+                    .map(|(obj_ref, _)| (obj_ref.0, sequence)),
+            )?;
+            batch.delete_batch(
+                &self.tables.transactions_by_move_function,
+                transaction
+                    .move_functions
+                    .iter()
+                    .map(|(obj_id, module, function)| {
+                        (*obj_id, module.to_string(), function.to_string(), sequence)
+                    }),
+            )?;
+            batch.delete_batch(
+                &self.tables.transactions_to_addr,
+                transaction
+                    .mutated_objects
+                    .iter()
+                    .filter_map(|(_, owner)| owner.get_owner_address().ok())
+                    .map(|addr| (addr, sequence)),
+            )?;
+
+            let events = transaction.events.data.iter().enumerate();
+            batch.delete_batch(
+                &self.tables.event_order,
+                events.clone().map(|(i, _)| (sequence, i)),
+            )?;
+            batch.delete_batch(
+                &self.tables.event_by_move_module,
+                events.clone().map(|(i, e)| {
+                    (
+                        ModuleId::new(e.package_id.into(), e.transaction_module.clone()),
+                        (sequence, i),
+                    )
+                }),
+            )?;
+            batch.delete_batch(
+                &self.tables.event_by_sender,
+                events.clone().map(|(i, e)| (e.sender, (sequence, i))),
+            )?;
+            batch.delete_batch(
+                &self.tables.event_by_move_event,
+                events
+                    .clone()
+                    .map(|(i, e)| (e.type_.clone(), (sequence, i))),
+            )?;
+            batch.delete_batch(
+                &self.tables.event_by_time,
+                events.map(|(i, _)| (timestamp_ms, (sequence, i))),
+            )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Compute the balances resulting from `changes`, split between the balances to update and
+    /// the balances of the coin types no longer owned by their address. The changes to owners not
+    /// reached by the backfill yet are skipped, as the backfill reads their coins once they are
+    /// indexed. Must be called with `coin_balances_lock` held.
+    fn apply_coin_balance_changes(
+        &self,
+        changes: Vec<(CoinBalanceKey, i128, i64)>,
+    ) -> SuiResult<(Vec<(CoinBalanceKey, CoinBalance)>, Vec<CoinBalanceKey>)> {
+        let backfilled = self.tables.coin_balances_backfilled.contains_key(&())?;
+        let backfill_cursor = self.tables.coin_balances_backfill_cursor.get(&())?;
+        let mut deltas: BTreeMap<CoinBalanceKey, (i128, i64)> = BTreeMap::new();
+        for (key, balance, count) in changes {
+            if !backfilled && backfill_cursor.map_or(true, |cursor| key.0 > cursor) {
+                continue;
+            }
+            let delta = deltas.entry(key).or_default();
+            delta.0 += balance;
+            delta.1 += count;
+        }
+
+        let mut updated = vec![];
+        let mut removed = vec![];
+        for (key, (balance, count)) in deltas {
+            if balance == 0 && count == 0 {
+                continue;
+            }
+            let current = self.tables.coin_balances.get(&key)?.unwrap_or_default();
+            let total_balance = current.total_balance as i128 + balance;
+            let coin_count = current.coin_count as i64 + count;
+            if total_balance < 0 || coin_count < 0 {
+                warn!(
+                    owner = ?key.0,
+                    coin_type = %key.1,
+                    "Coin balance index is inconsistent, balance {total_balance} for {coin_count} coins"
+                );
+            }
+            if coin_count <= 0 {
+                removed.push(key);
+            } else {
+                updated.push((
+                    key,
+                    CoinBalance {
+                        total_balance: total_balance.max(0) as u128,
+                        coin_count: coin_count as u64,
+                    },
+                ));
+            }
+        }
+        Ok((updated, removed))
+    }
+
+    /// Index the versions of the objects changed by the transactions of a checkpoint, given
+    /// their effects in checkpoint order. Checkpoints must be indexed in order from genesis,
+    /// and indexing stops at the first gap. Indexing a checkpoint again is a no-op.
+    pub fn index_checkpoint_objects(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        effects: &[TransactionEffects],
+    ) -> SuiResult {
+        let first = match self.tables.indexed_object_checkpoints.get(&())? {
+            Some((first, last)) if checkpoint == last + 1 => first,
+            None if checkpoint == 0 => 0,
+            // Either the checkpoint is already indexed, or indexing stopped at a gap, e.g. as the
+            // node executed checkpoints without indexes for a while.
+            _ => return Ok(()),
+        };
+
+        // Only the last version of each object in the checkpoint is indexed.
+        let mut versions = BTreeMap::new();
+        for effects in effects {
+            for (object_ref, owner, _) in effects.all_changed_objects() {
+                versions.insert(object_ref.0, (*object_ref, Some(*owner)));
+            }
+            for (object_ref, _) in effects.all_deleted() {
+                versions.insert(object_ref.0, (*object_ref, None));
+            }
+        }
+
+        let mut owner_history = vec![];
+        for (object_id, (object_ref, owner)) in &versions {
+            let previous_owner = match checkpoint.checked_sub(1) {
+                Some(previous) => self
+                    .object_version_at_checkpoint(*object_id, previous)?
+                    .and_then(|(_, owner)| owner),
+                None => None,
+            };
+            if let Some(Owner::AddressOwner(address)) = previous_owner {
+                if *owner != previous_owner {
+                    owner_history.push(((address, *object_id, checkpoint), None));
+                }
+            }
+            if let Some(Owner::AddressOwner(address)) = owner {
+                owner_history.push(((*address, *object_id, checkpoint), Some(*object_ref)));
+            }
+        }
+
+        let mut batch = self.tables.object_versions_by_checkpoint.batch();
+        batch.insert_batch(
+            &self.tables.object_versions_by_checkpoint,
+            versions
+                .into_iter()
+                .map(|(object_id, version)| ((object_id, checkpoint), version)),
+        )?;
+        batch.insert_batch(&self.tables.owner_history, owner_history)?;
+        batch.insert_batch(
+            &self.tables.indexed_object_checkpoints,
+            [((), (first, checkpoint))],
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Fails unless the objects at the end of `checkpoint` are indexed.
+    fn ensure_objects_indexed_at_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult {
+        let error = match self.tables.indexed_object_checkpoints.get(&())? {
+            Some((first, last)) if (first..=last).contains(&checkpoint) => return Ok(()),
+            Some((first, last)) => format!("indexed checkpoints are {first} to {last}"),
+            None => "this node doesn't index objects by checkpoint".to_string(),
+        };
+        Err(SuiError::ObjectsNotIndexedAtCheckpoint { checkpoint, error })
+    }
+
+    /// The version of the object at the end of the checkpoint, if the object was changed by any
+    /// indexed checkpoint up to that one.
+    pub fn get_object_version_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<ObjectVersionAtCheckpoint>> {
+        self.ensure_objects_indexed_at_checkpoint(checkpoint)?;
+        self.object_version_at_checkpoint(object_id, checkpoint)
+    }
+
+    fn object_version_at_checkpoint(
+        &self,
+        object_id: ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<ObjectVersionAtCheckpoint>> {
+        Ok(self
+            .tables
+            .object_versions_by_checkpoint
+            .iter()
+            .skip_prior_to(&(object_id, checkpoint))?
+            .next()
+            .filter(|((id, _), _)| *id == object_id)
+            .map(|(_, version)| version))
+    }
+
+    /// References to the objects owned by `owner` at the end of the checkpoint, ordered by object
+    /// id and starting after `cursor`.
+    pub fn get_owner_objects_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        checkpoint: CheckpointSequenceNumber,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<Vec<ObjectRef>> {
+        self.ensure_objects_indexed_at_checkpoint(checkpoint)?;
+        let entries = self
+            .tables
+            .owner_history
+            .iter()
+            .skip_to(&(owner, cursor.unwrap_or(ObjectID::ZERO), 0))?
+            .take_while(|((address, _, _), _)| *address == owner)
+            .filter(|((_, object_id, entry_checkpoint), _)| {
+                *entry_checkpoint <= checkpoint && Some(*object_id) != cursor
+            });
+
+        // Entries of an object are ordered by checkpoint, so the last one of each object is its
+        // state at the end of the checkpoint.
+        let mut objects = vec![];
+        let mut last: Option<(ObjectID, Option<ObjectRef>)> = None;
+        for ((_, object_id, _), object_ref) in entries {
+            if let Some((last_id, last_ref)) = last {
+                if last_id != object_id {
+                    objects.extend(last_ref);
+                    if objects.len() >= limit {
+                        return Ok(objects);
+                    }
+                }
+            }
+            last = Some((object_id, object_ref));
+        }
+        if let Some((_, last_ref)) = last {
+            objects.extend(last_ref);
+        }
+        objects.truncate(limit);
+        Ok(objects)
+    }
+
     pub fn next_sequence_number(&self) -> TxSequenceNumber {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
 
+    /// `checkpoint_of` resolves the checkpoint a transaction was included in, it is only used
+    /// by checkpoint predicates of composite filters.
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         reverse: bool,
+        checkpoint_of: impl Fn(&TransactionDigest) -> SuiResult<Option<CheckpointSequenceNumber>>,
     ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
         // Lookup TransactionDigest sequence number,
         let cursor = if let Some(cursor) = cursor {
@@ -351,6 +760,19 @@ impl IndexStore {
             Some(TransactionFilter::ToAddress(address)) => {
                 Ok(self.get_transactions_to_addr(address, cursor, limit, reverse)?)
             }
+            Some(
+                ref filter @ (TransactionFilter::And(..)
+                | TransactionFilter::Or(..)
+                | TransactionFilter::Not(_)
+                | TransactionFilter::TimeRange { .. }
+                | TransactionFilter::CheckpointRange { .. }),
+            ) => self.get_transactions_by_composite_filter(
+                filter,
+                cursor,
+                limit,
+                reverse,
+                &checkpoint_of,
+            ),
             // NOTE: filter via checkpoint sequence number is implemented in
             // `get_transactions` of authority.rs.
             Some(_) => Err(anyhow!("Unsupported filter: {:?}", filter)),
@@ -383,6 +805,284 @@ impl IndexStore {
         }
     }
 
+    /// Serves composite filters by walking the transactions of one indexed sub-filter and
+    /// checking the remaining predicates with point lookups. Filters without an indexed
+    /// sub-filter, e.g. a bare `TimeRange`, are rejected rather than served by a full scan of
+    /// the transactions.
+    fn get_transactions_by_composite_filter(
+        &self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        limit: Option<usize>,
+        reverse: bool,
+        checkpoint_of: &dyn Fn(&TransactionDigest) -> SuiResult<Option<CheckpointSequenceNumber>>,
+    ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        if any_filter(filter, &|f| {
+            matches!(f, TransactionFilter::TransactionKind(_))
+        }) {
+            return Err(anyhow!("Unsupported filter: {:?}", filter));
+        }
+        let Some(candidates) = self.indexed_transactions_iter(filter, cursor, reverse)? else {
+            return Err(anyhow!(
+                "Filter must be combined with an address, object or move function filter: {:?}",
+                filter
+            ));
+        };
+
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut digests = vec![];
+        for (seq, digest) in candidates {
+            if digests.len() >= limit {
+                break;
+            }
+            if self.transaction_matches(filter, seq, &digest, checkpoint_of)? {
+                digests.push(digest);
+            }
+        }
+        Ok(digests)
+    }
+
+    /// Returns an iterator over the transactions selected by the indexed part of the filter,
+    /// ordered by sequence number and starting after the cursor. The iterator may return a
+    /// superset of the matching transactions.
+    fn indexed_transactions_iter(
+        &self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<Option<TxIter<'_>>> {
+        Ok(match filter {
+            TransactionFilter::FromAddress(address)
+            | TransactionFilter::FromAndToAddress { from: address, .. } => Some(Self::index_iter(
+                &self.tables.transactions_from_addr,
+                *address,
+                cursor,
+                reverse,
+            )?),
+            TransactionFilter::ToAddress(address) => Some(Self::index_iter(
+                &self.tables.transactions_to_addr,
+                *address,
+                cursor,
+                reverse,
+            )?),
+            TransactionFilter::InputObject(object_id) => Some(Self::index_iter(
+                &self.tables.transactions_by_input_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?),
+            TransactionFilter::ChangedObject(object_id) => Some(Self::index_iter(
+                &self.tables.transactions_by_mutated_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?),
+            // Partial move function keys are not ordered by sequence number, they are only
+            // usable as predicates.
+            TransactionFilter::MoveFunction {
+                package,
+                module: Some(module),
+                function: Some(function),
+            } => {
+                let key = (*package, module.clone(), function.clone());
+                let index = &self.tables.transactions_by_move_function;
+                let iter: TxIter<'_> = if reverse {
+                    Box::new(
+                        index
+                            .iter()
+                            .skip_prior_to(&(
+                                key.0,
+                                key.1.clone(),
+                                key.2.clone(),
+                                cursor.unwrap_or(TxSequenceNumber::MAX),
+                            ))?
+                            .reverse()
+                            .take_while(move |((p, m, f, _), _)| {
+                                (p, m, f) == (&key.0, &key.1, &key.2)
+                            })
+                            .map(|((_, _, _, seq), digest)| (seq, digest)),
+                    )
+                } else {
+                    Box::new(
+                        index
+                            .iter()
+                            .skip_to(&(
+                                key.0,
+                                key.1.clone(),
+                                key.2.clone(),
+                                cursor.unwrap_or(TxSequenceNumber::MIN),
+                            ))?
+                            .take_while(move |((p, m, f, _), _)| {
+                                (p, m, f) == (&key.0, &key.1, &key.2)
+                            })
+                            .map(|((_, _, _, seq), digest)| (seq, digest)),
+                    )
+                };
+                Some(Box::new(
+                    iter.skip_while(move |(seq, _)| Some(*seq) == cursor),
+                ))
+            }
+            TransactionFilter::And(f1, f2) => {
+                match self.indexed_transactions_iter(f1, cursor, reverse)? {
+                    Some(iter) => Some(iter),
+                    None => self.indexed_transactions_iter(f2, cursor, reverse)?,
+                }
+            }
+            TransactionFilter::Or(f1, f2) => match (
+                self.indexed_transactions_iter(f1, cursor, reverse)?,
+                self.indexed_transactions_iter(f2, cursor, reverse)?,
+            ) {
+                (Some(left), Some(right)) => Some(Box::new(MergedTxIter {
+                    left: left.peekable(),
+                    right: right.peekable(),
+                    reverse,
+                })),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn index_iter<'a, KeyT: Clone + Serialize + DeserializeOwned + PartialEq + 'a>(
+        index: &'a DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: KeyT,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TxIter<'a>> {
+        let iter: TxIter<'a> = if reverse {
+            Box::new(
+                index
+                    .iter()
+                    .skip_prior_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MAX)))?
+                    .reverse()
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        } else {
+            Box::new(
+                index
+                    .iter()
+                    .skip_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MIN)))?
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        };
+        // The cursor transaction is not necessarily in this index, so skip it by value
+        // rather than by position.
+        Ok(Box::new(
+            iter.skip_while(move |(seq, _)| Some(*seq) == cursor),
+        ))
+    }
+
+    fn transaction_matches(
+        &self,
+        filter: &TransactionFilter,
+        seq: TxSequenceNumber,
+        digest: &TransactionDigest,
+        checkpoint_of: &dyn Fn(&TransactionDigest) -> SuiResult<Option<CheckpointSequenceNumber>>,
+    ) -> Result<bool, anyhow::Error> {
+        Ok(match filter {
+            TransactionFilter::FromAddress(address) => self
+                .tables
+                .transactions_from_addr
+                .contains_key(&(*address, seq))?,
+            TransactionFilter::ToAddress(address) => self
+                .tables
+                .transactions_to_addr
+                .contains_key(&(*address, seq))?,
+            TransactionFilter::FromAndToAddress { from, to } => {
+                self.tables
+                    .transactions_from_addr
+                    .contains_key(&(*from, seq))?
+                    && self.tables.transactions_to_addr.contains_key(&(*to, seq))?
+            }
+            TransactionFilter::InputObject(object_id) => self
+                .tables
+                .transactions_by_input_object_id
+                .contains_key(&(*object_id, seq))?,
+            TransactionFilter::ChangedObject(object_id) => self
+                .tables
+                .transactions_by_mutated_object_id
+                .contains_key(&(*object_id, seq))?,
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => {
+                self.called_move_function(*package, module.as_deref(), function.as_deref(), seq)?
+            }
+            TransactionFilter::Checkpoint(checkpoint) => {
+                checkpoint_of(digest)? == Some(*checkpoint)
+            }
+            TransactionFilter::CheckpointRange {
+                start_checkpoint,
+                end_checkpoint,
+            } => checkpoint_of(digest)?.map_or(false, |c| {
+                (*start_checkpoint..=*end_checkpoint).contains(&c)
+            }),
+            TransactionFilter::TimeRange {
+                start_time,
+                end_time,
+            } => self
+                .get_timestamp_ms(digest)?
+                .map_or(false, |t| (*start_time..*end_time).contains(&t)),
+            TransactionFilter::And(f1, f2) => {
+                self.transaction_matches(f1, seq, digest, checkpoint_of)?
+                    && self.transaction_matches(f2, seq, digest, checkpoint_of)?
+            }
+            TransactionFilter::Or(f1, f2) => {
+                self.transaction_matches(f1, seq, digest, checkpoint_of)?
+                    || self.transaction_matches(f2, seq, digest, checkpoint_of)?
+            }
+            TransactionFilter::Not(f) => {
+                !self.transaction_matches(f, seq, digest, checkpoint_of)?
+            }
+            TransactionFilter::TransactionKind(_) => {
+                return Err(anyhow!("Unsupported filter: {:?}", filter))
+            }
+        })
+    }
+
+    fn called_move_function(
+        &self,
+        package: ObjectID,
+        module: Option<&str>,
+        function: Option<&str>,
+        seq: TxSequenceNumber,
+    ) -> SuiResult<bool> {
+        let index = &self.tables.transactions_by_move_function;
+        if let (Some(module), Some(function)) = (module, function) {
+            return Ok(index.contains_key(&(
+                package,
+                module.to_string(),
+                function.to_string(),
+                seq,
+            ))?);
+        }
+        // Probe each distinct (module, function) pair of the package, jumping over the
+        // sequence numbers of a pair by seeking past its last possible key.
+        let mut key = (
+            package,
+            module.unwrap_or_default().to_string(),
+            String::new(),
+            TxSequenceNumber::MIN,
+        );
+        loop {
+            let Some(((p, m, f, _), _)) = index.iter().skip_to(&key)?.next() else {
+                return Ok(false);
+            };
+            if p != package || module.map_or(false, |module| module != m) {
+                return Ok(false);
+            }
+            if function.map_or(true, |function| function == f)
+                && index.contains_key(&(p, m.clone(), f.clone(), seq))?
+            {
+                return Ok(true);
+            }
+            key = (p, m, f, TxSequenceNumber::MAX);
+        }
+    }
+
     /// Returns unix timestamp for a transaction if it exists
     pub fn get_timestamp_ms(
         &self,
@@ -845,4 +1545,131 @@ impl IndexStore {
     pub fn is_empty(&self) -> bool {
         self.tables.owner_index.is_empty()
     }
+
+    /// Total balance and number of the coins of type `coin_type` owned by `owner`, or None if the
+    /// coin balance index is still being built.
+    pub fn get_coin_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: TypeTag,
+    ) -> SuiResult<Option<CoinBalance>> {
+        if self.needs_coin_balance_backfill()? {
+            return Ok(None);
+        }
+        Ok(Some(
+            self.tables
+                .coin_balances
+                .get(&(owner, coin_type))?
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// Total balance and number of the coins owned by `owner`, for every coin type it owns, or
+    /// None if the coin balance index is still being built.
+    pub fn get_coin_balances(
+        &self,
+        owner: SuiAddress,
+    ) -> SuiResult<Option<Vec<(TypeTag, CoinBalance)>>> {
+        if self.needs_coin_balance_backfill()? {
+            return Ok(None);
+        }
+        Ok(Some(
+            self.tables
+                .coin_balances
+                .iter()
+                // `TypeTag::Bool` is the smallest possible type tag
+                .skip_to(&(owner, TypeTag::Bool))?
+                .take_while(|((address, _), _)| address == &owner)
+                .map(|((_, coin_type), balance)| (coin_type, balance))
+                .collect(),
+        ))
+    }
+
+    /// Whether the coin balance index still needs to be built from the owner index.
+    pub fn needs_coin_balance_backfill(&self) -> SuiResult<bool> {
+        Ok(!self.tables.coin_balances_backfilled.contains_key(&())?)
+    }
+
+    /// Build the coin balance index from the coins in the owner index, one batch of owners at a
+    /// time so that transactions keep being indexed meanwhile. The backfill resumes where it
+    /// stopped if it is interrupted. `coin_balance` returns the balance of the version of a coin in
+    /// the owner index, or None if it is not owned by the owner of the entry.
+    pub fn backfill_coin_balances(
+        &self,
+        coin_balance: impl Fn(&ObjectInfo) -> SuiResult<Option<u64>>,
+    ) -> SuiResult {
+        while !self.backfill_coin_balances_batch(&coin_balance)? {}
+        Ok(())
+    }
+
+    /// Add the balances of the next batch of owners to the coin balance index, returning whether
+    /// the backfill is complete.
+    fn backfill_coin_balances_batch(
+        &self,
+        coin_balance: &impl Fn(&ObjectInfo) -> SuiResult<Option<u64>>,
+    ) -> SuiResult<bool> {
+        // Transactions changing coins are not indexed while the batch is built, so their changes
+        // are either in the owner index read here, or applied on top of the balances written here.
+        let _coin_balances_guard = self.coin_balances_lock.lock().unwrap();
+        if !self.needs_coin_balance_backfill()? {
+            return Ok(true);
+        }
+
+        let cursor = self.tables.coin_balances_backfill_cursor.get(&())?;
+        let mut entries = self.tables.owner_index.iter();
+        if let Some(cursor) = cursor {
+            entries = entries.skip_to(&(cursor, ObjectID::ZERO))?;
+        }
+        let mut entries = entries
+            .skip_while(|((owner, _), _)| Some(*owner) == cursor)
+            .peekable();
+
+        // Batches end at the last entry of an owner, so that the balances of an owner are built
+        // at once.
+        let mut balances: BTreeMap<CoinBalanceKey, CoinBalance> = BTreeMap::new();
+        let mut last_owner = None;
+        let mut count = 0;
+        while let Some(((owner, _), info)) = entries.next_if(|((owner, _), _)| {
+            count < COIN_BALANCE_BACKFILL_BATCH_SIZE || Some(*owner) == last_owner
+        }) {
+            count += 1;
+            last_owner = Some(owner);
+            let ObjectType::Struct(type_) = &info.type_ else {
+                continue;
+            };
+            if !type_.is_coin() {
+                continue;
+            }
+            let Some(coin_type) = type_.type_params().into_iter().next() else {
+                continue;
+            };
+            let Some(value) = coin_balance(&info)? else {
+                continue;
+            };
+            let balance = balances.entry((owner, coin_type)).or_default();
+            balance.total_balance += value as u128;
+            balance.coin_count += 1;
+        }
+        let done = entries.peek().is_none();
+
+        let mut batch = self.tables.coin_balances.batch();
+        batch.insert_batch(&self.tables.coin_balances, balances)?;
+        if done {
+            batch.insert_batch(
+                &self.tables.coin_balances_backfilled,
+                std::iter::once(((), ())),
+            )?;
+            batch.delete_batch(
+                &self.tables.coin_balances_backfill_cursor,
+                std::iter::once(()),
+            )?;
+        } else if let Some(owner) = last_owner {
+            batch.insert_batch(
+                &self.tables.coin_balances_backfill_cursor,
+                std::iter::once(((), owner)),
+            )?;
+        }
+        batch.write()?;
+        Ok(done)
+    }
 }
@@ -76,6 +76,15 @@ fn default_object_store_connection_limit() -> usize {
 }
 
 impl ObjectStoreConfig {
+    /// Config of an object store backed by the local `directory`.
+    pub fn local(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(directory.into()),
+            ..Default::default()
+        }
+    }
+
     fn new_local_fs(&self) -> Result<Arc<DynObjectStore>, anyhow::Error> {
         info!(directory=?self.directory, object_store_type="File", "Object Store");
         if let Some(path) = &self.directory {
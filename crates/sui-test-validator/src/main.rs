@@ -7,9 +7,9 @@ use axum::{
     routing::{get, post},
     Extension, Json, Router,
 };
-use clap::Parser;
+use clap::{Parser, ValueHint};
 use http::{Method, StatusCode};
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use sui_cluster_test::{
     cluster::{Cluster, LocalNewCluster},
     config::{ClusterTestOpt, Env},
@@ -23,10 +23,26 @@ use tower_http::cors::{Any, CorsLayer};
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    // TODO: Support a configuration directory for persisted networks:
-    // /// Config directory that will be used to store network configuration
-    // #[clap(short, long, parse(from_os_str), value_hint = ValueHint::DirPath)]
-    // config: Option<std::path::PathBuf>,
+    /// Config directory that will be used to store the network configuration, databases and
+    /// faucet wallet. Running again with the same directory restarts the persisted network
+    #[clap(long, parse(from_os_str), value_hint = ValueHint::DirPath)]
+    config_dir: Option<PathBuf>,
+
+    /// Directory of a state snapshot store whose objects are added to the genesis of the
+    /// network. Only used when the network is created
+    #[clap(long, parse(from_os_str), value_hint = ValueHint::DirPath)]
+    fork_from_snapshot: Option<PathBuf>,
+
+    /// Epoch of the state snapshot to fork from
+    #[clap(long)]
+    fork_epoch: Option<u64>,
+
+    /// Checkpoint store holding the last checkpoint of the forked epoch, e.g. the `checkpoints`
+    /// directory of the db checkpoint the snapshot was written from. The snapshot is verified
+    /// against the root state hash this checkpoint commits to
+    #[clap(long, parse(from_os_str), value_hint = ValueHint::DirPath)]
+    fork_checkpoint_store: Option<PathBuf>,
+
     /// Port to start the Fullnode RPC server on
     #[clap(long, default_value = "9000")]
     fullnode_rpc_port: u16,
@@ -48,6 +64,11 @@ struct Args {
     #[clap(long, default_value = "localhost")]
     pg_host: String,
 
+    /// Path of a SQLite database file for the Indexer. When set, the Indexer uses it
+    /// instead of the Postgres DB
+    #[clap(long)]
+    indexer_sqlite_path: Option<String>,
+
     /// The duration for epochs (defaults to one minute)
     #[clap(long, default_value = "60000")]
     epoch_duration_ms: u64,
@@ -73,10 +94,15 @@ async fn main() -> Result<()> {
         indexer_rpc_port,
         pg_port,
         pg_host,
+        indexer_sqlite_path,
         epoch_duration_ms,
         faucet_port,
         with_indexer,
         use_indexer_experimental_methods,
+        config_dir,
+        fork_from_snapshot,
+        fork_epoch,
+        fork_checkpoint_store,
     } = args;
 
     let cluster = LocalNewCluster::start(&ClusterTestOpt {
@@ -86,9 +112,14 @@ async fn main() -> Result<()> {
         pg_address: with_indexer.then_some(format!(
             "postgres://postgres@{pg_host}:{pg_port}/sui_indexer"
         )),
+        indexer_sqlite_path: indexer_sqlite_path.filter(|_| with_indexer),
         faucet_address: None,
         epoch_duration_ms: Some(epoch_duration_ms),
         use_indexer_experimental_methods,
+        config_dir,
+        fork_from_snapshot,
+        fork_epoch,
+        fork_checkpoint_store,
     })
     .await?;
 
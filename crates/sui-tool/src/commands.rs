@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    create_state_snapshot,
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     get_object, get_transaction_block, make_clients, restore_from_db_checkpoint,
-    ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
+    restore_from_state_snapshot, ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
 };
 use anyhow::Result;
 use std::path::PathBuf;
@@ -139,6 +140,37 @@ pub enum ToolCommand {
         #[clap(long = "db-checkpoint-path")]
         db_checkpoint_path: PathBuf,
     },
+
+    /// Write the state snapshot of an epoch, i.e. its live object set, from a db checkpoint
+    /// taken at the end of the epoch.
+    #[clap(name = "create-state-snapshot")]
+    CreateStateSnapshot {
+        #[clap(long = "db-checkpoint-path")]
+        db_checkpoint_path: PathBuf,
+        #[clap(long, help = "Epoch whose end the db checkpoint was taken at")]
+        epoch: EpochId,
+        #[clap(long = "snapshot-path")]
+        snapshot_path: PathBuf,
+        #[clap(
+            long = "file-size",
+            default_value = "134217728",
+            help = "Approximate size of each snapshot file, in bytes"
+        )]
+        file_size: usize,
+    },
+
+    /// Restore the authority store of a node from a state snapshot, independently of the db
+    /// layout of the node which wrote it. The db of the node must be empty, except for the
+    /// checkpoints of the snapshot epoch which the snapshot is verified against.
+    #[clap(name = "restore-state-snapshot")]
+    RestoreFromStateSnapshot {
+        #[clap(long = "config-path")]
+        config_path: PathBuf,
+        #[clap(long = "snapshot-path")]
+        snapshot_path: PathBuf,
+        #[clap(long, help = "Epoch of the snapshot to restore")]
+        epoch: EpochId,
+    },
 }
 
 trait OptionDebug<T> {
@@ -290,6 +322,23 @@ impl ToolCommand {
                 let config = sui_config::NodeConfig::load(config_path)?;
                 restore_from_db_checkpoint(&config, &db_checkpoint_path).await?;
             }
+            ToolCommand::CreateStateSnapshot {
+                db_checkpoint_path,
+                epoch,
+                snapshot_path,
+                file_size,
+            } => {
+                create_state_snapshot(&db_checkpoint_path, epoch, &snapshot_path, file_size)
+                    .await?;
+            }
+            ToolCommand::RestoreFromStateSnapshot {
+                config_path,
+                snapshot_path,
+                epoch,
+            } => {
+                let config = sui_config::NodeConfig::load(config_path)?;
+                restore_from_state_snapshot(&config, &snapshot_path, epoch).await?;
+            }
         };
         Ok(())
     }
@@ -10,8 +10,13 @@ use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 use sui_config::{genesis::Genesis, NodeConfig};
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_snapshot::reader::StateSnapshotReader;
+use sui_core::state_snapshot::writer::StateSnapshotWriter;
 use sui_network::default_mysten_network_config;
+use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::multiaddr::Multiaddr;
 use sui_types::object::ObjectFormatOptions;
 use sui_types::{base_types::*, messages::*, object::Owner};
@@ -564,3 +569,48 @@ pub async fn restore_from_db_checkpoint(
     copy_dir_all(db_checkpoint_path, config.db_path(), vec![])?;
     Ok(())
 }
+
+/// Write the state snapshot of `epoch` from a db checkpoint taken at the end of that epoch.
+pub async fn create_state_snapshot(
+    db_checkpoint_path: &Path,
+    epoch: EpochId,
+    snapshot_path: &Path,
+    file_size: usize,
+) -> Result<(), anyhow::Error> {
+    let perpetual_db = AuthorityPerpetualTables::open(&db_checkpoint_path.join("store"), None);
+    let checkpoint_store = CheckpointStore::new(&db_checkpoint_path.join("checkpoints"));
+    let manifest = StateSnapshotWriter::new(&ObjectStoreConfig::local(snapshot_path), file_size)?
+        .write(epoch, &perpetual_db, &checkpoint_store)
+        .await?;
+    println!(
+        "Wrote {} objects for epoch {epoch}, root state hash: {:?}",
+        manifest.num_objects(),
+        manifest.root_state_hash()
+    );
+    Ok(())
+}
+
+/// Restore the authority store of a node from the state snapshot of `epoch`. The checkpoints of
+/// the epoch must already be synced into the checkpoint store of the node, e.g. from a
+/// checkpoint archive, as the snapshot is verified against the last one.
+pub async fn restore_from_state_snapshot(
+    config: &NodeConfig,
+    snapshot_path: &Path,
+    epoch: EpochId,
+) -> Result<(), anyhow::Error> {
+    let perpetual_db = AuthorityPerpetualTables::open(&config.db_path().join("store"), None);
+    let checkpoint_store = CheckpointStore::new(&config.db_path().join("checkpoints"));
+    let manifest = StateSnapshotReader::new(epoch, &ObjectStoreConfig::local(snapshot_path))?
+        .restore(
+            &perpetual_db,
+            &checkpoint_store,
+            config.db_path(),
+            config.indirect_objects_threshold,
+        )
+        .await?;
+    println!(
+        "Restored {} objects from the snapshot of epoch {epoch}",
+        manifest.num_objects()
+    );
+    Ok(())
+}
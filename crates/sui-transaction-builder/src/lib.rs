@@ -17,7 +17,9 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 
 use sui_adapter::adapter::{resolve_and_type_check, CheckCallArg};
 use sui_adapter::execution_mode::ExecutionMode;
-use sui_json::{resolve_move_function_args, ResolvedCallArg, SuiJsonValue};
+use sui_json::{
+    resolve_move_function_args, resolve_partial_move_function_args, ResolvedCallArg, SuiJsonValue,
+};
 use sui_json_rpc_types::{
     RPCTransactionRequestParams, SuiData, SuiObjectDataOptions, SuiObjectResponse, SuiRawData,
     SuiTypeTag,
@@ -28,7 +30,8 @@ use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, ProgrammableTransaction,
+    TransactionData, TransactionKind,
 };
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Object, Owner};
@@ -56,6 +59,14 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// An argument to a Move call in a programmable transaction.
+pub enum ProgrammableMoveCallArg {
+    /// A value resolved against the signature of the called function.
+    Json(SuiJsonValue),
+    /// An argument already available in the transaction, e.g. the result of an earlier command.
+    Argument(Argument),
+}
+
 #[derive(Clone)]
 pub struct TransactionBuilder<Mode: ExecutionMode>(
     Arc<dyn DataReader + Sync + Send>,
@@ -278,7 +289,19 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             call_args,
         )
         .await?;
-        let pt = builder.finish();
+        self.programmable_transaction(signer, builder.finish(), gas, gas_budget)
+            .await
+    }
+
+    /// Build the transaction data for a programmable transaction, selecting a gas coin that is not
+    /// one of its inputs if `gas` is not provided.
+    pub async fn programmable_transaction(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
         let input_objects = pt
             .input_objects()?
             .iter()
@@ -330,11 +353,89 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         Ok(())
     }
 
+    /// Add a Move call to a programmable transaction, returning the argument referring to its
+    /// result. Unlike [Self::single_move_call], arguments can be results of earlier commands, and
+    /// public non-entry functions can be called.
+    pub async fn programmable_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package_id: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<SuiTypeTag>,
+        call_args: Vec<ProgrammableMoveCallArg>,
+    ) -> anyhow::Result<Argument> {
+        let module = Identifier::from_str(module)?;
+        let function = Identifier::from_str(function)?;
+        let type_args = type_args
+            .into_iter()
+            .map(|ty| ty.try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let package = self.get_package(package_id).await?;
+        let json_args = call_args
+            .iter()
+            .map(|arg| match arg {
+                ProgrammableMoveCallArg::Json(json) => Some(json.clone()),
+                ProgrammableMoveCallArg::Argument(_) => None,
+            })
+            .collect();
+        let resolved_args = resolve_partial_move_function_args(
+            &package,
+            module.clone(),
+            function.clone(),
+            &type_args,
+            json_args,
+            /* allow_arbitrary_function_call */ true,
+        )?;
+
+        let mut objects = BTreeMap::new();
+        let mut arguments = vec![];
+        for (call_arg, resolved) in call_args.into_iter().zip(resolved_args) {
+            arguments.push(match (call_arg, resolved) {
+                (ProgrammableMoveCallArg::Argument(arg), _) => arg,
+                (_, Some((ResolvedCallArg::Pure(bytes), _))) => {
+                    builder.input(CallArg::Pure(bytes))?
+                }
+                (_, Some((ResolvedCallArg::Object(id), expected_type))) => {
+                    let mutable = matches!(expected_type, SignatureToken::MutableReference(_));
+                    let obj = self.get_object_arg(id, &mut objects, mutable).await?;
+                    builder.obj(obj)?
+                }
+                (_, Some((ResolvedCallArg::ObjVec(ids), _))) => {
+                    let mut objs = vec![];
+                    for id in ids {
+                        objs.push(self.get_object_arg(id, &mut objects, false).await?);
+                    }
+                    builder.make_obj_vec(objs)?
+                }
+                (ProgrammableMoveCallArg::Json(_), None) => {
+                    bail!("Unresolved argument for {package_id}::{module}::{function}")
+                }
+            });
+        }
+
+        Ok(builder.programmable_move_call(package_id, module, function, type_args, arguments))
+    }
+
+    /// Add an object to the inputs of a programmable transaction. Shared objects are taken by
+    /// mutable reference.
+    pub async fn input_object(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        id: ObjectID,
+    ) -> anyhow::Result<Argument> {
+        let obj = self
+            .get_object_arg(id, &mut BTreeMap::new(), /* mutable */ true)
+            .await?;
+        builder.obj(obj)
+    }
+
     async fn get_object_arg(
         &self,
         id: ObjectID,
         objects: &mut BTreeMap<ObjectID, Object>,
-        expected_type: SignatureToken,
+        mutable: bool,
     ) -> Result<ObjectArg, anyhow::Error> {
         let response = self
             .0
@@ -351,7 +452,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             } => ObjectArg::SharedObject {
                 id,
                 initial_shared_version,
-                mutable: matches!(expected_type, SignatureToken::MutableReference(_)),
+                mutable,
             },
             Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
                 ObjectArg::ImmOrOwnedObject(obj_ref)
@@ -368,22 +469,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         type_args: &[TypeTag],
         json_args: Vec<SuiJsonValue>,
     ) -> Result<Vec<Argument>, anyhow::Error> {
-        let object = self
-            .0
-            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
-            .await?
-            .into_object()?;
-        let Some(SuiRawData::Package(package)) = object.bcs else {
-            bail!("Bcs field in object [{}] is missing or not a package.", package_id);
-        };
-        let package: MovePackage = MovePackage::new(
-            package.id,
-            object.version,
-            package.module_map,
-            ProtocolConfig::get_for_min_version().max_move_package_size(),
-            package.type_origin_table,
-            package.linkage_table,
-        )?;
+        let package = self.get_package(package_id).await?;
 
         let json_args_and_tokens = resolve_move_function_args(
             &package,
@@ -396,18 +482,16 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let mut check_args = Vec::new();
         let mut objects = BTreeMap::new();
         for (arg, expected_type) in json_args_and_tokens {
+            let mutable = matches!(expected_type, SignatureToken::MutableReference(_));
             check_args.push(match arg {
-                ResolvedCallArg::Object(id) => CheckCallArg::Object(
-                    self.get_object_arg(id, &mut objects, expected_type).await?,
-                ),
+                ResolvedCallArg::Object(id) => {
+                    CheckCallArg::Object(self.get_object_arg(id, &mut objects, mutable).await?)
+                }
                 ResolvedCallArg::Pure(p) => CheckCallArg::Pure(p),
                 ResolvedCallArg::ObjVec(v) => {
                     let mut object_ids = vec![];
                     for id in v {
-                        object_ids.push(
-                            self.get_object_arg(id, &mut objects, expected_type.clone())
-                                .await?,
-                        );
+                        object_ids.push(self.get_object_arg(id, &mut objects, mutable).await?);
                     }
                     CheckCallArg::ObjVec(object_ids)
                 }
@@ -650,27 +734,8 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                 }
             };
         }
-        let pt = builder.finish();
-        let all_inputs = pt.input_objects()?;
-        let inputs = all_inputs
-            .iter()
-            .flat_map(|obj| match obj {
-                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
-                _ => None,
-            })
-            .collect();
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, inputs, gas_price)
-            .await?;
-
-        Ok(TransactionData::new(
-            TransactionKind::programmable(pt),
-            signer,
-            gas,
-            gas_budget,
-            gas_price,
-        ))
+        self.programmable_transaction(signer, builder.finish(), gas, gas_budget)
+            .await
     }
 
     pub async fn request_add_stake(
@@ -779,6 +844,25 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         )
     }
 
+    async fn get_package(&self, package_id: ObjectID) -> anyhow::Result<MovePackage> {
+        let object = self
+            .0
+            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?;
+        let Some(SuiRawData::Package(package)) = object.bcs else {
+            bail!("Bcs field in object [{}] is missing or not a package.", package_id);
+        };
+        Ok(MovePackage::new(
+            package.id,
+            object.version,
+            package.module_map,
+            ProtocolConfig::get_for_min_version().max_move_package_size(),
+            package.type_origin_table,
+            package.linkage_table,
+        )?)
+    }
+
     // TODO: we should add retrial to reduce the transaction building error rate
     async fn get_object_ref(&self, object_id: ObjectID) -> anyhow::Result<ObjectRef> {
         self.get_object_ref_and_type(object_id)
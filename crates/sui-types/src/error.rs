@@ -6,6 +6,7 @@ use crate::{
     base_types::*,
     committee::{Committee, EpochId, StakeUnit},
     messages::{CommandIndex, ExecutionFailureStatus, MoveLocation, MoveLocationOpt},
+    messages_checkpoint::CheckpointSequenceNumber,
     object::Owner,
 };
 use move_binary_format::{access::ModuleAccess, errors::VMError};
@@ -183,6 +184,13 @@ pub enum UserInputError {
 
     #[error("Feature is not yet supported: {0}")]
     Unsupported(String),
+
+    #[error("Cursor {cursor} is not a transaction of checkpoints {start_checkpoint} to {end_checkpoint}")]
+    CursorOutsideCheckpointRange {
+        cursor: TransactionDigest,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+    },
 }
 
 #[derive(
@@ -349,6 +357,16 @@ pub enum SuiError {
     TransactionsNotFound { digests: Vec<TransactionDigest> },
     #[error("Could not find the referenced transaction events [{digest:?}].")]
     TransactionEventsNotFound { digest: TransactionEventsDigest },
+    #[error("Transaction [{digest:?}] was pruned from this node, history is only available from checkpoint {lowest_available_checkpoint}.")]
+    TransactionPruned {
+        digest: TransactionDigest,
+        lowest_available_checkpoint: u64,
+    },
+    #[error("Contents of checkpoint {sequence_number} were pruned from this node, history is only available from checkpoint {lowest_available_checkpoint}.")]
+    CheckpointContentsPruned {
+        sequence_number: u64,
+        lowest_available_checkpoint: u64,
+    },
     #[error(
         "Attempt to move to `Executed` state an transaction that has already been executed: {:?}.",
         digest
@@ -478,6 +496,9 @@ pub enum SuiError {
     #[error("Index store not available on this Fullnode.")]
     IndexStoreNotAvailable,
 
+    #[error("Objects at checkpoint {checkpoint} are not indexed: {error}")]
+    ObjectsNotIndexedAtCheckpoint { checkpoint: u64, error: String },
+
     #[error("Failed to read dynamic field from table in the object store: {0}")]
     DynamicFieldReadError(String),
 
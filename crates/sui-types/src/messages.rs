@@ -934,6 +934,16 @@ impl TransactionKind {
             TransactionKind::ProgrammableTransaction(pt) => pt.commands.len(),
         }
     }
+
+    /// Name of the transaction kind, as used by `TransactionFilter::TransactionKind`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ChangeEpoch(_) => "ChangeEpoch",
+            Self::Genesis(_) => "Genesis",
+            Self::ConsensusCommitPrologue(_) => "ConsensusCommitPrologue",
+            Self::ProgrammableTransaction(_) => "ProgrammableTransaction",
+        }
+    }
 }
 
 impl Display for TransactionKind {
@@ -1635,6 +1645,28 @@ impl SenderSignedData {
         &mut self.inner_mut().tx_signatures
     }
 
+    /// Check the serialized size of the transaction, signatures included, against the max
+    /// transaction size of the protocol.
+    pub fn check_size(&self, config: &ProtocolConfig) -> SuiResult {
+        let tx_size =
+            bcs::serialized_size(self).map_err(|e| SuiError::TransactionSerializationError {
+                error: e.to_string(),
+            })?;
+        let max_tx_size_bytes = config.max_tx_size_bytes();
+        fp_ensure!(
+            tx_size as u64 <= max_tx_size_bytes,
+            SuiError::UserInputError {
+                error: UserInputError::SizeLimitExceeded {
+                    limit: format!(
+                        "serialized transaction size exceeded maximum of {max_tx_size_bytes}"
+                    ),
+                    value: tx_size.to_string(),
+                }
+            }
+        );
+        Ok(())
+    }
+
     pub fn full_message_digest(&self) -> SenderSignedDataDigest {
         let mut digest = DefaultHash::default();
         bcs::serialize_into(&mut digest, self).expect("serialization should not fail");
@@ -38,4 +38,63 @@ pub enum TransactionFilter {
     FromAndToAddress { from: SuiAddress, to: SuiAddress },
     /// Query by transaction kind
     TransactionKind(String),
+    /// Query by checkpoint range, both ends inclusive.
+    #[serde(rename_all = "camelCase")]
+    CheckpointRange {
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        start_checkpoint: CheckpointSequenceNumber,
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        end_checkpoint: CheckpointSequenceNumber,
+    },
+    /// Query by transaction timestamp range. Fullnodes only serve it in combination with an
+    /// address, object or move function filter.
+    #[serde(rename_all = "camelCase")]
+    TimeRange {
+        /// left endpoint of time interval, milliseconds since epoch, inclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        start_time: u64,
+        /// right endpoint of time interval, milliseconds since epoch, exclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        end_time: u64,
+    },
+    /// Matches transactions matching both filters.
+    And(Box<TransactionFilter>, Box<TransactionFilter>),
+    /// Matches transactions matching either filter.
+    Or(Box<TransactionFilter>, Box<TransactionFilter>),
+    /// Matches transactions not matching the filter, only supported in combination with an
+    /// address, object or move function filter, e.g. `And(FromAddress(a), Not(ToAddress(b)))`.
+    Not(Box<TransactionFilter>),
+}
+
+impl TransactionFilter {
+    pub fn and(self, other_filter: TransactionFilter) -> Self {
+        Self::And(Box::new(self), Box::new(other_filter))
+    }
+
+    pub fn or(self, other_filter: TransactionFilter) -> Self {
+        Self::Or(Box::new(self), Box::new(other_filter))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Returns true if the filter, or any of its sub-filters, depends on the checkpoint or
+    /// the timestamp of the transaction, which are not known before the transaction is
+    /// included in a checkpoint.
+    pub fn requires_checkpoint(&self) -> bool {
+        match self {
+            Self::Checkpoint(_) | Self::CheckpointRange { .. } | Self::TimeRange { .. } => true,
+            Self::And(f1, f2) | Self::Or(f1, f2) => {
+                f1.requires_checkpoint() || f2.requires_checkpoint()
+            }
+            Self::Not(f) => f.requires_checkpoint(),
+            _ => false,
+        }
+    }
 }
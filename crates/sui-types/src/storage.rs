@@ -13,8 +13,8 @@ use crate::messages::{
     VerifiedTransaction,
 };
 use crate::messages_checkpoint::{
-    CheckpointContents, CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
-    VerifiedCheckpointContents,
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+    FullCheckpointContents, VerifiedCheckpoint, VerifiedCheckpointContents,
 };
 use crate::move_package::MovePackage;
 use crate::{
@@ -33,6 +33,7 @@ use std::convert::Infallible;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use tap::Pipe;
+use tracing::debug;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum WriteKind {
@@ -229,6 +230,10 @@ pub trait ReadStore {
 
     fn get_highest_synced_checkpoint(&self) -> Result<VerifiedCheckpoint, Self::Error>;
 
+    /// Sequence number of the lowest checkpoint whose contents are available in the store, the
+    /// contents of older checkpoints may have been pruned.
+    fn get_lowest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber, Self::Error>;
+
     fn get_full_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -277,6 +282,10 @@ impl<T: ReadStore> ReadStore for &T {
         ReadStore::get_highest_synced_checkpoint(*self)
     }
 
+    fn get_lowest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber, Self::Error> {
+        ReadStore::get_lowest_available_checkpoint(*self)
+    }
+
     fn get_full_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -352,6 +361,7 @@ impl<T: WriteStore> WriteStore for &T {
 pub struct InMemoryStore {
     highest_verified_checkpoint: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
     highest_synced_checkpoint: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
+    lowest_available_checkpoint: CheckpointSequenceNumber,
     checkpoints: HashMap<CheckpointDigest, VerifiedCheckpoint>,
     sequence_number_to_digest: HashMap<CheckpointSequenceNumber, CheckpointDigest>,
     checkpoint_contents: HashMap<CheckpointContentsDigest, CheckpointContents>,
@@ -403,6 +413,10 @@ impl InMemoryStore {
             .and_then(|(_, digest)| self.get_checkpoint_by_digest(digest))
     }
 
+    pub fn get_lowest_available_checkpoint(&self) -> CheckpointSequenceNumber {
+        self.lowest_available_checkpoint
+    }
+
     pub fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -410,6 +424,18 @@ impl InMemoryStore {
         self.checkpoint_contents.get(digest)
     }
 
+    /// Remove the contents of the checkpoints up to `checkpoint` (inclusive), keeping their
+    /// summaries, as a pruning node does.
+    pub fn prune_checkpoint_contents(&mut self, checkpoint: CheckpointSequenceNumber) {
+        for sequence_number in self.lowest_available_checkpoint..=checkpoint {
+            if let Some(digest) = self.sequence_number_to_digest.get(&sequence_number) {
+                let content_digest = self.checkpoints[digest].content_digest;
+                self.checkpoint_contents.remove(&content_digest);
+            }
+        }
+        self.lowest_available_checkpoint = self.lowest_available_checkpoint.max(checkpoint + 1);
+    }
+
     pub fn insert_checkpoint_contents(&mut self, contents: VerifiedCheckpointContents) {
         for tx in contents.iter() {
             self.transactions
@@ -557,6 +583,10 @@ impl ReadStore for SharedInMemoryStore {
             .pipe(Ok)
     }
 
+    fn get_lowest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber, Self::Error> {
+        Ok(self.inner().get_lowest_available_checkpoint())
+    }
+
     fn get_full_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -635,6 +665,67 @@ impl WriteStore for SharedInMemoryStore {
     }
 }
 
+/// Verify that `checkpoint` is the successor of `current` and is certified by the committee of
+/// its epoch, as known to `store`. The checkpoint is handed back if it fails verification.
+pub fn verify_checkpoint<S>(
+    current: &VerifiedCheckpoint,
+    store: S,
+    checkpoint: CertifiedCheckpointSummary,
+) -> Result<VerifiedCheckpoint, CertifiedCheckpointSummary>
+where
+    S: ReadStore,
+    <S as ReadStore>::Error: std::error::Error,
+{
+    assert_eq!(
+        *checkpoint.sequence_number(),
+        current.sequence_number().saturating_add(1)
+    );
+
+    if Some(*current.digest()) != checkpoint.previous_digest {
+        debug!(
+            current_sequence_number = current.sequence_number(),
+            current_digest =% current.digest(),
+            checkpoint_sequence_number = checkpoint.sequence_number(),
+            checkpoint_digest =% checkpoint.digest(),
+            checkpoint_previous_digest =? checkpoint.previous_digest,
+            "checkpoint not on same chain"
+        );
+        return Err(checkpoint);
+    }
+
+    let current_epoch = current.epoch();
+    if checkpoint.epoch() != current_epoch && checkpoint.epoch() != current_epoch.saturating_add(1)
+    {
+        debug!(
+            current_epoch = current_epoch,
+            checkpoint_epoch = checkpoint.epoch(),
+            "cannot verify checkpoint with too high of an epoch",
+        );
+        return Err(checkpoint);
+    }
+
+    if checkpoint.epoch() == current_epoch.saturating_add(1)
+        && current.next_epoch_committee().is_none()
+    {
+        debug!(
+            "next checkpoint claims to be from the next epoch but the latest verified \
+            checkpoint does not indicate that it is the last checkpoint of an epoch"
+        );
+        return Err(checkpoint);
+    }
+
+    let committee = store
+        .get_committee(checkpoint.epoch())
+        .expect("store operation should not fail")
+        .expect("BUG: should have a committee for an epoch before we try to verify checkpoints from an epoch");
+
+    checkpoint.verify_signature(&committee).map_err(|e| {
+        debug!("error verifying checkpoint: {e}");
+        checkpoint.clone()
+    })?;
+    Ok(VerifiedCheckpoint::new_unchecked(checkpoint))
+}
+
 // The primary key type for object storage.
 #[serde_as]
 #[derive(Eq, PartialEq, Clone, Copy, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
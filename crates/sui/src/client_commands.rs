@@ -6,11 +6,14 @@ use std::sync::Arc;
 use std::{
     collections::BTreeSet,
     fmt::{Debug, Display, Formatter, Write},
+    fs,
     path::{Path, PathBuf},
     time::Instant,
 };
 
+use crate::client_ptb::{PtbCommandSpec, PtbComposer};
 use crate::config::{Config, PersistedConfig, SuiClientConfig, SuiEnv};
+use crate::keytool::prompt_for_keystore_password;
 use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
@@ -23,7 +26,7 @@ use move_core_types::language_storage::TypeTag;
 use move_package::BuildConfig as MoveBuildConfig;
 use prettytable::Table;
 use prettytable::{row, table};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sui_framework::build_move_package;
 use sui_move::build::resolve_lock_file_path;
@@ -38,21 +41,22 @@ use sui_framework_build::compiled_package::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectDataFilter, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectDataFilter,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::SuiClient;
-use sui_types::crypto::SignatureScheme;
+use sui_types::crypto::{PublicKey, SignatureScheme};
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::move_package::UpgradeCap;
+use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use sui_types::signature::GenericSignature;
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
     gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    messages::{Transaction, TransactionData, TransactionDataAPI, VerifiedTransaction},
     object::Owner,
     parse_sui_type_tag,
 };
@@ -152,6 +156,9 @@ pub enum SuiClientCommands {
         /// Do not Sign transaction, output Base64-encoded Serialized Output
         #[clap(long)]
         serialize_output: bool,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Upgrade Move modules
@@ -191,6 +198,9 @@ pub enum SuiClientCommands {
         /// Also publish transitive dependencies that have not already been published.
         #[clap(long)]
         with_unpublished_dependencies: bool,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Verify local Move packages against on-chain packages, and optionally their dependencies.
@@ -255,6 +265,28 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
+    },
+
+    /// Compose a programmable transaction block out of the commands in a JSON file, see
+    /// `sui::client_ptb` for the file format. The transaction is dry run before it is executed.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Path to the JSON file listing the commands of the transaction block
+        commands: PathBuf,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this transaction block
+        #[clap(long)]
+        gas_budget: u64,
+        /// Only dry run the transaction block and show its effects, without executing it
+        #[clap(long)]
+        preview: bool,
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Transfer object
@@ -276,6 +308,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transfer
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
     /// Transfer SUI, and pay gas with the same SUI coin object.
     /// If amount is specified, only the amount is transferred; otherwise the entire object
@@ -297,6 +332,9 @@ pub enum SuiClientCommands {
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
         amount: Option<u64>,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
     /// Pay coins to recipients following specified amounts, with input coins.
     /// Length of recipients must be the same as that of amounts.
@@ -322,6 +360,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Pay SUI coins to recipients following following specified amounts, with input coins.
@@ -344,6 +385,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -361,6 +405,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Obtain the Addresses managed by the client.
@@ -428,6 +475,8 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Merge two coin objects into one coin
@@ -445,6 +494,8 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        unsigned_transaction: UnsignedTransactionArgs,
     },
 
     /// Serialize a transfer that can be signed. This is useful when user prefers to take the data to sign elsewhere.
@@ -477,6 +528,28 @@ pub enum SuiClientCommands {
         #[clap(long)]
         signatures: Vec<String>,
     },
+
+    /// Execute a transaction written with `--serialize-unsigned-transaction` and signed offline
+    /// with `sui keytool sign --tx-file`, using all signatures collected in the file. If the MultiSig
+    /// public keys, weights and threshold are provided, the collected signatures are combined into
+    /// a single MultiSig first.
+    ExecuteTxFile {
+        /// Path to the transaction file.
+        #[clap(long)]
+        tx_file: PathBuf,
+
+        /// Public keys `flag || pk` in Base64 of all the MultiSig participants.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        multisig_pks: Vec<PublicKey>,
+
+        /// Weights of the MultiSig participants, following the order of the public keys.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        multisig_weights: Vec<WeightUnit>,
+
+        /// Threshold of the MultiSig.
+        #[clap(long)]
+        multisig_threshold: Option<ThresholdUnit>,
+    },
 }
 
 impl SuiClientCommands {
@@ -493,6 +566,7 @@ impl SuiClientCommands {
                 gas_budget,
                 skip_dependency_verification,
                 with_unpublished_dependencies,
+                unsigned_transaction,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -561,6 +635,9 @@ impl SuiClientCommands {
                         gas_budget,
                     )
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature = context.config.keystore.sign_secure(
                     &sender,
                     &data,
@@ -583,6 +660,7 @@ impl SuiClientCommands {
                 skip_dependency_verification,
                 with_unpublished_dependencies,
                 serialize_output,
+                unsigned_transaction,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -612,6 +690,9 @@ impl SuiClientCommands {
                         bcs::to_bytes(&data).unwrap(),
                     )));
                 }
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
 
                 let signature = context.config.keystore.sign_secure(
                     &sender,
@@ -675,7 +756,15 @@ impl SuiClientCommands {
                 gas,
                 gas_budget,
                 args,
+                unsigned_transaction,
             } => {
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    let data = construct_move_call_transaction(
+                        package, &module, &function, type_args, gas, gas_budget, args, context,
+                    )
+                    .await?;
+                    return write_unsigned_transaction(&data, path);
+                }
                 let response = call_move(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
@@ -683,11 +772,64 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Call(response)
             }
 
+            SuiClientCommands::Ptb {
+                commands,
+                gas,
+                gas_budget,
+                preview,
+                unsigned_transaction,
+            } => {
+                let commands: Vec<PtbCommandSpec> = serde_json::from_str(
+                    &fs::read_to_string(&commands)
+                        .map_err(|e| anyhow!("Cannot read {commands:?}: {e}"))?,
+                )
+                .map_err(|e| anyhow!("Cannot parse {commands:?}: {e}"))?;
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+
+                let client = context.get_client().await?;
+                let pt = PtbComposer::new(&client).compose(commands).await?;
+                let data = client
+                    .transaction_builder()
+                    .programmable_transaction(sender, pt, gas, gas_budget)
+                    .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
+
+                let dry_run = client
+                    .read_api()
+                    .dry_run_transaction_block(data.clone())
+                    .await?;
+                if preview {
+                    return Ok(SuiClientCommandResult::PtbPreview(dry_run));
+                }
+                if let SuiExecutionStatus::Failure { error } = dry_run.effects.status() {
+                    return Err(anyhow!(
+                        "Dry run of the transaction block failed, not executing it: {error}"
+                    ));
+                }
+
+                let signature = context.config.keystore.sign_secure(
+                    &sender,
+                    &data,
+                    Intent::sui_transaction(),
+                )?;
+                let response = context
+                    .execute_transaction_block(
+                        Transaction::from_data(data, Intent::sui_transaction(), vec![signature])
+                            .verify()?,
+                    )
+                    .await?;
+                SuiClientCommandResult::Ptb(response)
+            }
+
             SuiClientCommands::Transfer {
                 to,
                 object_id,
                 gas,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
                 let time_start = Instant::now();
@@ -697,6 +839,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature =
                     context
                         .config
@@ -726,6 +871,7 @@ impl SuiClientCommands {
                 sui_coin_object_id: object_id,
                 gas_budget,
                 amount,
+                unsigned_transaction,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
 
@@ -734,6 +880,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_sui(from, object_id, gas_budget, to, amount)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature =
                     context
                         .config
@@ -760,6 +909,7 @@ impl SuiClientCommands {
                 amounts,
                 gas,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -783,6 +933,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature =
                     context
                         .config
@@ -811,6 +964,7 @@ impl SuiClientCommands {
                 recipients,
                 amounts,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -834,6 +988,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -861,6 +1018,7 @@ impl SuiClientCommands {
                 input_coins,
                 recipient,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -872,6 +1030,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay_all_sui(signer, input_coins, recipient, gas_budget)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
 
                 let signature = context.config.keystore.sign_secure(
                     &signer,
@@ -897,7 +1058,15 @@ impl SuiClientCommands {
             }
 
             SuiClientCommands::Addresses => SuiClientCommandResult::Addresses(
-                context.config.keystore.addresses(),
+                // Keys which cannot be listed, e.g. from an unreachable signer, are reported
+                // rather than shown as an empty list.
+                context
+                    .config
+                    .keystore
+                    .try_keys()?
+                    .iter()
+                    .map(|k| k.into())
+                    .collect(),
                 context.active_address().ok(),
             ),
 
@@ -958,6 +1127,7 @@ impl SuiClientCommands {
                 count,
                 gas,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
                 let client = context.get_client().await?;
@@ -981,6 +1151,9 @@ impl SuiClientCommands {
                         return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
                     }
                 };
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -999,6 +1172,7 @@ impl SuiClientCommands {
                 coin_to_merge,
                 gas,
                 gas_budget,
+                unsigned_transaction,
             } => {
                 let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
@@ -1006,6 +1180,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
                     .await?;
+                if let Some(path) = unsigned_transaction.serialize_unsigned_transaction {
+                    return write_unsigned_transaction(&data, path);
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -1087,6 +1264,45 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_block(verified).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
+            SuiClientCommands::ExecuteTxFile {
+                tx_file,
+                multisig_pks,
+                multisig_weights,
+                multisig_threshold,
+            } => {
+                let file = UnsignedTransactionFile::read(&tx_file)?;
+                let data = file.transaction_data()?;
+                let mut sigs = file.signatures()?;
+                ensure!(
+                    !sigs.is_empty(),
+                    "No signatures found in {tx_file:?}, sign it with `sui keytool sign --tx-file`"
+                );
+                if let Some(threshold) = multisig_threshold {
+                    let partial_sigs = sigs
+                        .into_iter()
+                        .map(|sig| match sig {
+                            GenericSignature::Signature(sig) => Ok(sig),
+                            _ => Err(anyhow!(
+                                "Only single signatures can be combined into a MultiSig"
+                            )),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let multisig_pk =
+                        MultiSigPublicKey::new(multisig_pks, multisig_weights, threshold)?;
+                    sigs = vec![MultiSig::combine(partial_sigs, multisig_pk)?.into()];
+                } else {
+                    ensure!(
+                        multisig_pks.is_empty() && multisig_weights.is_empty(),
+                        "MultiSig public keys and weights require a MultiSig threshold"
+                    );
+                }
+                let verified =
+                    Transaction::from_generic_sig_data(data, Intent::sui_transaction(), sigs)
+                        .verify()?;
+
+                let response = context.execute_transaction_block(verified).await?;
+                SuiClientCommandResult::ExecuteSignedTx(response)
+            }
             SuiClientCommands::NewEnv { alias, rpc, ws } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
@@ -1161,7 +1377,82 @@ impl SuiClientCommands {
     }
 }
 
-async fn compile_package(
+/// Lets a transaction building command write its transaction unsigned instead of executing it.
+#[derive(Args, Debug, Default)]
+pub struct UnsignedTransactionArgs {
+    /// Do not sign the transaction, write it unsigned to this file instead. It can then be
+    /// signed offline with `sui keytool sign --tx-file` and executed with
+    /// `sui client execute-tx-file`.
+    #[clap(long)]
+    pub serialize_unsigned_transaction: Option<PathBuf>,
+}
+
+/// A transaction written unsigned with `--serialize-unsigned-transaction`, along with the
+/// signatures collected for it offline with `sui keytool sign --tx-file`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnsignedTransactionFile {
+    /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+    pub tx_bytes: String,
+    /// Base64 encoded signatures `flag || signature || pubkey` collected so far.
+    #[serde(default)]
+    pub signatures: Vec<String>,
+}
+
+impl UnsignedTransactionFile {
+    pub fn new(data: &TransactionData) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            tx_bytes: Base64::encode(bcs::to_bytes(data)?),
+            signatures: vec![],
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read transaction file {path:?}: {e}"))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Cannot parse transaction file {path:?}: {e}"))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| anyhow!("Cannot write transaction file {path:?}: {e}"))
+    }
+
+    pub fn transaction_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let bytes = Base64::decode(&self.tx_bytes)
+            .map_err(|e| anyhow!("Cannot decode tx_bytes as Base64: {e}"))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    pub fn signatures(&self) -> Result<Vec<GenericSignature>, anyhow::Error> {
+        self.signatures
+            .iter()
+            .map(|sig| {
+                GenericSignature::from_bytes(
+                    &Base64::decode(sig).map_err(|e| anyhow!("Invalid signature {sig}: {e}"))?,
+                )
+                .map_err(|e| anyhow!("Invalid signature {sig}: {e}"))
+            })
+            .collect()
+    }
+
+    /// Add a signature, signatures that were already collected are ignored.
+    pub fn add_signature(&mut self, signature: String) {
+        if !self.signatures.contains(&signature) {
+            self.signatures.push(signature);
+        }
+    }
+}
+
+fn write_unsigned_transaction(
+    data: &TransactionData,
+    path: PathBuf,
+) -> Result<SuiClientCommandResult, anyhow::Error> {
+    UnsignedTransactionFile::new(data)?.write(&path)?;
+    Ok(SuiClientCommandResult::SerializeUnsignedTransaction(path))
+}
+
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1246,7 +1537,8 @@ impl WalletContext {
             )
         })?;
 
-        let config = config.persisted(config_path);
+        let mut config = config.persisted(config_path);
+        prompt_for_keystore_password(&mut config.keystore);
         let context = Self {
             config,
             request_timeout,
@@ -1458,6 +1750,21 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Call(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::PtbPreview(response) => {
+                writeln!(writer, "{}", "----- Transaction Data ----".bold())?;
+                writeln!(writer, "{}", response.input)?;
+                writeln!(writer, "{}", "----- Transaction Effects ----".bold())?;
+                writeln!(writer, "{}", response.effects)?;
+                writeln!(writer, "{}", "----- Events ----".bold())?;
+                writeln!(writer, "{:#?}", json!(response.events))?;
+                writeln!(writer, "{}", "----- Object changes ----".bold())?;
+                writeln!(writer, "{:#?}", json!(response.object_changes))?;
+                writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+                writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+            }
             SuiClientCommandResult::Transfer(time_elapsed, response) => {
                 writeln!(writer, "Transfer confirmed after {} us", time_elapsed)?;
                 write!(writer, "{}", write_transaction_response(response)?)?;
@@ -1594,6 +1901,15 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::SerializePublish(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
+            SuiClientCommandResult::SerializeUnsignedTransaction(path) => {
+                writeln!(writer, "Unsigned transaction written to {:?}", path)?;
+                writeln!(
+                    writer,
+                    "Sign it with `sui keytool sign --address <ADDRESS> --tx-file {}` and execute it with `sui client execute-tx-file --tx-file {}`",
+                    path.display(),
+                    path.display()
+                )?;
+            }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
             }
@@ -1617,7 +1933,7 @@ impl Display for SuiClientCommandResult {
     }
 }
 
-pub async fn call_move(
+pub async fn construct_move_call_transaction(
     package: ObjectID,
     module: &str,
     function: &str,
@@ -1626,7 +1942,7 @@ pub async fn call_move(
     gas_budget: u64,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
-) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
+) -> Result<TransactionData, anyhow::Error> {
     // Convert all numeric input to String, this will allow number input from the CLI without failing SuiJSON's checks.
     let args = args
         .into_iter()
@@ -1637,7 +1953,7 @@ pub async fn call_move(
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
     let client = context.get_client().await?;
-    let data = client
+    client
         .transaction_builder()
         .move_call(
             sender,
@@ -1652,7 +1968,24 @@ pub async fn call_move(
             gas,
             gas_budget,
         )
-        .await?;
+        .await
+}
+
+pub async fn call_move(
+    package: ObjectID,
+    module: &str,
+    function: &str,
+    type_args: Vec<TypeTag>,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
+    let data = construct_move_call_transaction(
+        package, module, function, type_args, gas, gas_budget, args, context,
+    )
+    .await?;
+    let sender = data.sender();
     let signature =
         context
             .config
@@ -1769,6 +2102,8 @@ pub enum SuiClientCommandResult {
     RawObject(SuiObjectResponse),
     TransactionBlock(SuiTransactionBlockResponse),
     Call(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
+    PtbPreview(DryRunTransactionBlockResponse),
     Transfer(
         // Skipping serialisation for elapsed time.
         #[serde(skip)] u128,
@@ -1792,6 +2127,7 @@ pub enum SuiClientCommandResult {
     Envs(Vec<SuiEnv>, Option<String>),
     SerializeTransferSui(String),
     SerializePublish(String),
+    SerializeUnsignedTransaction(PathBuf),
     ExecuteSignedTx(SuiTransactionBlockResponse),
     NewEnv(SuiEnv),
 }
@@ -0,0 +1,278 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Composer for `sui client ptb`, which builds a programmable transaction block from a JSON list
+//! of commands. Commands can be named, and later commands refer to their results as `$name`, or
+//! `$name.i` for the i-th value of a command returning multiple values. The gas coin is `$gas`.
+//! Any other argument is a JSON value, e.g. an object ID or a pure value:
+//!
+//! ```json
+//! [
+//!   { "name": "coins", "SplitCoins": { "coin": "$gas", "amounts": [1000, 2000] } },
+//!   { "TransferObjects": { "objects": ["$coins.0", "$coins.1"], "address": "0x..." } }
+//! ]
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::language_storage::TypeTag;
+use move_core_types::value::MoveTypeLayout;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::Deserialize;
+use serde_json::Value;
+
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::SuiTypeTag;
+use sui_sdk::SuiClient;
+use sui_transaction_builder::ProgrammableMoveCallArg;
+use sui_types::base_types::ObjectID;
+use sui_types::messages::{Argument, Command, ProgrammableTransaction};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::client_commands::compile_package;
+
+const REFERENCE_PREFIX: char = '$';
+const GAS_COIN_NAME: &str = "gas";
+
+/// A command of the programmable transaction block, optionally named so that later commands can
+/// use its result.
+#[derive(Deserialize, Debug)]
+pub struct PtbCommandSpec {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub command: PtbCommand,
+}
+
+#[derive(Deserialize, Debug)]
+pub enum PtbCommand {
+    /// Call a public or entry Move function.
+    #[serde(rename_all = "camelCase")]
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default)]
+        type_arguments: Vec<SuiTypeTag>,
+        #[serde(default)]
+        arguments: Vec<Value>,
+    },
+    /// Transfer objects to an address.
+    TransferObjects { objects: Vec<Value>, address: Value },
+    /// Split coins with the given amounts off a coin.
+    SplitCoins { coin: Value, amounts: Vec<Value> },
+    /// Merge coins into the destination coin.
+    MergeCoins {
+        destination: Value,
+        sources: Vec<Value>,
+    },
+    /// Make a Move vector. The element type is required for empty vectors and vectors of pure
+    /// values, elements are objects otherwise.
+    MakeMoveVec {
+        #[serde(default, rename = "type")]
+        type_: Option<SuiTypeTag>,
+        elements: Vec<Value>,
+    },
+    /// Publish the Move package at the given path, returning its `UpgradeCap`.
+    #[serde(rename_all = "camelCase")]
+    Publish { package_path: PathBuf },
+}
+
+/// Builds a programmable transaction out of [PtbCommandSpec]s, keeping track of the results of
+/// named commands.
+pub struct PtbComposer<'a> {
+    client: &'a SuiClient,
+    builder: ProgrammableTransactionBuilder,
+    results: BTreeMap<String, Argument>,
+}
+
+impl<'a> PtbComposer<'a> {
+    pub fn new(client: &'a SuiClient) -> Self {
+        Self {
+            client,
+            builder: ProgrammableTransactionBuilder::new(),
+            results: BTreeMap::new(),
+        }
+    }
+
+    pub async fn compose(
+        mut self,
+        commands: Vec<PtbCommandSpec>,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        ensure!(
+            !commands.is_empty(),
+            "Programmable transaction has no commands"
+        );
+        for (idx, PtbCommandSpec { name, command }) in commands.into_iter().enumerate() {
+            let result = self
+                .add_command(command)
+                .await
+                .map_err(|e| anyhow!("Invalid command at position {idx}: {e}"))?;
+            if let Some(name) = name {
+                ensure!(
+                    name != GAS_COIN_NAME && !self.results.contains_key(&name),
+                    "Command name [{name}] at position {idx} is already in use"
+                );
+                self.results.insert(name, result);
+            }
+        }
+        Ok(self.builder.finish())
+    }
+
+    async fn add_command(&mut self, command: PtbCommand) -> Result<Argument, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+            } => {
+                let arguments = arguments
+                    .into_iter()
+                    .map(|arg| {
+                        Ok(match self.reference(&arg)? {
+                            Some(arg) => ProgrammableMoveCallArg::Argument(arg),
+                            None => ProgrammableMoveCallArg::Json(SuiJsonValue::new(arg)?),
+                        })
+                    })
+                    .collect::<Result<_, anyhow::Error>>()?;
+                self.client
+                    .transaction_builder()
+                    .programmable_move_call(
+                        &mut self.builder,
+                        package,
+                        &module,
+                        &function,
+                        type_arguments,
+                        arguments,
+                    )
+                    .await?
+            }
+            PtbCommand::TransferObjects { objects, address } => {
+                let objects = self.objects(objects).await?;
+                let address = match self.reference(&address)? {
+                    Some(address) => address,
+                    None => {
+                        let address = SuiJsonValue::new(address)?.to_sui_address()?;
+                        self.builder.pure(address)?
+                    }
+                };
+                self.builder
+                    .command(Command::TransferObjects(objects, address))
+            }
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = self.object(coin).await?;
+                let amounts = amounts
+                    .into_iter()
+                    .map(|amount| self.pure(amount, &MoveTypeLayout::U64))
+                    .collect::<Result<_, _>>()?;
+                self.builder.command(Command::SplitCoins(coin, amounts))
+            }
+            PtbCommand::MergeCoins {
+                destination,
+                sources,
+            } => {
+                let destination = self.object(destination).await?;
+                let sources = self.objects(sources).await?;
+                self.builder
+                    .command(Command::MergeCoins(destination, sources))
+            }
+            PtbCommand::MakeMoveVec { type_, elements } => {
+                let type_: Option<TypeTag> = type_.map(SuiTypeTag::try_into).transpose()?;
+                let elements = match type_.as_ref().and_then(pure_layout) {
+                    Some(layout) => elements
+                        .into_iter()
+                        .map(|element| self.pure(element, &layout))
+                        .collect::<Result<_, _>>()?,
+                    None => self.objects(elements).await?,
+                };
+                self.builder.command(Command::MakeMoveVec(type_, elements))
+            }
+            PtbCommand::Publish { package_path } => {
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    self.client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    /* with_unpublished_dependencies */ false,
+                    /* skip_dependency_verification */ false,
+                )
+                .await?;
+                self.builder.publish_upgradeable(
+                    compiled_modules,
+                    dependencies.published.into_values().collect(),
+                )
+            }
+        })
+    }
+
+    /// Resolve `value` if it refers to the gas coin or to the result of an earlier command.
+    fn reference(&self, value: &Value) -> Result<Option<Argument>, anyhow::Error> {
+        let Some(reference) = value.as_str().and_then(|s| s.strip_prefix(REFERENCE_PREFIX)) else {
+            return Ok(None);
+        };
+        if reference == GAS_COIN_NAME {
+            return Ok(Some(Argument::GasCoin));
+        }
+        let (name, index) = match reference.split_once('.') {
+            Some((name, index)) => (name, Some(u16::from_str(index)?)),
+            None => (reference, None),
+        };
+        let Some(result) = self.results.get(name) else {
+            bail!("Unknown command result [{name}]");
+        };
+        Ok(Some(match (result, index) {
+            (Argument::Result(result), Some(index)) => Argument::NestedResult(*result, index),
+            (result, None) => *result,
+            (_, Some(_)) => bail!("Command result [{name}] has no nested results"),
+        }))
+    }
+
+    async fn object(&mut self, value: Value) -> Result<Argument, anyhow::Error> {
+        if let Some(arg) = self.reference(&value)? {
+            return Ok(arg);
+        }
+        let id = value
+            .as_str()
+            .ok_or_else(|| anyhow!("Expected an object ID, found {value}"))?;
+        self.client
+            .transaction_builder()
+            .input_object(&mut self.builder, ObjectID::from_str(id)?)
+            .await
+    }
+
+    async fn objects(&mut self, values: Vec<Value>) -> Result<Vec<Argument>, anyhow::Error> {
+        let mut objects = vec![];
+        for value in values {
+            objects.push(self.object(value).await?);
+        }
+        Ok(objects)
+    }
+
+    fn pure(&mut self, value: Value, layout: &MoveTypeLayout) -> Result<Argument, anyhow::Error> {
+        if let Some(arg) = self.reference(&value)? {
+            return Ok(arg);
+        }
+        let bytes = SuiJsonValue::new(value)?.to_bcs_bytes(layout)?;
+        Ok(self.builder.pure_bytes(bytes, /* force separate */ false))
+    }
+}
+
+/// The layout of values of `type_` if they can be passed as pure arguments.
+fn pure_layout(type_: &TypeTag) -> Option<MoveTypeLayout> {
+    Some(match type_ {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(pure_layout(inner)?)),
+        TypeTag::Struct(_) | TypeTag::Signer => return None,
+    })
+}
@@ -6,26 +6,37 @@ use clap::*;
 use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding};
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::KeyPair;
+use inquire::Password;
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sui_json_rpc_types::SuiTransactionBlockData;
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{
+    AccountKeystore, EncryptedFileBasedKeystore, Keystore, DEFAULT_KDF_ITERATIONS,
+};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
 use sui_types::crypto::{DefaultHash, PublicKey, Signature};
+use sui_types::in_memory_storage::InMemoryStorage;
 use sui_types::messages::TransactionData;
 use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use sui_types::signature::GenericSignature;
 use tracing::info;
+
+use crate::client_commands::UnsignedTransactionFile;
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
 mod keytool_tests;
 
+/// Environment variable the keystore password is read from instead of prompting for it.
+pub const SUI_KEYSTORE_PASSWORD_ENV: &str = "SUI_KEYSTORE_PASSWORD";
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
@@ -65,11 +76,19 @@ pub enum KeyToolCommand {
     /// [transaction builder API](https://docs.sui.io/sui-jsonrpc) and its intent. If
     /// intent is absent, default will be used. See [struct IntentMessage] and [struct Intent]
     /// for more details.
+    ///
+    /// With `--tx-file`, the transaction is read from a file written by
+    /// `sui client <COMMAND> --serialize-unsigned-transaction` and the signature is added to the
+    /// file, to be executed with `sui client execute-tx-file`. This does not require network
+    /// access, so it can be run on an air-gapped machine.
+    #[clap(group(ArgGroup::new("sign-data").required(true).args(&["data", "tx-file"])))]
     Sign {
         #[clap(long, parse(try_from_str = decode_bytes_hex))]
         address: SuiAddress,
         #[clap(long)]
-        data: String,
+        data: Option<String>,
+        #[clap(long)]
+        tx_file: Option<PathBuf>,
         #[clap(long)]
         intent: Option<Intent>,
     },
@@ -117,6 +136,32 @@ pub enum KeyToolCommand {
         #[clap(long)]
         threshold: ThresholdUnit,
     },
+
+    /// Encrypt the private keys of sui.keystore at rest with a password. The password is read
+    /// from the `SUI_KEYSTORE_PASSWORD` environment variable if set, otherwise it is prompted
+    /// for. The encryption key is derived with PBKDF2-HMAC-SHA256 and keys are sealed with
+    /// AES-256-GCM, public keys are kept in clear so addresses can be listed while locked.
+    EncryptKeystore {
+        /// Number of PBKDF2 rounds used to derive the encryption key from the password.
+        #[clap(long, default_value_t = DEFAULT_KDF_ITERATIONS)]
+        kdf_iterations: u32,
+    },
+
+    /// Change the password of an encrypted sui.keystore.
+    ChangeKeystorePassword,
+
+    /// Unlock an encrypted sui.keystore for a session. The derived encryption key is cached next
+    /// to the keystore, readable only by the current user, and commands needing private keys use
+    /// it until the session expires or `sui keytool lock` is run. Without a session, commands
+    /// prompt for the password the first time they sign, or read it from `SUI_KEYSTORE_PASSWORD`.
+    Unlock {
+        /// Length of the session in seconds.
+        #[clap(long, default_value_t = 900)]
+        duration_secs: u64,
+    },
+
+    /// End the session started by `sui keytool unlock`.
+    Lock,
 }
 
 impl KeyToolCommand {
@@ -191,8 +236,18 @@ impl KeyToolCommand {
             KeyToolCommand::Sign {
                 address,
                 data,
+                tx_file,
                 intent,
             } => {
+                let mut unsigned_tx = tx_file
+                    .as_deref()
+                    .map(UnsignedTransactionFile::read)
+                    .transpose()?;
+                let data = match (data, &unsigned_tx) {
+                    (Some(data), _) => data,
+                    (None, Some(unsigned_tx)) => unsigned_tx.tx_bytes.clone(),
+                    (None, None) => return Err(anyhow!("Either --data or --tx-file is required")),
+                };
                 println!("Signer address: {}", address);
                 println!("Raw tx_bytes to execute: {}", data);
                 let intent = intent.unwrap_or_else(Intent::sui_transaction);
@@ -201,6 +256,11 @@ impl KeyToolCommand {
                     bcs::from_bytes(&Base64::decode(&data).map_err(|e| {
                         anyhow!("Cannot deserialize data as TransactionData {:?}", e)
                     })?)?;
+                // Decode without any package available, Move call arguments are shown as raw
+                // bytes since their types cannot be resolved offline.
+                let summary =
+                    SuiTransactionBlockData::try_from(msg.clone(), &InMemoryStorage::default())?;
+                println!("Transaction to sign:\n{summary}");
                 let intent_msg = IntentMessage::new(intent, msg);
                 println!(
                     "Raw intent message: {:?}",
@@ -216,6 +276,15 @@ impl KeyToolCommand {
                     "Serialized signature (`flag || sig || pk` in Base64): {:?}",
                     sui_signature.encode_base64()
                 );
+                if let (Some(path), Some(unsigned_tx)) = (tx_file, unsigned_tx.as_mut()) {
+                    unsigned_tx.add_signature(sui_signature.encode_base64());
+                    unsigned_tx.write(&path)?;
+                    println!(
+                        "Signature added to {:?}, {} signature(s) collected",
+                        path,
+                        unsigned_tx.signatures.len()
+                    );
+                }
             }
 
             KeyToolCommand::Import {
@@ -295,12 +364,92 @@ impl KeyToolCommand {
                 println!("MultiSig parsed: {:?}", generic_sig);
                 println!("MultiSig serialized: {:?}", generic_sig.encode_base64());
             }
+            KeyToolCommand::EncryptKeystore { kdf_iterations } => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only a plaintext file based keystore can be encrypted"));
+                };
+                let path = file
+                    .path()
+                    .ok_or_else(|| anyhow!("Keystore has no path"))?
+                    .to_path_buf();
+                let password = read_new_password()?;
+                let encrypted = EncryptedFileBasedKeystore::create(
+                    &path,
+                    file.key_pairs().into_iter().map(|kp| kp.copy()).collect(),
+                    &password,
+                    kdf_iterations,
+                )?;
+                println!(
+                    "Encrypted {} key(s) in keystore {:?}",
+                    encrypted.keys().len(),
+                    path
+                );
+                *keystore = Keystore::Encrypted(encrypted);
+            }
+            KeyToolCommand::ChangeKeystorePassword => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("Keystore is not encrypted, see `sui keytool encrypt-keystore`"));
+                };
+                let old_password = read_password("Enter current keystore password:")?;
+                let new_password = read_new_password()?;
+                encrypted.change_password(&old_password, &new_password)?;
+                println!("Keystore password changed");
+            }
+            KeyToolCommand::Unlock { duration_secs } => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("Keystore is not encrypted, see `sui keytool encrypt-keystore`"));
+                };
+                if encrypted.is_locked() {
+                    let password = read_password("Enter keystore password:")?;
+                    encrypted.unlock(&password)?;
+                }
+                encrypted.start_session(Duration::from_secs(duration_secs))?;
+                println!("Keystore unlocked for {duration_secs} seconds for addresses:");
+                for address in keystore.addresses() {
+                    println!(" {address}");
+                }
+            }
+            KeyToolCommand::Lock => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("Keystore is not encrypted, see `sui keytool encrypt-keystore`"));
+                };
+                encrypted.lock()?;
+                println!("Keystore locked");
+            }
         }
 
         Ok(())
     }
 }
 
+/// Prompt for the password of an encrypted keystore the first time a private key is needed,
+/// this is a no-op for the other keystore types.
+pub fn prompt_for_keystore_password(keystore: &mut Keystore) {
+    if let Keystore::Encrypted(encrypted) = keystore {
+        encrypted.set_password_prompt(Box::new(|| read_password("Enter keystore password:")));
+    }
+}
+
+fn read_password(message: &str) -> Result<String, anyhow::Error> {
+    match std::env::var(SUI_KEYSTORE_PASSWORD_ENV) {
+        Ok(password) => Ok(password),
+        Err(_) => Ok(Password::new(message).without_confirmation().prompt()?),
+    }
+}
+
+fn read_new_password() -> Result<String, anyhow::Error> {
+    let password = match std::env::var(SUI_KEYSTORE_PASSWORD_ENV) {
+        Ok(password) => password,
+        Err(_) => Password::new("Enter new keystore password:")
+            .with_custom_confirmation_message("Confirm new keystore password:")
+            .prompt()?,
+    };
+    if password.is_empty() {
+        return Err(anyhow!("Keystore password cannot be empty"));
+    }
+    Ok(password)
+}
+
 fn store_and_print_keypair(address: SuiAddress, keypair: SuiKeyPair) {
     let path_str = format!("{}.key", address).to_lowercase();
     let path = Path::new(&path_str);
@@ -23,7 +23,9 @@ use sui_config::{
     sui_config_dir, Config, PersistedConfig, FULL_NODE_DB_PATH, SUI_CLIENT_CONFIG,
     SUI_FULLNODE_CONFIG, SUI_NETWORK_CONFIG,
 };
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::keystore::{
+    AccountKeystore, EncryptedFileBasedKeystore, FileBasedKeystore, Keystore,
+};
 use sui_swarm::memory::Swarm;
 use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 
@@ -32,7 +34,7 @@ use crate::config::{SuiClientConfig, SuiEnv};
 use crate::console::start_console;
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
-use crate::keytool::KeyToolCommand;
+use crate::keytool::{prompt_for_keystore_password, KeyToolCommand};
 use crate::validator_commands::SuiValidatorCommand;
 use sui_move::{self, execute_move_command};
 
@@ -250,8 +252,19 @@ impl SuiCommand {
             SuiCommand::KeyTool { keystore_path, cmd } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
-                cmd.execute(&mut keystore)
+                let mut keystore = if EncryptedFileBasedKeystore::is_encrypted_file(&keystore_path)?
+                {
+                    Keystore::from(EncryptedFileBasedKeystore::new(&keystore_path)?)
+                } else {
+                    Keystore::from(FileBasedKeystore::new(&keystore_path)?)
+                };
+                prompt_for_keystore_password(&mut keystore);
+                let encrypt = matches!(cmd, KeyToolCommand::EncryptKeystore { .. });
+                cmd.execute(&mut keystore)?;
+                if encrypt {
+                    use_encrypted_keystore_in_client_config(&keystore_path)?;
+                }
+                Ok(())
             }
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
@@ -594,3 +607,32 @@ fn read_line() -> Result<String, anyhow::Error> {
     io::stdin().read_line(&mut s)?;
     Ok(s.trim_end().to_string())
 }
+
+/// Point the client config at the encrypted keystore after `keytool encrypt-keystore`, if it was
+/// using the plaintext keystore at `keystore_path`.
+fn use_encrypted_keystore_in_client_config(keystore_path: &Path) -> Result<(), anyhow::Error> {
+    let config_path = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+    if !config_path.exists() {
+        return Ok(());
+    }
+    let mut config: serde_yaml::Value = serde_yaml::from_reader(fs::File::open(&config_path)?)?;
+    let uses_keystore = config
+        .get("keystore")
+        .and_then(|keystore| keystore.get("File"))
+        .and_then(|path| path.as_str())
+        .map_or(false, |path| Path::new(path) == keystore_path);
+    if uses_keystore {
+        let mut keystore = serde_yaml::Mapping::new();
+        keystore.insert(
+            "Encrypted".into(),
+            keystore_path.to_string_lossy().to_string().into(),
+        );
+        config["keystore"] = keystore.into();
+        fs::write(&config_path, serde_yaml::to_string(&config)?)?;
+        println!(
+            "Updated client config {:?} to use the encrypted keystore",
+            config_path
+        );
+    }
+    Ok(())
+}
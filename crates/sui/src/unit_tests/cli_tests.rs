@@ -17,8 +17,12 @@ use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
+    client_commands::{
+        SuiClientCommandResult, SuiClientCommands, UnsignedTransactionArgs,
+        UnsignedTransactionFile, WalletContext,
+    },
     config::SuiClientConfig,
+    keytool::KeyToolCommand,
     sui_commands::SuiCommand,
 };
 use sui_config::{
@@ -387,6 +391,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -437,6 +442,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -516,6 +522,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -553,6 +560,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -577,6 +585,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -603,6 +612,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -649,6 +659,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -717,6 +728,7 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -784,6 +796,7 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -829,6 +842,7 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -883,6 +897,7 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -924,6 +939,7 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await;
@@ -979,6 +995,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_output: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1049,6 +1066,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1109,6 +1127,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         to: recipient,
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1212,6 +1231,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         to: recipient,
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1499,6 +1519,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: Some(gas),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1552,6 +1573,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: None,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1616,6 +1638,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1682,6 +1705,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: None,
         count: Some(3),
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1751,6 +1775,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -1859,6 +1884,160 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_offline_signing() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(SuiObjectResponseQuery::new_with_options(
+                SuiObjectDataOptions::new(),
+            )),
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let coin = object_refs.get(1).unwrap().object().unwrap().object_id;
+
+    let temp_dir = tempfile::tempdir()?;
+    let tx_file = temp_dir.path().join("tx.json");
+
+    // Build the transaction without signing it.
+    let resp = SuiClientCommands::TransferSui {
+        to: address1,
+        sui_coin_object_id: coin,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: Some(1),
+        unsigned_transaction: UnsignedTransactionArgs {
+            serialize_unsigned_transaction: Some(tx_file.clone()),
+        },
+    }
+    .execute(context)
+    .await?;
+    assert!(matches!(
+        resp,
+        SuiClientCommandResult::SerializeUnsignedTransaction(_)
+    ));
+    assert!(UnsignedTransactionFile::read(&tx_file)?
+        .signatures()?
+        .is_empty());
+
+    // Executing before signing is rejected.
+    assert!(SuiClientCommands::ExecuteTxFile {
+        tx_file: tx_file.clone(),
+        multisig_pks: vec![],
+        multisig_weights: vec![],
+        multisig_threshold: None,
+    }
+    .execute(context)
+    .await
+    .is_err());
+
+    KeyToolCommand::Sign {
+        address,
+        data: None,
+        tx_file: Some(tx_file.clone()),
+        intent: None,
+    }
+    .execute(&mut context.config.keystore)?;
+    assert_eq!(
+        UnsignedTransactionFile::read(&tx_file)?.signatures()?.len(),
+        1
+    );
+
+    let resp = SuiClientCommands::ExecuteTxFile {
+        tx_file,
+        multisig_pks: vec![],
+        multisig_weights: vec![],
+        multisig_threshold: None,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExecuteSignedTx(response) = resp else {
+        panic!("Unexpected result: {resp}")
+    };
+    assert!(response.status_ok().unwrap());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let temp_dir = tempfile::tempdir()?;
+    let commands = temp_dir.path().join("ptb.json");
+    std::fs::write(
+        &commands,
+        json!([
+            { "name": "coins", "SplitCoins": { "coin": "$gas", "amounts": [1000, "2000"] } },
+            { "MergeCoins": { "destination": "$coins.0", "sources": ["$coins.1"] } },
+            { "TransferObjects": { "objects": ["$coins.0"], "address": address1.to_string() } },
+        ])
+        .to_string(),
+    )?;
+
+    // Preview the transaction block without executing it.
+    let resp = SuiClientCommands::Ptb {
+        commands: commands.clone(),
+        gas: None,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        preview: true,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::PtbPreview(dry_run) = resp else {
+        panic!("Unexpected result: {resp}")
+    };
+    assert!(dry_run.effects.status().is_ok());
+    assert_eq!(dry_run.effects.created().len(), 1);
+
+    let resp = SuiClientCommands::Ptb {
+        commands: commands.clone(),
+        gas: None,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        preview: false,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Unexpected result: {resp}")
+    };
+    assert!(response.status_ok().unwrap());
+    let created = response.effects.as_ref().unwrap().created();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].owner, Owner::AddressOwner(address1));
+
+    // References to unknown results are rejected before anything is executed.
+    std::fs::write(
+        &commands,
+        json!([{ "TransferObjects": { "objects": ["$coins"], "address": address1.to_string() } }])
+            .to_string(),
+    )?;
+    assert!(SuiClientCommands::Ptb {
+        commands,
+        gas: None,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        preview: true,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
+    }
+    .execute(context)
+    .await
+    .is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
@@ -3,6 +3,7 @@
 
 use std::str::FromStr;
 
+use crate::client_commands::UnsignedTransactionFile;
 use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
 
@@ -13,6 +14,7 @@ use fastcrypto::encoding::Encoding;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use shared_crypto::intent::Intent;
+use shared_crypto::intent::IntentMessage;
 use shared_crypto::intent::IntentScope;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
@@ -32,6 +34,7 @@ use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
 use sui_types::messages::TransactionData;
 use sui_types::messages::TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
+use sui_types::signature::AuthenticatorTrait;
 use tempfile::TempDir;
 
 const TEST_MNEMONIC: &str = "result crisp session latin must fruit genuine question prevent start coconut brave speak student dismiss";
@@ -370,7 +373,8 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
     // Sign an intent message for the transaction data and a passed-in intent with scope as PersonalMessage.
     KeyToolCommand::Sign {
         address: *sender,
-        data: Base64::encode(bcs::to_bytes(&tx_data)?),
+        data: Some(Base64::encode(bcs::to_bytes(&tx_data)?)),
+        tx_file: None,
         intent: Some(Intent::sui_app(IntentScope::PersonalMessage)),
     }
     .execute(&mut keystore)?;
@@ -378,9 +382,61 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
     // Sign an intent message for the transaction data without intent passed in, so default is used.
     KeyToolCommand::Sign {
         address: *sender,
-        data: Base64::encode(bcs::to_bytes(&tx_data)?),
+        data: Some(Base64::encode(bcs::to_bytes(&tx_data)?)),
+        tx_file: None,
         intent: None,
     }
     .execute(&mut keystore)?;
     Ok(())
 }
+
+#[test]
+fn test_sign_tx_file_command() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(2));
+    let addresses = keystore.addresses();
+    let (sender, sponsor) = (addresses[0], addresses[1]);
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let gas_price = 1;
+    let tx_data = TransactionData::new_pay_sui(
+        sender,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        gas_price * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_price,
+    )
+    .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let tx_file = temp_dir.path().join("tx.json");
+    UnsignedTransactionFile::new(&tx_data)?.write(&tx_file)?;
+
+    // Signatures of each signer are collected in the file, signing twice is a no-op.
+    for address in [sender, sponsor, sender] {
+        KeyToolCommand::Sign {
+            address,
+            data: None,
+            tx_file: Some(tx_file.clone()),
+            intent: None,
+        }
+        .execute(&mut keystore)?;
+    }
+
+    let unsigned_tx = UnsignedTransactionFile::read(&tx_file)?;
+    assert_eq!(unsigned_tx.transaction_data()?, tx_data);
+    let signatures = unsigned_tx.signatures()?;
+    assert_eq!(signatures.len(), 2);
+    for (signature, address) in signatures.iter().zip([sender, sponsor]) {
+        signature.verify_secure_generic(
+            &IntentMessage::new(Intent::sui_transaction(), tx_data.clone()),
+            address,
+        )?;
+    }
+    Ok(())
+}
@@ -12,18 +12,21 @@ use move_core_types::value::MoveStructLayout;
 use mysten_metrics::RegistryService;
 use prometheus::Registry;
 use serde_json::json;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
-use sui_json_rpc_types::EventFilter;
+use sui::client_commands::{
+    SuiClientCommandResult, SuiClientCommands, UnsignedTransactionArgs, WalletContext,
+};
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffectsAPI,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
+use sui_json_rpc_types::{EventFilter, PastObjectsPage, SuiPastObjectResponse};
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
 use sui_node::SuiNode;
 use sui_tool::restore_from_db_checkpoint;
 use sui_types::base_types::{ObjectRef, SequenceNumber};
 use sui_types::crypto::{get_key_pair, SuiKeyPair};
+use sui_types::error::{SuiError, UserInputError};
 use sui_types::event::{Event, EventID};
 use sui_types::message_envelope::Message;
 use sui_types::messages::{
@@ -33,6 +36,7 @@ use sui_types::messages::{
 use sui_types::object::{Object, ObjectRead, Owner, PastObjectRead};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 use sui_types::utils::to_sender_signed_transaction_with_multi_signers;
 use sui_types::{base_types::ObjectID, messages::TransactionInfoRequest};
 use test_utils::authority::test_and_configure_authority_configs;
@@ -211,6 +215,28 @@ async fn test_full_node_move_function_index() -> Result<(), anyhow::Error> {
     assert_eq!(txes.len(), 2);
     assert_eq!(txes[1], digest);
 
+    let increment = TransactionFilter::MoveFunction {
+        package: package_ref.0,
+        module: Some("counter".to_string()),
+        function: Some("increment".to_string()),
+    };
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::FromAddress(sender).and(increment.clone())),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::FromAddress(sender).and(increment.not())),
+        None,
+        None,
+        false,
+    )?;
+    assert!(!txes.is_empty());
+    assert!(!txes.contains(&digest));
+
     Ok(())
 }
 
@@ -288,6 +314,156 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     // timestamp is recorded
     let ts = node.state().get_timestamp_ms(&digest).await?;
     assert!(ts.is_some());
+    let ts = ts.unwrap();
+
+    // Or filters merge the indexes in sequence number order, without duplicates.
+    let to_receiver = node.state().get_transactions(
+        Some(TransactionFilter::ToAddress(receiver)),
+        None,
+        None,
+        false,
+    )?;
+    let from_sender_or_to_receiver =
+        TransactionFilter::FromAddress(sender).or(TransactionFilter::ToAddress(receiver));
+    let txes = node.state().get_transactions(
+        Some(from_sender_or_to_receiver.clone()),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, to_receiver);
+    let txes = node.state().get_transactions(
+        Some(from_sender_or_to_receiver.clone()),
+        None,
+        None,
+        true,
+    )?;
+    assert_eq!(txes, to_receiver.iter().rev().cloned().collect::<Vec<_>>());
+    let txes = node.state().get_transactions(
+        Some(from_sender_or_to_receiver.clone()),
+        Some(to_receiver[0]),
+        Some(1),
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+    let txes = node.state().get_transactions(
+        Some(from_sender_or_to_receiver),
+        Some(digest),
+        Some(1),
+        true,
+    )?;
+    assert_eq!(txes, vec![to_receiver[0]]);
+
+    // Time ranges are only served in combination with an indexed filter.
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender).and(TransactionFilter::TimeRange {
+                start_time: ts,
+                end_time: ts + 1,
+            }),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender).and(TransactionFilter::TimeRange {
+                start_time: 0,
+                end_time: ts,
+            }),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert!(txes.is_empty());
+    assert!(node
+        .state()
+        .get_transactions(
+            Some(TransactionFilter::TimeRange {
+                start_time: 0,
+                end_time: u64::MAX,
+            }),
+            None,
+            None,
+            false,
+        )
+        .is_err());
+    assert!(node
+        .state()
+        .get_transactions(
+            Some(TransactionFilter::FromAddress(sender).not()),
+            None,
+            None,
+            false,
+        )
+        .is_err());
+
+    // Paging through a checkpoint range with a cursor returns every transaction once.
+    let (_, checkpoint) = node
+        .state()
+        .get_transaction_checkpoint_sequence(&digest)?
+        .unwrap();
+    let range = TransactionFilter::CheckpointRange {
+        start_checkpoint: 0,
+        end_checkpoint: checkpoint,
+    };
+    let all = node
+        .state()
+        .get_transactions(Some(range.clone()), None, None, false)?;
+    assert!(all.contains(&digest));
+    let mut paged = vec![];
+    let mut cursor = None;
+    loop {
+        let page = node
+            .state()
+            .get_transactions(Some(range.clone()), cursor, Some(1), false)?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        cursor = Some(*last);
+        paged.extend(page);
+    }
+    assert_eq!(paged, all);
+    let txes = node
+        .state()
+        .get_transactions(Some(range), Some(digest), Some(2), true)?;
+    let expected: Vec<_> = all
+        .iter()
+        .rev()
+        .skip_while(|d| **d != digest)
+        .skip(1)
+        .take(2)
+        .cloned()
+        .collect();
+    assert_eq!(txes, expected);
+
+    // A cursor outside of the range is rejected rather than answered with an empty page.
+    for range in [
+        TransactionFilter::CheckpointRange {
+            start_checkpoint: checkpoint + 1,
+            end_checkpoint: checkpoint + 10,
+        },
+        TransactionFilter::CheckpointRange {
+            start_checkpoint: 0,
+            end_checkpoint: checkpoint - 1,
+        },
+    ] {
+        for reverse in [false, true] {
+            let error = node
+                .state()
+                .get_transactions(Some(range.clone()), Some(digest), Some(1), reverse)
+                .unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<SuiError>(),
+                Some(SuiError::UserInputError {
+                    error: UserInputError::CursorOutsideCheckpointRange { .. }
+                })
+            ));
+        }
+    }
 
     // This is a poor substitute for the post processing taking some time
     // Unfortunately event store writes seem to add some latency so this wait is needed
@@ -422,6 +598,114 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_objects_at_checkpoint() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let node = &test_cluster.fullnode_handle.sui_node;
+    let jsonrpc_client = &test_cluster.fullnode_handle.rpc_client;
+    let context = &mut test_cluster.wallet;
+
+    let (transferred_object, sender, receiver, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+    let (_, checkpoint) = loop {
+        if let Some(checkpoint) = node.state().get_transaction_checkpoint_sequence(&digest)? {
+            break checkpoint;
+        }
+        sleep(Duration::from_millis(100)).await;
+    };
+    // Objects are indexed once the checkpoint is executed.
+    let indexes = node.state().get_indexes()?;
+    timeout(Duration::from_secs(60), async {
+        while indexes
+            .get_object_version_at_checkpoint(transferred_object, checkpoint)
+            .is_err()
+        {
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await?;
+
+    // The version before the transfer is found in the previous checkpoint, owned by the sender.
+    let (old_ref, old_owner) = indexes
+        .get_object_version_at_checkpoint(transferred_object, checkpoint - 1)?
+        .unwrap();
+    assert_eq!(old_owner, Some(Owner::AddressOwner(sender)));
+    let (new_ref, new_owner) = indexes
+        .get_object_version_at_checkpoint(transferred_object, checkpoint)?
+        .unwrap();
+    assert_eq!(new_owner, Some(Owner::AddressOwner(receiver)));
+    assert!(old_ref.1 < new_ref.1);
+
+    let owned_by = |owner, checkpoint| {
+        node.state()
+            .get_owner_objects_at_checkpoint(owner, checkpoint, None, usize::MAX)
+            .unwrap()
+    };
+    assert!(owned_by(sender, checkpoint - 1).contains(&old_ref));
+    assert!(!owned_by(receiver, checkpoint - 1).contains(&old_ref));
+    assert!(!owned_by(sender, checkpoint)
+        .iter()
+        .any(|obj_ref| obj_ref.0 == transferred_object));
+    assert!(owned_by(receiver, checkpoint).contains(&new_ref));
+
+    // Pages of owned objects follow each other.
+    let all = owned_by(receiver, checkpoint);
+    let mut paged = vec![];
+    let mut cursor = None;
+    loop {
+        let page = node
+            .state()
+            .get_owner_objects_at_checkpoint(receiver, checkpoint, cursor, 1)?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        cursor = Some(last.0);
+        paged.extend(page);
+    }
+    assert_eq!(paged, all);
+
+    // Checkpoints not executed yet are rejected.
+    let latest = node.state().get_latest_checkpoint_sequence_number()?;
+    assert!(indexes
+        .get_object_version_at_checkpoint(transferred_object, latest + 1000)
+        .is_err());
+
+    // The same reads through JSON-RPC.
+    let response: SuiPastObjectResponse = jsonrpc_client
+        .request(
+            "sui_getObjectAtCheckpoint",
+            rpc_params![transferred_object, BigInt::from(checkpoint - 1)],
+        )
+        .await?;
+    assert_eq!(response.into_object()?.object_ref(), old_ref);
+    let response: SuiPastObjectResponse = jsonrpc_client
+        .request(
+            "sui_getObjectAtCheckpoint",
+            rpc_params![transferred_object, BigInt::from(checkpoint)],
+        )
+        .await?;
+    assert_eq!(response.into_object()?.object_ref(), new_ref);
+    let page: PastObjectsPage = jsonrpc_client
+        .request(
+            "suix_getOwnedObjectsAtCheckpoint",
+            rpc_params![receiver, BigInt::from(checkpoint)],
+        )
+        .await?;
+    assert!(page
+        .data
+        .into_iter()
+        .any(|response| response.into_object().unwrap().object_ref() == new_ref));
+    let response: Result<SuiPastObjectResponse, _> = jsonrpc_client
+        .request(
+            "sui_getObjectAtCheckpoint",
+            rpc_params![transferred_object, BigInt::from(latest + 1000)],
+        )
+        .await;
+    assert!(response.is_err());
+
+    Ok(())
+}
+
 // Test for syncing a node to an authority that already has many txes.
 #[sim_test]
 async fn test_full_node_cold_sync() -> Result<(), anyhow::Error> {
@@ -498,6 +782,7 @@ async fn test_full_node_sync_flood() -> Result<(), anyhow::Error> {
                         coin_id: object_to_split.0,
                         gas: Some(gas_object_id),
                         gas_budget: 50000,
+                        unsigned_transaction: UnsignedTransactionArgs::default(),
                     }
                     .execute(context)
                     .await
@@ -646,6 +931,76 @@ async fn test_full_node_sub_and_query_move_event_ok() -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_sub_to_tx_effects_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+
+    let fullnode = start_fullnode_from_config(
+        test_cluster
+            .fullnode_config_builder()
+            .with_event_store()
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+
+    let context = &mut test_cluster.wallet;
+    let sender = context.config.keystore.addresses()[0];
+
+    let mut sub: Subscription<SuiTransactionBlockEffects> = ws_client
+        .subscribe(
+            "suix_subscribeTransaction",
+            rpc_params![TransactionFilter::FromAddress(sender)],
+            "suix_unsubscribeTransaction",
+        )
+        .await
+        .unwrap();
+
+    let (_, _, _, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // Wait for streaming
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Ok(Some(Ok(effects))) => {
+            assert_eq!(effects.transaction_digest(), &digest);
+        }
+        other => panic!("Failed to get SuiTransactionBlockEffects, but {:?}", other),
+    };
+
+    // Subscribing by checkpoint or time is rejected, including within composite filters, since
+    // live transactions are not in a checkpoint yet.
+    for filter in [
+        TransactionFilter::Checkpoint(0),
+        TransactionFilter::FromAddress(sender).and(TransactionFilter::CheckpointRange {
+            start_checkpoint: 0,
+            end_checkpoint: 10,
+        }),
+        TransactionFilter::FromAddress(sender).or(TransactionFilter::TimeRange {
+            start_time: 0,
+            end_time: u64::MAX,
+        }),
+        TransactionFilter::Checkpoint(0).not(),
+    ] {
+        let res: Result<Subscription<SuiTransactionBlockEffects>, _> = ws_client
+            .subscribe(
+                "suix_subscribeTransaction",
+                rpc_params![filter],
+                "suix_unsubscribeTransaction",
+            )
+            .await;
+        assert!(res.is_err());
+    }
+
+    Ok(())
+}
+
 // Test fullnode has event read jsonrpc endpoints working
 #[sim_test]
 #[ignore]
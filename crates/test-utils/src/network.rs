@@ -3,6 +3,7 @@
 
 use futures::future::join_all;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -21,7 +22,8 @@ use sui::{client_commands::WalletContext, config::SuiClientConfig};
 use sui_config::builder::{ProtocolVersionsConfig, SupportedProtocolVersionsCallback};
 use sui_config::genesis_config::GenesisConfig;
 use sui_config::node::DBCheckpointConfig;
-use sui_config::{Config, SUI_CLIENT_CONFIG, SUI_NETWORK_CONFIG};
+use sui_config::SUI_FULLNODE_CONFIG;
+use sui_config::{Config, NetworkConfig, SUI_CLIENT_CONFIG, SUI_NETWORK_CONFIG};
 use sui_config::{FullnodeConfigBuilder, NodeConfig, PersistedConfig, SUI_KEYSTORE_FILENAME};
 use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
@@ -42,6 +44,8 @@ use sui_types::sui_system_state::SuiSystemState;
 use sui_types::sui_system_state::SuiSystemStateTrait;
 
 const NUM_VALIDAOTR: usize = 4;
+/// Name of the fullnode database directory, next to the validator databases.
+const FULLNODE_DB_DIR: &str = "fullnode";
 
 pub struct FullNodeHandle {
     pub sui_node: Arc<SuiNode>,
@@ -308,6 +312,7 @@ pub struct TestClusterBuilder {
     fullnode_supported_protocol_versions_config: Option<ProtocolVersionsConfig>,
     db_checkpoint_config_validators: DBCheckpointConfig,
     db_checkpoint_config_fullnodes: DBCheckpointConfig,
+    config_dir: Option<PathBuf>,
 }
 
 impl TestClusterBuilder {
@@ -323,6 +328,7 @@ impl TestClusterBuilder {
             fullnode_supported_protocol_versions_config: None,
             db_checkpoint_config_validators: DBCheckpointConfig::default(),
             db_checkpoint_config_fullnodes: DBCheckpointConfig::default(),
+            config_dir: None,
         }
     }
 
@@ -405,6 +411,14 @@ impl TestClusterBuilder {
         self
     }
 
+    /// Keep the network config, the databases and the wallet in `dir` instead of a temporary
+    /// directory. If `dir` already holds a network created by a previous run, that network is
+    /// restarted and the genesis related settings of the builder are ignored.
+    pub fn with_config_dir(mut self, dir: PathBuf) -> Self {
+        self.config_dir = Some(dir);
+        self
+    }
+
     pub async fn build(self) -> anyhow::Result<TestCluster> {
         let cluster = self.start_test_network_with_customized_ports().await?;
         Ok(cluster)
@@ -419,22 +433,42 @@ impl TestClusterBuilder {
         let mut wallet_conf: SuiClientConfig =
             PersistedConfig::read(&working_dir.join(SUI_CLIENT_CONFIG))?;
 
-        let fullnode_config = swarm
-            .config()
-            .fullnode_config_builder()
-            .with_supported_protocol_versions_config(
-                self.fullnode_supported_protocol_versions_config
-                    .clone()
-                    .unwrap_or_else(|| self.validator_supported_protocol_versions_config.clone()),
-            )
-            .with_db_checkpoint_config(self.db_checkpoint_config_fullnodes)
-            .set_event_store(self.enable_fullnode_events)
-            .set_rpc_port(self.fullnode_rpc_port)
-            .build()
-            .unwrap();
+        let fullnode_config_path = working_dir.join(SUI_FULLNODE_CONFIG);
+        let fullnode_config = if self.config_dir.is_some() && fullnode_config_path.exists() {
+            let mut config = NodeConfig::load(&fullnode_config_path)?;
+            if let Some(rpc_port) = self.fullnode_rpc_port {
+                config.json_rpc_address.set_port(rpc_port);
+            }
+            config.enable_event_processing = self.enable_fullnode_events;
+            config
+        } else {
+            let mut builder = swarm
+                .config()
+                .fullnode_config_builder()
+                .with_supported_protocol_versions_config(
+                    self.fullnode_supported_protocol_versions_config
+                        .clone()
+                        .unwrap_or_else(|| {
+                            self.validator_supported_protocol_versions_config.clone()
+                        }),
+                )
+                .with_db_checkpoint_config(self.db_checkpoint_config_fullnodes)
+                .set_event_store(self.enable_fullnode_events)
+                .set_rpc_port(self.fullnode_rpc_port);
+            if self.config_dir.is_some() {
+                builder = builder.with_dir(FULLNODE_DB_DIR.into());
+            }
+            let config = builder.build()?;
+            if self.config_dir.is_some() {
+                config.save(&fullnode_config_path)?;
+            }
+            config
+        };
 
         let fullnode_handle = start_fullnode_from_config(fullnode_config).await?;
 
+        // A restarted network already has an env from its previous run.
+        wallet_conf.envs.retain(|env| env.alias != "localnet");
         wallet_conf.envs.push(SuiEnv {
             alias: "localnet".to_string(),
             rpc: fullnode_handle.rpc_url.clone(),
@@ -461,6 +495,18 @@ impl TestClusterBuilder {
 
     /// Start a Swarm and set up WalletConfig
     async fn start_swarm(&mut self) -> Result<Swarm, anyhow::Error> {
+        if let Some(dir) = &self.config_dir {
+            let network_path = dir.join(SUI_NETWORK_CONFIG);
+            if network_path.exists() {
+                info!("Restarting the network persisted in {}", dir.display());
+                let network_config = NetworkConfig::load(&network_path)?;
+                let mut swarm = Swarm::builder().from_network_config(dir.clone(), network_config);
+                swarm.launch().await?;
+                return Ok(swarm);
+            }
+            std::fs::create_dir_all(dir)?;
+        }
+
         let mut builder: SwarmBuilder = Swarm::builder()
             .committee_size(
                 NonZeroUsize::new(self.num_validators.unwrap_or(NUM_VALIDAOTR)).unwrap(),
@@ -475,6 +521,9 @@ impl TestClusterBuilder {
         if let Some(genesis_config) = self.genesis_config.take() {
             builder = builder.initial_accounts_config(genesis_config);
         }
+        if let Some(dir) = &self.config_dir {
+            builder = builder.dir(dir.clone());
+        }
 
         let mut swarm = builder.build();
         swarm.launch().await?;
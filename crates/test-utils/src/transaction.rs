@@ -7,7 +7,7 @@ use shared_crypto::intent::Intent;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use sui::client_commands::WalletContext;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, UnsignedTransactionArgs};
 use sui_core::authority_client::AuthorityAPI;
 pub use sui_core::test_utils::{
     compile_basics_package, compile_nfts_package, wait_for_all_txes, wait_for_tx,
@@ -411,6 +411,7 @@ pub async fn create_devnet_nft(
         args,
         gas: Some(*gas_object),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_GENERIC * gas_price,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -458,6 +459,7 @@ pub async fn transfer_sui(
         amount: None,
         sui_coin_object_id: gas_ref.0,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -529,6 +531,7 @@ pub async fn transfer_coin(
         object_id: object_to_send,
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await?;
@@ -570,6 +573,7 @@ pub async fn split_coin_with_wallet_context(context: &mut WalletContext, coin_id
         count: Some(2),
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN * gas_price,
+        unsigned_transaction: UnsignedTransactionArgs::default(),
     }
     .execute(context)
     .await
@@ -14,6 +14,7 @@ pub mod consensus;
 pub mod consensus_utils;
 pub mod dag;
 pub mod metrics;
+pub mod replay;
 pub mod tusk;
 pub mod utils;
 
@@ -41,7 +42,7 @@ pub enum ConsensusError {
     ShuttingDown,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Outcome {
     // Certificate is not processed, since it's below the latest committed round for its origin.
     CertificateBelowCommitRound,
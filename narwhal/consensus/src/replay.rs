@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic replay of Bullshark over the certificates recorded by a primary. Certificates
+//! are fed to consensus in (round, origin) order, which commits the same sequence of leaders as
+//! the live run, so leader elections and reputation scores can be investigated offline and the
+//! replayed sub dags compared with the ones recorded in the `ConsensusStore`.
+
+use crate::bullshark::Bullshark;
+use crate::consensus::{ConsensusProtocol, ConsensusState};
+use crate::metrics::ConsensusMetrics;
+use crate::{ConsensusError, Outcome};
+use config::{AuthorityIdentifier, Committee};
+use fastcrypto::hash::Hash;
+use prometheus::Registry;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use storage::{CertificateStore, ConsensusStore};
+use types::{
+    CertificateDigest, CommittedSubDag, ConsensusCommit, ReputationScores, Round, SequenceNumber,
+};
+
+#[cfg(test)]
+#[path = "tests/replay_tests.rs"]
+pub mod replay_tests;
+
+/// The leader election of an even round, as decided by the certificates of the following round.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LeaderElection {
+    pub round: Round,
+    pub leader: AuthorityIdentifier,
+    /// `Commit` if a certificate of the following round committed the leader, otherwise the
+    /// outcome for the last certificate of that round.
+    pub outcome: Outcome,
+    /// The sub dag of the leader when it got committed, directly or through a later leader.
+    pub sub_dag_index: Option<SequenceNumber>,
+}
+
+/// The decisions taken by Bullshark during a replay.
+#[derive(Debug, Default)]
+pub struct ReplayOutput {
+    pub elections: Vec<LeaderElection>,
+    pub sub_dags: Vec<CommittedSubDag>,
+}
+
+/// A difference between a replayed sub dag and the one recorded by the primary.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayMismatch {
+    /// The sub dag was recorded but not committed by the replay.
+    Missing { sub_dag_index: SequenceNumber },
+    /// The sub dag was committed by the replay but not recorded.
+    Unexpected { sub_dag_index: SequenceNumber },
+    Leader {
+        sub_dag_index: SequenceNumber,
+        recorded: CertificateDigest,
+        replayed: CertificateDigest,
+    },
+    Certificates {
+        sub_dag_index: SequenceNumber,
+        recorded: Vec<CertificateDigest>,
+        replayed: Vec<CertificateDigest>,
+    },
+    ReputationScores {
+        sub_dag_index: SequenceNumber,
+        recorded: ReputationScores,
+        replayed: ReputationScores,
+    },
+}
+
+pub struct ConsensusReplay {
+    committee: Committee,
+    gc_depth: Round,
+    num_sub_dags_per_schedule: u64,
+}
+
+impl ConsensusReplay {
+    pub fn new(committee: Committee, gc_depth: Round, num_sub_dags_per_schedule: u64) -> Self {
+        Self {
+            committee,
+            gc_depth,
+            num_sub_dags_per_schedule,
+        }
+    }
+
+    /// Runs Bullshark from genesis over all the certificates of `certificate_store`. Bullshark
+    /// persists its state as it commits, so `scratch_store` must not be the store of the primary
+    /// being replayed.
+    pub fn replay(
+        &self,
+        certificate_store: &CertificateStore,
+        scratch_store: Arc<ConsensusStore>,
+    ) -> Result<ReplayOutput, ConsensusError> {
+        let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+        let mut state = ConsensusState::new(metrics.clone(), self.gc_depth);
+        let mut bullshark = Bullshark::new(
+            self.committee.clone(),
+            scratch_store,
+            metrics,
+            self.num_sub_dags_per_schedule,
+        );
+
+        let mut elections: BTreeMap<Round, LeaderElection> = BTreeMap::new();
+        let mut sub_dags = Vec::new();
+        for certificate in certificate_store.after_round(1)? {
+            let round = certificate.round();
+            let (outcome, committed) = bullshark.process_certificate(&mut state, certificate)?;
+            if !matches!(
+                outcome,
+                Outcome::CertificateBelowCommitRound | Outcome::NoLeaderElectedForOddRound
+            ) {
+                let election = self.election(&mut elections, round - 1);
+                if election.outcome != Outcome::Commit {
+                    election.outcome = outcome;
+                }
+            }
+            for sub_dag in committed {
+                self.election(&mut elections, sub_dag.leader_round())
+                    .sub_dag_index = Some(sub_dag.sub_dag_index);
+                sub_dags.push(sub_dag);
+            }
+        }
+
+        Ok(ReplayOutput {
+            elections: elections.into_values().collect(),
+            sub_dags,
+        })
+    }
+
+    fn election<'a>(
+        &self,
+        elections: &'a mut BTreeMap<Round, LeaderElection>,
+        round: Round,
+    ) -> &'a mut LeaderElection {
+        elections.entry(round).or_insert_with(|| LeaderElection {
+            round,
+            leader: Bullshark::leader_authority(&self.committee, round),
+            outcome: Outcome::LeaderNotFound,
+            sub_dag_index: None,
+        })
+    }
+}
+
+/// Compares the replayed sub dags with the commits recorded by the primary, by sub dag index.
+pub fn diff_commits(
+    replayed: &[CommittedSubDag],
+    recorded: &[ConsensusCommit],
+) -> Vec<ReplayMismatch> {
+    let replayed: BTreeMap<_, _> = replayed
+        .iter()
+        .map(|sub_dag| (sub_dag.sub_dag_index, sub_dag))
+        .collect();
+    let recorded: BTreeMap<_, _> = recorded
+        .iter()
+        .map(|commit| (commit.sub_dag_index(), commit))
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for (sub_dag_index, commit) in &recorded {
+        let sub_dag_index = *sub_dag_index;
+        let Some(sub_dag) = replayed.get(&sub_dag_index) else {
+            mismatches.push(ReplayMismatch::Missing { sub_dag_index });
+            continue;
+        };
+        if commit.leader() != sub_dag.leader.digest() {
+            mismatches.push(ReplayMismatch::Leader {
+                sub_dag_index,
+                recorded: commit.leader(),
+                replayed: sub_dag.leader.digest(),
+            });
+        }
+        let certificates: Vec<_> = sub_dag.certificates.iter().map(|c| c.digest()).collect();
+        if commit.certificates() != certificates {
+            mismatches.push(ReplayMismatch::Certificates {
+                sub_dag_index,
+                recorded: commit.certificates(),
+                replayed: certificates,
+            });
+        }
+        if commit.reputation_score() != sub_dag.reputation_score {
+            mismatches.push(ReplayMismatch::ReputationScores {
+                sub_dag_index,
+                recorded: commit.reputation_score(),
+                replayed: sub_dag.reputation_score.clone(),
+            });
+        }
+    }
+    mismatches.extend(
+        replayed
+            .keys()
+            .filter(|index| !recorded.contains_key(index))
+            .map(|sub_dag_index| ReplayMismatch::Unexpected {
+                sub_dag_index: *sub_dag_index,
+            }),
+    );
+    mismatches
+}
@@ -0,0 +1,85 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+
+use crate::consensus_utils::*;
+use std::collections::BTreeSet;
+use test_utils::CommitteeFixture;
+use types::Certificate;
+
+// Record 11 rounds with one dead node, the leader of round 8, by running Bullshark as the primary
+// does, then replay them from the certificate store. The replay should commit the leaders of
+// rounds 2, 4, 6 and 10, exactly like the recorded run.
+#[tokio::test]
+async fn replay_matches_recorded_commits() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let mut ids: Vec<_> = committee
+        .authorities()
+        .map(|authority| authority.id())
+        .collect();
+    ids.pop();
+
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (certificates, _) =
+        test_utils::make_optimal_certificates(&committee, 1..=11, &genesis, &ids);
+
+    let gc_depth = 50;
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let cert_store = make_certificate_store(&test_utils::temp_dir());
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let mut state = ConsensusState::new(metrics.clone(), gc_depth);
+    let mut bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        metrics,
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    );
+    for certificate in certificates.iter().cloned() {
+        bullshark
+            .process_certificate(&mut state, certificate)
+            .unwrap();
+    }
+    cert_store.write_all(certificates).unwrap();
+    let recorded = store.read_committed_sub_dags_from(&1).unwrap();
+    assert_eq!(recorded.len(), 4);
+
+    let replay = ConsensusReplay::new(committee.clone(), gc_depth, NUM_SUB_DAGS_PER_SCHEDULE);
+    let output = replay
+        .replay(&cert_store, make_consensus_store(&test_utils::temp_dir()))
+        .unwrap();
+
+    let elections: Vec<_> = output
+        .elections
+        .iter()
+        .map(|e| (e.round, e.outcome, e.sub_dag_index))
+        .collect();
+    assert_eq!(
+        elections,
+        vec![
+            (2, Outcome::Commit, Some(1)),
+            (4, Outcome::Commit, Some(2)),
+            (6, Outcome::Commit, Some(3)),
+            (8, Outcome::LeaderNotFound, None),
+            (10, Outcome::Commit, Some(4)),
+        ]
+    );
+    assert_eq!(
+        output.elections[3].leader,
+        Bullshark::leader_authority(&committee, 8)
+    );
+    assert!(diff_commits(&output.sub_dags, &recorded).is_empty());
+
+    // Sub dags only on one side are reported.
+    assert_eq!(
+        diff_commits(&output.sub_dags, &recorded[..3]),
+        vec![ReplayMismatch::Unexpected { sub_dag_index: 4 }]
+    );
+    assert_eq!(
+        diff_commits(&output.sub_dags[1..], &recorded),
+        vec![ReplayMismatch::Missing { sub_dag_index: 1 }]
+    );
+}
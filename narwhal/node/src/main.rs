@@ -10,12 +10,13 @@
 
 use clap::{crate_name, crate_version, App, AppSettings, ArgMatches, SubCommand};
 use config::{Committee, Import, Parameters, WorkerCache, WorkerId};
+use consensus::replay::{diff_commits, ConsensusReplay};
 use crypto::{KeyPair, NetworkKeyPair};
 use eyre::Context;
 use fastcrypto::traits::KeyPair as _;
 use mysten_metrics::RegistryService;
 use narwhal_node as node;
-use narwhal_node::primary_node::PrimaryNode;
+use narwhal_node::primary_node::{PrimaryNode, CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS};
 use narwhal_node::worker_node::WorkerNode;
 use network::client::NetworkClient;
 use node::{
@@ -23,6 +24,7 @@ use node::{
     metrics::{primary_metrics_registry, start_prometheus_server, worker_metrics_registry},
 };
 use prometheus::Registry;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use storage::{CertificateStoreCacheMetrics, NodeStorage};
 use sui_keys::keypair_file::{
@@ -81,6 +83,14 @@ async fn main() -> Result<(), eyre::Report> {
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp),
         )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("Replay Bullshark over the certificates of a stopped primary and print its decisions")
+                .args_from_usage("--committee=<FILE> 'The file containing committee information'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters'")
+                .args_from_usage("--store=<PATH> 'The path of the data store of the primary'")
+                .args_from_usage("--diff 'Compare the replayed sub dags with the ones recorded in the store'"),
+        )
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .get_matches();
 
@@ -187,6 +197,10 @@ async fn main() -> Result<(), eyre::Report> {
             )
             .await?
         }
+        ("replay", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            replay(sub_matches)?
+        }
         _ => unreachable!(),
     }
     Ok(())
@@ -239,6 +253,80 @@ fn setup_benchmark_telemetry(
     Ok(())
 }
 
+// Replays consensus over the certificate store of a primary, which must not be running.
+fn replay(matches: &ArgMatches<'_>) -> Result<(), eyre::Report> {
+    let committee_file = matches.value_of("committee").unwrap();
+    let mut committee =
+        Committee::import(committee_file).context("Failed to load the committee information")?;
+    committee.load();
+
+    let parameters = match matches.value_of("parameters") {
+        Some(filename) => {
+            Parameters::import(filename).context("Failed to load the node's parameters")?
+        }
+        None => Parameters::default(),
+    };
+
+    let store = NodeStorage::reopen(matches.value_of("store").unwrap(), None);
+    // Bullshark persists what it commits, keep that away from the replayed store.
+    let scratch_dir = tempfile::tempdir()?;
+    let scratch_store = NodeStorage::reopen(scratch_dir.path(), None);
+
+    let output = ConsensusReplay::new(
+        committee,
+        parameters.gc_depth,
+        CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
+    )
+    .replay(&store.certificate_store, scratch_store.consensus_store)?;
+
+    for election in &output.elections {
+        match election.sub_dag_index {
+            Some(sub_dag_index) => println!(
+                "Round {}: leader {} {:?}, sub dag {sub_dag_index}",
+                election.round, election.leader, election.outcome
+            ),
+            None => println!(
+                "Round {}: leader {} {:?}",
+                election.round, election.leader, election.outcome
+            ),
+        }
+    }
+    for sub_dag in &output.sub_dags {
+        let scores: BTreeMap<_, _> = sub_dag
+            .reputation_score
+            .scores_per_authority
+            .iter()
+            .collect();
+        println!(
+            "Sub dag {}: leader {} of round {}, {} certificates, reputation scores {scores:?}{}",
+            sub_dag.sub_dag_index,
+            sub_dag.leader.origin(),
+            sub_dag.leader_round(),
+            sub_dag.certificates.len(),
+            if sub_dag.reputation_score.final_of_schedule {
+                " (final of schedule)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    if matches.is_present("diff") {
+        let recorded = store.consensus_store.read_committed_sub_dags_from(&1)?;
+        let mismatches = diff_commits(&output.sub_dags, &recorded);
+        for mismatch in &mismatches {
+            println!("Mismatch: {mismatch:?}");
+        }
+        println!(
+            "{} recorded sub dags, {} replayed, {} mismatches",
+            recorded.len(),
+            output.sub_dags.len(),
+            mismatches.len()
+        );
+    }
+    Ok(())
+}
+
 // Runs either a worker or a primary.
 async fn run(
     matches: &ArgMatches<'_>,